@@ -0,0 +1,25 @@
+// Embeds the git commit the binary was built from as GENESIS_GIT_HASH, for run provenance.
+// Builds outside a git checkout get an empty value and report "unknown".
+
+use std::process::Command;
+
+fn main() {
+    let hash = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=GENESIS_GIT_HASH={hash}");
+
+    // Rebuild when HEAD moves: a checkout rewrites .git/HEAD, a commit the branch ref it names
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    if let Ok(head) = std::fs::read_to_string(".git/HEAD") {
+        if let Some(branch) = head.strip_prefix("ref: ") {
+            println!("cargo:rerun-if-changed=.git/{}", branch.trim());
+        }
+    }
+    println!("cargo:rerun-if-changed=.git/packed-refs");
+}
@@ -0,0 +1,99 @@
+use macroquad::prelude::*;
+
+use crate::config;
+use crate::energy_audit::EnergyLedger;
+use crate::entity::EntityArena;
+use crate::environment::Wall;
+use crate::spatial_hash::SpatialHash;
+use crate::world::World;
+
+/// Update each slot's call loudness from its call motor output. A call above the threshold
+/// emits a pulse at that loudness (costing energy); otherwise the last pulse fades out.
+pub fn update_calls(
+    arena: &mut EntityArena,
+    call_intents: &[f32],
+    calls: &mut Vec<f32>,
+    ledger: &mut EnergyLedger,
+    dt: f32,
+) {
+    if calls.len() < arena.entities.len() {
+        calls.resize(arena.entities.len(), 0.0);
+    }
+    let fade = (1.0 - config::CALL_DECAY_RATE * dt).max(0.0);
+
+    // Empty slots between the living are silenced as the walk passes them
+    let mut next = 0;
+    for (idx, entity) in arena.iter_alive_mut() {
+        calls[next..idx].fill(0.0);
+        next = idx + 1;
+        let intent = call_intents.get(idx).copied().unwrap_or(0.0);
+        calls[idx] *= fade;
+        if intent >= config::CALL_THRESHOLD {
+            calls[idx] = calls[idx].max(intent);
+            entity.energy -= config::CALL_ENERGY_COST * intent * dt;
+            ledger.actions += (config::CALL_ENERGY_COST * intent * dt) as f64;
+        }
+    }
+    calls[next..].fill(0.0);
+}
+
+/// Hearing inputs per slot: [relative bearing of the sound in [-1, 1] (negative = left),
+/// loudness in [0, 1]]. Sound fades with distance and is muffled, not blocked, by each wall
+/// between caller and listener. Unlike light signals it is unaffected by night.
+pub fn hear(
+    arena: &EntityArena,
+    calls: &[f32],
+    spatial: &SpatialHash,
+    world: &World,
+    walls: &[Wall],
+) -> Vec<[f32; 2]> {
+    let mut hearing = vec![[0.0f32; 2]; arena.entities.len()];
+    // Accumulate from each caller outward; usually only a few entities are calling at once
+    let mut heard = vec![(Vec2::ZERO, 0.0f32); arena.entities.len()];
+
+    for (idx, caller) in arena.iter_alive() {
+        let level = calls.get(idx).copied().unwrap_or(0.0);
+        if level <= 0.01 {
+            continue;
+        }
+        for listener_idx in spatial.query_radius_excluding(caller.pos, config::CALL_RANGE, idx as u32, world, arena) {
+            let Some(listener) = arena.get_by_index(listener_idx as usize) else { continue };
+            let delta = world.delta(listener.pos, caller.pos);
+            let dist = delta.length();
+            if dist < 1e-3 {
+                continue;
+            }
+            let dir = delta / dist;
+            let walls_between = walls
+                .iter()
+                .filter(|w| w.ray_intersection(listener.pos, dir, dist).is_some())
+                .count();
+            let loudness = level
+                * (1.0 - dist / config::CALL_RANGE)
+                * config::CALL_WALL_ATTENUATION.powi(walls_between as i32);
+            let entry = &mut heard[listener_idx as usize];
+            entry.0 += dir * loudness;
+            entry.1 += loudness;
+        }
+    }
+    for (idx, listener) in arena.iter_alive() {
+        let (direction, loudness) = heard[idx];
+        if loudness > 0.0 {
+            let bearing = direction.y.atan2(direction.x) - listener.heading;
+            let bearing = (bearing + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+            hearing[idx] = [bearing / std::f32::consts::PI, loudness.min(1.0)];
+        }
+    }
+
+    hearing
+}
+
+/// Draw an expanding ring around a calling entity (called from renderer).
+pub fn draw_call_ring(pos: Vec2, radius: f32, level: f32, time: f32) {
+    if level > 0.05 {
+        let phase = (time * 2.0).fract();
+        let ring_radius = radius + phase * config::CALL_RANGE * 0.3;
+        let alpha = level * (1.0 - phase) * 0.5;
+        draw_circle_lines(pos.x, pos.y, ring_radius, 1.5, Color::new(1.0, 0.95, 0.6, alpha));
+    }
+}
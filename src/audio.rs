@@ -0,0 +1,194 @@
+use macroquad::audio::{load_sound_from_bytes, play_sound, PlaySoundParams, Sound};
+
+const SAMPLE_RATE: u32 = 44100;
+
+/// Minimum seconds between two plays of the same category, so fast-forwarded
+/// worlds don't turn into a wall of noise.
+const CATEGORY_COOLDOWN: f32 = 0.08;
+
+/// Simulation events worth a sound, accumulated over the ticks of one frame.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SoundEvents {
+    pub births: u32,
+    pub deaths: u32,
+    pub combat_hits: u32,
+    pub storm_started: bool,
+}
+
+/// User-facing audio options, edited in the Settings panel.
+pub struct AudioSettings {
+    pub enabled: bool,
+    pub master_volume: f32,
+    pub births: bool,
+    pub deaths: bool,
+    pub combat: bool,
+    pub storms: bool,
+    pub ui_clicks: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            master_volume: 0.4,
+            births: true,
+            deaths: true,
+            combat: true,
+            storms: true,
+            ui_clicks: true,
+        }
+    }
+}
+
+/// Procedurally synthesized sound effects. Any sound that fails to load stays silent.
+pub struct AudioSystem {
+    birth: Option<Sound>,
+    death: Option<Sound>,
+    combat: Option<Sound>,
+    storm: Option<Sound>,
+    click: Option<Sound>,
+    // Seconds until each category (birth, death, combat, storm, click) may play again
+    cooldowns: [f32; 5],
+}
+
+impl AudioSystem {
+    pub async fn load() -> Self {
+        Self {
+            birth: load_tone(&[880.0, 1320.0], 0.12, Waveform::Sine).await,
+            death: load_tone(&[220.0, 110.0], 0.25, Waveform::Sine).await,
+            combat: load_tone(&[160.0], 0.08, Waveform::Noise).await,
+            storm: load_tone(&[70.0, 55.0], 1.2, Waveform::Noise).await,
+            click: load_tone(&[1500.0], 0.03, Waveform::Square).await,
+            cooldowns: [0.0; 5],
+        }
+    }
+
+    /// Play sounds for this frame's events and UI clicks.
+    pub fn update(&mut self, settings: &AudioSettings, events: SoundEvents, ui_clicked: bool, dt: f32) {
+        for c in self.cooldowns.iter_mut() {
+            *c = (*c - dt).max(0.0);
+        }
+        if !settings.enabled || settings.master_volume <= 0.0 {
+            return;
+        }
+
+        // Louder when many events happen at once, but never more than double
+        let volume_for = |count: u32| settings.master_volume * (1.0 + (count as f32).ln() * 0.25).min(2.0);
+
+        if settings.births && events.births > 0 {
+            self.play(0, volume_for(events.births) * 0.5);
+        }
+        if settings.deaths && events.deaths > 0 {
+            self.play(1, volume_for(events.deaths) * 0.6);
+        }
+        if settings.combat && events.combat_hits > 0 {
+            self.play(2, volume_for(events.combat_hits) * 0.4);
+        }
+        if settings.storms && events.storm_started {
+            self.play(3, settings.master_volume);
+        }
+        if settings.ui_clicks && ui_clicked {
+            self.play(4, settings.master_volume * 0.5);
+        }
+    }
+
+    fn play(&mut self, category: usize, volume: f32) {
+        if self.cooldowns[category] > 0.0 {
+            return;
+        }
+        let sound = match category {
+            0 => &self.birth,
+            1 => &self.death,
+            2 => &self.combat,
+            3 => &self.storm,
+            _ => &self.click,
+        };
+        if let Some(sound) = sound {
+            play_sound(sound, PlaySoundParams { looped: false, volume: volume.clamp(0.0, 1.0) });
+            self.cooldowns[category] = CATEGORY_COOLDOWN;
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Waveform {
+    Sine,
+    Square,
+    Noise,
+}
+
+/// Synthesize a short tone gliding through `freqs` and load it as a sound.
+async fn load_tone(freqs: &[f32], duration: f32, waveform: Waveform) -> Option<Sound> {
+    let bytes = synth_wav(freqs, duration, waveform);
+    match load_sound_from_bytes(&bytes).await {
+        Ok(sound) => Some(sound),
+        Err(e) => {
+            eprintln!("[GENESIS] Failed to load sound: {e}");
+            None
+        }
+    }
+}
+
+/// Render a mono 16-bit PCM WAV with a linear frequency glide and a decaying envelope.
+fn synth_wav(freqs: &[f32], duration: f32, waveform: Waveform) -> Vec<u8> {
+    let num_samples = (duration * SAMPLE_RATE as f32) as usize;
+    let mut samples = Vec::with_capacity(num_samples);
+    let mut phase = 0.0f32;
+    let mut noise_state = 0x1234_5678u32;
+    let mut noise_hold = 0.0f32;
+
+    for i in 0..num_samples {
+        let t = i as f32 / num_samples as f32;
+        let freq = match freqs.len() {
+            0 => 440.0,
+            1 => freqs[0],
+            n => {
+                let pos = t * (n - 1) as f32;
+                let k = (pos as usize).min(n - 2);
+                let frac = pos - k as f32;
+                freqs[k] + (freqs[k + 1] - freqs[k]) * frac
+            }
+        };
+        let prev_phase = phase;
+        phase = (phase + freq / SAMPLE_RATE as f32).fract();
+
+        let value = match waveform {
+            Waveform::Sine => (phase * std::f32::consts::TAU).sin(),
+            Waveform::Square => if phase < 0.5 { 0.6 } else { -0.6 },
+            Waveform::Noise => {
+                // Sample-and-hold noise, resampled once per cycle, gives pitched rumble
+                if phase < prev_phase {
+                    noise_state ^= noise_state << 13;
+                    noise_state ^= noise_state >> 17;
+                    noise_state ^= noise_state << 5;
+                    noise_hold = (noise_state as f32 / u32::MAX as f32) * 2.0 - 1.0;
+                }
+                noise_hold
+            }
+        };
+
+        // Short attack, exponential-ish decay
+        let attack = (t * 50.0).min(1.0);
+        let envelope = attack * (1.0 - t).powi(2);
+        samples.push((value * envelope * 0.8 * i16::MAX as f32) as i16);
+    }
+
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        wav.extend_from_slice(&s.to_le_bytes());
+    }
+    wav
+}
@@ -1,8 +1,90 @@
 use crate::config;
 use crate::genome::{Genome, N};
 
+/// Short display names for the sensor and motor neurons, in layout order.
+const SENSOR_LABELS: [&str; config::BRAIN_SENSOR_NEURONS] =
+    ["L.Prox", "R.Prox", "Food", "Entity", "Energy", "Env", "Shelter", "Snd.Dir", "Snd.Lvl", "Fear", "Light", "Flock", "Flk.Dir"];
+const MOTOR_LABELS: [&str; config::BRAIN_MOTOR_NEURONS] =
+    ["Fwd", "Turn", "Attack", "Signal", "Build", "Call", "Trail", "Cohere", "Align"];
+
+/// Short display name of neuron `i`, e.g. "Food", "Inter.3" or "Fwd".
+pub fn neuron_label(i: usize) -> String {
+    let motor_start = config::BRAIN_SENSOR_NEURONS + config::BRAIN_INTERNEURONS;
+    if i < config::BRAIN_SENSOR_NEURONS {
+        SENSOR_LABELS[i].to_string()
+    } else if i < motor_start {
+        format!("Inter.{}", i - config::BRAIN_SENSOR_NEURONS)
+    } else {
+        MOTOR_LABELS[i - motor_start].to_string()
+    }
+}
+
+/// What each sensor neuron reads, in layout order.
+const SENSOR_DESCRIPTIONS: [&str; config::BRAIN_SENSOR_NEURONS] = [
+    "Left proximity: how close things are on the left rays",
+    "Right proximity: how close things are on the right rays",
+    "Food: nearest food seen by any ray",
+    "Entity: nearest entity seen by any ray",
+    "Energy: own energy level",
+    "Environment: terrain danger and darkness",
+    "Shelter: nearest shelter (1 = inside)",
+    "Sound direction: bearing of the loudest call",
+    "Sound level: loudness of the loudest call",
+    "Fear: recent combat damage and nearby attacks",
+    "Light: daylight (0 at midnight, 1 at noon)",
+    "Flock: how many neighbours signal a color like its own",
+    "Flock heading: bearing of the flock's mean heading",
+];
+
+/// What each motor neuron drives, in layout order.
+const MOTOR_DESCRIPTIONS: [&str; config::BRAIN_MOTOR_NEURONS] = [
+    "Forward: thrust",
+    "Turn: steering (0.5 = straight ahead)",
+    "Attack: strikes above the intent threshold",
+    "Signal: broadcast intensity and food sharing",
+    "Build: raise a shelter",
+    "Call: emit a sound pulse",
+    "Trail: lay pheromone",
+    "Cohesion: steer toward the flock's centre",
+    "Alignment: steer along the flock's heading",
+];
+
+/// Magnitude of the largest decoded weight.
+pub const WEIGHT_SCALE: f32 = 16.0;
+
+/// Independent partial sums in the weighted-input dot product: one 256-bit SIMD register of f32.
+const LANES: usize = 8;
+const _: () = assert!(N.is_multiple_of(LANES), "neuron count must be a multiple of LANES");
+
+/// Longer description of neuron `i` for tooltips.
+pub fn neuron_description(i: usize) -> String {
+    let motor_start = config::BRAIN_SENSOR_NEURONS + config::BRAIN_INTERNEURONS;
+    if i < config::BRAIN_SENSOR_NEURONS {
+        format!("Sensor {i}. {}", SENSOR_DESCRIPTIONS[i])
+    } else if i < motor_start {
+        format!("Interneuron {}: hidden recurrent unit", i - config::BRAIN_SENSOR_NEURONS)
+    } else {
+        format!("Motor {}. {}", i - motor_start, MOTOR_DESCRIPTIONS[i - motor_start])
+    }
+}
+
+/// Decoded motor neuron outputs for one entity.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MotorOutputs {
+    pub forward: f32, // [0, 1]
+    pub turn: f32,    // [-1, 1]
+    pub attack: f32,  // [0, 1]
+    pub signal: f32,  // [0, 1]
+    pub build: f32,   // [0, 1]
+    pub call: f32,    // [0, 1]
+    pub trail: f32,   // [0, 1], pheromone deposit
+    pub cohesion: f32,  // [0, 1], steering toward the flock centre
+    pub alignment: f32, // [0, 1], steering along the flock heading
+}
+
 /// CTRNN brain storage in Structure-of-Arrays layout for cache performance.
-/// All brains are stored contiguously, indexed by entity slot index.
+/// All brains are stored contiguously, indexed by entity slot index: every brain has N neurons,
+/// so each array is one allocation of fixed-size rows with no per-entity offsets or indirection.
 pub struct BrainStorage {
     pub capacity: usize,
     /// Neuron internal states (membrane potential). [slot][neuron]
@@ -15,6 +97,8 @@ pub struct BrainStorage {
     pub weights: Vec<[[f32; N]; N]>,
     /// Output activations: sigmoid(state + bias). [slot][neuron]
     pub outputs: Vec<[f32; N]>,
+    /// Hebbian learning rate per simulated second (used when PLASTICITY_ENABLED). [slot]
+    pub learning_rates: Vec<f32>,
     /// Whether this slot is active.
     pub active: Vec<bool>,
 }
@@ -28,6 +112,7 @@ impl BrainStorage {
             biases: vec![[0.0; N]; capacity],
             weights: vec![[[0.0; N]; N]; capacity],
             outputs: vec![[0.0; N]; capacity],
+            learning_rates: vec![0.0; capacity],
             active: vec![false; capacity],
         }
     }
@@ -48,9 +133,37 @@ impl BrainStorage {
             }
         }
         self.outputs[slot] = [0.0; N];
+        self.learning_rates[slot] = genome.learning_rate();
         self.active[slot] = true;
     }
 
+    /// Start a newborn's neurons from `fraction` of its parent's current states, when
+    /// BRAIN_STATE_INHERITANCE_ENABLED is set. Call after `init_from_genome`.
+    pub fn inherit_state(&mut self, child: usize, parent: usize) {
+        if !config::BRAIN_STATE_INHERITANCE_ENABLED || parent >= self.active.len() || !self.active[parent] {
+            return;
+        }
+        let fraction = config::BRAIN_STATE_INHERITANCE_FRACTION.clamp(0.0, 1.0);
+        // Sensor states are overwritten by the next inputs, so only the rest carry over
+        for i in config::BRAIN_SENSOR_NEURONS..N {
+            self.states[child][i] = self.states[parent][i] * fraction;
+        }
+    }
+
+    /// Copy the weights a brain has learned into `genome`, so offspring built from it inherit
+    /// them, when PLASTICITY_HERITABLE is set. Only plastic weights (into non-sensor neurons)
+    /// are written.
+    pub fn write_back_learned(&self, slot: usize, genome: &mut Genome) {
+        if !config::PLASTICITY_ENABLED || !config::PLASTICITY_HERITABLE || slot >= self.active.len() || !self.active[slot] {
+            return;
+        }
+        for i in config::BRAIN_SENSOR_NEURONS..N {
+            for j in 0..N {
+                genome.set_weight(i, j, self.weights[slot][i][j]);
+            }
+        }
+    }
+
     /// Deactivate a brain slot.
     pub fn deactivate(&mut self, slot: usize) {
         if slot < self.active.len() {
@@ -58,14 +171,17 @@ impl BrainStorage {
         }
     }
 
-    fn ensure_capacity(&mut self, needed: usize) {
+    /// Grow to at least `needed` slots, in whole ENTITY_CAPACITY_CHUNKs rather than doubling, so
+    /// a large population never copies twice its brains at once.
+    pub fn ensure_capacity(&mut self, needed: usize) {
         if needed > self.capacity {
-            let new_cap = needed.max(self.capacity * 2);
+            let new_cap = needed.next_multiple_of(config::ENTITY_CAPACITY_CHUNK);
             self.states.resize(new_cap, [0.0; N]);
             self.tau_inv.resize(new_cap, [1.0; N]);
             self.biases.resize(new_cap, [0.0; N]);
             self.weights.resize(new_cap, [[0.0; N]; N]);
             self.outputs.resize(new_cap, [0.0; N]);
+            self.learning_rates.resize(new_cap, 0.0);
             self.active.resize(new_cap, false);
             self.capacity = new_cap;
         }
@@ -90,7 +206,7 @@ impl BrainStorage {
             let states = &mut self.states[slot];
             let tau_inv = &self.tau_inv[slot];
             let biases = &self.biases[slot];
-            let weights = &self.weights[slot];
+            let weights = &mut self.weights[slot];
 
             // Clamp sensor neurons to input values
             if slot < sensor_inputs.len() {
@@ -108,10 +224,7 @@ impl BrainStorage {
             // Forward Euler update for non-sensor neurons
             // dy_i/dt = (-y_i + bias_i + sum_j(w_ij * activation_j)) * (1/tau_i)
             for i in sensor_n..N {
-                let mut input_sum = biases[i];
-                for j in 0..N {
-                    input_sum += weights[i][j] * activations[j];
-                }
+                let input_sum = biases[i] + dot(&weights[i], &activations);
                 let dydt = (-states[i] + input_sum) * tau_inv[i];
                 states[i] += dydt * dt;
 
@@ -123,21 +236,55 @@ impl BrainStorage {
             for i in 0..N {
                 self.outputs[slot][i] = sigmoid(states[i]);
             }
+
+            // Hebbian plasticity (Oja's rule) on weights into non-sensor neurons, on weights
+            // normalized to [-1, 1]: dw_ij = rate * y_i * (x_j - y_i * w_ij), where x is the
+            // presynaptic activation and y the new postsynaptic one. The decay term keeps
+            // weights bounded.
+            let rate = self.learning_rates[slot] * dt;
+            if config::PLASTICITY_ENABLED && rate > 0.0 {
+                let outputs = &self.outputs[slot];
+                for i in sensor_n..N {
+                    let y = outputs[i];
+                    for j in 0..N {
+                        let w = weights[i][j] / WEIGHT_SCALE;
+                        let dw = rate * y * (activations[j] - y * w);
+                        weights[i][j] = ((w + dw) * WEIGHT_SCALE).clamp(-WEIGHT_SCALE, WEIGHT_SCALE);
+                    }
+                }
+            }
         }
     }
 
-    /// Get motor outputs for a slot: (forward_drive, turn, attack_intent, signal_intensity).
-    /// All values in [0, 1]. Turn is remapped to [-1, 1].
-    pub fn motor_outputs(&self, slot: usize) -> (f32, f32, f32, f32) {
+    /// Get motor outputs for a slot. All values in [0, 1] except turn, remapped to [-1, 1].
+    pub fn motor_outputs(&self, slot: usize) -> MotorOutputs {
         let o = &self.outputs[slot];
         let motor_start = config::BRAIN_SENSOR_NEURONS + config::BRAIN_INTERNEURONS;
-        (
-            o[motor_start],             // forward drive [0,1]
-            o[motor_start + 1] * 2.0 - 1.0, // turn [-1,1]
-            o[motor_start + 2],         // attack intent [0,1]
-            o[motor_start + 3],         // signal intensity [0,1]
-        )
+        MotorOutputs {
+            forward: o[motor_start],
+            turn: o[motor_start + 1] * 2.0 - 1.0,
+            attack: o[motor_start + 2],
+            signal: o[motor_start + 3],
+            build: o[motor_start + 4],
+            call: o[motor_start + 5],
+            trail: o[motor_start + 6],
+            cohesion: o[motor_start + 7],
+            alignment: o[motor_start + 8],
+        }
+    }
+}
+
+/// Dot product over LANES interleaved partial sums. A single running sum is a serial
+/// dependency chain the compiler may not reorder; separate lanes let it vectorize the loop.
+#[inline]
+fn dot(a: &[f32; N], b: &[f32; N]) -> f32 {
+    let mut lanes = [0.0f32; LANES];
+    for (ca, cb) in a.chunks_exact(LANES).zip(b.chunks_exact(LANES)) {
+        for k in 0..LANES {
+            lanes[k] += ca[k] * cb[k];
+        }
     }
+    lanes.iter().sum()
 }
 
 #[inline]
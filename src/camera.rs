@@ -2,13 +2,90 @@ use macroquad::prelude::*;
 
 use crate::config;
 use crate::entity::{EntityArena, EntityId};
+use crate::keybindings::{Action, KeyBindings};
+use crate::world::World;
+
+/// Number of camera bookmark slots (keys 1..9 and 0).
+pub const NUM_BOOKMARKS: usize = 10;
+
+/// Digit keys in bookmark slot order: slot 0 is key 1, slot 9 is key 0.
+const BOOKMARK_KEYS: [KeyCode; NUM_BOOKMARKS] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Key0,
+];
+
+/// A stored camera position and zoom level.
+#[derive(Clone, Copy, Debug)]
+pub struct CameraBookmark {
+    pub target: Vec2,
+    pub zoom: f32,
+}
+
+pub type CameraBookmarks = [Option<CameraBookmark>; NUM_BOOKMARKS];
+
+/// How much of each creature is drawn. Chosen from the camera zoom, independently of the
+/// graphics quality preset, so a zoomed-out view of hundreds of creatures stays cheap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EntityDetail {
+    /// A 2-triangle dart in the entity's color.
+    Body,
+    /// Dart, body disc, eyes and energy bar.
+    Basic,
+    /// Basic plus fins, a dorsal stripe and pupils.
+    Full,
+}
+
+impl EntityDetail {
+    pub fn for_zoom(zoom: f32) -> Self {
+        if zoom >= config::LOD_FULL_ZOOM {
+            EntityDetail::Full
+        } else if zoom >= config::LOD_BASIC_ZOOM {
+            EntityDetail::Basic
+        } else {
+            EntityDetail::Body
+        }
+    }
+
+    /// The level for `zoom`, keeping the current one until the zoom is LOD_HYSTERESIS past the
+    /// threshold, so zooming back and forth around it does not flicker.
+    pub fn update(self, zoom: f32) -> Self {
+        let up = Self::for_zoom(zoom / (1.0 + config::LOD_HYSTERESIS));
+        let down = Self::for_zoom(zoom / (1.0 - config::LOD_HYSTERESIS));
+        self.clamp(up, down)
+    }
+}
+
+/// A group of entities the camera can keep in frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraGroup {
+    Species(u32),
+}
 
 pub struct CameraController {
     pub target: Vec2,
     pub zoom: f32,
     pub following: Option<EntityId>,
+    /// When set, the camera frames this whole group instead of following a single entity.
+    pub following_group: Option<CameraGroup>,
     pub smooth_target: Vec2,
     pub smooth_zoom: f32,
+    pub bookmarks: CameraBookmarks,
+    /// Whether the mouse wheel zooms; off while a tool uses the wheel itself.
+    pub scroll_zoom: bool,
+    /// Whether bookmark keys and keyboard panning apply; off while a text field has focus.
+    pub keyboard: bool,
+    /// When set, following an entity no longer moves the camera (the follow inset tracks it instead).
+    pub detached: bool,
+    /// Creature detail for the current zoom, with hysteresis.
+    pub detail: EntityDetail,
     is_dragging: bool,
     drag_start: Vec2,
     drag_cam_start: Vec2,
@@ -21,17 +98,23 @@ impl CameraController {
             target: initial_target,
             zoom: initial_zoom,
             following: None,
+            following_group: None,
             smooth_target: initial_target,
             smooth_zoom: initial_zoom,
+            bookmarks: [None; NUM_BOOKMARKS],
+            scroll_zoom: true,
+            keyboard: true,
+            detached: false,
+            detail: EntityDetail::for_zoom(initial_zoom),
             is_dragging: false,
             drag_start: Vec2::ZERO,
             drag_cam_start: Vec2::ZERO,
         }
     }
 
-    pub fn update(&mut self, arena: &EntityArena, dt: f32) {
-        // Follow selected entity
-        if let Some(id) = self.following {
+    pub fn update(&mut self, arena: &EntityArena, keys: &KeyBindings, dt: f32) {
+        // Follow selected entity (group framing takes precedence)
+        if let Some(id) = self.following.filter(|_| self.following_group.is_none() && !self.detached) {
             if let Some(entity) = arena.get(id) {
                 self.target = entity.pos;
             } else {
@@ -39,19 +122,31 @@ impl CameraController {
             }
         }
 
-        // WASD pan (only when not following)
-        if self.following.is_none() {
+        // Bookmarks: Ctrl+digit stores, digit jumps
+        let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+        for (slot, key) in BOOKMARK_KEYS.iter().enumerate() {
+            if self.keyboard && is_key_pressed(*key) {
+                if ctrl {
+                    self.store_bookmark(slot);
+                } else {
+                    self.jump_to_bookmark(slot);
+                }
+            }
+        }
+
+        // Keyboard pan (only when not following)
+        if self.keyboard && self.following.is_none() && self.following_group.is_none() {
             let pan_speed = config::CAMERA_PAN_SPEED / self.zoom;
-            if is_key_down(KeyCode::W) || is_key_down(KeyCode::Up) {
+            if keys.down(Action::PanUp) {
                 self.target.y -= pan_speed * dt;
             }
-            if is_key_down(KeyCode::S) || is_key_down(KeyCode::Down) {
+            if keys.down(Action::PanDown) {
                 self.target.y += pan_speed * dt;
             }
-            if is_key_down(KeyCode::A) || is_key_down(KeyCode::Left) {
+            if keys.down(Action::PanLeft) {
                 self.target.x -= pan_speed * dt;
             }
-            if is_key_down(KeyCode::D) || is_key_down(KeyCode::Right) {
+            if keys.down(Action::PanRight) {
                 self.target.x += pan_speed * dt;
             }
         }
@@ -62,6 +157,7 @@ impl CameraController {
             self.drag_start = Vec2::from(mouse_position());
             self.drag_cam_start = self.target;
             self.following = None;
+            self.following_group = None;
         }
         if is_mouse_button_released(MouseButton::Middle) {
             self.is_dragging = false;
@@ -74,7 +170,7 @@ impl CameraController {
 
         // Scroll zoom
         let (_, scroll_y) = mouse_wheel();
-        if scroll_y != 0.0 {
+        if scroll_y != 0.0 && self.scroll_zoom {
             let zoom_factor = 1.0 + scroll_y.signum() * config::CAMERA_ZOOM_SPEED;
             self.zoom = (self.zoom * zoom_factor).clamp(config::CAMERA_ZOOM_MIN, config::CAMERA_ZOOM_MAX);
         }
@@ -83,6 +179,48 @@ impl CameraController {
         let smooth = 1.0 - (-config::CAMERA_SMOOTH_SPEED * dt).exp();
         self.smooth_target = self.smooth_target.lerp(self.target, smooth);
         self.smooth_zoom += (self.zoom - self.smooth_zoom) * smooth;
+        self.detail = self.detail.update(self.smooth_zoom);
+    }
+
+    /// Aim at the bounding box of `positions` and pick the zoom that fits it on screen.
+    /// Positions are unwrapped relative to the first one so groups straddling the seam stay compact.
+    pub fn frame_positions(&mut self, positions: &[Vec2], world: &World) {
+        let Some(&anchor) = positions.first() else { return };
+        let mut min = Vec2::ZERO;
+        let mut max = Vec2::ZERO;
+        for &p in positions {
+            let rel = world.delta(anchor, p);
+            min = min.min(rel);
+            max = max.max(rel);
+        }
+        self.target = world.wrap(anchor + (min + max) * 0.5);
+
+        let size = (max - min) + Vec2::splat(config::CAMERA_GROUP_MARGIN * 2.0);
+        let fit = (screen_width() / size.x).min(screen_height() / size.y);
+        self.zoom = fit.clamp(config::CAMERA_ZOOM_MIN, config::CAMERA_ZOOM_MAX);
+    }
+
+    /// Store the current camera position and zoom in a bookmark slot.
+    pub fn store_bookmark(&mut self, slot: usize) {
+        if slot < NUM_BOOKMARKS {
+            self.bookmarks[slot] = Some(CameraBookmark {
+                target: self.target,
+                zoom: self.zoom,
+            });
+        }
+    }
+
+    /// Move the camera to a stored bookmark (stops following). Returns false if the slot is empty.
+    pub fn jump_to_bookmark(&mut self, slot: usize) -> bool {
+        match self.bookmarks.get(slot).copied().flatten() {
+            Some(bookmark) => {
+                self.following = None;
+                self.target = bookmark.target;
+                self.zoom = bookmark.zoom;
+                true
+            }
+            None => false,
+        }
     }
 
     pub fn to_macroquad_camera(&self) -> Camera2D {
@@ -107,6 +245,14 @@ impl CameraController {
         )
     }
 
+    /// Convert world position to screen position.
+    pub fn world_to_screen(&self, world_pos: Vec2) -> Vec2 {
+        let cam = self.to_macroquad_camera();
+        let ndc_x = (world_pos.x - self.smooth_target.x) * cam.zoom.x;
+        let ndc_y = (world_pos.y - self.smooth_target.y) * cam.zoom.y;
+        vec2((ndc_x + 1.0) * 0.5 * screen_width(), (1.0 - ndc_y) * 0.5 * screen_height())
+    }
+
     /// Find the entity closest to a world position within a given radius.
     pub fn pick_entity(
         &self,
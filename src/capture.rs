@@ -0,0 +1,171 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write;
+#[cfg(not(target_arch = "wasm32"))]
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use image::ImageEncoder;
+use macroquad::prelude::{get_screen_data, get_time, Image};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::config;
+use crate::storage;
+
+/// Directory receiving recordings started with the hotkey when `--record-out` is not given.
+pub const RECORDING_DIR: &str = "recordings";
+
+/// How frames are recorded (`--record-out dir`, `--record-every N`, `--record-ffmpeg`).
+#[derive(Clone, Debug)]
+pub struct RecordSettings {
+    /// Output directory; giving it on the command line also starts recording at launch.
+    pub out: Option<String>,
+    /// Capture every Nth rendered frame.
+    pub every: u32,
+    /// Pipe raw frames to an external `ffmpeg` instead of writing numbered PNGs.
+    pub ffmpeg: bool,
+}
+
+impl Default for RecordSettings {
+    fn default() -> Self {
+        Self { out: None, every: 1, ffmpeg: false }
+    }
+}
+
+/// Where captured frames go.
+enum Sink {
+    Png,
+    /// Started on the first frame, once the frame size is known.
+    #[cfg(not(target_arch = "wasm32"))]
+    Ffmpeg(Option<FfmpegPipe>),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct FfmpegPipe {
+    child: Child,
+    stdin: ChildStdin,
+    width: u16,
+    height: u16,
+}
+
+/// A recording in progress. Each one gets its own numbered take directory under the output
+/// directory, so repeated hotkey presses never overwrite earlier frames.
+pub struct Recording {
+    pub dir: String,
+    every: u32,
+    sink: Sink,
+    /// Frames rendered since the recording started, captured or not.
+    rendered: u64,
+    /// Frames written.
+    pub frames: u64,
+    started_at: f64,
+}
+
+impl Recording {
+    pub fn start(settings: &RecordSettings) -> Result<Self, String> {
+        let base = settings.out.as_deref().unwrap_or(RECORDING_DIR);
+        let dir = (1..)
+            .map(|take| format!("{base}/take_{take:03}"))
+            .find(|dir| !storage::exists(dir))
+            .expect("unbounded take numbers");
+        storage::create_dir_all(&dir).map_err(|e| format!("Cannot create {dir}/: {e}"))?;
+        Ok(Self {
+            dir,
+            every: settings.every.max(1),
+            #[cfg(not(target_arch = "wasm32"))]
+            sink: if settings.ffmpeg { Sink::Ffmpeg(None) } else { Sink::Png },
+            #[cfg(target_arch = "wasm32")]
+            sink: Sink::Png,
+            rendered: 0,
+            frames: 0,
+            started_at: get_time(),
+        })
+    }
+
+    /// Seconds of real time since the recording started.
+    pub fn elapsed(&self) -> f64 {
+        get_time() - self.started_at
+    }
+
+    /// Capture the current screen if this is one of every Nth rendered frames. An error ends
+    /// the recording (ffmpeg missing or exited, window resized mid-video, disk full).
+    pub fn capture(&mut self) -> Result<(), String> {
+        self.rendered += 1;
+        if !(self.rendered - 1).is_multiple_of(self.every as u64) {
+            return Ok(());
+        }
+        let image = get_screen_data();
+        match &mut self.sink {
+            Sink::Png => {
+                let path = format!("{}/frame_{:06}.png", self.dir, self.frames + 1);
+                write_png(&path, &image)?;
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Sink::Ffmpeg(pipe) => {
+                let pipe = match pipe {
+                    Some(pipe) => pipe,
+                    None => pipe.insert(FfmpegPipe::spawn(&self.dir, image.width, image.height)?),
+                };
+                if (image.width, image.height) != (pipe.width, pipe.height) {
+                    return Err(format!(
+                        "Window resized to {}x{} while recording {}x{} video",
+                        image.width, image.height, pipe.width, pipe.height
+                    ));
+                }
+                pipe.stdin.write_all(&image.bytes).map_err(|e| format!("ffmpeg pipe closed: {e}"))?;
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Stop recording, waiting for ffmpeg to finish encoding. Returns what was written.
+    pub fn finish(self) -> Result<String, String> {
+        match self.sink {
+            Sink::Png => Ok(format!("{} frames in {}/", self.frames, self.dir)),
+            #[cfg(not(target_arch = "wasm32"))]
+            Sink::Ffmpeg(None) => Ok(format!("No frames recorded in {}/", self.dir)),
+            #[cfg(not(target_arch = "wasm32"))]
+            Sink::Ffmpeg(Some(pipe)) => {
+                let FfmpegPipe { mut child, stdin, .. } = pipe;
+                drop(stdin);
+                let status = child.wait().map_err(|e| format!("ffmpeg: {e}"))?;
+                if !status.success() {
+                    return Err(format!("ffmpeg exited with {status}"));
+                }
+                Ok(format!("{} frames → {}/{}", self.frames, self.dir, config::RECORD_VIDEO_FILE))
+            }
+        }
+    }
+}
+
+/// Encode screen data as a PNG and write it through `storage`. Unlike `Image::export_png`,
+/// which panics on any I/O error, failures are returned.
+pub fn write_png(path: &str, image: &Image) -> Result<(), String> {
+    // Screen data is bottom-up
+    let row = image.width as usize * 4;
+    let flipped: Vec<u8> = image.bytes.chunks_exact(row).rev().flatten().copied().collect();
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png)
+        .write_image(&flipped, image.width as u32, image.height as u32, image::ColorType::Rgba8)
+        .map_err(|e| format!("Cannot encode {path}: {e}"))?;
+    storage::write(path, png).map_err(|e| format!("Cannot write {path}: {e}"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FfmpegPipe {
+    /// Start ffmpeg reading raw RGBA frames on stdin. Screen data is bottom-up, so the video is
+    /// flipped, and padded to even dimensions for yuv420p.
+    fn spawn(dir: &str, width: u16, height: u16) -> Result<Self, String> {
+        let mut child = Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error", "-f", "rawvideo", "-pixel_format", "rgba"])
+            .args(["-video_size", &format!("{width}x{height}")])
+            .args(["-framerate", &config::RECORD_FFMPEG_FPS.to_string()])
+            .args(["-i", "-", "-vf", "vflip,pad=ceil(iw/2)*2:ceil(ih/2)*2"])
+            .args(["-c:v", "libx264", "-pix_fmt", "yuv420p"])
+            .arg(format!("{dir}/{}", config::RECORD_VIDEO_FILE))
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Cannot start ffmpeg: {e}"))?;
+        let stdin = child.stdin.take().ok_or("ffmpeg has no stdin")?;
+        Ok(Self { child, stdin, width, height })
+    }
+}
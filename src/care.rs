@@ -0,0 +1,84 @@
+use crate::config;
+use crate::energy_audit::{self, EnergyLedger};
+use crate::entity::{Entity, EntityArena};
+use crate::world::World;
+
+/// Hunger of a juvenile in [0, 1]: how far its energy is below a newborn's full allowance.
+/// Zero for adults and for original spawns, which have no parent to feed them.
+pub fn hunger(entity: &Entity) -> f32 {
+    if entity.parent_id.is_none() || entity.age >= config::CARE_DURATION {
+        return 0.0;
+    }
+    (1.0 - entity.energy / config::INITIAL_ENTITY_ENERGY).clamp(0.0, 1.0)
+}
+
+/// Parental care. Hungry juveniles beg with an involuntary call (heard like any other call),
+/// and a parent within CARE_RANGE feeds them in proportion to its care gene and their hunger,
+/// as long as it stays above CARE_PARENT_RESERVE.
+pub fn apply_parental_care(
+    arena: &mut EntityArena,
+    world: &World,
+    calls: &mut [f32],
+    ledger: &mut EnergyLedger,
+    dt: f32,
+) {
+    // Collect feedings first: (parent_idx, child_idx, amount)
+    let mut feedings: Vec<(usize, usize, f32)> = Vec::new();
+    for (idx, entity) in arena.iter_alive() {
+        let hunger = hunger(entity);
+        if hunger <= 0.0 {
+            continue;
+        }
+        if let Some(call) = calls.get_mut(idx) {
+            *call = call.max(hunger * config::CARE_HUNGER_CALL);
+        }
+        let Some(parent_id) = entity.parent_id else { continue };
+        let Some(parent) = arena.get(parent_id) else { continue };
+        if parent.care <= 0.0 || world.distance_sq(parent.pos, entity.pos) > config::CARE_RANGE * config::CARE_RANGE {
+            continue;
+        }
+        let spare = parent.energy - config::CARE_PARENT_RESERVE;
+        let amount = (config::CARE_FEED_RATE * parent.care * hunger * dt).min(spare);
+        if amount > 0.0 {
+            feedings.push((parent_id.index as usize, idx, amount));
+        }
+    }
+
+    // A parent feeding several juveniles may run into its reserve partway through
+    for (parent_idx, child_idx, amount) in feedings {
+        let Some(parent) = arena.get_mut_by_index(parent_idx) else { continue };
+        let amount = amount.min(parent.energy - config::CARE_PARENT_RESERVE);
+        if amount <= 0.0 {
+            continue;
+        }
+        parent.energy -= amount;
+        if let Some(child) = arena.get_mut_by_index(child_idx) {
+            ledger.capped += energy_audit::add_capped(&mut child.energy, amount) as f64;
+        }
+    }
+}
+
+/// Outcomes of bred juveniles: how many reached CARE_DURATION and how many died before it.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct JuvenileCounts {
+    pub matured: u64,
+    pub died: u64,
+}
+
+impl JuvenileCounts {
+    /// Count juveniles that came of age this tick or are about to be swept as dead.
+    pub fn observe(&mut self, arena: &EntityArena, dt: f32) {
+        for (_, entity) in arena.iter_alive() {
+            if entity.parent_id.is_none() || entity.age - dt >= config::CARE_DURATION {
+                continue;
+            }
+            if !entity.alive {
+                if entity.age < config::CARE_DURATION {
+                    self.died += 1;
+                }
+            } else if entity.age >= config::CARE_DURATION {
+                self.matured += 1;
+            }
+        }
+    }
+}
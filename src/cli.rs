@@ -0,0 +1,227 @@
+use crate::capture::RecordSettings;
+use crate::config::{SIM_HZ_MAX, SIM_HZ_MIN};
+use crate::environment::WorldPreset;
+use crate::qa::QaScenario;
+use crate::reporting::{BenchmarkConfig, BenchmarkScenario};
+use crate::run_target::RunTarget;
+use crate::snapshot::SnapshotPlan;
+
+/// Options parsed from the command line.
+#[derive(Default, Debug)]
+pub struct RunConfig {
+    /// Path to a rhai scenario script (`--scenario file.rhai`).
+    pub scenario: Option<String>,
+    /// Preset for the initial world (`--preset desert`, or `--world-preset desert`).
+    pub world_preset: Option<WorldPreset>,
+    /// Seed for the population and simulation RNG (`--seed 7`). Defaults to 42.
+    pub seed: Option<u64>,
+    /// Seed for the terrain noise (`--terrain-seed 7`). Defaults to the simulation seed, so
+    /// fixing it keeps the map constant while the population varies with `--seed`.
+    pub terrain_seed: Option<u32>,
+    /// Ticks per simulated second (`--sim-hz 120`), clamped to 30–240. Defaults to 60.
+    pub sim_hz: Option<f32>,
+    /// Start with the energy conservation audit enabled (`--energy-audit`).
+    pub energy_audit: bool,
+    /// Run a QA scenario headless and exit (`--qa save-load-round-trip`).
+    pub qa: Option<QaScenario>,
+    /// Time simulation ticks headless, write benchmark_report.json and exit (`--benchmark`).
+    /// `--benchmark-ticks`, `--percentiles`, `--histogram-buckets` and `--benchmark-scenario`
+    /// imply it.
+    pub benchmark: Option<BenchmarkConfig>,
+    /// Capture numbered screenshots and population samples, then exit (`--snapshot-ticks`,
+    /// `--snapshot-every` with `--snapshot-until`).
+    pub snapshot: Option<SnapshotPlan>,
+    /// Run a parameter-sweep experiment headless and exit (`--experiment spec.toml`).
+    pub experiment: Option<String>,
+    /// Stream per-tick aggregates, births, deaths and genome samples into an SQLite file
+    /// (`--db run.sqlite`).
+    pub db: Option<String>,
+    /// Stream tick stats and population summaries as JSON over WebSocket on this local port
+    /// (`--telemetry-port 9000`).
+    pub telemetry_port: Option<u16>,
+    /// Accept HTTP control requests (pause, speed, food, zones, storms, stats, save) on this
+    /// local port (`--control-port 9001`).
+    pub control_port: Option<u16>,
+    /// Fast-forward from the start until a target, then pause (`--run-until tick:36000`,
+    /// `generation:50` or `event:extinction`).
+    pub run_until: Option<RunTarget>,
+    /// Frame recording: `--record-out dir` starts recording at launch, `--record-every N` keeps
+    /// every Nth frame and `--record-ffmpeg` pipes frames to ffmpeg. The hotkey uses the same
+    /// settings.
+    pub record: RecordSettings,
+}
+
+impl RunConfig {
+    /// Parse options from an argument list (without the program name).
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = RunConfig::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--scenario" => {
+                    let path = args.next().ok_or("--scenario requires a file path")?;
+                    config.scenario = Some(path);
+                }
+                "--preset" | "--world-preset" => {
+                    let name = args.next().ok_or(format!("{arg} requires a preset name"))?;
+                    let preset = WorldPreset::from_id(&name)
+                        .ok_or_else(|| format!("Unknown world preset: {name}\n{}", usage()))?;
+                    config.world_preset = Some(preset);
+                }
+                "--seed" => {
+                    let value = args.next().ok_or("--seed requires a number")?;
+                    let seed = value.parse().map_err(|_| format!("Invalid seed: {value}"))?;
+                    config.seed = Some(seed);
+                }
+                "--terrain-seed" => {
+                    let value = args.next().ok_or("--terrain-seed requires a number")?;
+                    let seed = value.parse().map_err(|_| format!("Invalid terrain seed: {value}"))?;
+                    config.terrain_seed = Some(seed);
+                }
+                "--sim-hz" => {
+                    let value = args.next().ok_or("--sim-hz requires a rate")?;
+                    let hz: f32 = value.parse().map_err(|_| format!("Invalid sim rate: {value}"))?;
+                    if !(SIM_HZ_MIN..=SIM_HZ_MAX).contains(&hz) {
+                        return Err(format!("--sim-hz must be between {SIM_HZ_MIN} and {SIM_HZ_MAX}"));
+                    }
+                    config.sim_hz = Some(hz);
+                }
+                "--energy-audit" => config.energy_audit = true,
+                "--qa" => {
+                    let name = args.next().ok_or("--qa requires a scenario name")?;
+                    let qa = QaScenario::from_id(&name)
+                        .ok_or_else(|| format!("Unknown QA scenario: {name}\n{}", usage()))?;
+                    config.qa = Some(qa);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                "--benchmark" => {
+                    config.benchmark.get_or_insert_with(BenchmarkConfig::default);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                "--benchmark-ticks" => {
+                    let value = args.next().ok_or("--benchmark-ticks requires a number")?;
+                    let ticks = value.parse().map_err(|_| format!("Invalid tick count: {value}"))?;
+                    config.benchmark.get_or_insert_with(BenchmarkConfig::default).ticks = ticks;
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                "--percentiles" => {
+                    let value = args.next().ok_or("--percentiles requires a list such as 50,90,99,99.9")?;
+                    let percentiles = parse_list(&value)?;
+                    if percentiles.iter().any(|q| !(0.0..=100.0).contains(q)) {
+                        return Err(format!("Percentiles must be between 0 and 100: {value}"));
+                    }
+                    config.benchmark.get_or_insert_with(BenchmarkConfig::default).percentiles = percentiles;
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                "--histogram-buckets" => {
+                    let value = args.next().ok_or("--histogram-buckets requires a list of upper bounds in ms")?;
+                    let buckets = parse_list(&value)?;
+                    config.benchmark.get_or_insert_with(BenchmarkConfig::default).tick_buckets_ms = buckets;
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                "--benchmark-scenario" => {
+                    let name = args.next().ok_or("--benchmark-scenario requires a scenario name")?;
+                    let scenario = BenchmarkScenario::from_id(&name)
+                        .ok_or_else(|| format!("Unknown benchmark scenario: {name}\n{}", usage()))?;
+                    config.benchmark.get_or_insert_with(BenchmarkConfig::default).scenario = Some(scenario);
+                }
+                "--snapshot-ticks" => {
+                    let value = args.next().ok_or("--snapshot-ticks requires a list of ticks")?;
+                    let ticks = value
+                        .split(',')
+                        .map(|v| v.trim().parse().map_err(|_| format!("Invalid tick in list: {v}")))
+                        .collect::<Result<Vec<u64>, String>>()?;
+                    config.snapshot.get_or_insert_with(SnapshotPlan::default).ticks = ticks;
+                }
+                "--snapshot-every" => {
+                    let value = args.next().ok_or("--snapshot-every requires a tick interval")?;
+                    let every: u64 = value.parse().map_err(|_| format!("Invalid interval: {value}"))?;
+                    if every == 0 {
+                        return Err("--snapshot-every must be at least 1".to_string());
+                    }
+                    config.snapshot.get_or_insert_with(SnapshotPlan::default).every = Some(every);
+                }
+                "--snapshot-until" => {
+                    let value = args.next().ok_or("--snapshot-until requires a tick")?;
+                    let until = value.parse().map_err(|_| format!("Invalid tick: {value}"))?;
+                    config.snapshot.get_or_insert_with(SnapshotPlan::default).until = Some(until);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                "--experiment" => {
+                    let path = args.next().ok_or("--experiment requires a spec file path")?;
+                    config.experiment = Some(path);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                "--db" => {
+                    let path = args.next().ok_or("--db requires a database file path")?;
+                    config.db = Some(path);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                "--telemetry-port" => {
+                    let value = args.next().ok_or("--telemetry-port requires a port number")?;
+                    let port = value.parse().map_err(|_| format!("Invalid port: {value}"))?;
+                    config.telemetry_port = Some(port);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                "--control-port" => {
+                    let value = args.next().ok_or("--control-port requires a port number")?;
+                    let port = value.parse().map_err(|_| format!("Invalid port: {value}"))?;
+                    config.control_port = Some(port);
+                }
+                "--run-until" => {
+                    let value = args.next().ok_or("--run-until requires a target such as tick:36000")?;
+                    config.run_until = Some(RunTarget::parse(&value)?);
+                }
+                "--record-out" => {
+                    let dir = args.next().ok_or("--record-out requires a directory")?;
+                    config.record.out = Some(dir);
+                }
+                "--record-every" => {
+                    let value = args.next().ok_or("--record-every requires a frame interval")?;
+                    let every: u32 = value.parse().map_err(|_| format!("Invalid interval: {value}"))?;
+                    if every == 0 {
+                        return Err("--record-every must be at least 1".to_string());
+                    }
+                    config.record.every = every;
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                "--record-ffmpeg" => config.record.ffmpeg = true,
+                "--help" | "-h" => return Err(usage()),
+                other => return Err(format!("Unknown argument: {other}\n{}", usage())),
+            }
+        }
+        if let Some(ref plan) = config.snapshot {
+            if plan.every.is_some() != plan.until.is_some() {
+                return Err("--snapshot-every and --snapshot-until must be given together".to_string());
+            }
+            if plan.targets().is_empty() {
+                return Err("Snapshot run has no ticks to capture".to_string());
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+}
+
+/// Parse a comma-separated list of numbers.
+fn parse_list(value: &str) -> Result<Vec<f64>, String> {
+    value
+        .split(',')
+        .map(|v| v.trim().parse().map_err(|_| format!("Invalid number in list: {v}")))
+        .collect()
+}
+
+fn usage() -> String {
+    let presets: Vec<&str> = WorldPreset::ALL.iter().map(|p| p.id()).collect();
+    let qa: Vec<&str> = QaScenario::ALL.iter().map(|s| s.id()).collect();
+    let benchmarks: Vec<&str> = BenchmarkScenario::ALL.iter().map(|s| s.id()).collect();
+    format!(
+        "Usage: genesis [--scenario <file.rhai>] [--preset <name>] [--seed <n>] [--terrain-seed <n>] [--sim-hz <n>] [--energy-audit] [--qa <name>] [--benchmark] [--benchmark-ticks <n>] [--percentiles <list>] [--histogram-buckets <list>] [--benchmark-scenario <name>] [--snapshot-ticks <list>] [--snapshot-every <n> --snapshot-until <n>] [--experiment <spec.toml>] [--db <run.sqlite>] [--telemetry-port <port>] [--control-port <port>] [--run-until <tick:N|generation:N|event:name>] [--record-out <dir>] [--record-every <n>] [--record-ffmpeg]\nWorld presets: {}\nQA scenarios: {}\nBenchmark scenarios: {}",
+        presets.join(", "),
+        qa.join(", "),
+        benchmarks.join(", ")
+    )
+}
@@ -0,0 +1,104 @@
+// Entity color modes: the genome color, the species palette, or a gradient over one attribute.
+
+use macroquad::prelude::*;
+
+use crate::entity::{Entity, EntityEventKind};
+
+/// What entities are colored by.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorMode {
+    /// The color encoded in the genome.
+    #[default]
+    Genome,
+    /// Each species cluster's palette color.
+    Species,
+    Energy,
+    Age,
+    /// Generation depth relative to the deepest living lineage.
+    Generation,
+    /// Share of recent meals that were meat.
+    Diet,
+    /// Interneurons wired strongly into the motors.
+    BrainSize,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 7] = [
+        ColorMode::Genome,
+        ColorMode::Species,
+        ColorMode::Energy,
+        ColorMode::Age,
+        ColorMode::Generation,
+        ColorMode::Diet,
+        ColorMode::BrainSize,
+    ];
+
+    /// Identifier used in the preferences file.
+    pub fn id(&self) -> &'static str {
+        match self {
+            ColorMode::Genome => "genome",
+            ColorMode::Species => "species",
+            ColorMode::Energy => "energy",
+            ColorMode::Age => "age",
+            ColorMode::Generation => "generation",
+            ColorMode::Diet => "diet",
+            ColorMode::BrainSize => "brain_size",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<ColorMode> {
+        ColorMode::ALL.iter().copied().find(|m| m.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorMode::Genome => "Genome",
+            ColorMode::Species => "Species",
+            ColorMode::Energy => "Energy",
+            ColorMode::Age => "Age",
+            ColorMode::Generation => "Generation",
+            ColorMode::Diet => "Diet",
+            ColorMode::BrainSize => "Brain size",
+        }
+    }
+
+    /// The mode after this one, for the cycle key.
+    pub fn next(&self) -> ColorMode {
+        let i = ColorMode::ALL.iter().position(|m| m == self).unwrap_or(0);
+        ColorMode::ALL[(i + 1) % ColorMode::ALL.len()]
+    }
+}
+
+/// Color of entities with no recent meals in the diet mode.
+pub const NO_DIET_COLOR: Color = Color::new(0.55, 0.55, 0.6, 1.0);
+
+/// Gradient from blue (0) through green and yellow to red (1).
+pub fn ramp(t: f32) -> Color {
+    macroquad::color::hsl_to_rgb((1.0 - t.clamp(0.0, 1.0)) * 0.66, 0.8, 0.5)
+}
+
+/// Green for plant eaters, red for meat eaters.
+pub fn diet_color(meat_share: f32) -> Color {
+    let plants = Color::new(0.3, 0.85, 0.3, 1.0);
+    let meat = Color::new(0.9, 0.25, 0.2, 1.0);
+    let t = meat_share.clamp(0.0, 1.0);
+    Color::new(
+        plants.r + (meat.r - plants.r) * t,
+        plants.g + (meat.g - plants.g) * t,
+        plants.b + (meat.b - plants.b) * t,
+        1.0,
+    )
+}
+
+/// Share of the meals in the entity's event log that were meat, or None without any meals.
+pub fn meat_share(entity: &Entity) -> Option<f32> {
+    let (mut plants, mut meat) = (0u32, 0u32);
+    for event in &entity.events {
+        match event.kind {
+            EntityEventKind::AteFood => plants += 1,
+            EntityEventKind::AteMeat => meat += 1,
+            _ => {}
+        }
+    }
+    (plants + meat > 0).then(|| meat as f32 / (plants + meat) as f32)
+}
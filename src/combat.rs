@@ -1,7 +1,10 @@
 use macroquad::prelude::*;
 
 use crate::config;
-use crate::entity::EntityArena;
+use crate::energy_audit::{self, EnergyLedger};
+use crate::entity::{EntityArena, EntityEventKind};
+use crate::environment::Wall;
+use crate::nutrients::NutrientGrid;
 use crate::spatial_hash::SpatialHash;
 use crate::world::World;
 
@@ -13,14 +16,46 @@ pub struct MeatItem {
     pub decay_timer: f32,
 }
 
+/// Spit in flight, fired by an entity with the ranged gene.
+#[derive(Clone, Debug)]
+pub struct Projectile {
+    pub pos: Vec2,
+    pub velocity: Vec2,
+    pub damage: f32,
+    /// Slot and UID of the shooter; the UID tells whether the slot still holds it.
+    pub owner_slot: usize,
+    pub owner_uid: u64,
+    /// Seconds left before it falls.
+    pub life: f32,
+}
+
 /// Combat event for rendering effects.
 #[derive(Clone, Debug)]
 pub struct CombatEvent {
     pub attacker_pos: Vec2,
     pub target_pos: Vec2,
+    /// usize::MAX for a spit whose shooter has since died.
+    pub attacker_slot: usize,
+    pub target_slot: usize,
+    /// Whether this hit killed the target.
+    pub killed: bool,
+    pub damage: f32,
+    /// Whether the hit came from a spit rather than melee.
+    pub ranged: bool,
+}
+
+/// A combat death, kept for the kill feed. Names are captured at the time of the kill.
+#[derive(Clone, Debug)]
+pub struct KillRecord {
+    pub tick: u64,
+    pub killer_uid: u64,
+    pub killer: String,
+    pub victim_uid: u64,
+    pub victim: String,
 }
 
-/// Resolve combat interactions. Entities with attack intent > 0.7 attack the nearest entity.
+/// Resolve combat interactions. Entities with attack intent above ATTACK_INTENT_THRESHOLD
+/// attack the nearest entity.
 /// Returns list of combat events for visual effects.
 pub fn resolve_combat(
     arena: &mut EntityArena,
@@ -28,106 +63,323 @@ pub fn resolve_combat(
     spatial: &SpatialHash,
     world: &World,
     meat: &mut Vec<MeatItem>,
+    ledger: &mut EnergyLedger,
+    dt: f32,
 ) -> Vec<CombatEvent> {
-    let attack_threshold = 0.7;
+    // Damage and cost are tuned per tick at the base rate
+    let step = dt * config::BASE_SIM_HZ;
+    let attack_threshold = config::ATTACK_INTENT_THRESHOLD;
     let mut events = Vec::new();
 
     // Collect damage to apply (to avoid borrow conflicts)
-    let mut damage_list: Vec<(usize, f32, Vec2, Vec2)> = Vec::new(); // (target_idx, damage, attacker_pos, target_pos)
+    let mut damage_list: Vec<(usize, usize, f32, Vec2, Vec2)> = Vec::new(); // (attacker_idx, target_idx, damage, attacker_pos, target_pos)
 
-    for (idx, entity) in arena.entities.iter().enumerate() {
-        if let Some(e) = entity {
-            if idx >= attack_intents.len() {
-                continue;
-            }
-            let intent = attack_intents[idx];
-            if intent < attack_threshold {
-                continue;
-            }
+    for (idx, e) in arena.iter_alive() {
+        if idx >= attack_intents.len() {
+            continue;
+        }
+        let intent = attack_intents[idx];
+        if intent < attack_threshold {
+            continue;
+        }
 
-            // Find nearest entity within attack range
-            let neighbors = spatial.query_radius_excluding(
-                e.pos,
-                config::ATTACK_RANGE + e.radius,
-                idx as u32,
-                world,
-                arena,
-            );
-
-            if let Some(&target_idx) = neighbors.first() {
-                if let Some(target) = arena.get_by_index(target_idx as usize) {
-                    let damage = config::ATTACK_DAMAGE * (e.radius / config::ENTITY_BASE_RADIUS);
-                    damage_list.push((target_idx as usize, damage, e.pos, target.pos));
-                }
+        // Find nearest entity within attack range
+        let neighbors = spatial.query_radius_excluding(
+            e.pos,
+            config::ATTACK_RANGE + e.radius,
+            idx as u32,
+            world,
+            arena,
+        );
+
+        if let Some(&target_idx) = neighbors.first() {
+            if let Some(target) = arena.get_by_index(target_idx as usize) {
+                let armor = 1.0 - target.armor * config::ARMOR_DAMAGE_REDUCTION;
+                let damage = config::ATTACK_DAMAGE
+                    * (e.radius / config::ENTITY_BASE_RADIUS)
+                    * e.attack_power
+                    * armor
+                    * step;
+                damage_list.push((idx, target_idx as usize, damage, e.pos, target.pos));
             }
         }
     }
 
     // Apply damage and deduct attacker energy cost
-    for (target_idx, damage, attacker_pos, target_pos) in &damage_list {
-        if let Some(target) = arena.get_mut_by_index(*target_idx) {
-            target.health -= damage;
-            target.energy -= damage * 0.5; // damage also drains energy
-
+    let mut hits: Vec<(usize, u64, bool)> = Vec::new(); // (attacker_idx, target_uid, killed)
+    for (attacker_idx, target_idx, damage, attacker_pos, target_pos) in &damage_list {
+        let attacker_uid = arena.get_by_index(*attacker_idx).map_or(0, |a| a.uid);
+        if let Some((event, target_uid)) = strike(arena, *target_idx, attacker_uid, *damage, meat, ledger) {
             events.push(CombatEvent {
                 attacker_pos: *attacker_pos,
                 target_pos: *target_pos,
+                attacker_slot: *attacker_idx,
+                ..event
             });
-
-            if target.health <= 0.0 || target.energy <= 0.0 {
-                target.alive = false;
-                meat.push(MeatItem {
-                    pos: target.pos,
-                    energy: config::MEAT_ENERGY,
-                    decay_timer: config::MEAT_DECAY_TIME,
-                });
-            }
+            hits.push((*attacker_idx, target_uid, event.killed));
         }
     }
 
+    // Log hits on attackers and credit kills
+    for (attacker_idx, target_uid, killed) in hits {
+        credit_attacker(arena, attacker_idx, target_uid, killed);
+    }
+
     // Deduct attack energy cost from attackers
-    for (idx, entity) in arena.entities.iter().enumerate() {
-        if let Some(_e) = entity {
-            if idx < attack_intents.len() && attack_intents[idx] >= attack_threshold {
-                // Mark for energy deduction
-            }
+    for (idx, e) in arena.iter_alive_mut() {
+        if idx < attack_intents.len() && attack_intents[idx] >= attack_threshold {
+            e.energy -= config::ATTACK_COST * step;
+            ledger.actions += (config::ATTACK_COST * step) as f64;
         }
     }
-    // Actually deduct (separate loop for borrow reasons)
-    for (idx, entity) in arena.entities.iter_mut().enumerate() {
-        if let Some(e) = entity {
-            if idx < attack_intents.len() && attack_intents[idx] >= attack_threshold {
-                e.energy -= config::ATTACK_COST;
-            }
+
+    events
+}
+
+/// Damage a target: half the damage also drains its energy, and a target brought to zero
+/// health or energy dies and drops meat. Returns the event (with the attacker fields left for
+/// the caller) and the target's UID, or None if the slot is empty.
+fn strike(
+    arena: &mut EntityArena,
+    target_idx: usize,
+    attacker_uid: u64,
+    damage: f32,
+    meat: &mut Vec<MeatItem>,
+    ledger: &mut EnergyLedger,
+) -> Option<(CombatEvent, u64)> {
+    let target = arena.get_mut_by_index(target_idx)?;
+    let was_alive = target.alive;
+    target.health -= damage;
+    target.energy -= damage * 0.5; // damage also drains energy
+    ledger.hazards += (damage * 0.5) as f64;
+    target.log_event(EntityEventKind::WasAttacked(attacker_uid));
+    let mut killed = false;
+
+    if target.protection_ticks == 0 && (target.health <= 0.0 || target.energy <= 0.0) {
+        target.alive = false;
+        killed = was_alive;
+        meat.push(MeatItem {
+            pos: target.pos,
+            energy: config::MEAT_ENERGY,
+            decay_timer: config::MEAT_DECAY_TIME,
+        });
+        ledger.meat_dropped += config::MEAT_ENERGY as f64;
+    }
+    let event = CombatEvent {
+        attacker_pos: target.pos,
+        target_pos: target.pos,
+        attacker_slot: usize::MAX,
+        target_slot: target_idx,
+        killed,
+        damage,
+        ranged: false,
+    };
+    Some((event, target.uid))
+}
+
+/// Log a hit on the attacker and credit it with a kill.
+fn credit_attacker(arena: &mut EntityArena, attacker_idx: usize, target_uid: u64, killed: bool) {
+    if let Some(attacker) = arena.get_mut_by_index(attacker_idx) {
+        attacker.log_event(EntityEventKind::Attacked(target_uid));
+        if killed {
+            attacker.kills += 1;
+            attacker.log_event(EntityEventKind::Killed(target_uid));
+        }
+    }
+}
+
+/// Entities with the ranged gene that want to attack but have nothing in melee range spit
+/// along their heading, paying SPIT_COST and waiting SPIT_COOLDOWN before the next one.
+pub fn fire_spit(
+    arena: &mut EntityArena,
+    attack_intents: &[f32],
+    spatial: &SpatialHash,
+    world: &World,
+    projectiles: &mut Vec<Projectile>,
+    ledger: &mut EnergyLedger,
+    dt: f32,
+) {
+    let mut shooters = Vec::new();
+    for (idx, e) in arena.iter_alive() {
+        if e.ranged < config::SPIT_MIN_GENE
+            || e.spit_cooldown > 0.0
+            || attack_intents.get(idx).is_none_or(|&i| i < config::ATTACK_INTENT_THRESHOLD)
+        {
+            continue;
+        }
+        let in_reach = spatial.query_radius_excluding(e.pos, config::ATTACK_RANGE + e.radius, idx as u32, world, arena);
+        if in_reach.is_empty() {
+            shooters.push(idx);
+        }
+    }
+
+    for (_, entity) in arena.iter_alive_mut() {
+        entity.spit_cooldown = (entity.spit_cooldown - dt).max(0.0);
+    }
+    for idx in shooters {
+        if let Some(e) = arena.get_mut_by_index(idx) {
+            let dir = Vec2::from_angle(e.heading);
+            projectiles.push(Projectile {
+                pos: world.wrap(e.pos + dir * (e.radius + config::SPIT_RADIUS)),
+                velocity: dir * config::SPIT_SPEED,
+                damage: config::SPIT_DAMAGE * e.ranged,
+                owner_slot: idx,
+                owner_uid: e.uid,
+                life: config::SPIT_RANGE / config::SPIT_SPEED,
+            });
+            e.energy -= config::SPIT_COST;
+            ledger.actions += config::SPIT_COST as f64;
+            e.spit_cooldown = config::SPIT_COOLDOWN;
+        }
+    }
+}
+
+/// Move spit, dropping it when it hits a wall or runs out of range. Returns the spit that hit
+/// an entity other than its shooter, as (projectile index, target slot), for `apply_spit_hits`.
+pub fn move_projectiles(
+    projectiles: &mut [Projectile],
+    arena: &EntityArena,
+    spatial: &SpatialHash,
+    walls: &[Wall],
+    world: &World,
+    dt: f32,
+) -> Vec<(usize, usize)> {
+    let mut hits = Vec::new();
+    for (i, p) in projectiles.iter_mut().enumerate() {
+        let travel = p.velocity * dt;
+        let dist = travel.length();
+        let dir = travel / dist.max(1e-6);
+        p.life -= dt;
+        if p.life <= 0.0 || walls.iter().any(|w| w.ray_intersection(p.pos, dir, dist).is_some()) {
+            p.life = 0.0;
+            continue;
+        }
+        p.pos = world.wrap(p.pos + travel);
+        let reach = config::SPIT_RADIUS + config::ENTITY_BASE_RADIUS * 1.6;
+        let target = spatial
+            .query_radius_excluding(p.pos, reach, p.owner_slot as u32, world, arena)
+            .into_iter()
+            .find(|&idx| {
+                arena.get_by_index(idx as usize).is_some_and(|e| {
+                    e.alive && world.distance(p.pos, e.pos) <= config::SPIT_RADIUS + e.radius
+                })
+            });
+        if let Some(target) = target {
+            hits.push((i, target as usize));
+            p.life = 0.0;
         }
     }
+    hits
+}
 
+/// Apply spit damage (reduced by armor) to the entities it hit, then drop spent spit.
+pub fn apply_spit_hits(
+    arena: &mut EntityArena,
+    projectiles: &mut Vec<Projectile>,
+    hits: &[(usize, usize)],
+    meat: &mut Vec<MeatItem>,
+    ledger: &mut EnergyLedger,
+) -> Vec<CombatEvent> {
+    let mut events = Vec::new();
+    for &(i, target_idx) in hits {
+        let p = &projectiles[i];
+        let owner = arena.get_by_index(p.owner_slot).filter(|o| o.uid == p.owner_uid).map(|o| (o.uid, o.pos));
+        let armor = arena.get_by_index(target_idx).map_or(1.0, |t| 1.0 - t.armor * config::ARMOR_DAMAGE_REDUCTION);
+        let attacker_uid = owner.map_or(p.owner_uid, |(uid, _)| uid);
+        if let Some((event, target_uid)) = strike(arena, target_idx, attacker_uid, p.damage * armor, meat, ledger) {
+            if owner.is_some() {
+                credit_attacker(arena, p.owner_slot, target_uid, event.killed);
+            }
+            events.push(CombatEvent {
+                attacker_pos: owner.map_or(p.pos, |(_, pos)| pos),
+                attacker_slot: if owner.is_some() { p.owner_slot } else { usize::MAX },
+                ranged: true,
+                ..event
+            });
+        }
+    }
+    projectiles.retain(|p| p.life > 0.0);
     events
 }
 
+/// Small glob for each spit in flight, with a short streak behind it.
+pub fn draw_projectiles(projectiles: &[Projectile]) {
+    for p in projectiles {
+        let tail = p.pos - p.velocity * 0.03;
+        draw_line(tail.x, tail.y, p.pos.x, p.pos.y, 2.0, Color::new(0.7, 0.95, 0.3, 0.4));
+        draw_circle(p.pos.x, p.pos.y, config::SPIT_RADIUS, Color::new(0.75, 1.0, 0.35, 0.9));
+    }
+}
+
 /// Let entities eat nearby meat items.
-pub fn consume_meat(arena: &mut EntityArena, meat: &mut Vec<MeatItem>, world: &World) {
+pub fn consume_meat(arena: &mut EntityArena, meat: &mut Vec<MeatItem>, world: &World, ledger: &mut EnergyLedger) {
     let pickup_radius = config::ENTITY_BASE_RADIUS * 2.5;
     let pickup_sq = pickup_radius * pickup_radius;
 
     meat.retain(|item| {
-        for slot in arena.entities.iter_mut() {
-            if let Some(e) = slot {
-                let dist_sq = world.distance_sq(e.pos, item.pos);
-                if dist_sq < pickup_sq {
-                    e.energy = (e.energy + item.energy).min(config::MAX_ENTITY_ENERGY);
-                    return false;
-                }
+        for (_, e) in arena.iter_alive_mut() {
+            let dist_sq = world.distance_sq(e.pos, item.pos);
+            if dist_sq < pickup_sq {
+                ledger.capped += energy_audit::add_capped(&mut e.energy, item.energy) as f64;
+                e.log_event(EntityEventKind::AteMeat);
+                return false;
             }
         }
         true
     });
 }
 
-/// Decay meat timers and remove expired meat.
-pub fn decay_meat(meat: &mut Vec<MeatItem>, dt: f32) {
+/// Decay meat timers and remove expired meat, returning its energy to the soil as nutrients.
+pub fn decay_meat(meat: &mut Vec<MeatItem>, nutrients: &mut NutrientGrid, ledger: &mut EnergyLedger, dt: f32) {
     for item in meat.iter_mut() {
         item.decay_timer -= dt;
     }
-    meat.retain(|item| item.decay_timer > 0.0);
+    meat.retain(|item| {
+        if item.decay_timer > 0.0 {
+            return true;
+        }
+        ledger.meat_decayed += item.energy as f64;
+        nutrients.deposit(item.pos, item.energy);
+        false
+    });
+}
+
+/// Update each slot's fear level in [0, 1] from this tick's combat: being hit raises it with
+/// the damage taken, and attacks on others within FEAR_RANGE raise it by proximity. Fear fades
+/// at FEAR_DECAY_RATE, so it reflects recent danger even when the attacker is out of sight.
+pub fn update_fear(
+    fear: &mut Vec<f32>,
+    events: &[CombatEvent],
+    arena: &EntityArena,
+    spatial: &SpatialHash,
+    world: &World,
+    dt: f32,
+) {
+    if fear.len() < arena.entities.len() {
+        fear.resize(arena.entities.len(), 0.0);
+    }
+    // Empty slots between the living are cleared as the walk passes them
+    let mut next = 0;
+    for (idx, _) in arena.iter_alive() {
+        fear[next..idx].fill(0.0);
+        next = idx + 1;
+        fear[idx] = (fear[idx] - config::FEAR_DECAY_RATE * dt).max(0.0);
+    }
+    fear[next..].fill(0.0);
+
+    for event in events {
+        if let Some(level) = fear.get_mut(event.target_slot) {
+            *level = level.max((event.damage / config::FEAR_DAMAGE_SCALE).min(1.0));
+        }
+        for idx in spatial.query_radius(event.target_pos, config::FEAR_RANGE, world, arena) {
+            let idx = idx as usize;
+            if idx == event.attacker_slot || idx == event.target_slot {
+                continue;
+            }
+            let Some(witness) = arena.get_by_index(idx) else { continue };
+            let dist = world.distance_sq(witness.pos, event.target_pos).sqrt();
+            let level = config::FEAR_WITNESS * (1.0 - dist / config::FEAR_RANGE);
+            fear[idx] = fear[idx].max(level);
+        }
+    }
 }
@@ -7,14 +7,32 @@ pub const WORLD_TOROIDAL: bool = true;
 
 // Entities
 pub const INITIAL_ENTITY_COUNT: usize = 50;
-pub const MAX_ENTITY_COUNT: usize = 300;
+pub const MAX_ENTITY_COUNT: usize = 2048; // safety ceiling on the population; slots grow toward it on demand
+pub const INITIAL_ENTITY_CAPACITY: usize = 300; // entity slots (with brains, genomes and signals) allocated at startup
+pub const ENTITY_CAPACITY_CHUNK: usize = 128; // slots added per growth step
+pub const ENTITY_CAPACITY_HEADROOM: usize = 32; // grow once fewer slots than this are free
+pub const LINEAGE_RECORD_LIMIT: usize = 50_000; // birth/death records kept for tracing families, oldest dropped first
 pub const ENTITY_BASE_RADIUS: f32 = 8.0;
 pub const ENTITY_MAX_SPEED: f32 = 120.0;
 pub const ENTITY_TURN_RATE: f32 = 4.0;
 pub const ENTITY_FRICTION: f32 = 3.0;
+pub const ENTITY_EVENT_LOG_SIZE: usize = 32; // recent events kept per entity for the inspector
+
+// Swimming (aquatic gene)
+pub const WATER_BASE_SPEED: f32 = 0.3; // water speed multiplier for a pure land specialist
+pub const AQUATIC_LAND_SPEED_PENALTY: f32 = 0.4; // land speed lost by a full swimmer
+pub const WATER_DRAG: f32 = 0.1; // velocity loss per base-rate tick in water for a land specialist
+pub const WATER_ENERGY_DRAIN: f32 = 1.0; // energy/sec lost in water for a land specialist
+pub const AQUATIC_DRAIN_REDUCTION: f32 = 0.9; // fraction of the water drain a full swimmer avoids
+pub const DROWNING_AQUATIC_THRESHOLD: f32 = 0.3; // entities below this aquatic value drown in water
+pub const DROWNING_DAMAGE: f32 = 6.0; // health/sec lost by a drowning pure land specialist
+pub const ALGAE_MIN_AQUATIC: f32 = 0.5; // aquatic value needed to eat algae (food lying in water)
 
 // Simulation
 pub const FIXED_DT: f32 = 1.0 / 60.0;
+pub const BASE_SIM_HZ: f32 = 60.0; // rate the per-tick constants were tuned at
+pub const SIM_HZ_MIN: f32 = 30.0;
+pub const SIM_HZ_MAX: f32 = 240.0;
 
 // Spatial hash
 pub const SPATIAL_CELL_SIZE: f32 = 64.0;
@@ -30,17 +48,61 @@ pub const MOVE_METABOLIC_COST: f32 = 1.5;
 pub const REPRODUCTION_THRESHOLD: f32 = 150.0;
 pub const REPRODUCTION_COST: f32 = 80.0;
 pub const OFFSPRING_ENERGY_FRACTION: f32 = 0.3;
+pub const CARE_DURATION: f32 = 30.0; // seconds after birth during which parents feed offspring
+pub const CARE_RANGE: f32 = 60.0; // parent-offspring distance for feeding
+pub const CARE_FEED_RATE: f32 = 20.0; // energy/sec a full-care parent gives a starving juvenile
+pub const CARE_PARENT_RESERVE: f32 = 60.0; // parents stop feeding below this energy
+pub const CARE_HUNGER_CALL: f32 = 0.8; // involuntary call loudness of a starving juvenile
+pub const SHARE_NEPOTISM_BIAS: f32 = 4.0; // at full nepotism, sharing treats a neighbor as (1 + bias * genome distance / species threshold) times farther
+pub const SHARING_WINDOW: usize = 360; // stat samples (10 ticks each) in the kin share of sharing
+pub const SHARE_KIN_DISTANCE: f32 = 0.5; // genome distance (fraction of the species threshold) under which a recipient counts as kin
+pub const JUVENILE_SIZE_FRACTION: f32 = 0.5; // newborn radius relative to the genetic adult radius
+pub const GROWTH_RATE: f32 = 1.0 / 40.0; // growth/sec of a well-fed juvenile (0 = newborn, 1 = adult)
+pub const GROWTH_ENERGY_COST: f32 = 25.0; // energy to grow from newborn to adult, per unit of body size
+pub const GROWTH_MIN_ENERGY: f32 = 20.0; // juveniles only grow while their energy stays above this
+pub const JUVENILE_SURVIVAL_WINDOW: usize = 360; // stat samples (10 ticks each) in the survival rate
 pub const DEATH_AGE: f32 = 600.0;
 
+// Food types (grass, berries, fruit)
+pub const GRASS_ENERGY_MULT: f32 = 0.7; // of FOOD_ENERGY; grass never spoils
+pub const BERRY_ENERGY_MULT: f32 = 1.0; // hand-dropped food is berries
+pub const FRUIT_ENERGY_MULT: f32 = 1.6;
+pub const BERRY_SPOIL_TIME: f32 = 90.0; // seconds an uneaten berry lasts
+pub const FRUIT_SPOIL_TIME: f32 = 45.0;
+pub const TASTE_DIGESTION_PENALTY: f32 = 0.4; // energy lost per unit of distance between taste gene and food kind
+
+// Health: injuries heal while energy allows; badly injured entities are slowed
+pub const HEALTH_REGEN_MIN_ENERGY: f32 = 100.0; // energy needed before injuries heal
+pub const HEALTH_REGEN_RATE: f32 = 1.5; // health per second
+pub const HEALTH_REGEN_ENERGY_COST: f32 = 0.5; // energy per health point restored
+pub const INJURY_THRESHOLD: f32 = 0.5; // health fraction below which an entity is slowed
+pub const INJURY_SPEED_PENALTY: f32 = 0.5; // max speed lost at zero health
+
+// Aging
+pub const SENESCENCE_MIN_VIGOR: f32 = 0.4; // speed/sensor/health multiplier reached at DEATH_AGE
+pub const PRIME_AGE_METABOLIC_COST: f32 = 0.3; // extra metabolism for the latest possible prime
+
+// Circadian rhythm: specialists sense further and burn more in their active phase, less in the other
+pub const CIRCADIAN_SENSOR_SWING: f32 = 0.3; // sensor range gained (lost) by a full specialist at its best (worst) hour
+pub const CIRCADIAN_METABOLIC_SWING: f32 = 0.25; // metabolism added (saved) likewise
+
 // Mutation (Phase 3+)
 pub const MUTATION_RATE: f32 = 0.05;
 pub const MUTATION_SIGMA: f32 = 0.1;
 
 // Brain (Phase 2+)
-pub const BRAIN_NEURONS: usize = 12;
-pub const BRAIN_SENSOR_NEURONS: usize = 6;
+pub const BRAIN_SENSOR_NEURONS: usize = 13;
 pub const BRAIN_INTERNEURONS: usize = 2;
-pub const BRAIN_MOTOR_NEURONS: usize = 4;
+pub const BRAIN_MOTOR_NEURONS: usize = 9;
+pub const BRAIN_NEURONS: usize = BRAIN_SENSOR_NEURONS + BRAIN_INTERNEURONS + BRAIN_MOTOR_NEURONS;
+pub const BRAIN_HISTORY_LENGTH: usize = 600; // ticks of followed-entity brain activity kept for the sparklines
+// Inherited brain state: newborns start from a scaled copy of the parent's neuron states
+pub const BRAIN_STATE_INHERITANCE_ENABLED: bool = false;
+pub const BRAIN_STATE_INHERITANCE_FRACTION: f32 = 0.5; // share of each parent neuron state copied (0 = fresh brain)
+// Hebbian plasticity: Oja's rule on incoming weights, at a learning rate encoded in the genome
+pub const PLASTICITY_ENABLED: bool = false;
+pub const PLASTICITY_MAX_RATE: f32 = 0.01; // per simulated second, at the top of the learning-rate gene
+pub const PLASTICITY_HERITABLE: bool = false; // offspring inherit learned weights instead of the parent's genome weights
 
 // Sensory (Phase 2+)
 pub const NUM_SENSOR_RAYS: usize = 8;
@@ -51,8 +113,80 @@ pub const SENSOR_ARC: f32 = std::f32::consts::PI * 1.5; // 270 degrees
 pub const ATTACK_RANGE: f32 = 15.0;
 pub const ATTACK_COST: f32 = 5.0;
 pub const ATTACK_DAMAGE: f32 = 25.0;
+pub const ATTACK_INTENT_THRESHOLD: f32 = 0.7; // attack motor output needed to strike
+pub const ATTACK_POWER_METABOLIC_COST: f32 = 0.3; // extra metabolism for the strongest attack gene
+pub const ARMOR_DAMAGE_REDUCTION: f32 = 0.6; // share of incoming damage a full-armor entity shrugs off
+pub const ARMOR_METABOLIC_COST: f32 = 0.15; // extra metabolism for full armor
+pub const ARMOR_SPEED_PENALTY: f32 = 0.3; // max speed lost by full armor
+// Ranged attack (spit): fired along the heading when attacking with nothing in melee range
+pub const SPIT_MIN_GENE: f32 = 0.3; // ranged gene needed to spit at all
+pub const SPIT_COST: f32 = 6.0; // energy per spit
+pub const SPIT_DAMAGE: f32 = 20.0; // per hit at the top of the ranged gene (melee deals ATTACK_DAMAGE every tick)
+pub const SPIT_SPEED: f32 = 250.0; // world units per second
+pub const SPIT_RANGE: f32 = 200.0; // travel distance before the spit falls
+pub const SPIT_COOLDOWN: f32 = 1.0; // seconds between spits
+pub const SPIT_RADIUS: f32 = 3.0;
+pub const KILL_FEED_SIZE: usize = 8; // most recent combat deaths kept for the kill feed
+pub const KILL_FEED_TICKS: u64 = 600; // how long a kill stays in the feed
+
+// Notifications
+pub const NOTIFICATION_LOG_SIZE: usize = 200; // notices kept in the event log
+pub const TOAST_DURATION: f64 = 5.0; // real seconds a toast stays on screen
+pub const TOAST_MAX: usize = 4; // toasts shown at once; older ones are dismissed early
+pub const GENERATION_MILESTONE_STEP: u32 = 10; // a notice every this many generations
+pub const EXTINCTION_NOTICE_MIN_AGE: f32 = 60.0; // species younger than this (sim seconds) die out quietly
+pub const EXTINCTION_NOTICE_MIN_PEAK: usize = 5; // ...as do species that never had this many members
 pub const MEAT_ENERGY: f32 = 60.0;
 pub const MEAT_DECAY_TIME: f32 = 30.0;
+pub const FEAR_DAMAGE_SCALE: f32 = 25.0; // combat damage in one hit that saturates the fear sensor
+pub const FEAR_RANGE: f32 = 150.0; // attacks on others within this distance are felt
+pub const FEAR_WITNESS: f32 = 0.6; // fear from an attack right next to the entity, falling to 0 at FEAR_RANGE
+pub const FEAR_DECAY_RATE: f32 = 0.5; // fear lost per second
+
+// Calls (sound pulses emitted via the call motor output)
+pub const CALL_THRESHOLD: f32 = 0.6;
+pub const CALL_RANGE: f32 = 200.0;
+pub const CALL_DECAY_RATE: f32 = 2.0; // loudness lost per second once the call stops
+pub const CALL_ENERGY_COST: f32 = 0.5; // energy per second at full loudness
+pub const CALL_WALL_ATTENUATION: f32 = 0.4; // loudness kept per wall crossed
+
+// Flocking (neighbours broadcasting a signal close to the entity's own color)
+pub const FLOCK_RANGE: f32 = 120.0;
+pub const FLOCK_MIN_SIGNAL: f32 = 0.3; // signal intensity a neighbour needs to count as a flockmate
+pub const FLOCK_COLOR_TOLERANCE: f32 = 0.25; // RGB distance between signal and own color
+pub const FLOCK_DENSITY_SCALE: f32 = 8.0; // flockmates that saturate the density sensor
+
+// Energy audit (debug)
+pub const ENERGY_AUDIT_TOLERANCE: f64 = 0.05; // absolute energy units per tick
+
+// Shelters (built via the build motor output)
+pub const BUILD_THRESHOLD: f32 = 0.7;
+pub const SHELTER_BUILD_COST: f32 = 30.0;
+pub const SHELTER_RADIUS: f32 = 25.0;
+pub const SHELTER_LIFETIME: f32 = 240.0;
+pub const MAX_SHELTERS: usize = 60;
+pub const SHELTER_STORM_PROTECTION: f32 = 0.9; // fraction of storm damage/push blocked
+pub const SHELTER_REST_SAVING: f32 = 0.5; // fraction of idle metabolism saved while sheltered
+
+// User-placed world features (editing tools)
+pub const WALL_THICKNESS: f32 = 6.0;
+pub const WALL_MIN_LENGTH: f32 = 10.0;
+pub const TOXIC_ZONE_RADIUS: f32 = 60.0;
+pub const TOXIC_ZONE_RADIUS_MIN: f32 = 20.0;
+pub const TOXIC_ZONE_RADIUS_MAX: f32 = 300.0;
+pub const TOXIC_ZONE_DAMAGE: f32 = 8.0; // health per second at the centre, falling to 0 at the edge
+pub const TOXIN_RESISTANCE_MAX: f32 = 0.8; // share of toxic zone and toxic ground damage blocked by full toxin resistance
+pub const TOXIN_RESISTANCE_METABOLIC_COST: f32 = 0.15; // extra metabolism for full toxin resistance
+pub const TOXIC_ZONE_INTENSITY_MIN: f32 = 0.25;
+pub const TOXIC_ZONE_INTENSITY_MAX: f32 = 4.0;
+pub const TOXIC_BRUSH_SCROLL_STEP: f32 = 1.1; // brush size/intensity factor per scroll notch
+pub const ERASE_RADIUS: f32 = 50.0; // default erase tool radius
+pub const ERASE_RADIUS_MIN: f32 = 10.0;
+pub const ERASE_RADIUS_MAX: f32 = 400.0;
+pub const PORTAL_RADIUS: f32 = 30.0; // entities entering an endpoint disc leave through the other one
+pub const PORTAL_MIN_DISTANCE: f32 = 100.0; // minimum distance between the two endpoints of a portal
+pub const FOOD_CLUSTER_SIZE: usize = 12;
+pub const FOOD_CLUSTER_RADIUS: f32 = 40.0;
 
 // Environment (Phase 5+)
 pub const DAY_LENGTH: f32 = 120.0;
@@ -61,7 +195,126 @@ pub const STORM_DURATION: f32 = 45.0;
 pub const STORM_INTERVAL_MIN: f32 = 120.0;
 pub const STORM_INTERVAL_MAX: f32 = 300.0;
 pub const STORM_RADIUS: f32 = 200.0;
-pub const STORM_DAMAGE: f32 = 2.0;
+pub const STORM_DAMAGE: f32 = 2.0; // energy/sec at the peak ring of a strength-1 storm
+pub const STORM_RESISTANCE_MAX: f32 = 0.8; // share of storm damage blocked by full storm resistance (the push still applies)
+pub const STORM_RESISTANCE_METABOLIC_COST: f32 = 0.15; // extra metabolism for full storm resistance
+pub const STORM_PUSH: f32 = 20.0; // outward push (units/sec^2) at the peak ring of a strength-1 storm
+pub const STORM_STRENGTH_MIN: f32 = 0.6; // per-storm strength multiplier range
+pub const STORM_STRENGTH_MAX: f32 = 1.6;
+pub const STORM_EYE_MIN: f32 = 0.1; // calm eye radius range, as a fraction of the storm radius
+pub const STORM_EYE_MAX: f32 = 0.3;
+pub const STORM_PEAK_WIDTH: f32 = 0.25; // the intensity peaks this far (fraction of radius) outside the eye
+pub const STORM_EDGE_INTENSITY: f32 = 0.3; // intensity at the outer edge, relative to the peak ring
+// Seasonal latitudinal gradient: food favors the north in summer and the south in winter
+pub const LATITUDE_GRADIENT_ENABLED: bool = true;
+pub const LATITUDE_GRADIENT_STRENGTH: f32 = 0.8; // 0 = uniform, 1 = opposite pole gets no food at solstice
+// Climate zones: how strongly the season's food multiplier applies, from the equator band
+// (mid-height) to both poles. 1 = the global season; below 1 milder, above 1 harsher.
+pub const CLIMATE_ZONES_ENABLED: bool = true;
+pub const CLIMATE_EQUATOR_SEASONALITY: f32 = 0.3;
+pub const CLIMATE_POLE_SEASONALITY: f32 = 1.6;
+pub const CLIMATE_EQUATOR_BAND: f32 = 0.2; // half-width of the mild band, as a fraction of the pole distance
+
+// Pheromone transport
+pub const PHEROMONE_DECAY_RATE: f32 = 0.5; // ~2 second half-life
+pub const PHEROMONE_DIFFUSION: f32 = 2.0; // neighbor exchange rate (1/s)
+pub const PHEROMONE_STRIPS: usize = 4; // diffusion/advection spread over this many ticks
+pub const PHEROMONE_DEPOSIT_THRESHOLD: f32 = 0.5; // trail motor output below which nothing is laid
+pub const PHEROMONE_DEPOSIT_RATE: f32 = 1.0; // pheromone/sec laid at full trail output
+pub const PHEROMONE_DEPOSIT_COST: f32 = 0.2; // energy/sec at full trail output
+pub const WIND_BASE_SPEED: f32 = 6.0; // prevailing wind (world units/s), veers over the year
+pub const STORM_WIND_FACTOR: f32 = 1.5; // storms add their drift velocity times this
+
+// Weather
+pub const WIND_GUST_STRENGTH: f32 = 10.0; // max drifting gust on top of the prevailing wind (world units/s)
+pub const WIND_GUST_INTERVAL: f32 = 20.0; // seconds between new gust targets
+pub const WIND_PUSH: f32 = 0.5; // fraction of wind speed added to entity velocity per second
+pub const RAIN_INTERVAL_MIN: f32 = 40.0;
+pub const RAIN_INTERVAL_MAX: f32 = 120.0;
+pub const RAIN_DURATION: f32 = 30.0;
+pub const RAIN_CLOUD_RADIUS: f32 = 180.0;
+pub const RAIN_CLOUD_DRIFT: f32 = 4.0; // clouds move at this multiple of the wind speed
+pub const MAX_RAIN_CLOUDS: usize = 3;
+pub const RAIN_MOISTURE_RATE: f32 = 0.2; // moisture gained per second under rain
+pub const MOISTURE_DRY_RATE: f32 = 0.01; // moisture lost per second (~100s to dry out)
+pub const RAIN_FOOD_BOOST: f32 = 1.5; // food spawn multiplier is 1 + boost * moisture
+
+// Nutrient cycle: meat that decays uneaten enriches the terrain cell beneath it
+pub const NUTRIENTS_PER_CARCASS: f32 = 0.5; // nutrients added by a full (MEAT_ENERGY) piece of meat, capped at 1 per cell
+pub const NUTRIENT_DECAY_RATE: f32 = 0.005; // nutrients lost per second (~200s for a saturated cell)
+pub const NUTRIENT_FOOD_BOOST: f32 = 2.0; // food spawn multiplier is 1 + boost * nutrients
+
+// Plant patches (FoodModel::Patches)
+pub const PLANT_CELL_SIZE: f32 = 100.0; // world units per plant cell
+pub const PLANT_INITIAL_COVERAGE: f32 = 0.4; // fraction of cells that start with plants
+pub const PLANT_GROWTH_RATE: f32 = 0.02; // logistic growth rate (1/s) on fertility-1 ground
+pub const PLANT_SEED_RATE: f32 = 0.2; // how strongly neighbouring cover seeds a cell
+pub const PLANT_GERMINATION: f32 = 0.01; // spontaneous seeding so barren areas eventually recover
+pub const PLANT_MIN_GROWTH: f32 = 0.25; // growth-rate floor so infertile ground still dies back
+pub const PLANT_FOOD_BOOST: f32 = 2.0; // food spawn multiplier is cover * boost
+pub const PLANT_GRAZE_COST: f32 = 0.6; // cover removed from a cell per food item eaten there
+
+// Species clustering
+pub const SPECIES_UPDATE_INTERVAL: u64 = 60; // ticks between re-clustering
+pub const SPECIES_DISTANCE_THRESHOLD: f32 = 0.12; // mean abs gene difference to join a species
+pub const SPECIES_LEGEND_ROWS: usize = 10; // largest species listed in the color legend
+
+// Population genetics
+pub const BENCHMARK_TICKS: u64 = 3600; // measured ticks of a headless benchmark run
+pub const BENCHMARK_WARMUP_TICKS: u64 = 300; // unmeasured ticks before measuring starts
+pub const BENCHMARK_PERCENTILES: [f64; 4] = [50.0, 90.0, 99.0, 99.9];
+pub const BENCHMARK_TICK_BUCKETS_MS: [f64; 10] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.7, 33.3, 66.7, 100.0];
+pub const BENCHMARK_EVENT_FRACTIONS: [f64; 3] = [0.25, 0.5, 0.75]; // stress events, as fractions of the measured window
+pub const BENCHMARK_EVENT_WINDOW: u64 = 300; // ticks after each stress event counted in event_tick_ms
+pub const BENCHMARK_STORM_RADIUS_MULT: f32 = 4.0; // storm-surge storms are this many times the normal radius
+pub const BENCHMARK_MASS_SPAWN: usize = 200; // entities spawned per combat-heavy event (up to MAX_ENTITY_COUNT)
+pub const BENCHMARK_MASS_SPAWN_RADIUS: f32 = 150.0; // radius of the combat-heavy spawn cluster
+pub const BENCHMARK_FOOD_FLOOD: usize = 1000; // food items dropped per food-bloom event
+pub const EXPERIMENT_DEFAULT_TICKS: u64 = 18000; // ticks per experiment run unless the spec sets `ticks`
+pub const EXPERIMENT_DEFAULT_REPLICATES: u32 = 3; // seeded replicates per cell unless the spec sets `replicates`
+pub const EXPERIMENT_SAMPLE_INTERVAL: u64 = 60; // ticks between population samples for mean_population
+pub const FAST_FORWARD_MIN_SPEED: f32 = 50.0; // speed multipliers from here on skip world rendering
+pub const FAST_FORWARD_FRAME_BUDGET: f64 = 0.05; // seconds of ticking per frame while fast-forwarding
+pub const RUN_UNTIL_SPEED: f32 = 100.0; // speed multiplier while running until a target
+pub const RUN_UNTIL_DEFAULT_TICKS: u64 = 36_000; // default tick target offered ahead of the current tick
+pub const RUN_UNTIL_DEFAULT_GENERATIONS: u32 = 10; // default generation target offered beyond the deepest lineage
+pub const SNAPSHOT_MAX_TICKS_PER_FRAME: u64 = 600; // keeps the window responsive while a snapshot run fast-forwards
+pub const RECORD_FFMPEG_FPS: u32 = 30; // frame rate of videos encoded by the ffmpeg pipe
+pub const RECORD_VIDEO_FILE: &str = "recording.mp4"; // video written into the take directory when piping to ffmpeg
+pub const GENETICS_SAMPLE_INTERVAL: u64 = 60; // ticks between gene-pool samples
+pub const GENERATION_HISTOGRAM_INTERVAL: u64 = 60; // ticks between generation-depth histograms
+pub const GENERATION_HISTOGRAM_MAX_BINS: usize = 40; // deeper populations are drawn with wider bins
+pub const TRAIT_HISTOGRAM_INTERVAL: u64 = 60; // ticks between body-trait histograms
+pub const TRAIT_HISTOGRAM_BINS: usize = 20;
+pub const TRAIT_INTERNEURON_WEIGHT: f32 = 8.0; // mean |weight| to the motors above which an interneuron counts as in use
+pub const INTERNEURON_SAMPLE_INTERVAL: u64 = 300; // ticks between interneuron-count samples (the graph spans ~80 minutes)
+pub const INTERNEURON_SPECIES_ROWS: usize = 8; // largest species listed in the per-species breakdown
+
+// Run database (--db)
+pub const DB_FLUSH_INTERVAL: u64 = 600; // ticks of rows written per transaction
+pub const DB_GENOME_SAMPLE_INTERVAL: u64 = 3600; // ticks between genome samples (1 minute at 60 Hz)
+pub const DB_GENOME_SAMPLE_SIZE: usize = 32; // genomes stored per sample
+
+// Live telemetry (--telemetry-port)
+pub const TELEMETRY_TICK_INTERVAL: u64 = 10; // ticks between `tick` messages
+pub const TELEMETRY_SUMMARY_INTERVAL: u64 = 600; // ticks between population `summary` messages
+pub const TELEMETRY_SUMMARY_SPECIES: usize = 10; // largest species listed in a summary
+pub const TELEMETRY_MAX_BACKLOG: usize = 4 * 1024 * 1024; // unsent bytes after which a slow client is dropped
+
+// Remote control (--control-port)
+pub const CONTROL_SPEED_MIN: f32 = 0.25; // range accepted by /speed, matching the toolbar
+pub const CONTROL_SPEED_MAX: f32 = 100.0;
+pub const CONTROL_FOOD_MAX: usize = 2000; // most food items one /food request may scatter
+
+// Hall of fame
+pub const HALL_OF_FAME_SIZE: usize = 10; // genomes kept per category
+pub const HALL_OF_FAME_SNAPSHOT_INTERVAL: u64 = 3600; // ticks between archiving living entities and writing the library
+
+// Heatmap overlays
+pub const HEATMAP_CELL_SIZE: f32 = 50.0;
+pub const HEATMAP_OPACITY: f32 = 0.45; // default alpha of the busiest cell
+pub const POPULATION_HEATMAP_DECAY: f32 = 0.95; // per 10-tick sample, ~2s half-life
+pub const PRODUCTIVITY_HEATMAP_MAX: f32 = 3.0; // relative food spawn rate drawn at full opacity
 
 // Camera
 pub const CAMERA_ZOOM_MIN: f32 = 0.05;
@@ -69,3 +322,20 @@ pub const CAMERA_ZOOM_MAX: f32 = 2.0;
 pub const CAMERA_PAN_SPEED: f32 = 500.0;
 pub const CAMERA_ZOOM_SPEED: f32 = 0.1;
 pub const CAMERA_SMOOTH_SPEED: f32 = 8.0;
+pub const CAMERA_GROUP_MARGIN: f32 = 150.0; // world units of padding around a framed group
+pub const FOLLOW_INSET_SIZE: u32 = 240; // picture-in-picture side, pixels
+pub const FOLLOW_INSET_ZOOM: f32 = 1.5;
+pub const FOLLOW_INSET_MARGIN: f32 = 12.0; // gap to the right screen edge
+pub const FOLLOW_INSET_TOP: f32 = 48.0; // below the toolbar
+pub const ENTITY_LABEL_MIN_ZOOM: f32 = 0.6; // name/tag labels are hidden when zoomed out further
+pub const INTENT_BUBBLE_ROWS: usize = 3; // strongest motor intents shown above the followed entity
+pub const INTENT_BUBBLE_MIN: f32 = 0.15; // intents weaker than this are left out of the bubble
+pub const LOD_BASIC_ZOOM: f32 = 0.15; // below this zoom creatures are a bare 2-triangle body
+pub const LOD_FULL_ZOOM: f32 = 0.6; // from this zoom creatures get fins, stripe and pupils
+pub const LOD_HYSTERESIS: f32 = 0.15; // fraction past a threshold the zoom must go before the detail level switches
+
+// Night lighting (post-processing light buffer)
+pub const NIGHT_LIGHT_ENTITY_RADIUS: f32 = 5.0; // glow around each creature at night, in body radii
+pub const NIGHT_LIGHT_ENTITY_STRENGTH: f32 = 0.35; // light added at the centre of a creature's glow
+pub const NIGHT_LIGHT_SIGNAL_RADIUS: f32 = 2.5; // signal light radius, relative to the signal aura
+pub const NIGHT_LIGHT_SIGNAL_STRENGTH: f32 = 0.8; // light added at the centre of a full-intensity signal
@@ -0,0 +1,392 @@
+// Remote control: a small HTTP server (`--control-port 9001`) so scripts can drive a running
+// windowed session: pause and resume, change speed, drop food and toxic zones, trigger storms,
+// query stats and request a save. Like telemetry, sockets are non-blocking and serviced once
+// per frame; every connection carries one request and is closed after the response.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use macroquad::prelude::{vec2, Vec2};
+use rand::Rng;
+
+use crate::camera::CameraController;
+use crate::config;
+use crate::editing::{self, EditHistory, EditOp};
+use crate::environment::{Storm, ToxicZone};
+use crate::journal::InterventionKind;
+use crate::manifest::json_string;
+use crate::reporting::json_number;
+use crate::save_load;
+use crate::simulation::SimState;
+use crate::telemetry;
+
+/// Largest request (headers plus body) accepted.
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+/// Open connections served at once; further clients are turned away until one closes.
+const MAX_CONNECTIONS: usize = 16;
+/// Time a client has to send a complete request before it is answered with 408; a connection
+/// still open after twice this (a client not reading its response) is dropped.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Save file used when `/save` is not given a `path`.
+const DEFAULT_SAVE_PATH: &str = "genesis_save.bin";
+const TOO_LARGE: &str = "413 Payload Too Large";
+const TIMEOUT: &str = "408 Request Timeout";
+
+struct Connection {
+    stream: TcpStream,
+    addr: SocketAddr,
+    inbox: Vec<u8>,
+    outbox: Vec<u8>,
+    /// A response has been queued; close once it is sent.
+    responded: bool,
+    accepted: Instant,
+}
+
+pub struct ControlServer {
+    pub port: u16,
+    listener: TcpListener,
+    connections: Vec<Connection>,
+}
+
+/// A parsed request: method, path and the query and form parameters merged.
+struct Request {
+    method: String,
+    path: String,
+    params: Vec<(String, String)>,
+}
+
+impl Request {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    /// A numeric parameter; Ok(None) if absent, Err if present but not a finite number.
+    fn number(&self, name: &str) -> Result<Option<f32>, String> {
+        match self.param(name) {
+            None => Ok(None),
+            Some(v) => match v.parse::<f32>() {
+                Ok(n) if n.is_finite() => Ok(Some(n)),
+                _ => Err(format!("{name} must be a number, got {v:?}")),
+            },
+        }
+    }
+
+    /// `x` and `y` together, or None if neither is given.
+    fn position(&self) -> Result<Option<Vec2>, String> {
+        match (self.number("x")?, self.number("y")?) {
+            (Some(x), Some(y)) => Ok(Some(vec2(x, y))),
+            (None, None) => Ok(None),
+            _ => Err("x and y must be given together".to_string()),
+        }
+    }
+}
+
+impl ControlServer {
+    /// Listen on `127.0.0.1:port`.
+    pub fn bind(port: u16) -> Result<Self, String> {
+        let listener =
+            TcpListener::bind(("127.0.0.1", port)).map_err(|e| format!("could not listen on port {port}: {e}"))?;
+        listener.set_nonblocking(true).map_err(|e| format!("could not configure the socket: {e}"))?;
+        Ok(Self {
+            port,
+            listener,
+            connections: Vec::new(),
+        })
+    }
+
+    /// Accept new connections, handle complete requests and send responses. Edits go through
+    /// `history` so they can be undone like edits made in the window. Call once per frame.
+    pub fn poll(&mut self, sim: &mut SimState, camera: &CameraController, history: &mut EditHistory) {
+        loop {
+            match self.listener.accept() {
+                Ok((mut stream, addr)) => {
+                    if self.connections.len() >= MAX_CONNECTIONS {
+                        // Best effort: the socket is still blocking, so this write is immediate
+                        let _ = stream.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                        continue;
+                    }
+                    if stream.set_nonblocking(true).is_ok() {
+                        let _ = stream.set_nodelay(true);
+                        self.connections.push(Connection {
+                            stream,
+                            addr,
+                            inbox: Vec::new(),
+                            outbox: Vec::new(),
+                            responded: false,
+                            accepted: Instant::now(),
+                        });
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    eprintln!("[GENESIS] Control accept failed: {e}");
+                    break;
+                }
+            }
+        }
+
+        self.connections.retain_mut(|conn| conn.service(sim, camera, history));
+    }
+}
+
+impl Connection {
+    /// Read, handle and write whatever the socket allows without blocking. Returns false
+    /// once the connection should be dropped.
+    fn service(&mut self, sim: &mut SimState, camera: &CameraController, history: &mut EditHistory) -> bool {
+        let mut buf = [0u8; 4096];
+        let mut closed = false;
+        // Stop reading past the request limit; the oversized request is rejected below
+        while self.inbox.len() <= MAX_REQUEST_BYTES {
+            match self.stream.read(&mut buf) {
+                Ok(0) => {
+                    closed = true;
+                    break;
+                }
+                Ok(n) => self.inbox.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => return false,
+            }
+        }
+
+        if !self.responded {
+            match parse_request(&self.inbox) {
+                Some(Ok(request)) => {
+                    let (status, body) = match handle(&request, sim, camera, history) {
+                        Ok(body) => ("200 OK", body),
+                        Err((status, message)) => (status, format!("{{\"ok\": false, \"error\": {}}}", json_string(&message))),
+                    };
+                    eprintln!("[GENESIS] Control {} {} {} -> {status}", self.addr, request.method, request.path);
+                    self.respond(status, &body);
+                }
+                Some(Err((status, message))) => {
+                    self.respond(status, &format!("{{\"ok\": false, \"error\": {}}}", json_string(&message)));
+                }
+                None if self.inbox.len() > MAX_REQUEST_BYTES => {
+                    self.respond(TOO_LARGE, "{\"ok\": false, \"error\": \"request too large\"}");
+                }
+                // The client hung up before finishing its request
+                None if closed => return false,
+                None if self.accepted.elapsed() >= REQUEST_TIMEOUT => {
+                    self.respond(TIMEOUT, "{\"ok\": false, \"error\": \"request timed out\"}");
+                }
+                None => {}
+            }
+        }
+
+        while !self.outbox.is_empty() {
+            match self.stream.write(&self.outbox) {
+                Ok(0) => return false,
+                Ok(n) => {
+                    self.outbox.drain(..n);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => return false,
+            }
+        }
+        !(self.responded && self.outbox.is_empty()) && self.accepted.elapsed() < REQUEST_TIMEOUT * 2
+    }
+
+    fn respond(&mut self, status: &str, body: &str) {
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}\n",
+            body.len() + 1
+        );
+        self.outbox.extend_from_slice(response.as_bytes());
+        self.responded = true;
+    }
+}
+
+/// Parse a complete request, or None if more bytes are needed. Parameters come from the query
+/// string and, for form-encoded bodies, the body. Errors carry the response status.
+fn parse_request(data: &[u8]) -> Option<Result<Request, (&'static str, String)>> {
+    let end = data.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = String::from_utf8_lossy(&data[..end]);
+    let mut lines = head.lines();
+    let mut parts = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Some(Err(("400 Bad Request", "malformed request line".to_string())));
+    };
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    let body_start = end + 4;
+    // Checked before adding: the length is the client's word and may be absurd
+    if content_length > MAX_REQUEST_BYTES - body_start.min(MAX_REQUEST_BYTES) {
+        return Some(Err((TOO_LARGE, "request too large".to_string())));
+    }
+    if data.len() < body_start + content_length {
+        return None;
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let body = String::from_utf8_lossy(&data[body_start..body_start + content_length]);
+    let params = query
+        .split('&')
+        .chain(body.trim().split('&'))
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect();
+    Some(Ok(Request {
+        method: method.to_ascii_uppercase(),
+        path: path.trim_end_matches('/').to_string(),
+        params,
+    }))
+}
+
+/// Decode `%XX` escapes and `+` as space; malformed escapes are kept as they are.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => match (bytes.get(i + 1).and_then(hex_digit), bytes.get(i + 2).and_then(hex_digit)) {
+                (Some(hi), Some(lo)) => {
+                    out.push(hi << 4 | lo);
+                    i += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex_digit(b: &u8) -> Option<u8> {
+    (*b as char).to_digit(16).map(|d| d as u8)
+}
+
+type Response = Result<String, (&'static str, String)>;
+
+fn bad_request(message: String) -> (&'static str, String) {
+    ("400 Bad Request", message)
+}
+
+/// Run one request against the simulation and return the JSON response body.
+fn handle(request: &Request, sim: &mut SimState, camera: &CameraController, history: &mut EditHistory) -> Response {
+    let read_only = matches!(request.path.as_str(), "" | "/status" | "/stats");
+    let expected = if read_only { "GET" } else { "POST" };
+    let known = read_only
+        || matches!(request.path.as_str(), "/pause" | "/resume" | "/speed" | "/food" | "/zone" | "/storm" | "/save");
+    if !known {
+        return Err(("404 Not Found", format!("no endpoint {}", request.path)));
+    }
+    if request.method != expected {
+        return Err(("405 Method Not Allowed", format!("{} expects {expected}", request.path)));
+    }
+
+    match request.path.as_str() {
+        "" | "/status" => Ok(status_json(sim)),
+        "/stats" => Ok(telemetry::summary_json(sim)),
+        "/pause" | "/resume" => {
+            sim.paused = request.path == "/pause";
+            Ok(format!("{{\"ok\": true, \"paused\": {}}}", sim.paused))
+        }
+        "/speed" => {
+            let speed = request.number("value").map_err(bad_request)?.ok_or_else(|| bad_request("value is required".to_string()))?;
+            if !(config::CONTROL_SPEED_MIN..=config::CONTROL_SPEED_MAX).contains(&speed) {
+                return Err(bad_request(format!(
+                    "speed must be between {} and {}",
+                    config::CONTROL_SPEED_MIN,
+                    config::CONTROL_SPEED_MAX
+                )));
+            }
+            if (sim.speed_multiplier - speed).abs() >= 0.01 {
+                let detail = format!("{}x to {speed}x (remote)", sim.speed_multiplier);
+                sim.journal.record(sim.tick_count, InterventionKind::Speed, detail);
+                sim.speed_multiplier = speed;
+            }
+            Ok(format!("{{\"ok\": true, \"speed\": {}}}", json_number(sim.speed_multiplier as f64)))
+        }
+        "/food" => {
+            let op = match (request.position().map_err(bad_request)?, request.number("count").map_err(bad_request)?) {
+                (Some(pos), _) => editing::food_cluster_op(sim, sim.world.wrap(pos)),
+                (None, Some(count)) => editing::scattered_food_op(sim, count.clamp(0.0, config::CONTROL_FOOD_MAX as f32) as usize),
+                (None, None) => return Err(bad_request("give x and y for a cluster or count to scatter".to_string())),
+            };
+            let added = match &op {
+                EditOp::AddFood(positions) => positions.len(),
+                _ => 0,
+            };
+            history.perform(sim, op);
+            Ok(format!("{{\"ok\": true, \"food_added\": {added}, \"food\": {}}}", sim.food.len()))
+        }
+        "/zone" => {
+            let pos = request
+                .position()
+                .map_err(bad_request)?
+                .ok_or_else(|| bad_request("x and y are required".to_string()))?;
+            let radius = request.number("radius").map_err(bad_request)?.unwrap_or(config::TOXIC_ZONE_RADIUS);
+            let intensity = request.number("intensity").map_err(bad_request)?.unwrap_or(1.0);
+            let zone = ToxicZone {
+                pos: sim.world.wrap(pos),
+                radius: radius.clamp(config::TOXIC_ZONE_RADIUS_MIN, config::TOXIC_ZONE_RADIUS_MAX),
+                intensity: intensity.clamp(config::TOXIC_ZONE_INTENSITY_MIN, config::TOXIC_ZONE_INTENSITY_MAX),
+            };
+            history.perform(sim, EditOp::AddToxicZone(zone));
+            Ok(format!(
+                "{{\"ok\": true, \"zone\": {{\"x\": {}, \"y\": {}, \"radius\": {}, \"intensity\": {}}}}}",
+                json_number(zone.pos.x as f64),
+                json_number(zone.pos.y as f64),
+                json_number(zone.radius as f64),
+                json_number(zone.intensity as f64)
+            ))
+        }
+        "/storm" => {
+            let center = match request.position().map_err(bad_request)? {
+                Some(pos) => sim.world.wrap(pos),
+                None => vec2(sim.rng.gen_range(0.0..sim.world.width), sim.rng.gen_range(0.0..sim.world.height)),
+            };
+            sim.environment.storm = Some(Storm::new(center, config::STORM_RADIUS, &mut sim.rng));
+            sim.sound_events.storm_started = true;
+            let detail = format!("at ({:.0}, {:.0}) (remote)", center.x, center.y);
+            sim.journal.record(sim.tick_count, InterventionKind::Storm, detail);
+            Ok(format!(
+                "{{\"ok\": true, \"storm\": {{\"x\": {}, \"y\": {}}}}}",
+                json_number(center.x as f64),
+                json_number(center.y as f64)
+            ))
+        }
+        "/save" => {
+            let path = request.param("path").unwrap_or(DEFAULT_SAVE_PATH);
+            // Plain file names only: the server must not write outside the working directory
+            if path.is_empty() || path.contains(['/', '\\']) || path.starts_with('.') {
+                return Err(bad_request(format!("path must be a plain file name, got {path:?}")));
+            }
+            save_load::save_to_file(sim, camera, path).map_err(|e| ("500 Internal Server Error", e))?;
+            eprintln!("[GENESIS] Saved to {path} (remote)");
+            Ok(format!("{{\"ok\": true, \"path\": {}, \"tick\": {}}}", json_string(path), sim.tick_count))
+        }
+        _ => unreachable!("endpoint checked above"),
+    }
+}
+
+/// Where the run is and how it is going.
+fn status_json(sim: &SimState) -> String {
+    let alive = sim.arena.count.max(1) as f64;
+    let energy: f64 = sim.arena.iter_alive().map(|(_, e)| e.energy as f64).sum();
+    format!(
+        "{{\"ok\": true, \"tick\": {}, \"sim_seconds\": {}, \"paused\": {}, \"speed\": {}, \"population\": {}, \"food\": {}, \"avg_energy\": {}, \"max_generation\": {}, \"species\": {}, \"season\": {}, \"storm\": {}, \"toxic_zones\": {}}}",
+        sim.tick_count,
+        json_number(sim.tick_count as f64 * sim.dt as f64),
+        sim.paused,
+        json_number(sim.speed_multiplier as f64),
+        sim.arena.count,
+        sim.food.len(),
+        json_number(energy / alive),
+        sim.arena.deepest_generation(),
+        sim.species.species.len(),
+        json_string(sim.environment.season.name()),
+        sim.environment.storm.is_some(),
+        sim.environment.toxic_zones.len()
+    )
+}
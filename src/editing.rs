@@ -0,0 +1,455 @@
+use macroquad::prelude::*;
+use ::rand::Rng;
+
+use crate::combat::MeatItem;
+use crate::config;
+use crate::environment::{Portal, ToxicZone, Wall};
+use crate::journal::InterventionKind;
+use crate::plants::FoodKind;
+use crate::simulation::{FoodItem, SimState};
+
+/// Maximum number of edits kept for undo.
+const HISTORY_LIMIT: usize = 100;
+
+/// Active world-editing tool. `Select` is the default click-to-follow behavior.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToolMode {
+    Select,
+    Wall,
+    /// Select walls, drag their endpoints and delete them.
+    EditWalls,
+    ToxicZone,
+    Food,
+    /// Pick up an entity and drop it elsewhere.
+    Drag,
+    /// Mark a rectangle or polygon and watch its population statistics.
+    Region,
+    /// Drag from one endpoint of a portal pair to the other.
+    Portal,
+    /// Remove food, meat and hazards (and with Shift, walls) under the cursor.
+    Erase,
+}
+
+impl ToolMode {
+    pub const ALL: [ToolMode; 9] = [
+        ToolMode::Select,
+        ToolMode::Wall,
+        ToolMode::EditWalls,
+        ToolMode::ToxicZone,
+        ToolMode::Food,
+        ToolMode::Drag,
+        ToolMode::Region,
+        ToolMode::Portal,
+        ToolMode::Erase,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToolMode::Select => "Select",
+            ToolMode::Wall => "Wall",
+            ToolMode::EditWalls => "Edit walls",
+            ToolMode::ToxicZone => "Hazard",
+            ToolMode::Food => "Food",
+            ToolMode::Drag => "Drag",
+            ToolMode::Region => "Region",
+            ToolMode::Portal => "Portal",
+            ToolMode::Erase => "Erase",
+        }
+    }
+}
+
+/// Everything one click of the erase tool removed, kept whole so undo restores it exactly.
+#[derive(Clone, Debug)]
+pub struct Erased {
+    pub center: Vec2,
+    pub food: Vec<FoodItem>,
+    pub meat: Vec<MeatItem>,
+    pub zones: Vec<ToxicZone>,
+    pub walls: Vec<Wall>,
+}
+
+/// A reversible world edit. Every operation has an exact inverse.
+#[derive(Clone, Debug)]
+pub enum EditOp {
+    AddWall(Wall),
+    RemoveWall(Wall),
+    MoveWall { from: Wall, to: Wall },
+    AddToxicZone(ToxicZone),
+    RemoveToxicZone(ToxicZone),
+    AddPortal(Portal),
+    RemovePortal(Portal),
+    AddFood(Vec<Vec2>),
+    RemoveFood(Vec<Vec2>),
+    Erase(Erased),
+    Unerase(Erased),
+}
+
+impl EditOp {
+    pub fn inverse(&self) -> EditOp {
+        match self {
+            EditOp::AddWall(w) => EditOp::RemoveWall(*w),
+            EditOp::RemoveWall(w) => EditOp::AddWall(*w),
+            EditOp::MoveWall { from, to } => EditOp::MoveWall { from: *to, to: *from },
+            EditOp::AddToxicZone(z) => EditOp::RemoveToxicZone(*z),
+            EditOp::RemoveToxicZone(z) => EditOp::AddToxicZone(*z),
+            EditOp::AddPortal(p) => EditOp::RemovePortal(*p),
+            EditOp::RemovePortal(p) => EditOp::AddPortal(*p),
+            EditOp::AddFood(p) => EditOp::RemoveFood(p.clone()),
+            EditOp::RemoveFood(p) => EditOp::AddFood(p.clone()),
+            EditOp::Erase(e) => EditOp::Unerase(e.clone()),
+            EditOp::Unerase(e) => EditOp::Erase(e.clone()),
+        }
+    }
+
+    /// Apply the operation to the simulation. Removing something that no longer exists
+    /// (e.g. food that has since been eaten) is a no-op.
+    pub fn apply(&self, sim: &mut SimState) {
+        match self {
+            EditOp::AddWall(w) => sim.environment.walls.push(*w),
+            EditOp::RemoveWall(w) => {
+                if let Some(i) = sim.environment.walls.iter().rposition(|x| x == w) {
+                    sim.environment.walls.remove(i);
+                }
+            }
+            EditOp::MoveWall { from, to } => {
+                if let Some(w) = sim.environment.walls.iter_mut().rev().find(|x| *x == from) {
+                    *w = *to;
+                }
+            }
+            EditOp::AddToxicZone(z) => sim.environment.toxic_zones.push(*z),
+            EditOp::RemoveToxicZone(z) => {
+                if let Some(i) = sim.environment.toxic_zones.iter().rposition(|x| x == z) {
+                    sim.environment.toxic_zones.remove(i);
+                }
+            }
+            EditOp::AddPortal(p) => sim.environment.portals.push(*p),
+            EditOp::RemovePortal(p) => {
+                if let Some(i) = sim.environment.portals.iter().rposition(|x| x == p) {
+                    sim.environment.portals.remove(i);
+                }
+            }
+            EditOp::AddFood(positions) => {
+                for &pos in positions {
+                    sim.food.push(FoodItem::new(pos, FoodKind::Berry));
+                }
+            }
+            EditOp::RemoveFood(positions) => {
+                for pos in positions {
+                    if let Some(i) = sim.food.iter().rposition(|f| f.pos == *pos) {
+                        sim.food.swap_remove(i);
+                    }
+                }
+            }
+            EditOp::Erase(e) => {
+                for item in &e.food {
+                    if let Some(i) = sim.food.iter().rposition(|f| f.pos == item.pos) {
+                        sim.food.swap_remove(i);
+                    }
+                }
+                for item in &e.meat {
+                    if let Some(i) = sim.meat.iter().rposition(|m| m.pos == item.pos) {
+                        sim.meat.swap_remove(i);
+                    }
+                }
+                sim.environment.toxic_zones.retain(|z| !e.zones.contains(z));
+                sim.environment.walls.retain(|w| !e.walls.contains(w));
+            }
+            EditOp::Unerase(e) => {
+                sim.food.extend(e.food.iter().cloned());
+                sim.meat.extend(e.meat.iter().cloned());
+                sim.environment.toxic_zones.extend(&e.zones);
+                sim.environment.walls.extend(&e.walls);
+            }
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            EditOp::AddWall(_) => "add wall",
+            EditOp::RemoveWall(_) => "remove wall",
+            EditOp::MoveWall { .. } => "move wall",
+            EditOp::AddToxicZone(_) => "add hazard",
+            EditOp::RemoveToxicZone(_) => "remove hazard",
+            EditOp::AddPortal(_) => "add portal",
+            EditOp::RemovePortal(_) => "remove portal",
+            EditOp::AddFood(_) => "spawn food",
+            EditOp::RemoveFood(_) => "remove food",
+            EditOp::Erase(_) => "erase",
+            EditOp::Unerase(_) => "restore erased",
+        }
+    }
+
+    /// `describe` plus where the edit happened, for the intervention journal.
+    pub fn summary(&self) -> String {
+        let at = |p: Vec2| format!("({:.0}, {:.0})", p.x, p.y);
+        let place = match self {
+            EditOp::AddWall(w) | EditOp::RemoveWall(w) => format!("{} to {}", at(w.a), at(w.b)),
+            EditOp::MoveWall { to, .. } => format!("to {} - {}", at(to.a), at(to.b)),
+            EditOp::AddToxicZone(z) | EditOp::RemoveToxicZone(z) => {
+                format!("at {} r {:.0} x{:.2}", at(z.pos), z.radius, z.intensity)
+            }
+            EditOp::AddPortal(p) | EditOp::RemovePortal(p) => format!("{} to {}", at(p.a), at(p.b)),
+            EditOp::AddFood(positions) | EditOp::RemoveFood(positions) => {
+                let center = positions.iter().copied().sum::<Vec2>() / positions.len().max(1) as f32;
+                format!("x{} around {}", positions.len(), at(center))
+            }
+            EditOp::Erase(e) | EditOp::Unerase(e) => format!(
+                "{} food, {} meat, {} hazards, {} walls around {}",
+                e.food.len(),
+                e.meat.len(),
+                e.zones.len(),
+                e.walls.len(),
+                at(e.center)
+            ),
+        };
+        format!("{} {place}", self.describe())
+    }
+}
+
+/// Undo/redo stacks of world edits.
+#[derive(Default)]
+pub struct EditHistory {
+    undo_stack: Vec<EditOp>,
+    redo_stack: Vec<EditOp>,
+}
+
+impl EditHistory {
+    /// Apply a new edit and record it. Clears the redo stack.
+    pub fn perform(&mut self, sim: &mut SimState, op: EditOp) {
+        op.apply(sim);
+        sim.journal.record(sim.tick_count, InterventionKind::Edit, op.summary());
+        self.undo_stack.push(op);
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    /// Revert the most recent edit. Returns false if there is nothing to undo.
+    pub fn undo(&mut self, sim: &mut SimState) -> bool {
+        let Some(op) = self.undo_stack.pop() else {
+            return false;
+        };
+        op.inverse().apply(sim);
+        sim.journal.record(sim.tick_count, InterventionKind::Undo, op.summary());
+        self.redo_stack.push(op);
+        true
+    }
+
+    /// Re-apply the most recently undone edit. Returns false if there is nothing to redo.
+    pub fn redo(&mut self, sim: &mut SimState) -> bool {
+        let Some(op) = self.redo_stack.pop() else {
+            return false;
+        };
+        op.apply(sim);
+        sim.journal.record(sim.tick_count, InterventionKind::Redo, op.summary());
+        self.undo_stack.push(op);
+        true
+    }
+
+    pub fn next_undo(&self) -> Option<&EditOp> {
+        self.undo_stack.last()
+    }
+
+    pub fn next_redo(&self) -> Option<&EditOp> {
+        self.redo_stack.last()
+    }
+
+    /// Forget all history (e.g. after loading a different world).
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+/// Which end of a wall segment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WallEnd {
+    A,
+    B,
+}
+
+/// An endpoint drag in progress: the wall is edited live and recorded as one move on release.
+#[derive(Clone, Copy, Debug)]
+pub struct WallDrag {
+    pub index: usize,
+    pub end: WallEnd,
+    pub original: Wall,
+}
+
+/// State of the wall-edit tool.
+#[derive(Default)]
+pub struct WallEditState {
+    pub hovered: Option<usize>,
+    pub selected: Option<usize>,
+    pub dragging: Option<WallDrag>,
+}
+
+impl WallEditState {
+    /// Drop references to walls that no longer exist (e.g. after undo or load).
+    pub fn validate(&mut self, walls: &[Wall]) {
+        if self.hovered.is_some_and(|i| i >= walls.len()) {
+            self.hovered = None;
+        }
+        if self.selected.is_some_and(|i| i >= walls.len()) {
+            self.selected = None;
+        }
+        if self.dragging.is_some_and(|d| d.index >= walls.len()) {
+            self.dragging = None;
+        }
+    }
+
+    /// Abort any endpoint drag (restoring the wall) and forget hover and selection.
+    pub fn cancel(&mut self, walls: &mut [Wall]) {
+        if let Some(drag) = self.dragging {
+            if let Some(w) = walls.get_mut(drag.index) {
+                *w = drag.original;
+            }
+        }
+        *self = Self::default();
+    }
+}
+
+/// Index of the wall closest to `pos`, if within `radius`.
+pub fn pick_wall(walls: &[Wall], pos: Vec2, radius: f32) -> Option<usize> {
+    walls
+        .iter()
+        .enumerate()
+        .map(|(i, w)| (i, w.closest_point(pos).distance(pos)))
+        .filter(|(_, d)| *d <= radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+/// The endpoint of `wall` within `radius` of `pos`, preferring the nearer one.
+pub fn pick_wall_end(wall: &Wall, pos: Vec2, radius: f32) -> Option<WallEnd> {
+    let (da, db) = (wall.a.distance(pos), wall.b.distance(pos));
+    if da.min(db) > radius {
+        None
+    } else if da <= db {
+        Some(WallEnd::A)
+    } else {
+        Some(WallEnd::B)
+    }
+}
+
+/// Build a wall edit from a drag, or None if the drag was too short.
+pub fn wall_op(from: Vec2, to: Vec2) -> Option<EditOp> {
+    if from.distance(to) < config::WALL_MIN_LENGTH {
+        return None;
+    }
+    Some(EditOp::AddWall(Wall { a: from, b: to }))
+}
+
+/// Build a portal edit from a drag, or None if the endpoints are too close together.
+pub fn portal_op(from: Vec2, to: Vec2) -> Option<EditOp> {
+    if from.distance(to) < config::PORTAL_MIN_DISTANCE {
+        return None;
+    }
+    Some(EditOp::AddPortal(Portal { a: from, b: to }))
+}
+
+/// Size and strength of the next toxic zone placed with the hazard tool.
+#[derive(Clone, Copy)]
+pub struct ToxicBrush {
+    pub radius: f32,
+    pub intensity: f32,
+}
+
+impl Default for ToxicBrush {
+    fn default() -> Self {
+        Self {
+            radius: config::TOXIC_ZONE_RADIUS,
+            intensity: 1.0,
+        }
+    }
+}
+
+impl ToxicBrush {
+    /// Scroll resizes the brush; with Shift held it changes intensity instead.
+    pub fn scroll(&mut self, notches: f32, shift: bool) {
+        let factor = config::TOXIC_BRUSH_SCROLL_STEP.powf(notches);
+        if shift {
+            self.intensity = (self.intensity * factor)
+                .clamp(config::TOXIC_ZONE_INTENSITY_MIN, config::TOXIC_ZONE_INTENSITY_MAX);
+        } else {
+            self.radius = (self.radius * factor).clamp(config::TOXIC_ZONE_RADIUS_MIN, config::TOXIC_ZONE_RADIUS_MAX);
+        }
+    }
+}
+
+pub fn toxic_zone_op(pos: Vec2, brush: &ToxicBrush) -> EditOp {
+    EditOp::AddToxicZone(ToxicZone {
+        pos,
+        radius: brush.radius,
+        intensity: brush.intensity,
+    })
+}
+
+/// Radius of the erase tool.
+#[derive(Clone, Copy)]
+pub struct EraseBrush {
+    pub radius: f32,
+}
+
+impl Default for EraseBrush {
+    fn default() -> Self {
+        Self { radius: config::ERASE_RADIUS }
+    }
+}
+
+impl EraseBrush {
+    pub fn scroll(&mut self, notches: f32) {
+        let factor = config::TOXIC_BRUSH_SCROLL_STEP.powf(notches);
+        self.radius = (self.radius * factor).clamp(config::ERASE_RADIUS_MIN, config::ERASE_RADIUS_MAX);
+    }
+}
+
+/// Erase the food, meat and hazards within the brush of `center` (a hazard counts when the
+/// cursor is inside it or its centre is inside the brush), plus the walls passing through it
+/// when `walls` is set. None if there is nothing to erase.
+pub fn erase_op(sim: &SimState, center: Vec2, brush: &EraseBrush, walls: bool) -> Option<EditOp> {
+    let r = brush.radius;
+    let world = &sim.world;
+    let erased = Erased {
+        center,
+        food: sim.food.iter().filter(|f| world.distance(f.pos, center) <= r).cloned().collect(),
+        meat: sim.meat.iter().filter(|m| world.distance(m.pos, center) <= r).cloned().collect(),
+        zones: sim
+            .environment
+            .toxic_zones
+            .iter()
+            .filter(|z| world.distance(z.pos, center) <= r.max(z.radius))
+            .copied()
+            .collect(),
+        walls: if walls {
+            sim.environment.walls.iter().filter(|w| w.closest_point(center).distance(center) <= r).copied().collect()
+        } else {
+            Vec::new()
+        },
+    };
+    if erased.food.is_empty() && erased.meat.is_empty() && erased.zones.is_empty() && erased.walls.is_empty() {
+        return None;
+    }
+    Some(EditOp::Erase(erased))
+}
+
+/// Scatter `count` food items uniformly over the world.
+pub fn scattered_food_op(sim: &mut SimState, count: usize) -> EditOp {
+    let positions = (0..count)
+        .map(|_| vec2(sim.rng.gen_range(0.0..sim.world.width), sim.rng.gen_range(0.0..sim.world.height)))
+        .collect();
+    EditOp::AddFood(positions)
+}
+
+/// Scatter a cluster of food around `center`.
+pub fn food_cluster_op(sim: &mut SimState, center: Vec2) -> EditOp {
+    let positions = (0..config::FOOD_CLUSTER_SIZE)
+        .map(|_| {
+            let angle = sim.rng.gen_range(0.0..std::f32::consts::TAU);
+            let dist = sim.rng.gen_range(0.0..config::FOOD_CLUSTER_RADIUS);
+            sim.world.wrap(center + Vec2::from_angle(angle) * dist)
+        })
+        .collect();
+    EditOp::AddFood(positions)
+}
@@ -2,7 +2,10 @@ use macroquad::prelude::*;
 use ::rand::Rng;
 
 use crate::config;
-use crate::entity::EntityArena;
+use crate::energy_audit::{self, EnergyLedger};
+use crate::entity::{EntityArena, EntityEventKind};
+use crate::environment::{TerrainGrid, TerrainType};
+use crate::plants::FoodKind;
 use crate::simulation::FoodItem;
 use crate::world::World;
 
@@ -17,22 +20,35 @@ impl FoodSpawner {
     }
 }
 
-/// Deduct metabolic costs from all alive entities.
-pub fn deduct_metabolism(arena: &mut EntityArena, dt: f32) {
-    for slot in arena.entities.iter_mut() {
-        if let Some(entity) = slot {
-            let speed_frac = entity.velocity.length()
-                / (config::ENTITY_MAX_SPEED * entity.speed_multiplier).max(1.0);
-            let cost = (config::IDLE_METABOLIC_COST
-                + config::MOVE_METABOLIC_COST * speed_frac)
-                * entity.metabolic_rate;
-            entity.energy -= cost * dt;
-        }
+/// Deduct metabolic costs from all alive entities. Circadian specialists burn more in their
+/// active phase and less in the other, at the given `darkness`.
+pub fn deduct_metabolism(arena: &mut EntityArena, ledger: &mut EnergyLedger, darkness: f32, dt: f32) {
+    for (_, entity) in arena.iter_alive_mut() {
+        let speed_frac = entity.velocity.length()
+            / (config::ENTITY_MAX_SPEED * entity.effective_speed()).max(1.0);
+        let idle_cost = if entity.sheltered {
+            config::IDLE_METABOLIC_COST * (1.0 - config::SHELTER_REST_SAVING)
+        } else {
+            config::IDLE_METABOLIC_COST
+        };
+        let cost = (idle_cost + config::MOVE_METABOLIC_COST * speed_frac)
+            * entity.metabolic_rate
+            * entity.size_stage()
+            * (1.0 + config::CIRCADIAN_METABOLIC_SWING * entity.circadian_activity(darkness));
+        entity.energy -= cost * dt;
+        ledger.metabolism += (cost * dt) as f64;
     }
 }
 
-/// Let entities eat nearby food. Returns positions of eaten food items.
-pub fn consume_food(arena: &mut EntityArena, food: &mut Vec<FoodItem>, world: &World) -> Vec<Vec2> {
+/// Let entities eat nearby food. Food lying in water is algae, which only entities with an
+/// aquatic adaptation of at least ALGAE_MIN_AQUATIC can eat. Returns positions of eaten food items.
+pub fn consume_food(
+    arena: &mut EntityArena,
+    food: &mut Vec<FoodItem>,
+    world: &World,
+    terrain: &TerrainGrid,
+    ledger: &mut EnergyLedger,
+) -> Vec<Vec2> {
     let pickup_radius = config::ENTITY_BASE_RADIUS * 2.0;
     let pickup_radius_sq = pickup_radius * pickup_radius;
     let mut eaten_positions = Vec::new();
@@ -41,20 +57,25 @@ pub fn consume_food(arena: &mut EntityArena, food: &mut Vec<FoodItem>, world: &W
     food.retain(|item| {
         let mut best_idx: Option<usize> = None;
         let mut best_dist_sq = pickup_radius_sq;
+        let algae = terrain.get_at(item.pos) == TerrainType::Water;
 
-        for (idx, entity) in arena.entities.iter().enumerate() {
-            if let Some(e) = entity {
-                let dist_sq = world.distance_sq(e.pos, item.pos);
-                if dist_sq < best_dist_sq {
-                    best_dist_sq = dist_sq;
-                    best_idx = Some(idx);
-                }
+        for (idx, e) in arena.iter_alive() {
+            if algae && e.aquatic < config::ALGAE_MIN_AQUATIC {
+                continue;
+            }
+            let dist_sq = world.distance_sq(e.pos, item.pos);
+            if dist_sq < best_dist_sq {
+                best_dist_sq = dist_sq;
+                best_idx = Some(idx);
             }
         }
 
         if let Some(idx) = best_idx {
             if let Some(e) = &mut arena.entities[idx] {
-                e.energy = (e.energy + item.energy).min(config::MAX_ENTITY_ENERGY);
+                let digested = item.energy * item.kind.digestion(e.taste);
+                ledger.indigestion += (item.energy - digested) as f64;
+                ledger.capped += energy_audit::add_capped(&mut e.energy, digested) as f64;
+                e.log_event(EntityEventKind::AteFood);
                 eaten_positions.push(item.pos);
                 return false; // consumed
             }
@@ -65,17 +86,72 @@ pub fn consume_food(arena: &mut EntityArena, food: &mut Vec<FoodItem>, world: &W
     eaten_positions
 }
 
-/// Kill entities with no energy or exceeding max age.
+/// Age food and remove berries and fruit past their spoil time.
+pub fn spoil_food(food: &mut Vec<FoodItem>, ledger: &mut EnergyLedger, dt: f32) {
+    food.retain_mut(|item| {
+        item.age += dt;
+        if item.kind.spoil_time().is_some_and(|t| item.age >= t) {
+            ledger.food_spoiled += item.energy as f64;
+            return false;
+        }
+        true
+    });
+}
+
+/// Heal injured entities with energy to spare, at an energy cost per health point restored.
+pub fn regenerate_health(arena: &mut EntityArena, ledger: &mut EnergyLedger, dt: f32) {
+    for (_, entity) in arena.iter_alive_mut() {
+        let missing = entity.effective_max_health() - entity.health;
+        if missing <= 0.0 || entity.energy < config::HEALTH_REGEN_MIN_ENERGY {
+            continue;
+        }
+        let healed = (config::HEALTH_REGEN_RATE * dt).min(missing);
+        let cost = healed * config::HEALTH_REGEN_ENERGY_COST;
+        entity.health += healed;
+        entity.energy -= cost;
+        ledger.metabolism += cost as f64;
+    }
+}
+
+/// Kill entities with no energy, no health or exceeding max age. Protected entities are spared.
 pub fn kill_starved(arena: &mut EntityArena) {
-    for slot in arena.entities.iter_mut() {
-        if let Some(entity) = slot {
-            if entity.energy <= 0.0 || entity.age > config::DEATH_AGE {
-                entity.alive = false;
-            }
+    for (_, entity) in arena.iter_alive_mut() {
+        if entity.protection_ticks > 0 {
+            continue;
+        }
+        if entity.energy <= 0.0 || entity.health <= 0.0 || entity.age > config::DEATH_AGE {
+            entity.alive = false;
         }
     }
 }
 
+/// Grow juveniles toward their adult size, paying for it from energy above GROWTH_MIN_ENERGY.
+/// Health grows in proportion, so growing keeps the entity's health fraction.
+pub fn grow_juveniles(arena: &mut EntityArena, ledger: &mut EnergyLedger, dt: f32) {
+    for (_, entity) in arena.iter_alive_mut() {
+        if entity.growth >= 1.0 {
+            continue;
+        }
+        let step = (config::GROWTH_RATE * dt).min(1.0 - entity.growth);
+        let cost = step * config::GROWTH_ENERGY_COST * entity.adult_radius / config::ENTITY_BASE_RADIUS;
+        if entity.energy - cost < config::GROWTH_MIN_ENERGY {
+            continue;
+        }
+        let old_stage = entity.size_stage();
+        entity.set_growth(entity.growth + step);
+        entity.health *= entity.size_stage() / old_stage;
+        entity.energy -= cost;
+        ledger.metabolism += cost as f64;
+    }
+}
+
+/// Clamp health to the senescence-reduced maximum.
+pub fn apply_senescence(arena: &mut EntityArena) {
+    for (_, entity) in arena.iter_alive_mut() {
+        entity.health = entity.health.min(entity.effective_max_health());
+    }
+}
+
 /// Respawn food up to a maximum amount.
 pub fn respawn_food(
     food: &mut Vec<FoodItem>,
@@ -88,10 +164,8 @@ pub fn respawn_food(
     spawner.accumulator += config::FOOD_RESPAWN_RATE * dt;
 
     while spawner.accumulator >= 1.0 && food.len() < max_food {
-        food.push(FoodItem {
-            pos: vec2(rng.gen_range(0.0..world.width), rng.gen_range(0.0..world.height)),
-            energy: config::FOOD_ENERGY,
-        });
+        let pos = vec2(rng.gen_range(0.0..world.width), rng.gen_range(0.0..world.height));
+        food.push(FoodItem::new(pos, FoodKind::Berry));
         spawner.accumulator -= 1.0;
     }
 }
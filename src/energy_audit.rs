@@ -0,0 +1,133 @@
+use crate::combat::MeatItem;
+use crate::config;
+use crate::entity::EntityArena;
+use crate::simulation::FoodItem;
+
+/// Energy that entered or left the system during one tick, by category. Every subsystem that
+/// changes energy records its flow here; all amounts are positive.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnergyLedger {
+    // Inflows
+    pub food_spawned: f64,
+    pub meat_dropped: f64,
+    pub offspring: f64,
+    // Outflows
+    pub metabolism: f64,
+    /// Attacks, building, calls and reproduction costs.
+    pub actions: f64,
+    /// Combat damage, terrain, toxic zones and storms.
+    pub hazards: f64,
+    pub meat_decayed: f64,
+    pub food_spoiled: f64,
+    /// Food energy lost to eating kinds far from the eater's taste.
+    pub indigestion: f64,
+    /// Energy still held by entities when they are swept.
+    pub deaths: f64,
+    /// Intake lost to the MAX_ENTITY_ENERGY cap (eating, sharing).
+    pub capped: f64,
+}
+
+impl EnergyLedger {
+    pub fn inflow(&self) -> f64 {
+        self.food_spawned + self.meat_dropped + self.offspring
+    }
+
+    pub fn outflow(&self) -> f64 {
+        self.metabolism
+            + self.actions
+            + self.hazards
+            + self.meat_decayed
+            + self.food_spoiled
+            + self.indigestion
+            + self.deaths
+            + self.capped
+    }
+
+    /// Labelled rows for display, inflows first.
+    pub fn rows(&self) -> [(&'static str, f64); 11] {
+        [
+            ("Food spawned", self.food_spawned),
+            ("Meat dropped", self.meat_dropped),
+            ("Offspring", self.offspring),
+            ("Metabolism", -self.metabolism),
+            ("Actions", -self.actions),
+            ("Hazards", -self.hazards),
+            ("Meat decayed", -self.meat_decayed),
+            ("Food spoiled", -self.food_spoiled),
+            ("Indigestion", -self.indigestion),
+            ("Deaths", -self.deaths),
+            ("Capped intake", -self.capped),
+        ]
+    }
+}
+
+/// Total energy held by entities (including those killed but not yet swept), food and meat.
+pub fn energy_stock(arena: &EntityArena, food: &[FoodItem], meat: &[MeatItem]) -> f64 {
+    let entities: f64 = arena.iter_alive().map(|(_, e)| e.energy as f64).sum();
+    let food: f64 = food.iter().map(|f| f.energy as f64).sum();
+    let meat: f64 = meat.iter().map(|m| m.energy as f64).sum();
+    entities + food + meat
+}
+
+/// Add `amount` to an entity's energy, respecting the cap. Returns the part lost to the cap.
+pub fn add_capped(energy: &mut f32, amount: f32) -> f32 {
+    let before = *energy;
+    *energy = (before + amount).min(config::MAX_ENTITY_ENERGY);
+    amount - (*energy - before)
+}
+
+/// Debug mode that checks, every tick, that the opening energy stock plus recorded inflows minus
+/// recorded outflows equals the closing stock. Any mismatch means some code path created or
+/// destroyed energy without recording it.
+#[derive(Default)]
+pub struct EnergyAudit {
+    pub enabled: bool,
+    pub last: EnergyLedger,
+    pub opening: f64,
+    pub closing: f64,
+    /// Closing minus expected stock for the last tick.
+    pub discrepancy: f64,
+    /// Stock change between ticks (user edits, scenario actions), which the ledger does not cover.
+    pub external: f64,
+    pub ticks_checked: u64,
+    pub violations: u64,
+    pub worst: f64,
+    last_closing: Option<f64>,
+}
+
+impl EnergyAudit {
+    pub fn toggle(&mut self, enabled: bool) {
+        *self = EnergyAudit {
+            enabled,
+            ..Default::default()
+        };
+    }
+
+    /// Balance one tick's ledger against the measured stock before and after it.
+    pub fn check(&mut self, opening: f64, closing: f64, ledger: EnergyLedger, tick: u64) {
+        self.external = self.last_closing.map_or(0.0, |c| opening - c);
+        self.last_closing = Some(closing);
+        self.opening = opening;
+        self.closing = closing;
+        self.last = ledger;
+        self.discrepancy = closing - (opening + ledger.inflow() - ledger.outflow());
+        self.ticks_checked += 1;
+
+        let tolerance = config::ENERGY_AUDIT_TOLERANCE * (1.0 + opening.abs() * 1e-6);
+        if self.discrepancy.abs() > tolerance {
+            self.violations += 1;
+            self.worst = self.worst.max(self.discrepancy.abs());
+            eprintln!(
+                "[GENESIS] Energy audit: tick {tick} unbalanced by {:+.4} (opening {opening:.2}, in {:.2}, out {:.2}, closing {closing:.2})",
+                self.discrepancy,
+                ledger.inflow(),
+                ledger.outflow()
+            );
+            debug_assert!(
+                self.discrepancy.abs() <= tolerance,
+                "energy ledger unbalanced by {} at tick {tick}",
+                self.discrepancy
+            );
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use macroquad::prelude::*;
 
 /// Stable handle to an entity. The generation field invalidates stale references.
@@ -7,13 +9,54 @@ pub struct EntityId {
     pub generation: u32,
 }
 
+/// Something notable that happened to an entity. Other entities are referred to by UID.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EntityEventKind {
+    Born,
+    AteFood,
+    AteMeat,
+    Attacked(u64),
+    WasAttacked(u64),
+    Killed(u64),
+    Reproduced(u64),
+    EnteredStorm,
+}
+
+impl EntityEventKind {
+    pub fn describe(&self) -> String {
+        match self {
+            EntityEventKind::Born => "Born".to_string(),
+            EntityEventKind::AteFood => "Ate food".to_string(),
+            EntityEventKind::AteMeat => "Ate meat".to_string(),
+            EntityEventKind::Attacked(uid) => format!("Attacked #{uid}"),
+            EntityEventKind::WasAttacked(uid) => format!("Was attacked by #{uid}"),
+            EntityEventKind::Killed(uid) => format!("Killed #{uid}"),
+            EntityEventKind::Reproduced(uid) => format!("Reproduced (child #{uid})"),
+            EntityEventKind::EnteredStorm => "Entered storm".to_string(),
+        }
+    }
+}
+
+/// An event stamped with the entity's age (seconds) when it happened.
+#[derive(Clone, Copy, Debug)]
+pub struct EntityEvent {
+    pub age: f32,
+    pub kind: EntityEventKind,
+}
+
 #[derive(Clone, Debug)]
 pub struct Entity {
+    /// Persistent unique ID, assigned by the arena at spawn. Never reused, survives save/load.
+    pub uid: u64,
     pub pos: Vec2,
     pub prev_pos: Vec2,
     pub velocity: Vec2,
     pub heading: f32,
     pub radius: f32,
+    /// Genetic radius reached when fully grown.
+    pub adult_radius: f32,
+    /// Growth from newborn (0) to adult (1); the radius follows it.
+    pub growth: f32,
     pub color: Color,
     pub energy: f32,
     pub health: f32,
@@ -25,8 +68,64 @@ pub struct Entity {
     pub metabolic_rate: f32,
     pub generation_depth: u32,
     pub parent_id: Option<EntityId>,
+    /// Persistent ID of the parent, valid even after the parent's slot is reused.
+    pub parent_uid: Option<u64>,
     pub offspring_count: u32,
+    /// Entities this one has killed in combat.
+    pub kills: u32,
+    /// Energy given to neighbors through food sharing.
+    pub energy_donated: f32,
+    /// Energy received from neighbors through food sharing.
+    pub energy_received: f32,
+    /// User-assigned name (set from the inspector).
+    pub name: Option<String>,
+    /// User-assigned group label (set from the inspector or selection panel). Entities sharing
+    /// a tag share its color, see `tag_color`.
+    pub tag: Option<String>,
     pub tick_born: u64,
+    /// Aquatic adaptation [0, 1] trading land speed for water speed.
+    pub aquatic: f32,
+    /// Age (seconds) after which vigor and fertility start to decline.
+    pub prime_age: f32,
+    /// Parental care [0, 1]: share of the full feeding rate given to hungry offspring.
+    pub care: f32,
+    /// Combat damage multiplier [0.5, 1.5].
+    pub attack_power: f32,
+    /// Armor [0, 1]: reduces incoming combat damage.
+    pub armor: f32,
+    /// Ranged attack gene [0, 1]: spitting ability and spit damage.
+    pub ranged: f32,
+    /// Taste gene [0, 1]: which food kind the entity digests best.
+    pub taste: f32,
+    /// Circadian gene [0, 1]: diurnal at 0, nocturnal at 1.
+    pub circadian: f32,
+    /// Flocking gene [0, 1]: weight of the cohesion and alignment motors in steering.
+    pub flocking: f32,
+    /// Nepotism gene [0, 1]: bias of food sharing toward relatives.
+    pub nepotism: f32,
+    /// Toxin resistance gene [0, 1]: damage blocked in toxic zones and on toxic ground.
+    pub toxin_resistance: f32,
+    /// Storm resistance gene [0, 1]: storm damage blocked.
+    pub storm_resistance: f32,
+    /// Seconds until the entity can spit again.
+    pub spit_cooldown: f32,
+    /// Whether the entity is currently inside a shelter (recomputed every tick).
+    pub sheltered: bool,
+    /// Remaining ticks during which the entity cannot die (set by the breeding tool).
+    pub protection_ticks: u32,
+    /// Whether the entity was inside the storm last tick (for storm entry events).
+    pub in_storm: bool,
+    /// Most recent events, oldest first, bounded by ENTITY_EVENT_LOG_SIZE. Not saved.
+    pub events: VecDeque<EntityEvent>,
+}
+
+/// Label color for a tag, derived from its text so the same tag always gets the same color.
+pub fn tag_color(tag: &str) -> Color {
+    // FNV-1a hash of the tag picks the hue
+    let hash = tag
+        .bytes()
+        .fold(0x811c9dc5u32, |h, b| (h ^ b as u32).wrapping_mul(0x01000193));
+    macroquad::color::hsl_to_rgb((hash % 360) as f32 / 360.0, 0.75, 0.6)
 }
 
 impl Entity {
@@ -35,25 +134,182 @@ impl Entity {
         let size = genome.body_size();
         let max_health = 80.0 + size * 40.0; // larger = more HP
         Self {
+            uid: 0,
             pos,
             prev_pos: pos,
             velocity: Vec2::ZERO,
             heading: 0.0,
             radius: crate::config::ENTITY_BASE_RADIUS * size,
+            adult_radius: crate::config::ENTITY_BASE_RADIUS * size,
+            growth: 1.0,
             color: genome.body_color(),
             energy: crate::config::INITIAL_ENTITY_ENERGY,
             health: max_health,
             max_health,
             age: 0.0,
             alive: true,
-            speed_multiplier: genome.max_speed(),
+            speed_multiplier: genome.armored_speed(),
             sensor_range: genome.sensor_range(),
-            metabolic_rate: genome.metabolic_rate(),
+            metabolic_rate: genome.metabolic_rate() * genome.maintenance_cost(),
             generation_depth: 0,
             parent_id: None,
+            parent_uid: None,
             offspring_count: 0,
+            kills: 0,
+            energy_donated: 0.0,
+            energy_received: 0.0,
+            name: None,
+            tag: None,
             tick_born: tick,
+            aquatic: genome.aquatic(),
+            prime_age: genome.prime_age(),
+            care: genome.care(),
+            attack_power: genome.attack_power(),
+            armor: genome.armor(),
+            ranged: genome.ranged(),
+            taste: genome.taste(),
+            circadian: genome.circadian(),
+            flocking: genome.flocking(),
+            nepotism: genome.nepotism(),
+            toxin_resistance: genome.toxin_resistance(),
+            storm_resistance: genome.storm_resistance(),
+            spit_cooldown: 0.0,
+            sheltered: false,
+            protection_ticks: 0,
+            in_storm: false,
+            events: VecDeque::from([EntityEvent {
+                age: 0.0,
+                kind: EntityEventKind::Born,
+            }]),
+        }
+    }
+
+    /// The user-assigned name, or `#uid` for unnamed entities.
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| format!("#{}", self.uid))
+    }
+
+    /// Re-derive the genome-determined body attributes after the genome was edited in place.
+    /// Health keeps its current fraction of the new maximum.
+    pub fn apply_genome(&mut self, genome: &crate::genome::Genome) {
+        let size = genome.body_size();
+        let health_frac = self.health / self.max_health;
+        self.max_health = 80.0 + size * 40.0;
+        self.health = health_frac * self.max_health;
+        self.adult_radius = crate::config::ENTITY_BASE_RADIUS * size;
+        self.radius = self.adult_radius * self.size_stage();
+        self.color = genome.body_color();
+        self.speed_multiplier = genome.armored_speed();
+        self.sensor_range = genome.sensor_range();
+        self.metabolic_rate = genome.metabolic_rate() * genome.maintenance_cost();
+        self.aquatic = genome.aquatic();
+        self.prime_age = genome.prime_age();
+        self.care = genome.care();
+        self.attack_power = genome.attack_power();
+        self.armor = genome.armor();
+        self.ranged = genome.ranged();
+        self.taste = genome.taste();
+        self.circadian = genome.circadian();
+        self.flocking = genome.flocking();
+        self.nepotism = genome.nepotism();
+        self.toxin_resistance = genome.toxin_resistance();
+        self.storm_resistance = genome.storm_resistance();
+    }
+
+    /// Share of toxic damage that gets through this entity's toxin resistance.
+    pub fn toxin_exposure(&self) -> f32 {
+        1.0 - crate::config::TOXIN_RESISTANCE_MAX * self.toxin_resistance
+    }
+
+    /// Append an event to the log, dropping the oldest once it is full.
+    pub fn log_event(&mut self, kind: EntityEventKind) {
+        if self.events.len() >= crate::config::ENTITY_EVENT_LOG_SIZE {
+            self.events.pop_front();
         }
+        self.events.push_back(EntityEvent { age: self.age, kind });
+    }
+
+    /// Progress through senescence: 0 up to the prime age, rising to 1 at DEATH_AGE.
+    pub fn senescence(&self) -> f32 {
+        let span = (crate::config::DEATH_AGE - self.prime_age).max(1.0);
+        ((self.age - self.prime_age) / span).clamp(0.0, 1.0)
+    }
+
+    /// Multiplier on speed, sensor range and max health, declining after the prime age.
+    pub fn vigor(&self) -> f32 {
+        1.0 - (1.0 - crate::config::SENESCENCE_MIN_VIGOR) * self.senescence()
+    }
+
+    /// Reproductive capacity [0, 1], falling to zero by DEATH_AGE.
+    pub fn fertility(&self) -> f32 {
+        let s = 1.0 - self.senescence();
+        s * s
+    }
+
+    /// Energy needed to reproduce: REPRODUCTION_THRESHOLD in the prime, rising to
+    /// MAX_ENTITY_ENERGY as fertility falls.
+    pub fn reproduction_threshold(&self) -> f32 {
+        crate::config::REPRODUCTION_THRESHOLD
+            + (crate::config::MAX_ENTITY_ENERGY - crate::config::REPRODUCTION_THRESHOLD) * (1.0 - self.fertility())
+    }
+
+    /// Max speed multiplier after senescence and injuries.
+    pub fn effective_speed(&self) -> f32 {
+        self.speed_multiplier * self.vigor() * self.injury_speed_mult()
+    }
+
+    /// Speed multiplier from injuries: 1 down to INJURY_THRESHOLD of max health, then falling
+    /// linearly to 1 - INJURY_SPEED_PENALTY at zero health.
+    pub fn injury_speed_mult(&self) -> f32 {
+        let frac = (self.health / self.effective_max_health()).clamp(0.0, 1.0);
+        let injury = (1.0 - frac / crate::config::INJURY_THRESHOLD).max(0.0);
+        1.0 - crate::config::INJURY_SPEED_PENALTY * injury
+    }
+
+    /// Speed multiplier on land: swimmers are slower out of the water.
+    pub fn land_speed_mult(&self) -> f32 {
+        1.0 - crate::config::AQUATIC_LAND_SPEED_PENALTY * self.aquatic
+    }
+
+    /// Speed multiplier in water: land specialists barely make headway.
+    pub fn water_speed_mult(&self) -> f32 {
+        crate::config::WATER_BASE_SPEED + (1.0 - crate::config::WATER_BASE_SPEED) * self.aquatic
+    }
+
+    /// Sensor range multiplier after senescence.
+    pub fn effective_sensor_range(&self) -> f32 {
+        self.sensor_range * self.vigor()
+    }
+
+    /// How active the entity's rhythm makes it at `darkness` (0 at noon, 1 at midnight), in
+    /// [-1, 1]: positive in its active phase, negative while it should be resting, zero for
+    /// entities without a preference.
+    pub fn circadian_activity(&self, darkness: f32) -> f32 {
+        (self.circadian * 2.0 - 1.0) * (darkness * 2.0 - 1.0)
+    }
+
+    /// Max health after senescence.
+    pub fn effective_max_health(&self) -> f32 {
+        self.max_health * self.vigor() * self.size_stage()
+    }
+
+    /// Current size relative to the adult size: JUVENILE_SIZE_FRACTION at birth, 1 when grown.
+    /// Scales max health and metabolism.
+    pub fn size_stage(&self) -> f32 {
+        let juvenile = crate::config::JUVENILE_SIZE_FRACTION;
+        juvenile + (1.0 - juvenile) * self.growth
+    }
+
+    /// Set the growth stage and resize the body to match.
+    pub fn set_growth(&mut self, growth: f32) {
+        self.growth = growth.clamp(0.0, 1.0);
+        self.radius = self.adult_radius * self.size_stage();
+    }
+
+    /// Body mass relative to a base-sized adult; bigger bodies shove smaller ones aside.
+    pub fn mass(&self) -> f32 {
+        let r = self.radius / crate::config::ENTITY_BASE_RADIUS;
+        r * r
     }
 
     /// Create with a random heading.
@@ -75,6 +331,36 @@ pub struct EntityArena {
     pub generations: Vec<u32>,
     pub free_list: Vec<u32>,
     pub count: usize,
+    /// Next persistent UID to hand out. Monotonically increasing, starts at 1.
+    pub next_uid: u64,
+    /// Slot indices of the living, ascending, so iteration costs the population rather than
+    /// the capacity.
+    alive: Vec<u32>,
+}
+
+/// How well the arena's slots are used.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ArenaOccupancy {
+    pub capacity: usize,
+    pub alive: usize,
+    /// Highest occupied slot + 1: the span every slot-indexed loop still walks.
+    pub span: usize,
+}
+
+impl ArenaOccupancy {
+    /// Fraction of all slots holding a living entity.
+    pub fn occupancy(&self) -> f32 {
+        self.alive as f32 / self.capacity.max(1) as f32
+    }
+
+    /// Fraction of the walked span that is empty holes left by the dead.
+    pub fn fragmentation(&self) -> f32 {
+        if self.span == 0 {
+            0.0
+        } else {
+            1.0 - self.alive as f32 / self.span as f32
+        }
+    }
 }
 
 impl EntityArena {
@@ -84,29 +370,60 @@ impl EntityArena {
             generations: vec![0; capacity],
             free_list: (0..capacity as u32).rev().collect(),
             count: 0,
+            next_uid: 1,
+            alive: Vec::new(),
         }
     }
 
-    pub fn spawn(&mut self, entity: Entity) -> Option<EntityId> {
-        if let Some(index) = self.free_list.pop() {
-            let idx = index as usize;
-            self.entities[idx] = Some(entity);
-            self.count += 1;
-            Some(EntityId {
-                index,
-                generation: self.generations[idx],
-            })
-        } else {
-            // Grow the arena
-            let index = self.entities.len() as u32;
-            self.entities.push(Some(entity));
-            self.generations.push(0);
-            self.count += 1;
-            Some(EntityId {
-                index,
-                generation: 0,
-            })
+    /// Rebuild an arena from saved slots; the free list, count and alive index are derived.
+    pub fn from_slots(entities: Vec<Option<Entity>>, generations: Vec<u32>, next_uid: u64) -> Self {
+        let free_list = (0..entities.len() as u32).rev().filter(|&i| entities[i as usize].is_none()).collect();
+        let alive: Vec<u32> = (0..entities.len() as u32).filter(|&i| entities[i as usize].is_some()).collect();
+        Self {
+            count: alive.len(),
+            entities,
+            generations,
+            free_list,
+            next_uid,
+            alive,
+        }
+    }
+
+    fn index_alive(&mut self, index: u32) {
+        if let Err(pos) = self.alive.binary_search(&index) {
+            self.alive.insert(pos, index);
+        }
+    }
+
+    /// Add empty slots up to `capacity`. The new slots are handed out lowest index first.
+    pub fn grow(&mut self, capacity: usize) {
+        let old = self.entities.len();
+        if capacity <= old {
+            return;
         }
+        self.entities.resize(capacity, None);
+        self.generations.resize(capacity, 0);
+        // The free list is popped from the back; keep the new slots below the recycled ones
+        let mut free: Vec<u32> = (old as u32..capacity as u32).rev().collect();
+        free.append(&mut self.free_list);
+        self.free_list = free;
+    }
+
+    /// Insert an entity into a free slot, assigning it the next persistent UID. Returns None
+    /// when every slot is taken: the arena only grows through `grow`, so the per-slot stores
+    /// kept alongside it grow with it.
+    pub fn spawn(&mut self, mut entity: Entity) -> Option<EntityId> {
+        let index = self.free_list.pop()?;
+        entity.uid = self.next_uid;
+        self.next_uid += 1;
+        let idx = index as usize;
+        self.entities[idx] = Some(entity);
+        self.count += 1;
+        self.index_alive(index);
+        Some(EntityId {
+            index,
+            generation: self.generations[idx],
+        })
     }
 
     pub fn despawn(&mut self, id: EntityId) -> bool {
@@ -119,6 +436,9 @@ impl EntityArena {
             self.generations[idx] += 1;
             self.free_list.push(id.index);
             self.count -= 1;
+            if let Ok(pos) = self.alive.binary_search(&id.index) {
+                self.alive.remove(pos);
+            }
             true
         } else {
             false
@@ -143,6 +463,14 @@ impl EntityArena {
         }
     }
 
+    /// Look up the current handle of an alive entity by its persistent UID.
+    pub fn find_by_uid(&self, uid: u64) -> Option<EntityId> {
+        self.iter_alive().find(|(_, e)| e.uid == uid).map(|(idx, _)| EntityId {
+            index: idx as u32,
+            generation: self.generations[idx],
+        })
+    }
+
     pub fn get_by_index(&self, index: usize) -> Option<&Entity> {
         self.entities.get(index).and_then(|e| e.as_ref())
     }
@@ -154,26 +482,55 @@ impl EntityArena {
     /// Remove dead entities and reclaim their slots.
     pub fn sweep_dead(&mut self) -> Vec<(usize, Vec2)> {
         let mut dead_positions = Vec::new();
-        for (idx, slot) in self.entities.iter_mut().enumerate() {
-            if let Some(entity) = slot {
-                if !entity.alive {
-                    dead_positions.push((idx, entity.pos));
-                    *slot = None;
-                    self.generations[idx] += 1;
-                    self.free_list.push(idx as u32);
-                    self.count -= 1;
-                }
-            }
-        }
+        let Self { entities, generations, free_list, count, alive, .. } = self;
+        alive.retain(|&index| {
+            let idx = index as usize;
+            let Some(entity) = entities[idx].take_if(|e| !e.alive) else {
+                return true;
+            };
+            dead_positions.push((idx, entity.pos));
+            generations[idx] += 1;
+            free_list.push(index);
+            *count -= 1;
+            false
+        });
         dead_positions
     }
 
-    /// Iterate over (index, &Entity) for all alive entities.
+    /// Iterate over (index, &Entity) for all alive entities, in slot order.
     pub fn iter_alive(&self) -> impl Iterator<Item = (usize, &Entity)> {
-        self.entities
+        self.alive
             .iter()
-            .enumerate()
-            .filter_map(|(i, slot)| slot.as_ref().map(|e| (i, e)))
+            .filter_map(|&i| self.entities[i as usize].as_ref().map(|e| (i as usize, e)))
+    }
+
+    /// Iterate over (index, &mut Entity) for all alive entities, in slot order. Walks the
+    /// living index, so the cost follows the population rather than the capacity.
+    pub fn iter_alive_mut(&mut self) -> impl Iterator<Item = (usize, &mut Entity)> {
+        // The index is ascending, so each living slot is split off the front of what remains
+        let mut rest: &mut [Option<Entity>] = &mut self.entities;
+        let mut start = 0;
+        self.alive.iter().filter_map(move |&i| {
+            let i = i as usize;
+            let (slot, tail) = std::mem::take(&mut rest)[i - start..].split_first_mut()?;
+            rest = tail;
+            start = i + 1;
+            slot.as_mut().map(|e| (i, e))
+        })
+    }
+
+    /// Slot usage for profiling: capacity, living count and the span slot loops walk.
+    pub fn occupancy(&self) -> ArenaOccupancy {
+        ArenaOccupancy {
+            capacity: self.entities.len(),
+            alive: self.alive.len(),
+            span: self.alive.last().map_or(0, |&i| i as usize + 1),
+        }
+    }
+
+    /// Highest generation depth among the living (0 when empty).
+    pub fn deepest_generation(&self) -> u32 {
+        self.iter_alive().map(|(_, e)| e.generation_depth).max().unwrap_or(0)
     }
 
     pub fn capacity(&self) -> usize {
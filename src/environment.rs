@@ -1,8 +1,13 @@
+use std::cell::OnceCell;
+
 use macroquad::prelude::*;
 use noise::{NoiseFn, Fbm, Perlin};
 
 use crate::config;
-use crate::entity::EntityArena;
+use crate::energy_audit::EnergyLedger;
+use crate::entity::{EntityArena, EntityEventKind};
+use crate::nutrients::NutrientGrid;
+use crate::weather::Weather;
 use crate::world::World;
 
 /// Terrain types with different properties.
@@ -33,12 +38,12 @@ impl TerrainType {
             TerrainType::Plains => 1.0,
             TerrainType::Forest => 2.0,
             TerrainType::Desert => 0.3,
-            TerrainType::Water => 0.0,
+            TerrainType::Water => 0.5, // algae, edible only by swimmers
             TerrainType::Toxic => 0.0,
         }
     }
 
-    /// Energy drain per second on this terrain.
+    /// Health damage per second on this terrain.
     pub fn damage_per_sec(&self) -> f32 {
         match self {
             TerrainType::Toxic => 3.0,
@@ -58,6 +63,162 @@ impl TerrainType {
     }
 }
 
+/// Named terrain layouts for world generation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorldPreset {
+    /// Single fractal noise layer (the original layout).
+    Default,
+    /// Islands scattered across open water.
+    Archipelago,
+    /// A winding river gorge cutting through desert.
+    Canyon,
+    /// A fertile ring around a central lake, surrounded by desert.
+    OasisRing,
+    /// Alternating forest and desert tiles.
+    Checkerboard,
+    /// Mostly open water with scattered wetlands; swimmers are favoured.
+    Aquatic,
+    /// Arid dunes with rare oases, scarce food and frequent storms.
+    Desert,
+}
+
+impl WorldPreset {
+    pub const ALL: [WorldPreset; 7] = [
+        WorldPreset::Default,
+        WorldPreset::Archipelago,
+        WorldPreset::Canyon,
+        WorldPreset::OasisRing,
+        WorldPreset::Checkerboard,
+        WorldPreset::Aquatic,
+        WorldPreset::Desert,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorldPreset::Default => "Default",
+            WorldPreset::Archipelago => "Archipelago",
+            WorldPreset::Canyon => "Canyon",
+            WorldPreset::OasisRing => "Oasis ring",
+            WorldPreset::Checkerboard => "Checkerboard",
+            WorldPreset::Aquatic => "Aquatic",
+            WorldPreset::Desert => "Desert",
+        }
+    }
+
+    /// Identifier used on the command line and in save files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            WorldPreset::Default => "default",
+            WorldPreset::Archipelago => "archipelago",
+            WorldPreset::Canyon => "canyon",
+            WorldPreset::OasisRing => "oasis-ring",
+            WorldPreset::Checkerboard => "checkerboard",
+            WorldPreset::Aquatic => "aquatic",
+            WorldPreset::Desert => "desert",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<WorldPreset> {
+        WorldPreset::ALL.iter().copied().find(|p| p.id() == id)
+    }
+
+    /// Multiplier on the food spawn rate.
+    pub fn food_rate(&self) -> f32 {
+        match self {
+            WorldPreset::Canyon => 0.8,
+            WorldPreset::OasisRing => 0.9,
+            WorldPreset::Aquatic => 0.9,
+            WorldPreset::Desert => 0.6,
+            _ => 1.0,
+        }
+    }
+
+    /// Multiplier on how often storms arrive (the gap between storms is divided by it).
+    pub fn storm_frequency(&self) -> f32 {
+        match self {
+            WorldPreset::Archipelago => 1.2,
+            WorldPreset::Aquatic => 1.4,
+            WorldPreset::Desert => 1.6,
+            _ => 1.0,
+        }
+    }
+
+    /// Terrain at normalized map coordinates (u, v) in [0, 1). `noise` is fractal noise in
+    /// roughly [-1, 1] sampled at the same spot, used to roughen the hand-shaped presets.
+    fn terrain_at(&self, u: f32, v: f32, noise: f32) -> TerrainType {
+        match self {
+            WorldPreset::Default => match noise {
+                n if n < -0.45 => TerrainType::Water,
+                n if n < -0.1 => TerrainType::Forest,
+                n if n < 0.3 => TerrainType::Plains,
+                n if n < 0.55 => TerrainType::Desert,
+                _ => TerrainType::Toxic,
+            },
+            WorldPreset::Archipelago => match noise {
+                n if n < 0.05 => TerrainType::Water,
+                n if n < 0.2 => TerrainType::Forest,
+                n if n < 0.4 => TerrainType::Plains,
+                n if n < 0.6 => TerrainType::Desert,
+                _ => TerrainType::Toxic,
+            },
+            WorldPreset::Canyon => {
+                // River meanders north-south; integer sine periods keep it seamless on the torus
+                let center = 0.5 + 0.18 * (v * std::f32::consts::TAU * 2.0).sin() + noise * 0.04;
+                let d = (u - center).rem_euclid(1.0);
+                let d = d.min(1.0 - d);
+                match d {
+                    d if d < 0.03 => TerrainType::Water,
+                    d if d < 0.08 => TerrainType::Forest,
+                    d if d < 0.16 => TerrainType::Plains,
+                    _ if noise > 0.45 => TerrainType::Toxic,
+                    _ => TerrainType::Desert,
+                }
+            }
+            WorldPreset::OasisRing => {
+                let d = vec2(u - 0.5, v - 0.5).length() + noise * 0.04;
+                match d {
+                    d if d < 0.1 => TerrainType::Water,
+                    d if d < 0.2 => TerrainType::Forest,
+                    d if d < 0.3 => TerrainType::Plains,
+                    _ if noise > 0.5 => TerrainType::Toxic,
+                    _ => TerrainType::Desert,
+                }
+            }
+            WorldPreset::Checkerboard => {
+                let tx = (u * 8.0) as u32;
+                let ty = (v * 8.0) as u32;
+                if (tx + ty).is_multiple_of(2) {
+                    TerrainType::Forest
+                } else {
+                    TerrainType::Desert
+                }
+            }
+            WorldPreset::Aquatic => match noise {
+                n if n < 0.15 => TerrainType::Water,
+                n if n < 0.4 => TerrainType::Forest,
+                n if n < 0.55 => TerrainType::Plains,
+                n if n < 0.7 => TerrainType::Desert,
+                _ => TerrainType::Toxic,
+            },
+            WorldPreset::Desert => match noise {
+                n if n < -0.6 => TerrainType::Water,
+                n if n < -0.45 => TerrainType::Forest,
+                n if n < -0.3 => TerrainType::Plains,
+                n if n < 0.6 => TerrainType::Desert,
+                _ => TerrainType::Toxic,
+            },
+        }
+    }
+
+    /// Noise frequency (features per map width) for this preset.
+    fn noise_scale(&self) -> f64 {
+        match self {
+            WorldPreset::Archipelago => 6.0,
+            _ => 4.0,
+        }
+    }
+}
+
 /// Terrain grid covering the world.
 pub struct TerrainGrid {
     pub cells: Vec<TerrainType>,
@@ -65,30 +226,28 @@ pub struct TerrainGrid {
     pub height: usize,
     pub cell_size: f32,
     inv_cell_size: f32,
+    /// One texel per cell, uploaded on first draw. Terrain is regenerated as a new grid, so the
+    /// cache never goes stale.
+    texture: OnceCell<Texture2D>,
 }
 
 impl TerrainGrid {
-    pub fn generate(world_w: f32, world_h: f32, cell_size: f32, seed: u32) -> Self {
+    pub fn generate(world_w: f32, world_h: f32, cell_size: f32, seed: u32, preset: WorldPreset) -> Self {
         let width = (world_w / cell_size).ceil() as usize;
         let height = (world_h / cell_size).ceil() as usize;
 
         let fbm: Fbm<Perlin> = Fbm::new(seed);
+        let scale = preset.noise_scale();
         let mut cells = Vec::with_capacity(width * height);
 
         for y in 0..height {
             for x in 0..width {
-                let nx = x as f64 / width as f64 * 4.0;
-                let ny = y as f64 / height as f64 * 4.0;
+                let nx = x as f64 / width as f64 * scale;
+                let ny = y as f64 / height as f64 * scale;
                 let val = fbm.get([nx, ny]) as f32;
-
-                let terrain = match val {
-                    v if v < -0.45 => TerrainType::Water,
-                    v if v < -0.1 => TerrainType::Forest,
-                    v if v < 0.3 => TerrainType::Plains,
-                    v if v < 0.55 => TerrainType::Desert,
-                    _ => TerrainType::Toxic,
-                };
-                cells.push(terrain);
+                let u = (x as f32 + 0.5) / width as f32;
+                let v = (y as f32 + 0.5) / height as f32;
+                cells.push(preset.terrain_at(u, v, val));
             }
         }
 
@@ -98,6 +257,7 @@ impl TerrainGrid {
             height,
             cell_size,
             inv_cell_size: 1.0 / cell_size,
+            texture: OnceCell::new(),
         }
     }
 
@@ -144,29 +304,164 @@ pub struct Storm {
     pub radius: f32,
     pub velocity: Vec2,
     pub timer: f32,
+    /// Damage and push multiplier, rolled per storm.
+    pub strength: f32,
+    /// Radius of the calm eye as a fraction of `radius`.
+    pub eye: f32,
+}
+
+impl Storm {
+    /// A new storm with a random heading, strength and eye size.
+    pub fn new(center: Vec2, radius: f32, rng: &mut impl ::rand::Rng) -> Self {
+        Self {
+            center,
+            radius,
+            velocity: Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU)) * 30.0,
+            timer: config::STORM_DURATION,
+            strength: rng.gen_range(config::STORM_STRENGTH_MIN..config::STORM_STRENGTH_MAX),
+            eye: rng.gen_range(config::STORM_EYE_MIN..config::STORM_EYE_MAX),
+        }
+    }
+
+    /// Radial profile in [0, 1] at `dist` from the center: calm in the eye, rising to 1 at the
+    /// peak ring just outside it, then easing to STORM_EDGE_INTENSITY at the edge.
+    pub fn profile(&self, dist: f32) -> f32 {
+        let d = dist / self.radius.max(1.0);
+        let peak = (self.eye + config::STORM_PEAK_WIDTH).min(0.95);
+        if d >= 1.0 || d <= self.eye {
+            0.0
+        } else if d < peak {
+            let t = (d - self.eye) / (peak - self.eye);
+            t * t * (3.0 - 2.0 * t)
+        } else {
+            1.0 + (config::STORM_EDGE_INTENSITY - 1.0) * (d - peak) / (1.0 - peak)
+        }
+    }
+
+    /// Damage and push multiplier at `dist` from the center.
+    pub fn intensity(&self, dist: f32) -> f32 {
+        self.strength * self.profile(dist)
+    }
+}
+
+/// User-placed wall segment. Blocks movement and sensor rays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wall {
+    pub a: Vec2,
+    pub b: Vec2,
+}
+
+impl Wall {
+    /// Closest point on the segment to `p`.
+    pub fn closest_point(&self, p: Vec2) -> Vec2 {
+        let ab = self.b - self.a;
+        let len_sq = ab.length_squared();
+        if len_sq < 1e-6 {
+            return self.a;
+        }
+        let t = ((p - self.a).dot(ab) / len_sq).clamp(0.0, 1.0);
+        self.a + ab * t
+    }
+
+    /// Distance along a ray to this segment, if the ray crosses it within `max_dist`.
+    pub fn ray_intersection(&self, origin: Vec2, dir: Vec2, max_dist: f32) -> Option<f32> {
+        let seg = self.b - self.a;
+        let denom = dir.perp_dot(seg);
+        if denom.abs() < 1e-6 {
+            return None; // parallel
+        }
+        let to_a = self.a - origin;
+        let t = to_a.perp_dot(seg) / denom;
+        let u = to_a.perp_dot(dir) / denom;
+        if (0.0..=max_dist).contains(&t) && (0.0..=1.0).contains(&u) {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+/// User-placed toxic hazard: drains energy and health of entities inside, strongest at the centre.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ToxicZone {
+    pub pos: Vec2,
+    pub radius: f32,
+    /// Damage multiplier applied to TOXIC_ZONE_DAMAGE.
+    pub intensity: f32,
+}
+
+impl ToxicZone {
+    /// Damage multiplier at `pos`: intensity at the centre, falling linearly to 0 at the edge.
+    pub fn exposure(&self, pos: Vec2, world: &World) -> f32 {
+        let dist = world.distance_sq(self.pos, pos).sqrt();
+        (1.0 - dist / self.radius).max(0.0) * self.intensity
+    }
+}
+
+/// User-placed portal pair: an entity entering either endpoint disc comes out of the other one,
+/// moving the same way. Sensor rays stop at the endpoints.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Portal {
+    pub a: Vec2,
+    pub b: Vec2,
+}
+
+impl Portal {
+    /// Distance along a ray to the nearer endpoint disc, if the ray reaches one within `max_dist`.
+    pub fn ray_intersection(&self, origin: Vec2, dir: Vec2, max_dist: f32) -> Option<f32> {
+        let r_sq = config::PORTAL_RADIUS * config::PORTAL_RADIUS;
+        [self.a, self.b]
+            .into_iter()
+            .filter_map(|center| {
+                let to_center = center - origin;
+                let along = to_center.dot(dir);
+                let miss_sq = to_center.length_squared() - along * along;
+                if miss_sq > r_sq {
+                    return None;
+                }
+                let t = (along - (r_sq - miss_sq).sqrt()).max(0.0);
+                (along >= 0.0 && t <= max_dist).then_some(t)
+            })
+            .min_by(f32::total_cmp)
+    }
 }
 
 /// Full environment state.
 pub struct EnvironmentState {
     pub terrain: TerrainGrid,
+    pub world_preset: WorldPreset,
+    /// Seed the terrain noise was generated from; independent of the simulation seed.
+    pub terrain_seed: u32,
     pub time_of_day: f32, // [0, 1) where 0.5 = noon
     pub day_progress: f32, // total time in current cycle
     pub season: Season,
     pub season_progress: f32,
     pub storm: Option<Storm>,
     pub storm_cooldown: f32,
+    pub walls: Vec<Wall>,
+    pub toxic_zones: Vec<ToxicZone>,
+    pub portals: Vec<Portal>,
+    pub weather: Weather,
+    pub nutrients: NutrientGrid,
 }
 
 impl EnvironmentState {
     pub fn new(world_w: f32, world_h: f32, seed: u32) -> Self {
         Self {
-            terrain: TerrainGrid::generate(world_w, world_h, 50.0, seed),
+            terrain: TerrainGrid::generate(world_w, world_h, 50.0, seed, WorldPreset::Default),
+            world_preset: WorldPreset::Default,
+            terrain_seed: seed,
             time_of_day: 0.25, // start at dawn
             day_progress: 0.0,
             season: Season::Spring,
             season_progress: 0.0,
             storm: None,
             storm_cooldown: config::STORM_INTERVAL_MIN,
+            walls: Vec::new(),
+            toxic_zones: Vec::new(),
+            portals: Vec::new(),
+            weather: Weather::new(world_w, world_h, 50.0),
+            nutrients: NutrientGrid::new(world_w, world_h, 50.0),
         }
     }
 
@@ -195,19 +490,36 @@ impl EnvironmentState {
             storm.center = world.wrap(storm.center);
             if storm.timer <= 0.0 {
                 self.storm = None;
-                self.storm_cooldown = rng.gen_range(config::STORM_INTERVAL_MIN..config::STORM_INTERVAL_MAX);
+                self.storm_cooldown = rng.gen_range(config::STORM_INTERVAL_MIN..config::STORM_INTERVAL_MAX)
+                    / self.world_preset.storm_frequency();
             }
         } else {
             self.storm_cooldown -= dt;
             if self.storm_cooldown <= 0.0 {
-                self.storm = Some(Storm {
-                    center: vec2(rng.gen_range(0.0..world.width), rng.gen_range(0.0..world.height)),
-                    radius: config::STORM_RADIUS,
-                    velocity: Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU)) * 30.0,
-                    timer: config::STORM_DURATION,
-                });
+                let center = vec2(rng.gen_range(0.0..world.width), rng.gen_range(0.0..world.height));
+                self.storm = Some(Storm::new(center, config::STORM_RADIUS, rng));
             }
         }
+
+        // Wind gusts and rain (storms rain too)
+        let wind = self.wind();
+        let storm = self.storm.as_ref().map(|s| (s.center, s.radius));
+        self.weather.tick(wind, storm, dt, world, rng);
+        self.nutrients.decay(dt);
+    }
+
+    /// Replace the terrain with a freshly generated layout.
+    pub fn regenerate_terrain(&mut self, preset: WorldPreset, seed: u32) {
+        let t = &self.terrain;
+        let (w, h) = (t.width as f32 * t.cell_size, t.height as f32 * t.cell_size);
+        self.terrain = TerrainGrid::generate(w, h, t.cell_size, seed, preset);
+        self.world_preset = preset;
+        self.terrain_seed = seed;
+    }
+
+    /// Seconds until the next storm, or None while one is active.
+    pub fn storm_eta(&self) -> Option<f32> {
+        self.storm.is_none().then_some(self.storm_cooldown.max(0.0))
     }
 
     /// Is it daytime? (roughly 6am to 6pm)
@@ -223,99 +535,319 @@ impl EnvironmentState {
         0.3 + raw * 0.7
     }
 
-    /// Food spawn multiplier considering season + time of day.
+    /// Darkness [0, 1]: 0 at noon, 1 at midnight.
+    pub fn darkness(&self) -> f32 {
+        (1.0 - self.day_brightness()) / 0.7
+    }
+
+    /// Position in the yearly cycle [0, 1): 0 = start of spring, 0.5 = start of autumn.
+    pub fn year_phase(&self) -> f32 {
+        let season_idx = match self.season {
+            Season::Spring => 0.0,
+            Season::Summer => 1.0,
+            Season::Autumn => 2.0,
+            Season::Winter => 3.0,
+        };
+        (season_idx + self.season_progress) * 0.25
+    }
+
+    /// Seasonal hemisphere bias [-1, 1]: +1 at midsummer (north favored), -1 at midwinter (south favored).
+    pub fn hemisphere_bias(&self) -> f32 {
+        ((self.year_phase() - 0.125) * std::f32::consts::TAU).sin()
+    }
+
+    /// Food spawn multiplier for a latitude: the hemisphere gradient times the climate zone.
+    /// North is the top of the map (y = 0).
+    pub fn latitude_food_multiplier(&self, y: f32, world_h: f32) -> f32 {
+        let latitude = 1.0 - 2.0 * (y / world_h).clamp(0.0, 1.0); // +1 north, -1 south
+        let gradient = if config::LATITUDE_GRADIENT_ENABLED {
+            (1.0 + config::LATITUDE_GRADIENT_STRENGTH * self.hemisphere_bias() * latitude).max(0.0)
+        } else {
+            1.0
+        };
+        gradient * self.climate_zone_multiplier(latitude)
+    }
+
+    /// How strongly the season bites at a latitude in [-1, 1]: the equator seasonality inside the
+    /// equator band, rising smoothly to the pole seasonality at either edge.
+    pub fn seasonality(latitude: f32) -> f32 {
+        if !config::CLIMATE_ZONES_ENABLED {
+            return 1.0;
+        }
+        let band = config::CLIMATE_EQUATOR_BAND;
+        let t = ((latitude.abs() - band) / (1.0 - band)).clamp(0.0, 1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+        config::CLIMATE_EQUATOR_SEASONALITY
+            + (config::CLIMATE_POLE_SEASONALITY - config::CLIMATE_EQUATOR_SEASONALITY) * t
+    }
+
+    /// Rescales the global season multiplier (already in `food_rate_multiplier`) to the local
+    /// climate: the season's departure from 1 is scaled by the seasonality, so a winter that
+    /// halves food globally barely dents the equator and nearly empties the poles.
+    pub fn climate_zone_multiplier(&self, latitude: f32) -> f32 {
+        let season = self.season.food_multiplier();
+        let local = (1.0 + (season - 1.0) * Self::seasonality(latitude)).max(0.0);
+        local / season
+    }
+
+    /// Global wind: a prevailing breeze that veers through the year, drifting gusts, and the
+    /// active storm's drift.
+    pub fn wind(&self) -> Vec2 {
+        let base = Vec2::from_angle(self.year_phase() * std::f32::consts::TAU) * config::WIND_BASE_SPEED
+            + self.weather.gust;
+        match self.storm {
+            Some(ref storm) => base + storm.velocity * config::STORM_WIND_FACTOR * storm.strength,
+            None => base,
+        }
+    }
+
+    /// Whether a position lies inside any user-placed toxic zone.
+    pub fn in_toxic_zone(&self, pos: Vec2, world: &World) -> bool {
+        self.toxic_zones
+            .iter()
+            .any(|z| world.distance_sq(z.pos, pos) < z.radius * z.radius)
+    }
+
+    /// Food spawn multiplier considering world preset, season and time of day.
     pub fn food_rate_multiplier(&self) -> f32 {
         let season_mult = self.season.food_multiplier();
         let day_mult = if self.is_day() { 1.5 } else { 0.5 };
-        season_mult * day_mult
+        self.world_preset.food_rate() * season_mult * day_mult
     }
 }
 
-/// Apply terrain effects to entities (damage from toxic, push from water).
-pub fn apply_terrain_effects(arena: &mut EntityArena, terrain: &TerrainGrid, _world: &World, dt: f32) {
-    for slot in arena.entities.iter_mut() {
-        if let Some(entity) = slot {
-            let t = terrain.get_at(entity.pos);
-            let damage = t.damage_per_sec() * dt;
-            if damage > 0.0 {
-                entity.energy -= damage;
-                entity.health -= damage;
+/// Apply terrain effects to entities (damage from toxic, drag and drain from water).
+/// Aquatic adaptation reduces water drag and drain; below DROWNING_AQUATIC_THRESHOLD an entity
+/// also drowns, losing health the faster the less adapted it is.
+pub fn apply_terrain_effects(arena: &mut EntityArena, terrain: &TerrainGrid, ledger: &mut EnergyLedger, dt: f32) {
+    for (_, entity) in arena.iter_alive_mut() {
+        let t = terrain.get_at(entity.pos);
+        entity.health -= t.damage_per_sec() * entity.toxin_exposure() * dt;
+
+        if t == TerrainType::Water {
+            let landlubber = 1.0 - entity.aquatic;
+            entity.velocity *= (1.0 - config::WATER_DRAG * landlubber).powf(dt * config::BASE_SIM_HZ);
+            let drain = config::WATER_ENERGY_DRAIN * (1.0 - config::AQUATIC_DRAIN_REDUCTION * entity.aquatic);
+            entity.energy -= drain * dt;
+            ledger.hazards += (drain * dt) as f64;
+
+            let drowning = 1.0 - entity.aquatic / config::DROWNING_AQUATIC_THRESHOLD;
+            if drowning > 0.0 {
+                entity.health -= config::DROWNING_DAMAGE * drowning * dt;
             }
+        }
+    }
+}
+
+/// Damage the health of entities inside user-placed toxic zones, reduced by toxin resistance.
+pub fn apply_toxic_zones(arena: &mut EntityArena, zones: &[ToxicZone], world: &World, dt: f32) {
+    if zones.is_empty() {
+        return;
+    }
+    for (_, entity) in arena.iter_alive_mut() {
+        // Overlapping zones add up
+        let exposure: f32 = zones.iter().map(|z| z.exposure(entity.pos, world)).sum();
+        entity.health -= config::TOXIC_ZONE_DAMAGE * exposure * entity.toxin_exposure() * dt;
+    }
+}
 
-            // Push entities out of water
-            if t == TerrainType::Water {
-                // Slow them down heavily and drain energy
-                entity.velocity *= 0.9;
-                entity.energy -= 1.0 * dt;
+/// Move entities that entered a portal endpoint to just outside the other endpoint, ahead of
+/// their direction of travel so they do not fall straight back in. Velocity is kept.
+pub fn apply_portals(arena: &mut EntityArena, portals: &[Portal], world: &World) {
+    if portals.is_empty() {
+        return;
+    }
+    let r_sq = config::PORTAL_RADIUS * config::PORTAL_RADIUS;
+    for (_, entity) in arena.iter_alive_mut() {
+        let exit = portals.iter().find_map(|p| {
+            if world.distance_sq(entity.pos, p.a) < r_sq {
+                Some(p.b)
+            } else if world.distance_sq(entity.pos, p.b) < r_sq {
+                Some(p.a)
+            } else {
+                None
             }
+        });
+        if let Some(exit) = exit {
+            let dir = entity.velocity.try_normalize().unwrap_or(Vec2::from_angle(entity.heading));
+            let pos = world.wrap(exit + dir * (config::PORTAL_RADIUS + entity.radius + 1.0));
+            entity.pos = pos;
+            entity.prev_pos = pos;
         }
     }
 }
 
-/// Apply storm effects to entities within the storm radius.
-/// Entities on Forest terrain receive shelter (reduced damage and push).
-pub fn apply_storm_effects(arena: &mut EntityArena, storm: &Storm, world: &World, terrain: &TerrainGrid, dt: f32) {
-    for slot in arena.entities.iter_mut() {
-        if let Some(entity) = slot {
-            let dist_sq = world.distance_sq(entity.pos, storm.center);
-            if dist_sq < storm.radius * storm.radius {
-                // Shelter: forest terrain reduces storm damage by 70%
-                let terrain_type = terrain.get_at(entity.pos);
-                let shelter_mult = if terrain_type == TerrainType::Forest { 0.3 } else { 1.0 };
-
-                // Storm damage
-                entity.energy -= config::STORM_DAMAGE * shelter_mult * dt;
-                // Wind push
-                let push_dir = world.delta(storm.center, entity.pos);
-                if push_dir.length_squared() > 0.001 {
-                    entity.velocity += push_dir.normalize() * 20.0 * shelter_mult * dt;
-                }
+/// Apply storm effects to entities within the storm radius, scaled by the storm's intensity at
+/// their distance from the center (nothing in the calm eye). Entities on Forest terrain receive shelter (reduced damage and push), and entities inside a
+/// built shelter are almost fully protected.
+pub fn apply_storm_effects(
+    arena: &mut EntityArena,
+    storm: &Storm,
+    world: &World,
+    terrain: &TerrainGrid,
+    ledger: &mut EnergyLedger,
+    dt: f32,
+) {
+    for (_, entity) in arena.iter_alive_mut() {
+        let dist = world.distance(entity.pos, storm.center);
+        let inside = dist < storm.radius;
+        if inside && !entity.in_storm {
+            entity.log_event(EntityEventKind::EnteredStorm);
+        }
+        entity.in_storm = inside;
+        if inside {
+            // Shelter: forest terrain reduces storm damage by 70%
+            let terrain_type = terrain.get_at(entity.pos);
+            let mut shelter_mult = if terrain_type == TerrainType::Forest { 0.3 } else { 1.0 };
+            if entity.sheltered {
+                shelter_mult *= 1.0 - config::SHELTER_STORM_PROTECTION;
+            }
+
+            let intensity = storm.intensity(dist) * shelter_mult;
+
+            // Storm damage, reduced by storm resistance
+            let damage = config::STORM_DAMAGE
+                * intensity
+                * (1.0 - config::STORM_RESISTANCE_MAX * entity.storm_resistance)
+                * dt;
+            entity.energy -= damage;
+            ledger.hazards += damage as f64;
+            // Wind push
+            let push_dir = world.delta(storm.center, entity.pos);
+            if push_dir.length_squared() > 0.001 {
+                entity.velocity += push_dir.normalize() * config::STORM_PUSH * intensity * dt;
             }
         }
     }
 }
 
-/// Draw terrain grid.
+/// Draw the terrain as one textured quad, one texel per cell.
 pub fn draw_terrain(terrain: &TerrainGrid) {
-    for y in 0..terrain.height {
-        for x in 0..terrain.width {
-            let t = terrain.cells[y * terrain.width + x];
-            let color = t.color();
-            draw_rectangle(
-                x as f32 * terrain.cell_size,
-                y as f32 * terrain.cell_size,
-                terrain.cell_size,
-                terrain.cell_size,
-                color,
-            );
+    let texture = terrain.texture.get_or_init(|| {
+        let bytes: Vec<u8> = terrain
+            .cells
+            .iter()
+            .flat_map(|t| {
+                let c = t.color();
+                [c.r, c.g, c.b, c.a].map(|v| (v * 255.0).round() as u8)
+            })
+            .collect();
+        let texture = Texture2D::from_rgba8(terrain.width as u16, terrain.height as u16, &bytes);
+        texture.set_filter(FilterMode::Nearest);
+        texture
+    });
+    draw_texture_ex(
+        texture,
+        0.0,
+        0.0,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(vec2(
+                terrain.width as f32 * terrain.cell_size,
+                terrain.height as f32 * terrain.cell_size,
+            )),
+            ..Default::default()
+        },
+    );
+}
+
+/// Draw user-placed walls.
+pub fn draw_walls(walls: &[Wall]) {
+    for w in walls {
+        draw_line(w.a.x, w.a.y, w.b.x, w.b.y, config::WALL_THICKNESS, Color::new(0.55, 0.55, 0.6, 0.9));
+        draw_circle(w.a.x, w.a.y, config::WALL_THICKNESS * 0.5, Color::new(0.55, 0.55, 0.6, 0.9));
+        draw_circle(w.b.x, w.b.y, config::WALL_THICKNESS * 0.5, Color::new(0.55, 0.55, 0.6, 0.9));
+    }
+}
+
+/// Draw user-placed portals: two swirling rings joined by a faint dashed line.
+pub fn draw_portals(portals: &[Portal], time: f32) {
+    for p in portals {
+        draw_portal(p.a, p.b, time, 1.0);
+    }
+}
+
+/// Draw one portal pair; `alpha` scales the whole drawing (for previews).
+pub fn draw_portal(a: Vec2, b: Vec2, time: f32, alpha: f32) {
+    let color = |a_mult: f32| Color::new(0.55, 0.45, 1.0, a_mult * alpha);
+    const DASHES: usize = 24;
+    for i in (0..DASHES).step_by(2) {
+        let (s, e) = (a.lerp(b, i as f32 / DASHES as f32), a.lerp(b, (i + 1) as f32 / DASHES as f32));
+        draw_line(s.x, s.y, e.x, e.y, 1.5, color(0.15));
+    }
+    for end in [a, b] {
+        let r = config::PORTAL_RADIUS;
+        draw_circle(end.x, end.y, r, color(0.15));
+        draw_circle_lines(end.x, end.y, r, 2.5, color(0.8));
+        for k in 0..3 {
+            let angle = time * 2.0 + k as f32 * std::f32::consts::TAU / 3.0;
+            let p = end + Vec2::from_angle(angle) * r * 0.6;
+            draw_circle(p.x, p.y, 3.0, color(0.7));
         }
     }
 }
 
-/// Draw storm visual.
-pub fn draw_storm(storm: &Storm) {
-    // Multiple concentric circles for the storm
-    let alpha_base = 0.15;
-    for i in 0..3 {
-        let r = storm.radius * (0.5 + i as f32 * 0.25);
-        let alpha = alpha_base * (1.0 - i as f32 * 0.3);
-        draw_circle(
-            storm.center.x,
-            storm.center.y,
-            r,
-            Color::new(0.4, 0.4, 0.6, alpha),
-        );
+/// Draw user-placed toxic zones.
+pub fn draw_toxic_zones(zones: &[ToxicZone]) {
+    for z in zones {
+        draw_toxic_gradient(z.pos, z.radius, z.intensity, 1.0);
     }
-    // Storm center marker
-    draw_circle(
+}
+
+/// Draw a toxic zone as stacked discs that darken toward the centre, matching the damage falloff.
+/// More intense zones are more opaque; `alpha` scales the whole drawing (for previews).
+pub fn draw_toxic_gradient(pos: Vec2, radius: f32, intensity: f32, alpha: f32) {
+    const RINGS: usize = 8;
+    let ring_alpha = (0.035 + 0.025 * intensity).min(0.14) * alpha;
+    for i in 0..RINGS {
+        let r = radius * (1.0 - i as f32 / RINGS as f32);
+        draw_circle(pos.x, pos.y, r, Color::new(0.6, 0.1, 0.5, ring_alpha));
+    }
+    draw_circle_lines(pos.x, pos.y, radius, 2.0, Color::new(0.8, 0.2, 0.7, 0.5 * alpha));
+}
+
+/// Draw storm visual.
+/// Draw a storm as a dark disc with a clear eye and wind streaks circling it; streaks are longer
+/// and brighter where the storm is most intense and turn faster in stronger storms.
+pub fn draw_storm(storm: &Storm, time: f32) {
+    draw_circle(storm.center.x, storm.center.y, storm.radius, Color::new(0.4, 0.4, 0.6, 0.08));
+    let peak = storm.radius * (storm.eye + config::STORM_PEAK_WIDTH).min(0.95);
+    draw_circle_lines(storm.center.x, storm.center.y, peak, 6.0, Color::new(0.5, 0.5, 0.75, 0.12 * storm.strength));
+    draw_circle_lines(
         storm.center.x,
         storm.center.y,
-        8.0,
-        Color::new(0.6, 0.6, 0.8, 0.4),
+        storm.radius * storm.eye,
+        1.5,
+        Color::new(0.7, 0.75, 0.9, 0.3),
     );
+
+    for i in 0..64 {
+        let dist = storm.radius * (i as f32 * 0.618).fract();
+        let intensity = storm.intensity(dist);
+        if intensity <= 0.0 {
+            continue;
+        }
+        // Deterministic streaks, spread by the golden angle, circling counter-clockwise
+        let angle = i as f32 * 2.399 + time * storm.strength * 40.0 * storm.radius / dist.max(1.0) / 100.0;
+        let p = storm.center + Vec2::from_angle(angle) * dist;
+        let tangent = Vec2::from_angle(angle + std::f32::consts::FRAC_PI_2);
+        let end = p + tangent * 10.0 * intensity;
+        draw_line(p.x, p.y, end.x, end.y, 1.5, Color::new(0.75, 0.8, 0.95, 0.2 + 0.25 * intensity.min(1.5)));
+    }
 }
 
 /// Draw day/night overlay tint (called after all world objects, before HUD).
+/// Ambient light for the night lighting pass: the flat overlay's darkening and blue tint as a
+/// multiplier, white by day.
+pub fn night_ambient(brightness: f32) -> Color {
+    if brightness >= 0.95 {
+        return WHITE;
+    }
+    let darkness = 1.0 - brightness;
+    let level = 1.0 - darkness * 0.6;
+    Color::new(level, level, level + darkness * 0.09, 1.0)
+}
+
 pub fn draw_day_night_overlay(brightness: f32) {
     if brightness < 0.95 {
         let darkness = 1.0 - brightness;
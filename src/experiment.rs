@@ -0,0 +1,408 @@
+// Parameter-sweep experiments: run every combination of the swept options headless, with
+// seeded replicates per cell, and write per-run and aggregated results tables.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::config;
+use crate::environment::WorldPreset;
+use crate::plants::FoodModel;
+use crate::preferences::PrefValue;
+use crate::simulation::SimState;
+use crate::storage;
+
+/// A run-time option an experiment can sweep. Tuning constants in config.rs are compiled in,
+/// so they cannot be swept.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Factor {
+    Preset,
+    FoodModel,
+    /// Initial entity count.
+    Population,
+    SimHz,
+    /// Fixed terrain seed; by default each replicate's terrain follows its simulation seed.
+    TerrainSeed,
+}
+
+impl Factor {
+    pub const ALL: [Factor; 5] = [
+        Factor::Preset,
+        Factor::FoodModel,
+        Factor::Population,
+        Factor::SimHz,
+        Factor::TerrainSeed,
+    ];
+
+    /// Key in the spec's `[matrix]` section and column name in the results.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Factor::Preset => "preset",
+            Factor::FoodModel => "food_model",
+            Factor::Population => "population",
+            Factor::SimHz => "sim_hz",
+            Factor::TerrainSeed => "terrain_seed",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Factor> {
+        Factor::ALL.iter().copied().find(|f| f.id() == id)
+    }
+
+    /// Set this option on a cell, checking the value.
+    fn apply(&self, cell: &mut Cell, value: &PrefValue) -> Result<(), String> {
+        let id = self.id();
+        match (self, value) {
+            (Factor::Preset, PrefValue::Text(t)) => {
+                cell.preset = WorldPreset::from_id(t).ok_or(format!("unknown world preset `{t}`"))?;
+            }
+            (Factor::FoodModel, PrefValue::Text(t)) => {
+                cell.food_model = FoodModel::from_id(t).ok_or(format!("unknown food model `{t}`"))?;
+            }
+            (Factor::Population, PrefValue::Number(n)) => {
+                if n.fract() != 0.0 || *n < 1.0 || *n > config::MAX_ENTITY_COUNT as f32 {
+                    return Err(format!("population must be a whole number from 1 to {}", config::MAX_ENTITY_COUNT));
+                }
+                cell.population = *n as usize;
+            }
+            (Factor::SimHz, PrefValue::Number(n)) => {
+                if !(config::SIM_HZ_MIN..=config::SIM_HZ_MAX).contains(n) {
+                    return Err(format!("sim_hz must be between {} and {}", config::SIM_HZ_MIN, config::SIM_HZ_MAX));
+                }
+                cell.sim_hz = *n;
+            }
+            (Factor::TerrainSeed, PrefValue::Number(n)) => {
+                if n.fract() != 0.0 || *n < 0.0 {
+                    return Err("terrain_seed must be a whole number".to_string());
+                }
+                cell.terrain_seed = Some(*n as u32);
+            }
+            _ => return Err(format!("invalid value for {id}")),
+        }
+        Ok(())
+    }
+}
+
+/// One combination of swept options.
+#[derive(Clone, Debug)]
+pub struct Cell {
+    pub preset: WorldPreset,
+    pub food_model: FoodModel,
+    pub population: usize,
+    pub sim_hz: f32,
+    pub terrain_seed: Option<u32>,
+    /// Swept values in factor order, as written in the results.
+    pub labels: Vec<String>,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            preset: WorldPreset::Default,
+            food_model: FoodModel::Patches,
+            population: config::INITIAL_ENTITY_COUNT,
+            sim_hz: config::BASE_SIM_HZ,
+            terrain_seed: None,
+            labels: Vec::new(),
+        }
+    }
+}
+
+impl Cell {
+    /// Build the simulation for one replicate of this cell.
+    pub fn build(&self, seed: u64) -> SimState {
+        let mut sim = SimState::new(self.population, seed);
+        let terrain_seed = self.terrain_seed.unwrap_or(seed as u32);
+        sim.environment.regenerate_terrain(self.preset, terrain_seed);
+        sim.plants.model = self.food_model;
+        sim.plants.seed(&sim.environment, &sim.world, &mut sim.rng);
+        sim.set_sim_hz(self.sim_hz);
+        sim
+    }
+}
+
+/// An experiment spec, read from a flat subset of TOML:
+///
+/// ```toml
+/// [experiment]
+/// name = "food-models"
+/// ticks = 18000
+/// replicates = 5
+/// seed = 1
+///
+/// [matrix]
+/// preset = ["default", "desert"]
+/// food_model = ["uniform", "patches"]
+/// ```
+#[derive(Debug)]
+pub struct ExperimentSpec {
+    pub name: String,
+    pub ticks: u64,
+    pub replicates: u32,
+    /// Replicate `r` of every cell runs with seed `seed + r`.
+    pub seed: u64,
+    /// Swept factors in file order, each with its levels.
+    pub factors: Vec<(Factor, Vec<PrefValue>)>,
+}
+
+impl ExperimentSpec {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = storage::read_to_string(path).map_err(|e| format!("Read error: {e}"))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut spec = ExperimentSpec {
+            name: "experiment".to_string(),
+            ticks: config::EXPERIMENT_DEFAULT_TICKS,
+            replicates: config::EXPERIMENT_DEFAULT_REPLICATES,
+            seed: 42,
+            factors: Vec::new(),
+        };
+        let mut section = String::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let err = |e: String| format!("Line {}: {e}", line_no + 1);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| err("expected `key = value`".to_string()))?;
+            let (key, value) = (key.trim(), value.trim());
+            match (section.as_str(), key) {
+                ("experiment", "name") => match PrefValue::parse(value).map_err(err)? {
+                    // The name becomes part of the output file names
+                    PrefValue::Text(t)
+                        if !t.is_empty() && t.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+                    {
+                        spec.name = t
+                    }
+                    _ => return Err(err("name must be a string of letters, digits, - and _".to_string())),
+                },
+                ("experiment", "ticks") => spec.ticks = whole_number(value).map_err(err)?,
+                ("experiment", "replicates") => spec.replicates = whole_number(value).map_err(err)? as u32,
+                ("experiment", "seed") => spec.seed = whole_number(value).map_err(err)?,
+                ("matrix", key) => {
+                    let factor = Factor::from_id(key).ok_or_else(|| {
+                        let known: Vec<&str> = Factor::ALL.iter().map(|f| f.id()).collect();
+                        err(format!("unknown factor `{key}` (known: {})", known.join(", ")))
+                    })?;
+                    if spec.factors.iter().any(|(f, _)| *f == factor) {
+                        return Err(err(format!("factor `{key}` given twice")));
+                    }
+                    let levels = parse_levels(value).map_err(err)?;
+                    for level in &levels {
+                        factor.apply(&mut Cell::default(), level).map_err(err)?;
+                    }
+                    spec.factors.push((factor, levels));
+                }
+                _ => return Err(err(format!("unknown key `{key}` in [{section}]"))),
+            }
+        }
+        if spec.ticks == 0 || spec.replicates == 0 {
+            return Err("ticks and replicates must be at least 1".to_string());
+        }
+        Ok(spec)
+    }
+
+    /// Every combination of the factor levels, the last factor varying fastest.
+    pub fn cells(&self) -> Vec<Cell> {
+        let mut cells = vec![Cell::default()];
+        for (factor, levels) in &self.factors {
+            cells = cells
+                .iter()
+                .flat_map(|cell| {
+                    levels.iter().map(move |level| {
+                        let mut cell = cell.clone();
+                        // Levels were checked when the spec was parsed
+                        let _ = factor.apply(&mut cell, level);
+                        cell.labels.push(level_label(level));
+                        cell
+                    })
+                })
+                .collect();
+        }
+        cells
+    }
+}
+
+fn whole_number(value: &str) -> Result<u64, String> {
+    value.parse().map_err(|_| format!("expected a whole number, got `{value}`"))
+}
+
+/// A single value or a `[a, b, c]` list.
+fn parse_levels(value: &str) -> Result<Vec<PrefValue>, String> {
+    let levels = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(list) => list
+            .split(',')
+            .filter(|v| !v.trim().is_empty())
+            .map(PrefValue::parse)
+            .collect::<Result<Vec<_>, _>>()?,
+        None => vec![PrefValue::parse(value)?],
+    };
+    if levels.is_empty() {
+        return Err("a factor needs at least one level".to_string());
+    }
+    Ok(levels)
+}
+
+fn level_label(level: &PrefValue) -> String {
+    match level {
+        PrefValue::Bool(b) => b.to_string(),
+        PrefValue::Number(n) => format!("{n}"),
+        PrefValue::Text(t) => t.clone(),
+    }
+}
+
+/// Metrics recorded for one replicate, in results-table order.
+const METRICS: [&str; 9] = [
+    "final_population",
+    "mean_population",
+    "peak_population",
+    "extinct",
+    "survival_ticks",
+    "avg_generation",
+    "max_generation",
+    "species",
+    "avg_energy",
+];
+
+/// One replicate's values for each of METRICS.
+type Metrics = [f64; METRICS.len()];
+
+/// Run one replicate, sampling the population every EXPERIMENT_SAMPLE_INTERVAL ticks and
+/// stopping early if it dies out.
+fn run_replicate(cell: &Cell, seed: u64, ticks: u64) -> Metrics {
+    let mut sim = cell.build(seed);
+    let (mut samples, mut total, mut peak) = (0u64, 0u64, sim.arena.count);
+    while sim.tick_count < ticks && sim.arena.count > 0 {
+        sim.tick();
+        if sim.tick_count.is_multiple_of(config::EXPERIMENT_SAMPLE_INTERVAL) {
+            samples += 1;
+            total += sim.arena.count as u64;
+        }
+        peak = peak.max(sim.arena.count);
+    }
+    let alive: Vec<_> = sim.arena.iter_alive().map(|(_, e)| e).collect();
+    let n = alive.len().max(1) as f64;
+    [
+        alive.len() as f64,
+        total as f64 / samples.max(1) as f64,
+        peak as f64,
+        if alive.is_empty() { 1.0 } else { 0.0 },
+        sim.tick_count as f64,
+        alive.iter().map(|e| e.generation_depth as f64).sum::<f64>() / n,
+        sim.arena.deepest_generation() as f64,
+        sim.species.species.len() as f64,
+        alive.iter().map(|e| e.energy as f64).sum::<f64>() / n,
+    ]
+}
+
+/// Mean and sample standard deviation.
+fn mean_sd(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n.max(1.0);
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, var.sqrt())
+}
+
+/// Run every cell's replicates on all cores and write `experiment_<name>_runs.csv` (one row
+/// per replicate) and `experiment_<name>_results.csv` (mean and standard deviation per cell).
+/// Returns a one-line summary.
+pub fn run_experiment(spec: &ExperimentSpec) -> Result<String, String> {
+    let cells = spec.cells();
+    let jobs: Vec<(usize, u32)> = (0..cells.len()).flat_map(|c| (0..spec.replicates).map(move |r| (c, r))).collect();
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get()).min(jobs.len());
+    eprintln!(
+        "[GENESIS] Experiment {}: {} cells x {} replicates, {} ticks each, on {workers} threads",
+        spec.name,
+        cells.len(),
+        spec.replicates,
+        spec.ticks
+    );
+
+    let start = Instant::now();
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<(Metrics, f64)>>> = Mutex::new(vec![None; jobs.len()]);
+    let worker = || {
+        while let Some(&(c, r)) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+            let run_start = Instant::now();
+            let metrics = run_replicate(&cells[c], spec.seed + r as u64, spec.ticks);
+            let secs = run_start.elapsed().as_secs_f64();
+            let mut results = results.lock().unwrap_or_else(|e| e.into_inner());
+            results[c * spec.replicates as usize + r as usize] = Some((metrics, secs));
+            let done = results.iter().filter(|r| r.is_some()).count();
+            eprintln!(
+                "[GENESIS] Experiment {done}/{}: cell {} [{}] seed {} -> population {}",
+                jobs.len(),
+                c + 1,
+                cells[c].labels.join(", "),
+                spec.seed + r as u64,
+                metrics[0]
+            );
+        }
+    };
+    if workers <= 1 {
+        worker();
+    } else {
+        std::thread::scope(|s| {
+            for _ in 0..workers {
+                s.spawn(worker);
+            }
+        });
+    }
+    let results: Vec<_> = results.into_inner().unwrap_or_else(|e| e.into_inner()).into_iter().flatten().collect();
+
+    let factor_ids: Vec<&str> = spec.factors.iter().map(|(f, _)| f.id()).collect();
+    let header = format!(
+        "# genesis experiment={} ticks={} replicates={} seed={}",
+        spec.name, spec.ticks, spec.replicates, spec.seed
+    );
+    let mut runs = format!("{header}\ncell,{}seed,{},wall_seconds\n", prefix_list(&factor_ids), METRICS.join(","));
+    let mut table = format!("{header}\ncell,{}replicates", prefix_list(&factor_ids));
+    for m in METRICS {
+        table.push_str(&format!(",{m}_mean,{m}_sd"));
+    }
+    table.push('\n');
+    for (c, cell) in cells.iter().enumerate() {
+        let cell_runs = &results[c * spec.replicates as usize..(c + 1) * spec.replicates as usize];
+        for (r, (metrics, secs)) in cell_runs.iter().enumerate() {
+            let values: Vec<String> = metrics.iter().map(|v| format!("{v:.4}")).collect();
+            runs.push_str(&format!(
+                "{},{}{},{},{secs:.2}\n",
+                c + 1,
+                prefix_list(&cell.labels),
+                spec.seed + r as u64,
+                values.join(",")
+            ));
+        }
+        table.push_str(&format!("{},{}{}", c + 1, prefix_list(&cell.labels), spec.replicates));
+        for m in 0..METRICS.len() {
+            let values: Vec<f64> = cell_runs.iter().map(|(metrics, _)| metrics[m]).collect();
+            let (mean, sd) = mean_sd(&values);
+            table.push_str(&format!(",{mean:.4},{sd:.4}"));
+        }
+        table.push('\n');
+    }
+
+    let runs_path = format!("experiment_{}_runs.csv", spec.name);
+    let results_path = format!("experiment_{}_results.csv", spec.name);
+    storage::write(&runs_path, runs).map_err(|e| format!("Write error: {e}"))?;
+    storage::write(&results_path, table).map_err(|e| format!("Write error: {e}"))?;
+    Ok(format!(
+        "{} runs in {:.1}s → {results_path} (per run: {runs_path})",
+        jobs.len(),
+        start.elapsed().as_secs_f64()
+    ))
+}
+
+/// Values joined with commas and followed by one, or nothing for an empty list.
+fn prefix_list<S: AsRef<str>>(values: &[S]) -> String {
+    values.iter().map(|v| format!("{},", v.as_ref())).collect()
+}
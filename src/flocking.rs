@@ -0,0 +1,81 @@
+use macroquad::prelude::*;
+
+use crate::config;
+use crate::entity::EntityArena;
+use crate::signals::SignalState;
+use crate::spatial_hash::SpatialHash;
+use crate::world::World;
+
+/// What an entity perceives of its flock: the neighbours within FLOCK_RANGE that broadcast a
+/// signal close to its own color.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FlockView {
+    /// Flockmates relative to FLOCK_DENSITY_SCALE, in [0, 1].
+    pub density: f32,
+    /// Bearing of the flock's mean heading relative to the entity's, in [-1, 1] (negative = left).
+    pub heading: f32,
+    /// Bearing of the flock's centre, in [-1, 1] (negative = left).
+    pub center: f32,
+}
+
+/// Relative bearing of `dir` from `heading`, scaled to [-1, 1].
+fn bearing(dir: Vec2, heading: f32) -> f32 {
+    let angle = dir.y.atan2(dir.x) - heading;
+    let angle = (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+    angle / std::f32::consts::PI
+}
+
+/// Flock view per slot. A neighbour counts as a flockmate while its last signal was at least
+/// FLOCK_MIN_SIGNAL and within FLOCK_COLOR_TOLERANCE (RGB distance) of the viewer's color.
+pub fn sense_flocks(
+    arena: &EntityArena,
+    signals: &[SignalState],
+    spatial: &SpatialHash,
+    world: &World,
+) -> Vec<FlockView> {
+    let mut views = vec![FlockView::default(); arena.entities.len()];
+    let tolerance_sq = config::FLOCK_COLOR_TOLERANCE * config::FLOCK_COLOR_TOLERANCE;
+
+    for (idx, entity) in arena.iter_alive() {
+        let mut count = 0usize;
+        let mut heading_sum = Vec2::ZERO;
+        let mut offset_sum = Vec2::ZERO;
+        for other_idx in spatial.query_radius_excluding(entity.pos, config::FLOCK_RANGE, idx as u32, world, arena) {
+            let Some(other) = arena.get_by_index(other_idx as usize) else { continue };
+            let Some(signal) = signals.get(other_idx as usize) else { continue };
+            if signal.intensity < config::FLOCK_MIN_SIGNAL {
+                continue;
+            }
+            let (dr, dg, db) = (
+                signal.color.r - entity.color.r,
+                signal.color.g - entity.color.g,
+                signal.color.b - entity.color.b,
+            );
+            if dr * dr + dg * dg + db * db > tolerance_sq {
+                continue;
+            }
+            count += 1;
+            heading_sum += Vec2::from_angle(other.heading);
+            offset_sum += world.delta(entity.pos, other.pos);
+        }
+        if count == 0 {
+            continue;
+        }
+        views[idx] = FlockView {
+            density: (count as f32 / config::FLOCK_DENSITY_SCALE).min(1.0),
+            heading: if heading_sum.length_squared() > 1e-6 { bearing(heading_sum, entity.heading) } else { 0.0 },
+            center: if offset_sum.length_squared() > 1e-6 { bearing(offset_sum, entity.heading) } else { 0.0 },
+        };
+    }
+
+    views
+}
+
+/// Blend the brain's turn output with flocking steering: cohesion turns toward the flock's
+/// centre and alignment toward its mean heading, both scaled by the entity's flocking gene.
+pub fn steer(turn: f32, cohesion: f32, alignment: f32, flocking: f32, view: &FlockView) -> f32 {
+    if view.density <= 0.0 {
+        return turn;
+    }
+    (turn + flocking * (cohesion * view.center + alignment * view.heading)).clamp(-1.0, 1.0)
+}
@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+use std::io::Write;
+
+use crate::config;
+use crate::entity::EntityArena;
+use crate::genome::{self, Genome};
+use crate::simulation::SimState;
+use crate::species::SpeciesTracker;
+use crate::stats::RingBuffer;
+use crate::storage;
+
+/// Variance of a uniform [0, 1] gene: what a freshly randomised population shows.
+const UNIFORM_VARIANCE: f32 = 1.0 / 12.0;
+
+/// Gene-wise statistics over the living population at one tick.
+pub struct GeneStats {
+    pub tick: u64,
+    pub sample_size: usize,
+    pub mean: Vec<f32>,
+    pub variance: Vec<f32>,
+    /// Heterozygosity proxy per gene: each gene is read as a two-allele locus (below or above
+    /// 0.5) and scored 2p(1-p), so 0 = fixed and 0.5 = evenly split.
+    pub heterozygosity: Vec<f32>,
+}
+
+impl GeneStats {
+    /// Mean gene variance as a percentage of a random population's.
+    pub fn variance_percent(&self) -> f32 {
+        mean(&self.variance) / UNIFORM_VARIANCE * 100.0
+    }
+
+    /// Mean heterozygosity as a percentage of its maximum.
+    pub fn heterozygosity_percent(&self) -> f32 {
+        mean(&self.heterozygosity) / 0.5 * 100.0
+    }
+}
+
+fn mean(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f32>() / values.len() as f32
+    }
+}
+
+/// Compute gene-wise statistics over every living entity's genome. None if nobody is alive.
+pub fn gene_stats(arena: &EntityArena, genomes: &[Option<Genome>], tick: u64) -> Option<GeneStats> {
+    let len = genome::TOTAL_GENOME_SIZE;
+    let mut sum = vec![0.0f64; len];
+    let mut sum_sq = vec![0.0f64; len];
+    let mut high = vec![0u32; len];
+    let mut n = 0usize;
+
+    for (idx, _entity) in arena.iter_alive() {
+        let Some(Some(genome)) = genomes.get(idx) else { continue };
+        for (i, &g) in genome.genes.iter().take(len).enumerate() {
+            sum[i] += g as f64;
+            sum_sq[i] += (g as f64) * (g as f64);
+            if g > 0.5 {
+                high[i] += 1;
+            }
+        }
+        n += 1;
+    }
+    if n == 0 {
+        return None;
+    }
+
+    let count = n as f64;
+    let mean: Vec<f32> = sum.iter().map(|s| (s / count) as f32).collect();
+    let variance = sum_sq
+        .iter()
+        .zip(&sum)
+        .map(|(sq, s)| (sq / count - (s / count).powi(2)).max(0.0) as f32)
+        .collect();
+    let heterozygosity = high
+        .iter()
+        .map(|&h| {
+            let p = h as f32 / n as f32;
+            2.0 * p * (1.0 - p)
+        })
+        .collect();
+    Some(GeneStats {
+        tick,
+        sample_size: n,
+        mean,
+        variance,
+        heterozygosity,
+    })
+}
+
+/// Mean genetic distance between species representatives, in [0, 1]. Zero with fewer than two species.
+pub fn species_divergence(species: &SpeciesTracker) -> f32 {
+    let reps = &species.species;
+    let mut total = 0.0;
+    let mut pairs = 0u32;
+    for (i, a) in reps.iter().enumerate() {
+        for b in &reps[i + 1..] {
+            total += a.representative.distance(&b.representative);
+            pairs += 1;
+        }
+    }
+    if pairs == 0 {
+        0.0
+    } else {
+        total / pairs as f32
+    }
+}
+
+/// Gene-pool diversity over time, sampled every GENETICS_SAMPLE_INTERVAL ticks.
+pub struct GeneticsStats {
+    /// Mean gene variance, % of a random population.
+    pub variance: RingBuffer,
+    /// Mean heterozygosity proxy, % of maximum.
+    pub heterozygosity: RingBuffer,
+    /// Mean distance between species representatives, %.
+    pub divergence: RingBuffer,
+    pub species_count: RingBuffer,
+    ticks: VecDeque<u64>,
+    capacity: usize,
+    /// Per-gene breakdown from the latest sample.
+    pub latest: Option<GeneStats>,
+    pub last_export: Option<String>,
+}
+
+impl GeneticsStats {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            variance: RingBuffer::new(capacity),
+            heterozygosity: RingBuffer::new(capacity),
+            divergence: RingBuffer::new(capacity),
+            species_count: RingBuffer::new(capacity),
+            ticks: VecDeque::with_capacity(capacity),
+            capacity,
+            latest: None,
+            last_export: None,
+        }
+    }
+
+    /// Take a sample if one is due this tick.
+    pub fn record(&mut self, sim: &SimState) {
+        if !sim.tick_count.is_multiple_of(config::GENETICS_SAMPLE_INTERVAL) {
+            return;
+        }
+        let Some(stats) = gene_stats(&sim.arena, &sim.genomes, sim.tick_count) else { return };
+
+        self.variance.push(stats.variance_percent());
+        self.heterozygosity.push(stats.heterozygosity_percent());
+        self.divergence.push(species_divergence(&sim.species) * 100.0);
+        self.species_count.push(sim.species.species.len() as f32);
+        if self.ticks.len() == self.capacity {
+            self.ticks.pop_front();
+        }
+        self.ticks.push_back(sim.tick_count);
+        self.latest = Some(stats);
+    }
+
+    /// Write the diversity time series to `{prefix}_history.csv` and the latest per-gene
+    /// breakdown to `{prefix}_genes.csv`, each starting with the `header` comment line.
+    /// Returns a summary of what was written.
+    pub fn export_csv(&self, prefix: &str, header: &str) -> Result<String, String> {
+        let history_path = format!("{prefix}_history.csv");
+        let mut writer = storage::create(&history_path).map_err(|e| format!("Create error: {e}"))?;
+        writeln!(writer, "{header}").map_err(|e| format!("Write error: {e}"))?;
+        writeln!(writer, "tick,variance_pct,heterozygosity_pct,divergence_pct,species")
+            .map_err(|e| format!("Write error: {e}"))?;
+        let rows = self
+            .ticks
+            .iter()
+            .zip(self.variance.iter())
+            .zip(self.heterozygosity.iter())
+            .zip(self.divergence.iter())
+            .zip(self.species_count.iter());
+        for ((((tick, var), het), div), species) in rows {
+            writeln!(writer, "{tick},{var:.3},{het:.3},{div:.3},{species:.0}")
+                .map_err(|e| format!("Write error: {e}"))?;
+        }
+        writer.flush().map_err(|e| format!("Write error: {e}"))?;
+
+        let Some(ref latest) = self.latest else {
+            return Ok(format!("{} samples → {history_path}", self.ticks.len()));
+        };
+        let genes_path = format!("{prefix}_genes.csv");
+        let mut writer = storage::create(&genes_path).map_err(|e| format!("Create error: {e}"))?;
+        writeln!(writer, "{header}").map_err(|e| format!("Write error: {e}"))?;
+        writeln!(writer, "gene,name,mean,variance,heterozygosity").map_err(|e| format!("Write error: {e}"))?;
+        for i in 0..latest.mean.len() {
+            writeln!(
+                writer,
+                "{i},{},{:.5},{:.5},{:.5}",
+                genome::gene_name(i),
+                latest.mean[i],
+                latest.variance[i],
+                latest.heterozygosity[i]
+            )
+            .map_err(|e| format!("Write error: {e}"))?;
+        }
+        writer.flush().map_err(|e| format!("Write error: {e}"))?;
+        Ok(format!("{} samples → {history_path}, {genes_path}", self.ticks.len()))
+    }
+}
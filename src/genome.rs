@@ -4,33 +4,102 @@ use macroquad::prelude::*;
 use crate::config;
 
 /// Number of neurons in the CTRNN brain.
-pub const N: usize = config::BRAIN_NEURONS; // 12
+pub const N: usize = config::BRAIN_NEURONS; // 24
 
 /// Total genome floats for neural params: N*N weights + N biases + N taus.
-pub const NEURAL_GENOME_SIZE: usize = N * N + N + N; // 144 + 12 + 12 = 168
+pub const NEURAL_GENOME_SIZE: usize = N * N + N + N; // 576 + 24 + 24 = 624
 
 /// Full genome including body parameters.
 #[derive(Clone, Debug)]
 pub struct Genome {
     /// Raw genome values, all normalized to roughly [0, 1].
-    /// Layout: [weights: N*N] [biases: N] [taus: N] [body_params: 8]
+    /// Layout: [weights: N*N] [biases: N] [taus: N] [body_params: BODY_PARAMS_COUNT]
     pub genes: Vec<f32>,
 }
 
 // Body param indices (offsets from NEURAL_GENOME_SIZE)
-const BODY_COLOR_R: usize = 0;
-const BODY_COLOR_G: usize = 1;
-const BODY_COLOR_B: usize = 2;
-const BODY_SIZE: usize = 3;
-const BODY_MAX_SPEED: usize = 4;
-const BODY_SENSOR_RANGE: usize = 5;
-const BODY_METABOLIC_RATE: usize = 6;
-const BODY_MUTATION_RATE: usize = 7;
-
-pub const BODY_PARAMS_COUNT: usize = 8;
-pub const TOTAL_GENOME_SIZE: usize = NEURAL_GENOME_SIZE + BODY_PARAMS_COUNT; // 176
+pub const BODY_COLOR_R: usize = 0;
+pub const BODY_COLOR_G: usize = 1;
+pub const BODY_COLOR_B: usize = 2;
+pub const BODY_SIZE: usize = 3;
+pub const BODY_MAX_SPEED: usize = 4;
+pub const BODY_SENSOR_RANGE: usize = 5;
+pub const BODY_METABOLIC_RATE: usize = 6;
+pub const BODY_MUTATION_RATE: usize = 7;
+pub const BODY_PRIME_AGE: usize = 8;
+pub const BODY_AQUATIC: usize = 9;
+pub const BODY_CARE: usize = 10;
+pub const BODY_ATTACK: usize = 11;
+pub const BODY_ARMOR: usize = 12;
+pub const BODY_LEARNING_RATE: usize = 13;
+pub const BODY_RANGED: usize = 14;
+pub const BODY_TASTE: usize = 15;
+pub const BODY_CIRCADIAN: usize = 16;
+pub const BODY_FLOCKING: usize = 17;
+pub const BODY_NEPOTISM: usize = 18;
+pub const BODY_TOXIN_RESISTANCE: usize = 19;
+pub const BODY_STORM_RESISTANCE: usize = 20;
+
+pub const BODY_PARAMS_COUNT: usize = 21;
+
+/// Display names for the body genes, in genome order.
+pub const BODY_GENE_NAMES: [&str; BODY_PARAMS_COUNT] = [
+    "Red",
+    "Green",
+    "Blue",
+    "Size",
+    "Max speed",
+    "Sensor range",
+    "Metabolism",
+    "Mutation rate",
+    "Prime age",
+    "Aquatic",
+    "Care",
+    "Attack",
+    "Armor",
+    "Learning rate",
+    "Ranged",
+    "Taste",
+    "Circadian",
+    "Flocking",
+    "Nepotism",
+    "Toxin resistance",
+    "Storm resistance",
+];
+pub const TOTAL_GENOME_SIZE: usize = NEURAL_GENOME_SIZE + BODY_PARAMS_COUNT; // 645
+
+/// Version of the gene layout. Bump whenever N, the neural section or the body genes change
+/// meaning or position: genomes stored under another layout are rejected, since padding or
+/// truncating them would shift every weight, bias and body gene.
+pub const GENOME_LAYOUT_VERSION: u32 = 1;
+
+/// Short name of the gene at `index`, e.g. "w3_12", "bias4", "tau0" or "body_size".
+pub fn gene_name(index: usize) -> String {
+    if index < N * N {
+        format!("w{}_{}", index / N, index % N)
+    } else if index < N * N + N {
+        format!("bias{}", index - N * N)
+    } else if index < NEURAL_GENOME_SIZE {
+        format!("tau{}", index - N * N - N)
+    } else {
+        let name = BODY_GENE_NAMES.get(index - NEURAL_GENOME_SIZE).copied().unwrap_or("unknown");
+        format!("body_{}", name.to_lowercase().replace(' ', "_"))
+    }
+}
 
 impl Genome {
+    /// Stable FNV-1a hash of the genes, for identifying a genome across runs.
+    pub fn checksum(&self) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for gene in &self.genes {
+            for byte in gene.to_bits().to_le_bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        hash
+    }
+
     pub fn random(rng: &mut impl Rng) -> Self {
         let genes: Vec<f32> = (0..TOTAL_GENOME_SIZE).map(|_| rng.gen_range(0.0..1.0)).collect();
         Self { genes }
@@ -52,6 +121,27 @@ impl Genome {
         child
     }
 
+    /// Uniform crossover of two parent genomes (each gene drawn from either parent).
+    pub fn crossover(a: &Genome, b: &Genome, rng: &mut impl Rng) -> Self {
+        let genes = a
+            .genes
+            .iter()
+            .zip(&b.genes)
+            .map(|(&ga, &gb)| if rng.gen::<bool>() { ga } else { gb })
+            .collect();
+        Self { genes }
+    }
+
+    /// Genetic distance: mean absolute gene difference, in [0, 1].
+    pub fn distance(&self, other: &Genome) -> f32 {
+        let n = self.genes.len().min(other.genes.len());
+        if n == 0 {
+            return 0.0;
+        }
+        let sum: f32 = self.genes.iter().zip(&other.genes).map(|(a, b)| (a - b).abs()).sum();
+        sum / n as f32
+    }
+
     // --- Weight/Bias/Tau decoding ---
 
     /// Decode weight W[i][j] from gene. Maps [0,1] -> [-16, 16].
@@ -59,6 +149,11 @@ impl Genome {
         (self.genes[i * N + j] - 0.5) * 32.0
     }
 
+    /// Encode weight W[i][j] back into its gene (the inverse of `weight`).
+    pub fn set_weight(&mut self, i: usize, j: usize, w: f32) {
+        self.genes[i * N + j] = (w / 32.0 + 0.5).clamp(0.0, 1.0);
+    }
+
     /// Decode bias for neuron i. Maps [0,1] -> [-16, 16].
     pub fn bias(&self, i: usize) -> f32 {
         (self.genes[N * N + i] - 0.5) * 32.0
@@ -71,10 +166,15 @@ impl Genome {
 
     // --- Body parameter decoding ---
 
-    fn body_gene(&self, offset: usize) -> f32 {
+    /// Raw body gene in [0, 1] at `offset` (one of the BODY_* indices).
+    pub fn body_gene(&self, offset: usize) -> f32 {
         self.genes[NEURAL_GENOME_SIZE + offset]
     }
 
+    pub fn set_body_gene(&mut self, offset: usize, value: f32) {
+        self.genes[NEURAL_GENOME_SIZE + offset] = value.clamp(0.0, 1.0);
+    }
+
     pub fn body_color(&self) -> Color {
         Color::new(
             0.2 + self.body_gene(BODY_COLOR_R) * 0.8,
@@ -108,4 +208,86 @@ impl Genome {
     pub fn mutation_rate(&self) -> f32 {
         0.01 + self.body_gene(BODY_MUTATION_RATE) * 0.14
     }
+
+    /// Age (seconds) after which senescence sets in [0.2, 0.8] * DEATH_AGE.
+    pub fn prime_age(&self) -> f32 {
+        (0.2 + self.body_gene(BODY_PRIME_AGE) * 0.6) * config::DEATH_AGE
+    }
+
+    /// Aquatic adaptation [0, 1]: 0 = land specialist, 1 = swimmer.
+    pub fn aquatic(&self) -> f32 {
+        self.body_gene(BODY_AQUATIC)
+    }
+
+    /// Parental care [0, 1]: how readily the entity feeds its hungry offspring.
+    pub fn care(&self) -> f32 {
+        self.body_gene(BODY_CARE)
+    }
+
+    /// Attack strength multiplier on combat damage [0.5, 1.5].
+    pub fn attack_power(&self) -> f32 {
+        0.5 + self.body_gene(BODY_ATTACK) * 1.0
+    }
+
+    /// Armor [0, 1]: full armor blocks ARMOR_DAMAGE_REDUCTION of incoming damage.
+    pub fn armor(&self) -> f32 {
+        self.body_gene(BODY_ARMOR)
+    }
+
+    /// Hebbian learning rate per simulated second [0, PLASTICITY_MAX_RATE].
+    pub fn learning_rate(&self) -> f32 {
+        self.body_gene(BODY_LEARNING_RATE) * config::PLASTICITY_MAX_RATE
+    }
+
+    /// Ranged attack [0, 1]: at SPIT_MIN_GENE and above the entity spits when it wants to
+    /// attack but nothing is in melee range; spit damage scales with it.
+    pub fn ranged(&self) -> f32 {
+        self.body_gene(BODY_RANGED)
+    }
+
+    /// Food taste [0, 1]: grass at 0, berries at 0.5, fruit at 1. Food far from the taste
+    /// is digested less efficiently.
+    pub fn taste(&self) -> f32 {
+        self.body_gene(BODY_TASTE)
+    }
+
+    /// Circadian rhythm [0, 1]: diurnal at 0, nocturnal at 1, no preference at 0.5.
+    pub fn circadian(&self) -> f32 {
+        self.body_gene(BODY_CIRCADIAN)
+    }
+
+    /// Flocking [0, 1]: how strongly the cohesion and alignment motors steer.
+    pub fn flocking(&self) -> f32 {
+        self.body_gene(BODY_FLOCKING)
+    }
+
+    /// Nepotism [0, 1]: how strongly food sharing favors relatives over closer strangers.
+    pub fn nepotism(&self) -> f32 {
+        self.body_gene(BODY_NEPOTISM)
+    }
+
+    /// Toxin resistance [0, 1]: full resistance blocks TOXIN_RESISTANCE_MAX of toxic damage.
+    pub fn toxin_resistance(&self) -> f32 {
+        self.body_gene(BODY_TOXIN_RESISTANCE)
+    }
+
+    /// Storm resistance [0, 1]: full resistance blocks STORM_RESISTANCE_MAX of storm damage.
+    pub fn storm_resistance(&self) -> f32 {
+        self.body_gene(BODY_STORM_RESISTANCE)
+    }
+
+    /// Max speed after the weight of armor.
+    pub fn armored_speed(&self) -> f32 {
+        self.max_speed() * (1.0 - self.armor() * config::ARMOR_SPEED_PENALTY)
+    }
+
+    /// Metabolic multiplier paid for somatic maintenance: a later prime, stronger attacks,
+    /// heavier armor and hazard resistances all cost more [1.0, 2.05].
+    pub fn maintenance_cost(&self) -> f32 {
+        1.0 + self.body_gene(BODY_PRIME_AGE) * config::PRIME_AGE_METABOLIC_COST
+            + self.body_gene(BODY_ATTACK) * config::ATTACK_POWER_METABOLIC_COST
+            + self.armor() * config::ARMOR_METABOLIC_COST
+            + self.toxin_resistance() * config::TOXIN_RESISTANCE_METABOLIC_COST
+            + self.storm_resistance() * config::STORM_RESISTANCE_METABOLIC_COST
+    }
 }
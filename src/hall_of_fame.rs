@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::entity::{Entity, EntityArena};
+use crate::genome::{Genome, GENOME_LAYOUT_VERSION, TOTAL_GENOME_SIZE};
+use crate::storage;
+
+/// Default location of the genome library written during a run.
+pub const LIBRARY_PATH: &str = "genesis_hall_of_fame.bin";
+
+/// What an archived genome was recognized for.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FameCategory {
+    Offspring,
+    Lifespan,
+    Kills,
+}
+
+impl FameCategory {
+    pub const ALL: [FameCategory; 3] = [FameCategory::Offspring, FameCategory::Lifespan, FameCategory::Kills];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FameCategory::Offspring => "Most offspring",
+            FameCategory::Lifespan => "Longest lived",
+            FameCategory::Kills => "Most kills",
+        }
+    }
+
+    /// The entity's score in this category (offspring count, age in seconds, kills).
+    pub fn score(&self, entity: &Entity) -> f32 {
+        match self {
+            FameCategory::Offspring => entity.offspring_count as f32,
+            FameCategory::Lifespan => entity.age,
+            FameCategory::Kills => entity.kills as f32,
+        }
+    }
+}
+
+/// A genome kept in the archive, with metadata about the entity that carried it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ArchivedGenome {
+    pub category: FameCategory,
+    pub score: f32,
+    /// UID of the carrier in its own run. UIDs restart every run, so entries are told apart by
+    /// `genome_hash` instead.
+    pub uid: u64,
+    /// `Genome::checksum` of `genes`.
+    pub genome_hash: u64,
+    /// GENOME_LAYOUT_VERSION the genes were written under.
+    pub layout: u32,
+    pub generation_depth: u32,
+    pub offspring_count: u32,
+    pub kills: u32,
+    pub age: f32,
+    /// Tick at which the entry was last updated.
+    pub tick: u64,
+    pub genes: Vec<f32>,
+}
+
+impl ArchivedGenome {
+    pub fn genome(&self) -> Genome {
+        Genome { genes: self.genes.clone() }
+    }
+
+    /// Whether the genes were written under the current layout and can be used as a genome.
+    pub fn is_current_layout(&self) -> bool {
+        self.layout == GENOME_LAYOUT_VERSION && self.genes.len() == TOTAL_GENOME_SIZE
+    }
+}
+
+/// The best genomes seen during a run: the top HALL_OF_FAME_SIZE per category.
+#[derive(Default)]
+pub struct HallOfFame {
+    pub entries: Vec<ArchivedGenome>,
+}
+
+impl HallOfFame {
+    /// Offer an entity for every category. A genome already archived in a category
+    /// has its entry updated rather than duplicated.
+    pub fn consider(&mut self, entity: &Entity, genome: &Genome, tick: u64) {
+        let genome_hash = genome.checksum();
+        for category in FameCategory::ALL {
+            let score = category.score(entity);
+            if score <= 0.0 {
+                continue;
+            }
+            let entry = ArchivedGenome {
+                category,
+                score,
+                uid: entity.uid,
+                genome_hash,
+                layout: GENOME_LAYOUT_VERSION,
+                generation_depth: entity.generation_depth,
+                offspring_count: entity.offspring_count,
+                kills: entity.kills,
+                age: entity.age,
+                tick,
+                genes: genome.genes.clone(),
+            };
+
+            if let Some(existing) = self
+                .entries
+                .iter_mut()
+                .find(|e| e.category == category && e.genome_hash == genome_hash)
+            {
+                if score >= existing.score {
+                    *existing = entry;
+                }
+                continue;
+            }
+
+            let ranked = self.ranked(category);
+            if ranked.len() < config::HALL_OF_FAME_SIZE {
+                self.entries.push(entry);
+            } else if let Some(worst) = ranked.last() {
+                if score > worst.score {
+                    let worst_hash = worst.genome_hash;
+                    self.entries.retain(|e| !(e.category == category && e.genome_hash == worst_hash));
+                    self.entries.push(entry);
+                }
+            }
+        }
+    }
+
+    /// Offer every living entity (used for periodic snapshots).
+    pub fn consider_all(&mut self, arena: &EntityArena, genomes: &[Option<Genome>], tick: u64) {
+        for (idx, entity) in arena.iter_alive() {
+            if let Some(Some(genome)) = genomes.get(idx) {
+                self.consider(entity, genome, tick);
+            }
+        }
+    }
+
+    /// Entries of one category, best first.
+    pub fn ranked(&self, category: FameCategory) -> Vec<&ArchivedGenome> {
+        let mut ranked: Vec<&ArchivedGenome> =
+            self.entries.iter().filter(|e| e.category == category).collect();
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+        ranked
+    }
+
+    /// Write the archive to a library file.
+    pub fn save_library(&self, path: &str) -> Result<(), String> {
+        let bytes = bincode::serialize(&self.entries).map_err(|e| format!("Serialize error: {e}"))?;
+        storage::write(path, bytes).map_err(|e| format!("Write error: {e}"))?;
+        Ok(())
+    }
+
+    /// Read an archive from a library file. Entries from another genome layout are dropped;
+    /// their count is returned alongside.
+    pub fn load_library(path: &str) -> Result<(Self, usize), String> {
+        let bytes = storage::read(path).map_err(|e| format!("Read error: {e}"))?;
+        let mut entries: Vec<ArchivedGenome> =
+            bincode::deserialize(&bytes).map_err(|e| format!("Deserialize error: {e}"))?;
+        let total = entries.len();
+        entries.retain(ArchivedGenome::is_current_layout);
+        let skipped = total - entries.len();
+        Ok((Self { entries }, skipped))
+    }
+}
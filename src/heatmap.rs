@@ -0,0 +1,229 @@
+use macroquad::prelude::*;
+
+use crate::config;
+use crate::entity::EntityArena;
+
+/// How often (in ticks) entity positions are accumulated into the density layer.
+const DENSITY_SAMPLE_INTERVAL: u64 = 30;
+
+/// How often (in ticks) the live population layer is decayed and re-sampled.
+const POPULATION_SAMPLE_INTERVAL: u64 = 10;
+
+/// How often (in ticks) the productivity layer is recomputed from the environment.
+pub const PRODUCTIVITY_SAMPLE_INTERVAL: u64 = 60;
+
+/// Kinds of accumulated activity that can be shown as an overlay.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeatmapLayer {
+    /// Recent entity density, decaying so it tracks where colonies are now.
+    Population,
+    /// Entity density accumulated over the whole run.
+    Density,
+    Deaths,
+    Births,
+    Combat,
+    /// Current food spawn rate of each cell (terrain, season, time of day, fertility).
+    Productivity,
+}
+
+impl HeatmapLayer {
+    pub const ALL: [HeatmapLayer; 6] = [
+        HeatmapLayer::Population,
+        HeatmapLayer::Density,
+        HeatmapLayer::Deaths,
+        HeatmapLayer::Births,
+        HeatmapLayer::Combat,
+        HeatmapLayer::Productivity,
+    ];
+
+    /// Identifier used in the preferences file.
+    pub fn id(&self) -> &'static str {
+        match self {
+            HeatmapLayer::Population => "population",
+            HeatmapLayer::Density => "density",
+            HeatmapLayer::Deaths => "deaths",
+            HeatmapLayer::Births => "births",
+            HeatmapLayer::Combat => "combat",
+            HeatmapLayer::Productivity => "productivity",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<HeatmapLayer> {
+        HeatmapLayer::ALL.iter().copied().find(|l| l.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeatmapLayer::Population => "Population",
+            HeatmapLayer::Density => "Density",
+            HeatmapLayer::Deaths => "Deaths",
+            HeatmapLayer::Births => "Births",
+            HeatmapLayer::Combat => "Combat",
+            HeatmapLayer::Productivity => "Productivity",
+        }
+    }
+
+    /// Overlay tint for this layer.
+    pub fn color(&self) -> Color {
+        match self {
+            HeatmapLayer::Population => Color::new(1.0, 0.85, 0.2, 1.0),
+            HeatmapLayer::Density => Color::new(0.2, 0.7, 1.0, 1.0),
+            HeatmapLayer::Deaths => Color::new(1.0, 0.2, 0.15, 1.0),
+            HeatmapLayer::Births => Color::new(0.3, 1.0, 0.5, 1.0),
+            HeatmapLayer::Combat => Color::new(1.0, 0.6, 0.1, 1.0),
+            HeatmapLayer::Productivity => Color::new(0.45, 0.95, 0.25, 1.0),
+        }
+    }
+
+    /// Whether the layer accumulates activity (and can be cleared) rather than showing a
+    /// field recomputed from the current state.
+    pub fn accumulates(&self) -> bool {
+        *self != HeatmapLayer::Productivity
+    }
+}
+
+/// Low-resolution accumulation grid for one heatmap layer.
+pub struct HeatmapGrid {
+    pub cells: Vec<f32>,
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: f32,
+    inv_cell_size: f32,
+}
+
+impl HeatmapGrid {
+    pub fn new(world_width: f32, world_height: f32, cell_size: f32) -> Self {
+        let width = (world_width / cell_size).ceil() as usize;
+        let height = (world_height / cell_size).ceil() as usize;
+        Self {
+            cells: vec![0.0; width * height],
+            width,
+            height,
+            cell_size,
+            inv_cell_size: 1.0 / cell_size,
+        }
+    }
+
+    /// Add `amount` to the cell containing a world position.
+    pub fn add(&mut self, pos: Vec2, amount: f32) {
+        let cx = ((pos.x * self.inv_cell_size) as usize).min(self.width - 1);
+        let cy = ((pos.y * self.inv_cell_size) as usize).min(self.height - 1);
+        self.cells[cy * self.width + cx] += amount;
+    }
+
+    /// World position of the centre of cell `index`.
+    pub fn cell_center(&self, index: usize) -> Vec2 {
+        let (x, y) = (index % self.width, index / self.width);
+        vec2((x as f32 + 0.5) * self.cell_size, (y as f32 + 0.5) * self.cell_size)
+    }
+
+    pub fn max_value(&self) -> f32 {
+        self.cells.iter().cloned().fold(0.0, f32::max)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.iter_mut().for_each(|c| *c = 0.0);
+    }
+
+    /// Multiply every cell by `factor` (< 1 fades old activity).
+    pub fn decay(&mut self, factor: f32) {
+        self.cells.iter_mut().for_each(|c| *c *= factor);
+    }
+}
+
+/// Accumulated spatial activity over the lifetime of the run.
+pub struct Heatmaps {
+    pub population: HeatmapGrid,
+    pub density: HeatmapGrid,
+    pub deaths: HeatmapGrid,
+    pub births: HeatmapGrid,
+    pub combat: HeatmapGrid,
+    /// Relative food spawn rate per cell, refreshed every PRODUCTIVITY_SAMPLE_INTERVAL ticks.
+    pub productivity: HeatmapGrid,
+}
+
+impl Heatmaps {
+    pub fn new(world_width: f32, world_height: f32, cell_size: f32) -> Self {
+        Self {
+            population: HeatmapGrid::new(world_width, world_height, cell_size),
+            density: HeatmapGrid::new(world_width, world_height, cell_size),
+            deaths: HeatmapGrid::new(world_width, world_height, cell_size),
+            births: HeatmapGrid::new(world_width, world_height, cell_size),
+            combat: HeatmapGrid::new(world_width, world_height, cell_size),
+            productivity: HeatmapGrid::new(world_width, world_height, cell_size),
+        }
+    }
+
+    pub fn layer(&self, layer: HeatmapLayer) -> &HeatmapGrid {
+        match layer {
+            HeatmapLayer::Population => &self.population,
+            HeatmapLayer::Density => &self.density,
+            HeatmapLayer::Deaths => &self.deaths,
+            HeatmapLayer::Births => &self.births,
+            HeatmapLayer::Combat => &self.combat,
+            HeatmapLayer::Productivity => &self.productivity,
+        }
+    }
+
+    /// Periodically accumulate alive entity positions into the density layer.
+    pub fn sample_density(&mut self, arena: &EntityArena, tick: u64) {
+        if !tick.is_multiple_of(DENSITY_SAMPLE_INTERVAL) {
+            return;
+        }
+        for (_idx, e) in arena.iter_alive() {
+            self.density.add(e.pos, 1.0);
+        }
+    }
+
+    /// Periodically fade the population layer and add the current entity positions.
+    pub fn sample_population(&mut self, arena: &EntityArena, tick: u64) {
+        if !tick.is_multiple_of(POPULATION_SAMPLE_INTERVAL) {
+            return;
+        }
+        self.population.decay(config::POPULATION_HEATMAP_DECAY);
+        for (_idx, e) in arena.iter_alive() {
+            self.population.add(e.pos, 1.0);
+        }
+    }
+
+    /// Clear the accumulated layers.
+    pub fn clear(&mut self) {
+        self.population.clear();
+        self.density.clear();
+        self.deaths.clear();
+        self.births.clear();
+        self.combat.clear();
+    }
+}
+
+/// Draw a heatmap layer as translucent colored cells. Accumulated layers are normalized to
+/// the busiest cell; productivity to a fixed rate, so seasonal and daily swings stay visible.
+/// `opacity` is the alpha of a cell at the maximum.
+pub fn draw_heatmap(grid: &HeatmapGrid, layer: HeatmapLayer, opacity: f32) {
+    let max = if layer.accumulates() {
+        grid.max_value()
+    } else {
+        config::PRODUCTIVITY_HEATMAP_MAX
+    };
+    let color = layer.color();
+    if max <= 0.0 {
+        return;
+    }
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            let val = grid.cells[y * grid.width + x];
+            if val <= 0.0 {
+                continue;
+            }
+            // Square-root scaling keeps sparse cells visible next to hotspots
+            let t = (val / max).min(1.0).sqrt();
+            draw_rectangle(
+                x as f32 * grid.cell_size,
+                y as f32 * grid.cell_size,
+                grid.cell_size,
+                grid.cell_size,
+                Color::new(color.r, color.g, color.b, t * opacity),
+            );
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use macroquad::prelude::*;
+
+use crate::camera::{CameraController, EntityDetail};
+use crate::config;
+use crate::renderer;
+use crate::simulation::SimState;
+
+/// Picture-in-picture view locked onto the followed entity at high zoom, rendered to its own
+/// target so the main camera can roam freely.
+pub struct FollowInset {
+    target: Option<RenderTarget>,
+}
+
+impl FollowInset {
+    pub fn new() -> Self {
+        Self { target: None }
+    }
+
+    /// Render the followed entity's surroundings and composite them in the top-right corner.
+    /// Does nothing if no entity is followed.
+    pub fn draw(&mut self, sim: &SimState, camera: &CameraController, alpha: f32) {
+        let Some(entity) = camera.following.and_then(|id| sim.arena.get(id)) else { return };
+        let size = config::FOLLOW_INSET_SIZE;
+        let rt = self
+            .target
+            .get_or_insert_with(|| {
+                let rt = render_target(size, size);
+                rt.texture.set_filter(FilterMode::Linear);
+                rt
+            })
+            .clone();
+
+        let mut view = CameraController::new(entity.prev_pos.lerp(entity.pos, alpha));
+        view.zoom = config::FOLLOW_INSET_ZOOM;
+        view.smooth_zoom = config::FOLLOW_INSET_ZOOM;
+        view.detail = EntityDetail::for_zoom(config::FOLLOW_INSET_ZOOM);
+        renderer::draw_world_scene(sim, &view, alpha, Some(rt.clone()), true);
+        set_default_camera();
+
+        let side = size as f32;
+        let x = screen_width() - side - config::FOLLOW_INSET_MARGIN;
+        let y = config::FOLLOW_INSET_TOP;
+        draw_rectangle(x - 2.0, y - 2.0, side + 4.0, side + 4.0, Color::new(0.0, 0.0, 0.0, 0.6));
+        draw_texture_ex(
+            &rt.texture,
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(side, side)),
+                ..Default::default()
+            },
+        );
+        draw_rectangle_lines(x - 2.0, y - 2.0, side + 4.0, side + 4.0, 2.0, Color::new(0.5, 0.7, 1.0, 0.8));
+        draw_text(&format!("#{}", entity.uid), x + 6.0, y + 16.0, 16.0, Color::new(0.8, 0.9, 1.0, 0.9));
+    }
+}
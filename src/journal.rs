@@ -0,0 +1,82 @@
+// Intervention journal: a timestamped record of everything the user did to the world (edits,
+// spawns, deletions, speed changes, ...). It is stored in saves and can be exported, so
+// emergent dynamics can later be told apart from user meddling.
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+/// What kind of intervention a journal entry records.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InterventionKind {
+    /// A world edit: walls, hazards, portals or food drops.
+    Edit,
+    Undo,
+    Redo,
+    Spawn,
+    Delete,
+    /// Entities moved with the drag tool or the selection's Relocate.
+    Move,
+    /// Energy or genes of living entities changed by hand.
+    Modify,
+    Breed,
+    Storm,
+    Speed,
+}
+
+impl InterventionKind {
+    pub fn id(&self) -> &'static str {
+        match self {
+            InterventionKind::Edit => "edit",
+            InterventionKind::Undo => "undo",
+            InterventionKind::Redo => "redo",
+            InterventionKind::Spawn => "spawn",
+            InterventionKind::Delete => "delete",
+            InterventionKind::Move => "move",
+            InterventionKind::Modify => "modify",
+            InterventionKind::Breed => "breed",
+            InterventionKind::Storm => "storm",
+            InterventionKind::Speed => "speed",
+        }
+    }
+}
+
+/// One user intervention.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Intervention {
+    pub tick: u64,
+    pub kind: InterventionKind,
+    pub detail: String,
+}
+
+/// Every intervention of the run, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct InterventionJournal {
+    pub entries: Vec<Intervention>,
+}
+
+impl InterventionJournal {
+    pub fn record(&mut self, tick: u64, kind: InterventionKind, detail: impl Into<String>) {
+        self.entries.push(Intervention {
+            tick,
+            kind,
+            detail: detail.into(),
+        });
+    }
+
+    /// Write the journal as CSV (`tick,sim_seconds,kind,detail`). Returns the number of rows.
+    pub fn export_csv(&self, path: &str, header: &str, dt: f32) -> Result<usize, String> {
+        let mut csv = format!("{header}\ntick,sim_seconds,kind,detail\n");
+        for entry in &self.entries {
+            csv.push_str(&format!(
+                "{},{:.2},{},{}\n",
+                entry.tick,
+                entry.tick as f32 * dt,
+                entry.kind.id(),
+                entry.detail.replace([',', '\n'], " ")
+            ));
+        }
+        storage::write(path, csv).map_err(|e| format!("Write error: {e}"))?;
+        Ok(self.entries.len())
+    }
+}
@@ -0,0 +1,321 @@
+use std::fmt;
+
+use macroquad::prelude::*;
+
+use crate::storage;
+
+/// Preferences file holding the user's key bindings.
+pub const KEYBINDINGS_PATH: &str = "genesis_keys.cfg";
+
+/// Number of alternative bindings per action (e.g. W and Up both pan up).
+pub const SLOTS: usize = 2;
+
+/// Keys reserved for camera bookmarks (digit jumps, Ctrl+digit stores).
+const RESERVED_DIGITS: [KeyCode; 10] = [
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// Keys that can be bound, looked up by their `Debug` name in the preferences file.
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Space, KeyCode::Escape, KeyCode::Enter, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::Delete, KeyCode::Insert, KeyCode::Home, KeyCode::End, KeyCode::PageUp,
+    KeyCode::PageDown, KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
+    KeyCode::Minus, KeyCode::Equal, KeyCode::Comma, KeyCode::Period, KeyCode::Slash,
+    KeyCode::Semicolon, KeyCode::Apostrophe, KeyCode::LeftBracket, KeyCode::RightBracket,
+    KeyCode::Backslash, KeyCode::GraveAccent,
+];
+
+/// A rebindable input action.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    TogglePause,
+    ToggleRays,
+    FrameGroup,
+    Deselect,
+    DeleteEntity,
+    Save,
+    Load,
+    Undo,
+    Redo,
+    ToggleInset,
+    CycleColorMode,
+    ToggleRecording,
+}
+
+impl Action {
+    pub const ALL: [Action; 16] = [
+        Action::PanUp,
+        Action::PanDown,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::TogglePause,
+        Action::ToggleRays,
+        Action::FrameGroup,
+        Action::Deselect,
+        Action::DeleteEntity,
+        Action::Save,
+        Action::Load,
+        Action::Undo,
+        Action::Redo,
+        Action::ToggleInset,
+        Action::CycleColorMode,
+        Action::ToggleRecording,
+    ];
+
+    /// Human-readable name for the settings editor.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::PanUp => "Pan up",
+            Action::PanDown => "Pan down",
+            Action::PanLeft => "Pan left",
+            Action::PanRight => "Pan right",
+            Action::TogglePause => "Pause / resume",
+            Action::ToggleRays => "Cycle sensor rays (off / selected / all)",
+            Action::FrameGroup => "Frame species",
+            Action::Deselect => "Deselect / cancel",
+            Action::DeleteEntity => "Delete entity",
+            Action::Save => "Save",
+            Action::Load => "Load",
+            Action::Undo => "Undo edit",
+            Action::Redo => "Redo edit",
+            Action::ToggleInset => "Toggle follow inset",
+            Action::CycleColorMode => "Cycle entity color mode",
+            Action::ToggleRecording => "Start / stop frame recording",
+        }
+    }
+
+    /// Identifier used in the preferences file.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Action::PanUp => "pan_up",
+            Action::PanDown => "pan_down",
+            Action::PanLeft => "pan_left",
+            Action::PanRight => "pan_right",
+            Action::TogglePause => "pause",
+            Action::ToggleRays => "toggle_rays",
+            Action::FrameGroup => "frame_species",
+            Action::Deselect => "deselect",
+            Action::DeleteEntity => "delete_entity",
+            Action::Save => "save",
+            Action::Load => "load",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::ToggleInset => "toggle_inset",
+            Action::CycleColorMode => "cycle_color_mode",
+            Action::ToggleRecording => "toggle_recording",
+        }
+    }
+
+    fn default_bindings(&self) -> [Option<KeyBinding>; SLOTS] {
+        let key = |key| Some(KeyBinding { key, ctrl: false });
+        let ctrl = |key| Some(KeyBinding { key, ctrl: true });
+        match self {
+            Action::PanUp => [key(KeyCode::W), key(KeyCode::Up)],
+            Action::PanDown => [key(KeyCode::S), key(KeyCode::Down)],
+            Action::PanLeft => [key(KeyCode::A), key(KeyCode::Left)],
+            Action::PanRight => [key(KeyCode::D), key(KeyCode::Right)],
+            Action::TogglePause => [key(KeyCode::Space), None],
+            Action::ToggleRays => [key(KeyCode::R), None],
+            Action::FrameGroup => [key(KeyCode::G), None],
+            Action::Deselect => [key(KeyCode::Escape), None],
+            Action::DeleteEntity => [key(KeyCode::Delete), key(KeyCode::Backspace)],
+            Action::Save => [ctrl(KeyCode::S), None],
+            Action::Load => [ctrl(KeyCode::L), None],
+            Action::Undo => [ctrl(KeyCode::Z), None],
+            Action::Redo => [ctrl(KeyCode::Y), None],
+            Action::ToggleInset => [key(KeyCode::P), None],
+            Action::CycleColorMode => [key(KeyCode::C), None],
+            Action::ToggleRecording => [key(KeyCode::F9), None],
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|a| a.id() == id)
+    }
+}
+
+/// A key, optionally chorded with Ctrl.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub ctrl: bool,
+}
+
+impl KeyBinding {
+    fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let (ctrl, name) = match s.get(..5) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("ctrl+") => (true, &s[5..]),
+            _ => (false, s),
+        };
+        BINDABLE_KEYS
+            .iter()
+            .find(|k| format!("{k:?}").eq_ignore_ascii_case(name))
+            .map(|&key| KeyBinding { key, ctrl })
+            .ok_or_else(|| format!("Unknown key: {s}"))
+    }
+
+    /// Whether the modifier state matches this binding (Ctrl chords need Ctrl, plain keys need it released).
+    fn modifier_matches(&self) -> bool {
+        let ctrl_down = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+        self.ctrl == ctrl_down
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        write!(f, "{:?}", self.key)
+    }
+}
+
+/// Two actions (or an action and a camera bookmark) sharing the same binding.
+pub struct Conflict {
+    pub binding: KeyBinding,
+    pub first: &'static str,
+    pub second: &'static str,
+}
+
+/// Key binding map, one row of alternative bindings per action in `Action::ALL` order.
+#[derive(Clone, PartialEq)]
+pub struct KeyBindings {
+    bindings: Vec<[Option<KeyBinding>; SLOTS]>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL.iter().map(|a| a.default_bindings()).collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: Action) -> [Option<KeyBinding>; SLOTS] {
+        self.bindings[action as usize]
+    }
+
+    pub fn set(&mut self, action: Action, slot: usize, binding: Option<KeyBinding>) {
+        self.bindings[action as usize][slot] = binding;
+    }
+
+    /// Whether any binding for `action` was pressed this frame.
+    pub fn pressed(&self, action: Action) -> bool {
+        self.get(action)
+            .iter()
+            .flatten()
+            .any(|b| is_key_pressed(b.key) && b.modifier_matches())
+    }
+
+    /// Whether any binding for `action` is held.
+    pub fn down(&self, action: Action) -> bool {
+        self.get(action)
+            .iter()
+            .flatten()
+            .any(|b| is_key_down(b.key) && b.modifier_matches())
+    }
+
+    /// Every binding used more than once, including clashes with the bookmark digit keys.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        let mut seen: Vec<(KeyBinding, &'static str)> = Vec::new();
+        for action in Action::ALL {
+            for binding in self.get(action).iter().flatten() {
+                if let Some(&(_, other)) = seen.iter().find(|(b, _)| b == binding) {
+                    if other != action.name() {
+                        conflicts.push(Conflict {
+                            binding: *binding,
+                            first: other,
+                            second: action.name(),
+                        });
+                    }
+                } else if RESERVED_DIGITS.contains(&binding.key) {
+                    conflicts.push(Conflict {
+                        binding: *binding,
+                        first: "Camera bookmarks",
+                        second: action.name(),
+                    });
+                }
+                seen.push((*binding, action.name()));
+            }
+        }
+        conflicts
+    }
+
+    /// Parse a preferences file. Actions missing from the file keep their defaults.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut keys = KeyBindings::default();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (id, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Line {}: expected `action = key`", line_no + 1))?;
+            let action = Action::from_id(id.trim())
+                .ok_or_else(|| format!("Line {}: unknown action `{}`", line_no + 1, id.trim()))?;
+            let mut slots = [None; SLOTS];
+            for (slot, part) in value.split(',').filter(|p| !p.trim().is_empty()).enumerate() {
+                if slot >= SLOTS {
+                    return Err(format!("Line {}: at most {SLOTS} keys per action", line_no + 1));
+                }
+                slots[slot] = Some(KeyBinding::parse(part).map_err(|e| format!("Line {}: {e}", line_no + 1))?);
+            }
+            keys.bindings[action as usize] = slots;
+        }
+        Ok(keys)
+    }
+
+    /// Render as a preferences file.
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# GENESIS key bindings: action = key[, key]. Prefix Ctrl+ for a Ctrl chord.\n");
+        for action in Action::ALL {
+            let keys: Vec<String> = self.get(action).iter().flatten().map(|b| b.to_string()).collect();
+            text.push_str(&format!("{} = {}\n", action.id(), keys.join(", ")));
+        }
+        text
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = storage::read_to_string(path).map_err(|e| format!("Read error: {e}"))?;
+        Self::parse(&text)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        storage::write(path, self.to_text()).map_err(|e| format!("Write error: {e}"))
+    }
+}
+
+/// The first bindable key pressed this frame, with the current Ctrl state.
+pub fn capture_key() -> Option<KeyBinding> {
+    let key = get_last_key_pressed()?;
+    if !BINDABLE_KEYS.contains(&key) {
+        return None;
+    }
+    let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+    Some(KeyBinding { key, ctrl })
+}
@@ -0,0 +1,116 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::entity::EntityArena;
+
+/// Birth and death of one entity, kept after it dies so its family can still be traced.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct LineageRecord {
+    pub uid: u64,
+    pub parent_uid: Option<u64>,
+    pub generation: u32,
+    pub born_tick: u64,
+    pub died_tick: Option<u64>,
+}
+
+/// Family records of the most recent LINEAGE_RECORD_LIMIT entities, oldest evicted first.
+/// Stored in saves.
+#[derive(Default)]
+pub struct LineageLog {
+    records: HashMap<u64, LineageRecord>,
+    /// UIDs in the order they were recorded.
+    order: VecDeque<u64>,
+    /// Highest UID seen; UIDs only increase, so entities above it are new.
+    newest_uid: u64,
+}
+
+impl LineageLog {
+    pub fn from_records(records: Vec<LineageRecord>) -> Self {
+        let mut log = Self::default();
+        for record in records {
+            log.insert(record);
+        }
+        log
+    }
+
+    /// Records oldest first, for saving.
+    pub fn records(&self) -> Vec<LineageRecord> {
+        self.order.iter().filter_map(|uid| self.records.get(uid)).copied().collect()
+    }
+
+    fn insert(&mut self, record: LineageRecord) {
+        if self.order.len() >= config::LINEAGE_RECORD_LIMIT {
+            if let Some(oldest) = self.order.pop_front() {
+                self.records.remove(&oldest);
+            }
+        }
+        self.newest_uid = self.newest_uid.max(record.uid);
+        self.order.push_back(record.uid);
+        self.records.insert(record.uid, record);
+    }
+
+    /// Record entities born since the last call. Births are stamped with the current tick.
+    pub fn record_births(&mut self, arena: &EntityArena, tick: u64) {
+        if arena.next_uid <= self.newest_uid + 1 {
+            return;
+        }
+        let newest = self.newest_uid;
+        let mut born: Vec<LineageRecord> = arena
+            .iter_alive()
+            .filter(|(_, e)| e.uid > newest)
+            .map(|(_, e)| LineageRecord {
+                uid: e.uid,
+                parent_uid: e.parent_uid,
+                generation: e.generation_depth,
+                born_tick: tick,
+                died_tick: None,
+            })
+            .collect();
+        born.sort_by_key(|r| r.uid);
+        for record in born {
+            self.insert(record);
+        }
+    }
+
+    /// Stamp the death tick of every entity about to be swept.
+    pub fn record_deaths(&mut self, arena: &EntityArena, tick: u64) {
+        for (_, entity) in arena.iter_alive().filter(|(_, e)| !e.alive) {
+            if let Some(record) = self.records.get_mut(&entity.uid) {
+                record.died_tick.get_or_insert(tick);
+            }
+        }
+    }
+
+    /// Ancestors starting from `parent_uid`, parent first, as far back as records reach (at
+    /// most `limit`).
+    pub fn ancestors(&self, parent_uid: Option<u64>, limit: usize) -> Vec<LineageRecord> {
+        let mut chain = Vec::new();
+        let mut next = parent_uid;
+        while let Some(parent) = next.and_then(|p| self.records.get(&p)) {
+            if chain.len() >= limit {
+                break;
+            }
+            chain.push(*parent);
+            next = parent.parent_uid;
+        }
+        chain
+    }
+
+    /// Every recorded child of `uid`, oldest first.
+    pub fn children(&self, uid: u64) -> Vec<LineageRecord> {
+        let mut children: Vec<LineageRecord> =
+            self.records.values().filter(|r| r.parent_uid == Some(uid)).copied().collect();
+        children.sort_by_key(|r| r.uid);
+        children
+    }
+
+    /// Recorded children of `parent_uid` other than `uid`: (all, still alive).
+    pub fn sibling_counts(&self, uid: u64, parent_uid: u64) -> (usize, usize) {
+        self.records
+            .values()
+            .filter(|r| r.parent_uid == Some(parent_uid) && r.uid != uid)
+            .fold((0, 0), |(all, alive), r| (all + 1, alive + usize::from(r.died_tick.is_none())))
+    }
+}
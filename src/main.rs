@@ -1,32 +1,99 @@
+// The browser build has no sockets, subprocesses, threads or monotonic clock, so the control
+// API, telemetry, run database, experiments, benchmarks and ffmpeg recording are native-only;
+// their settings and helpers stay in the tree unused there.
+#![cfg_attr(target_arch = "wasm32", allow(dead_code))]
+
 use macroquad::prelude::*;
 
+mod acoustics;
+mod audio;
 mod brain;
 mod camera;
+mod capture;
+mod care;
+mod cli;
+mod coloring;
 mod combat;
 mod config;
+#[cfg(not(target_arch = "wasm32"))]
+mod control;
+mod editing;
 mod energy;
+mod energy_audit;
 mod entity;
 mod environment;
+#[cfg(not(target_arch = "wasm32"))]
+mod experiment;
+mod flocking;
+mod genetics;
 mod genome;
+mod hall_of_fame;
+mod inset;
+mod journal;
+mod keybindings;
+mod lineage;
+mod manifest;
+mod notifications;
+mod nutrients;
+mod heatmap;
 mod particles;
 mod physics;
+mod plants;
 mod post_processing;
+mod preferences;
+mod qa;
+mod recorder;
 mod renderer;
+mod reporting;
 mod reproduction;
+#[cfg(not(target_arch = "wasm32"))]
+mod run_db;
+mod run_target;
 mod save_load;
+mod scenario;
 mod sensory;
+mod shelter;
+mod snapshot;
 mod signals;
+mod species;
 mod simulation;
 mod spatial_hash;
 mod stats;
+mod storage;
+#[cfg(not(target_arch = "wasm32"))]
+mod telemetry;
 mod ui;
+mod weather;
 mod world;
 
-use camera::CameraController;
+use audio::AudioSystem;
+use camera::{CameraController, CameraGroup};
+use editing::ToolMode;
+use journal::InterventionKind;
+use keybindings::{Action, KeyBindings};
+use notifications::NoticeKind;
+use preferences::Preferences;
+use recorder::NeuronRecorder;
 use simulation::SimState;
 use stats::SimStats;
 use ui::UiState;
 
+/// Entropy for getrandom 0.3 in the browser, which has no backend without wasm-bindgen. Only
+/// ahash's hasher seeds draw on it (the simulation RNG is seeded explicitly), so a xorshift
+/// stream keyed by the page clock is enough.
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+unsafe extern "Rust" fn __getrandom_v03_custom(dest: *mut u8, len: usize) -> Result<(), getrandom::Error> {
+    let mut state = macroquad::miniquad::date::now().to_bits() | 1;
+    for i in 0..len {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        *dest.add(i) = state as u8;
+    }
+    Ok(())
+}
+
 fn window_conf() -> Conf {
     Conf {
         window_title: "GENESIS — Neural Evolution Simulator".to_string(),
@@ -39,18 +106,235 @@ fn window_conf() -> Conf {
 }
 
 const AUTOSAVE_INTERVAL: f64 = 300.0; // 5 minutes
+const SELECT_DRAG_THRESHOLD: f32 = 6.0; // screen pixels before a click becomes a rubber-band drag
+const WALL_PICK_PIXELS: f32 = 10.0; // screen-space pick distance for walls and their endpoints
+
+fn main() {
+    let run_config = match cli::RunConfig::from_env() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+    let run_info = manifest::RunInfo::new(&run_config);
+    // QA scenarios run headless, before any window is opened
+    if let Some(qa) = run_config.qa {
+        write_manifest(".", "qa", &run_info, None);
+        let report = qa.run(run_config.seed.unwrap_or(42), &run_info);
+        if let Err(e) = report.write_json() {
+            eprintln!("[GENESIS] {e}");
+        }
+        std::process::exit(report.print());
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(ref path) = run_config.experiment {
+        let spec = match experiment::ExperimentSpec::load(path) {
+            Ok(spec) => spec,
+            Err(e) => {
+                eprintln!("[GENESIS] Experiment spec {path}: {e}");
+                std::process::exit(2);
+            }
+        };
+        write_manifest(".", "experiment", &run_info, None);
+        match experiment::run_experiment(&spec) {
+            Ok(summary) => eprintln!("[GENESIS] Experiment: {summary}"),
+            Err(e) => {
+                eprintln!("[GENESIS] Experiment failed: {e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(ref bench) = run_config.benchmark {
+        let sim = new_sim(&run_config, &run_info);
+        write_manifest(".", "benchmark", &run_info, Some(&sim));
+        match reporting::run_benchmark(sim, bench) {
+            Ok(summary) => eprintln!("[GENESIS] Benchmark: {summary}"),
+            Err(e) => {
+                eprintln!("[GENESIS] Benchmark failed: {e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    macroquad::Window::from_config(window_conf(), run(run_config, run_info));
+}
 
-#[macroquad::main(window_conf)]
-async fn main() {
-    let mut sim = SimState::new(config::INITIAL_ENTITY_COUNT, 42);
+/// Record the configuration of a QA, benchmark, snapshot or recorded run next to its output.
+fn write_manifest(dir: &str, mode: &str, run_info: &manifest::RunInfo, sim: Option<&SimState>) {
+    match manifest::write_run_manifest(dir, mode, run_info, sim) {
+        Ok(path) => eprintln!("[GENESIS] Run manifest → {path}"),
+        Err(e) => eprintln!("[GENESIS] Run manifest failed: {e}"),
+    }
+}
+
+/// Build the initial simulation from the command-line seed, preset and rate options.
+fn new_sim(run_config: &cli::RunConfig, run_info: &manifest::RunInfo) -> SimState {
+    let seed = run_config.seed.unwrap_or(42);
+    let mut sim = SimState::new(config::INITIAL_ENTITY_COUNT, seed);
+    if run_config.world_preset.is_some() || run_config.terrain_seed.is_some() {
+        let preset = run_config.world_preset.unwrap_or(sim.environment.world_preset);
+        let terrain_seed = run_config.terrain_seed.unwrap_or(sim.environment.terrain_seed);
+        sim.environment.regenerate_terrain(preset, terrain_seed);
+    }
+    eprintln!(
+        "[GENESIS] Seed {}, terrain seed {} ({})",
+        sim.seed,
+        sim.environment.terrain_seed,
+        sim.environment.world_preset.id()
+    );
+    if let Some(hz) = run_config.sim_hz {
+        sim.set_sim_hz(hz);
+    }
+    sim.energy_audit.toggle(run_config.energy_audit);
+    sim.run_info = run_info.clone();
+    sim
+}
+
+async fn run(run_config: cli::RunConfig, run_info: manifest::RunInfo) {
+    let mut scenario = match run_config.scenario.as_deref().map(scenario::Scenario::load) {
+        Some(Ok(s)) => Some(s),
+        Some(Err(e)) => {
+            eprintln!("[GENESIS] Failed to load scenario: {e}");
+            std::process::exit(1);
+        }
+        None => None,
+    };
+    let mut snapshots = match run_config.snapshot.as_ref().map(snapshot::SnapshotRun::new) {
+        Some(Ok(s)) => Some(s),
+        Some(Err(e)) => {
+            eprintln!("[GENESIS] Snapshot run failed: {e}");
+            std::process::exit(1);
+        }
+        None => None,
+    };
+
+    let mut sim = new_sim(&run_config, &run_info);
+    if snapshots.is_some() {
+        write_manifest(snapshot::SNAPSHOT_DIR, "snapshot", &run_info, Some(&sim));
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut run_db = match run_config.db.as_deref().map(run_db::RunDatabase::open) {
+        Some(Ok(db)) => {
+            eprintln!("[GENESIS] Recording run to {}", db.path);
+            Some(db)
+        }
+        Some(Err(e)) => {
+            eprintln!("[GENESIS] Run database failed: {e}");
+            std::process::exit(1);
+        }
+        None => None,
+    };
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut telemetry = match run_config.telemetry_port.map(telemetry::TelemetryServer::bind) {
+        Some(Ok(server)) => {
+            eprintln!("[GENESIS] Telemetry on ws://127.0.0.1:{}", server.port);
+            Some(server)
+        }
+        Some(Err(e)) => {
+            eprintln!("[GENESIS] Telemetry failed: {e}");
+            std::process::exit(1);
+        }
+        None => None,
+    };
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut control = match run_config.control_port.map(control::ControlServer::bind) {
+        Some(Ok(server)) => {
+            eprintln!("[GENESIS] Control API on http://127.0.0.1:{}", server.port);
+            Some(server)
+        }
+        Some(Err(e)) => {
+            eprintln!("[GENESIS] Control API failed: {e}");
+            std::process::exit(1);
+        }
+        None => None,
+    };
     let mut camera = CameraController::new(sim.world.center());
     let mut accumulator = 0.0f64;
     let mut sim_stats = SimStats::new(1000);
     let mut ui_state = UiState::default();
+    if storage::exists(keybindings::KEYBINDINGS_PATH) {
+        match KeyBindings::load(keybindings::KEYBINDINGS_PATH) {
+            Ok(keys) => ui_state.keybindings = keys,
+            Err(e) => eprintln!("[GENESIS] Key bindings load failed, using defaults: {e}"),
+        }
+    }
+    if storage::exists(preferences::PREFERENCES_PATH) {
+        match Preferences::load(preferences::PREFERENCES_PATH) {
+            Ok(prefs) => prefs.apply(&mut sim, &mut ui_state),
+            Err(e) => eprintln!("[GENESIS] Preferences load failed, using defaults: {e}"),
+        }
+    }
+    let mut saved_prefs = Preferences::capture(&sim, &mut ui_state);
+    if let Some(target) = run_config.run_until.filter(|_| snapshots.is_none()) {
+        ui_state.run_until = Some(run_target::RunUntil::start(&mut sim, target));
+    }
     let mut bloom = post_processing::BloomPipeline::new();
+    let mut follow_inset = inset::FollowInset::new();
     let mut autosave_timer = 0.0f64;
+    // Smoothed speed actually reached while fast-forwarding (simulated seconds per real second)
+    let mut fast_forward_speed = 0.0f64;
+    let mut neuron_recorder = NeuronRecorder::new();
+    let mut audio = AudioSystem::load().await;
+    if storage::exists(hall_of_fame::LIBRARY_PATH) {
+        match hall_of_fame::HallOfFame::load_library(hall_of_fame::LIBRARY_PATH) {
+            Ok((library, skipped)) => {
+                eprintln!(
+                    "[GENESIS] Loaded {} archived genomes from {}",
+                    library.entries.len(),
+                    hall_of_fame::LIBRARY_PATH
+                );
+                if skipped > 0 {
+                    eprintln!("[GENESIS] Skipped {skipped} archived genomes from an older genome layout");
+                }
+                sim.hall_of_fame = library;
+            }
+            Err(e) => eprintln!("[GENESIS] Hall of fame load failed: {e}"),
+        }
+    }
+
+    // Offer the newest valid autosave; after a crash the new world waits paused for the answer
+    let mut pending_recovery = None;
+    let crashed = save_load::begin_session();
+    if snapshots.is_none() {
+        if let Some((path, loaded, view)) = save_load::latest_valid_autosave() {
+            ui_state.recovery = Some(ui::recovery::RecoveryPrompt {
+                message: format!("Restore {path} (tick {})?", loaded.tick_count),
+                crashed,
+                choice: None,
+            });
+            pending_recovery = Some((loaded, view));
+            sim.paused |= crashed;
+        }
+    }
+    let mut recording = None;
+    if run_config.record.out.is_some() {
+        start_recording(&mut recording, &run_config.record, &run_info, &mut ui_state, sim.tick_count);
+    }
+    prevent_quit();
 
     loop {
+        if is_quit_requested() {
+            save_load::end_session();
+            break;
+        }
+
+        if let Some((recover, crashed)) = ui_state.recovery.as_ref().and_then(|p| p.choice.map(|c| (c, p.crashed))) {
+            ui_state.recovery = None;
+            if let (true, Some((loaded, view))) = (recover, pending_recovery.take()) {
+                install_loaded(&mut sim, &mut camera, &mut ui_state, &mut neuron_recorder, loaded, view);
+                let message = format!("Recovered autosave (tick {})", sim.tick_count);
+                eprintln!("[GENESIS] {message}");
+                ui_state.notifications.log.post(NoticeKind::Save, sim.tick_count, message, None);
+            }
+            pending_recovery = None;
+            if crashed {
+                sim.paused = false;
+            }
+        }
+
         let frame_time = get_frame_time() as f64;
         accumulator += frame_time.min(0.1);
 
@@ -59,92 +343,409 @@ async fn main() {
             autosave_timer += frame_time;
             if autosave_timer >= AUTOSAVE_INTERVAL {
                 autosave_timer = 0.0;
-                match save_load::save_to_file(&sim, "genesis_autosave.bin") {
-                    Ok(()) => eprintln!("[GENESIS] Autosaved to genesis_autosave.bin (tick {})", sim.tick_count),
-                    Err(e) => eprintln!("[GENESIS] Autosave failed: {e}"),
-                }
+                let message = match save_load::rotate_autosave(&sim, &camera) {
+                    Ok(path) => format!("Autosaved to {path}"),
+                    Err(e) => format!("Autosave failed: {e}"),
+                };
+                eprintln!("[GENESIS] {message} (tick {})", sim.tick_count);
+                ui_state.notifications.log.post(NoticeKind::Save, sim.tick_count, message, None);
             }
         }
 
-        let effective_dt = config::FIXED_DT as f64 / sim.speed_multiplier as f64;
+        sim.ray_targets.clear();
+        sim.ray_targets.extend(camera.following.iter().chain(&ui_state.selection.entities));
+
+        let effective_dt = sim.dt as f64 / sim.speed_multiplier as f64;
+        // Snapshot runs fast-forward to the next capture instead of following the clock
+        let snapshot_ticks = snapshots.as_ref().map(|s| s.ticks_this_frame(sim.tick_count));
+        // Fast-forward batches as many ticks as fit in the frame budget and skips world rendering
+        let fast_forward = snapshots.is_none() && sim.speed_multiplier >= config::FAST_FORWARD_MIN_SPEED;
+        let tick_start = get_time();
+        let mut ticks = 0u64;
         if !sim.paused {
-            while accumulator >= effective_dt {
+            while snapshot_ticks.map_or(accumulator >= effective_dt, |n| ticks < n)
+                && !(fast_forward && get_time() - tick_start >= config::FAST_FORWARD_FRAME_BUDGET)
+            {
                 sim.tick();
 
                 // Record stats each tick
-                let (avg_energy, avg_gen) = compute_averages(&sim);
-                sim_stats.record(
-                    sim.arena.count,
-                    avg_energy,
-                    sim.food.len(),
-                    avg_gen,
-                );
+                sim_stats.record(&sim);
+                sim_stats.genetics.record(&sim);
+                sim_stats.generations.record(&sim.arena, sim.tick_count);
+                sim_stats.traits.record(&sim.arena, &sim.genomes, sim.tick_count);
+                sim_stats.interneurons.record(&sim);
+                neuron_recorder.record(&sim);
+                neuron_recorder.history.record(&sim, camera.following);
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(db) = run_db.as_mut() {
+                    if let Err(e) = db.record(&sim) {
+                        eprintln!("[GENESIS] Run database stopped: {e}");
+                        run_db = None;
+                    }
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(server) = telemetry.as_mut() {
+                    server.record(&sim);
+                }
+
+                // Periodically archive the living population and write the genome library
+                if sim.tick_count.is_multiple_of(config::HALL_OF_FAME_SNAPSHOT_INTERVAL) {
+                    sim.hall_of_fame.consider_all(&sim.arena, &sim.genomes, sim.tick_count);
+                    if let Err(e) = sim.hall_of_fame.save_library(hall_of_fame::LIBRARY_PATH) {
+                        eprintln!("[GENESIS] Hall of fame save failed: {e}");
+                    }
+                }
+
+                if let Some(ref mut s) = scenario {
+                    s.tick(&mut sim);
+                    if s.is_finished() {
+                        eprintln!("[GENESIS] Scenario {} finished at tick {}", s.path, sim.tick_count);
+                        scenario = None;
+                    }
+                }
 
                 accumulator -= effective_dt;
+                ticks += 1;
+
+                if ui_state.run_until.is_some_and(|run| run.reached(&sim)) {
+                    if let Some(run) = ui_state.run_until.take() {
+                        run.finish(&mut sim);
+                    }
+                    accumulator = 0.0;
+                    break;
+                }
+            }
+            if snapshot_ticks.is_some() {
+                accumulator = 0.0;
+            }
+            if fast_forward {
+                // Drop the backlog the budget could not cover instead of carrying it over
+                accumulator = accumulator.min(effective_dt);
             }
         } else {
             accumulator = 0.0;
         }
+        sim.notices.drain_into(&mut ui_state.notifications.log);
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(server) = telemetry.as_mut() {
+            server.poll(&sim);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(server) = control.as_mut() {
+            server.poll(&mut sim, &camera, &mut ui_state.edit_history);
+        }
+        let achieved_speed = ticks as f64 * sim.dt as f64 / frame_time.max(1e-6);
+        fast_forward_speed = if fast_forward { fast_forward_speed * 0.9 + achieved_speed * 0.1 } else { 0.0 };
 
-        camera.update(&sim.arena, get_frame_time());
+        // Group framing: keep every member of the followed group in view
+        if let Some(group) = camera.following_group {
+            let positions = match group {
+                CameraGroup::Species(id) => sim.species.member_positions(&sim.arena, id),
+            };
+            if positions.is_empty() {
+                camera.following_group = None;
+            } else {
+                camera.frame_positions(&positions, &sim.world);
+            }
+        }
+        // Typing in a text field (entity name, tag, ...) must not trigger bound keys
+        let mut egui_wants_keyboard = false;
+        egui_macroquad::cfg(|ctx| {
+            egui_wants_keyboard = ctx.wants_keyboard_input();
+        });
+        camera.keyboard = !egui_wants_keyboard;
+        camera.scroll_zoom = !matches!(ui_state.tool_mode, ToolMode::ToxicZone | ToolMode::Erase);
+        camera.detached = ui_state.show_follow_inset;
+        camera.update(&sim.arena, &ui_state.keybindings, get_frame_time());
 
         // Entity selection via left click (only if egui doesn't want the input)
         let mut egui_wants_pointer = false;
         egui_macroquad::cfg(|ctx| {
             egui_wants_pointer = ctx.wants_pointer_input();
         });
+        let ui_clicked = egui_wants_pointer && is_mouse_button_pressed(MouseButton::Left);
+        let sound_events = std::mem::take(&mut sim.sound_events);
+        audio.update(&ui_state.audio, sound_events, ui_clicked, get_frame_time());
+
+        let mouse_world = camera.screen_to_world(Vec2::from(mouse_position()));
+
+        // Hazard tool: the wheel sizes the brush, Shift+wheel sets its intensity
+        if ui_state.tool_mode == ToolMode::ToxicZone && !egui_wants_pointer {
+            let (_, scroll_y) = mouse_wheel();
+            if scroll_y != 0.0 {
+                let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+                ui_state.toxic_brush.scroll(scroll_y.signum(), shift);
+            }
+        }
+        // Erase tool: the wheel sizes the brush; Shift extends it to walls
+        let erase_walls = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if ui_state.tool_mode == ToolMode::Erase && !egui_wants_pointer {
+            let (_, scroll_y) = mouse_wheel();
+            if scroll_y != 0.0 {
+                ui_state.erase_brush.scroll(scroll_y.signum());
+            }
+        }
+
         if !egui_wants_pointer && is_mouse_button_pressed(MouseButton::Left) {
-            let mouse_screen = Vec2::from(mouse_position());
-            let mouse_world = camera.screen_to_world(mouse_screen);
-            let pick_radius = 30.0 / camera.smooth_zoom;
-            if let Some(id) = camera.pick_entity(mouse_world, &sim.arena, pick_radius) {
-                camera.following = Some(id);
+            if ui_state.breeding.picking_location {
+                // Breeding tool: this click chooses where the offspring spawns
+                ui_state.breeding.chosen_pos = Some(sim.world.wrap(mouse_world));
+                ui_state.breeding.picking_location = false;
+            } else if ui_state.selection.picking_destination {
+                // Selection relocate: this click chooses where the group moves
+                ui_state.selection.picking_destination = false;
+                let moved = ui_state.selection.relocate(&mut sim, mouse_world);
+                ui_state.selection.last_result = Some(format!("Relocated {moved} entities"));
             } else {
-                camera.following = None;
+                match ui_state.tool_mode {
+                    ToolMode::Select => ui_state.select_drag_start = Some(mouse_world),
+                    ToolMode::Wall => ui_state.wall_drag_start = Some(mouse_world),
+                    ToolMode::EditWalls => {
+                        // Grab an endpoint of the selected wall first, otherwise of the wall under the cursor
+                        let pick_radius = WALL_PICK_PIXELS / camera.smooth_zoom;
+                        let walls = &sim.environment.walls;
+                        let ws = &mut ui_state.wall_edit;
+                        let picked = editing::pick_wall(walls, mouse_world, pick_radius);
+                        let grab = ws
+                            .selected
+                            .and_then(|i| editing::pick_wall_end(&walls[i], mouse_world, pick_radius).map(|e| (i, e)))
+                            .or_else(|| {
+                                picked.and_then(|i| editing::pick_wall_end(&walls[i], mouse_world, pick_radius).map(|e| (i, e)))
+                            });
+                        match grab {
+                            Some((index, end)) => {
+                                ws.selected = Some(index);
+                                ws.dragging = Some(editing::WallDrag {
+                                    index,
+                                    end,
+                                    original: walls[index],
+                                });
+                            }
+                            None => ws.selected = picked,
+                        }
+                    }
+                    ToolMode::ToxicZone => {
+                        let op = editing::toxic_zone_op(sim.world.wrap(mouse_world), &ui_state.toxic_brush);
+                        ui_state.edit_history.perform(&mut sim, op);
+                    }
+                    ToolMode::Food => {
+                        let op = editing::food_cluster_op(&mut sim, mouse_world);
+                        ui_state.edit_history.perform(&mut sim, op);
+                    }
+                    ToolMode::Drag => {
+                        let pick_radius = 30.0 / camera.smooth_zoom;
+                        sim.held = camera.pick_entity(mouse_world, &sim.arena, pick_radius);
+                    }
+                    ToolMode::Region => ui_state.region.drag_start = Some(mouse_world),
+                    ToolMode::Portal => ui_state.portal_drag_start = Some(mouse_world),
+                    ToolMode::Erase => {
+                        let center = sim.world.wrap(mouse_world);
+                        if let Some(op) = editing::erase_op(&sim, center, &ui_state.erase_brush, erase_walls) {
+                            ui_state.edit_history.perform(&mut sim, op);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Wall tool: a wall is placed when the drag is released
+        if is_mouse_button_released(MouseButton::Left) {
+            if let Some(start) = ui_state.wall_drag_start.take() {
+                if let Some(op) = editing::wall_op(start, mouse_world) {
+                    ui_state.edit_history.perform(&mut sim, op);
+                }
+            }
+            if let Some(start) = ui_state.portal_drag_start.take() {
+                if let Some(op) = editing::portal_op(sim.world.wrap(start), sim.world.wrap(mouse_world)) {
+                    ui_state.edit_history.perform(&mut sim, op);
+                }
             }
         }
 
-        if is_key_pressed(KeyCode::Escape) {
+        // Wall-edit tool: hover highlight, live endpoint drag, one undoable move on release
+        ui_state.wall_edit.validate(&sim.environment.walls);
+        if ui_state.tool_mode == ToolMode::EditWalls {
+            if let Some(drag) = ui_state.wall_edit.dragging {
+                let wall = &mut sim.environment.walls[drag.index];
+                match drag.end {
+                    editing::WallEnd::A => wall.a = mouse_world,
+                    editing::WallEnd::B => wall.b = mouse_world,
+                }
+                if is_mouse_button_released(MouseButton::Left) {
+                    let moved = *wall;
+                    *wall = drag.original;
+                    ui_state.wall_edit.dragging = None;
+                    if moved != drag.original && moved.a.distance(moved.b) >= config::WALL_MIN_LENGTH {
+                        let op = editing::EditOp::MoveWall {
+                            from: drag.original,
+                            to: moved,
+                        };
+                        ui_state.edit_history.perform(&mut sim, op);
+                    }
+                }
+            } else if !egui_wants_pointer {
+                let pick_radius = WALL_PICK_PIXELS / camera.smooth_zoom;
+                ui_state.wall_edit.hovered = editing::pick_wall(&sim.environment.walls, mouse_world, pick_radius);
+            }
+        } else {
+            ui_state.wall_edit.cancel(&mut sim.environment.walls);
+        }
+
+        // Drag tool: the held entity follows the cursor and is dropped on release
+        if let Some(id) = sim.held {
+            let released = is_mouse_button_released(MouseButton::Left);
+            if ui_state.tool_mode != ToolMode::Drag || released || !sim.teleport_entity(id, mouse_world) {
+                sim.held = None;
+                if let Some(e) = sim.arena.get(id) {
+                    let detail = format!("#{} to ({:.0}, {:.0})", e.uid, e.pos.x, e.pos.y);
+                    sim.journal.record(sim.tick_count, InterventionKind::Move, detail);
+                }
+            }
+        }
+
+        // Select tool: a click selects (follows) one entity, a drag rubber-band selects many.
+        // Shift adds to the current multi-selection.
+        if is_mouse_button_released(MouseButton::Left) {
+            if let Some(start) = ui_state.select_drag_start.take() {
+                let add = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+                let drag_pixels = start.distance(mouse_world) * camera.smooth_zoom;
+                if drag_pixels >= SELECT_DRAG_THRESHOLD {
+                    ui_state.selection.select_rect(&sim, start, mouse_world, add);
+                } else {
+                    let pick_radius = 30.0 / camera.smooth_zoom;
+                    camera.following = camera.pick_entity(mouse_world, &sim.arena, pick_radius);
+                    if !add {
+                        ui_state.selection.entities.clear();
+                    }
+                }
+            }
+        }
+        ui_state.selection.prune(&sim);
+
+        // Region tool: a drag marks a rectangle, clicks place polygon vertices
+        if is_mouse_button_released(MouseButton::Left) {
+            if let Some(start) = ui_state.region.drag_start.take() {
+                let zoom = camera.smooth_zoom;
+                ui_state.region.release(start, mouse_world, SELECT_DRAG_THRESHOLD / zoom, WALL_PICK_PIXELS / zoom);
+            }
+        }
+        if ui_state.tool_mode != ToolMode::Region {
+            ui_state.region.drag_start = None;
+            ui_state.region.polygon.clear();
+        }
+
+        // Keyboard actions (suspended while the settings editor is capturing a new binding or a
+        // text field has focus)
+        let keys_active = ui_state.key_editor.capture.is_none() && !egui_wants_keyboard;
+        let pressed = |action| keys_active && ui_state.keybindings.pressed(action);
+
+        let frame_group = pressed(Action::FrameGroup);
+        let deselect = pressed(Action::Deselect);
+        let toggle_pause = pressed(Action::TogglePause);
+        let toggle_rays = pressed(Action::ToggleRays);
+        let delete_entity = pressed(Action::DeleteEntity);
+        let save = pressed(Action::Save);
+        let load = pressed(Action::Load);
+        let undo = pressed(Action::Undo);
+        let redo = pressed(Action::Redo);
+        let toggle_inset = pressed(Action::ToggleInset);
+        let cycle_colors = pressed(Action::CycleColorMode);
+        let toggle_recording = pressed(Action::ToggleRecording);
+
+        // Frame the selected entity's whole species (toggle)
+        if frame_group {
+            if camera.following_group.is_some() {
+                camera.following_group = None;
+            } else if let Some(id) = camera.following {
+                camera.following_group = sim.species.species_of(id.index as usize).map(CameraGroup::Species);
+            }
+        }
+
+        if deselect {
             camera.following = None;
+            camera.following_group = None;
+            ui_state.wall_drag_start = None;
+            ui_state.portal_drag_start = None;
+            ui_state.select_drag_start = None;
+            ui_state.selection.entities.clear();
+            ui_state.selection.picking_destination = false;
+            ui_state.region.clear();
+            ui_state.wall_edit.cancel(&mut sim.environment.walls);
         }
 
-        if is_key_pressed(KeyCode::Space) {
+        if toggle_pause {
             sim.paused = !sim.paused;
         }
 
-        // Toggle sensor ray visualization
-        if is_key_pressed(KeyCode::R) {
-            sim.show_rays = !sim.show_rays;
+        // Cycle sensor ray visualization: off, selected only, all
+        if toggle_rays {
+            sim.ray_mode = sim.ray_mode.next();
+        }
+
+        if toggle_inset {
+            ui_state.show_follow_inset = !ui_state.show_follow_inset;
+        }
+
+        if cycle_colors {
+            sim.color_mode = sim.color_mode.next();
+        }
+
+        if toggle_recording {
+            match recording.take() {
+                Some(rec) => stop_recording(rec, Ok(()), &mut ui_state, sim.tick_count),
+                None => start_recording(&mut recording, &run_config.record, &run_info, &mut ui_state, sim.tick_count),
+            }
         }
 
-        // Delete selected entity
-        if is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace) {
+        // Delete the selected wall in the wall-edit tool
+        if delete_entity && ui_state.tool_mode == ToolMode::EditWalls {
+            if let Some(i) = ui_state.wall_edit.selected.take().filter(|_| ui_state.wall_edit.dragging.is_none()) {
+                let op = editing::EditOp::RemoveWall(sim.environment.walls[i]);
+                ui_state.edit_history.perform(&mut sim, op);
+            }
+        }
+        // Delete selected entity (and any multi-selection)
+        else if delete_entity {
+            ui_state.selection.delete_all(&mut sim);
             if let Some(id) = camera.following {
                 if let Some(entity) = sim.arena.get_mut(id) {
                     entity.alive = false;
+                    let detail = format!("#{}", entity.uid);
+                    sim.journal.record(sim.tick_count, InterventionKind::Delete, detail);
                 }
                 camera.following = None;
             }
         }
 
-        // Save/Load (Ctrl+S / Ctrl+L)
-        if is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl) {
-            if is_key_pressed(KeyCode::S) {
-                match save_load::save_to_file(&sim, "genesis_save.bin") {
-                    Ok(()) => eprintln!("[GENESIS] Saved to genesis_save.bin"),
-                    Err(e) => eprintln!("[GENESIS] Save failed: {e}"),
-                }
-            }
-            if is_key_pressed(KeyCode::L) {
-                match save_load::load_from_file("genesis_save.bin") {
-                    Ok(loaded) => {
-                        sim = loaded;
-                        camera = CameraController::new(sim.world.center());
-                        eprintln!("[GENESIS] Loaded from genesis_save.bin (tick {})", sim.tick_count);
-                    }
-                    Err(e) => eprintln!("[GENESIS] Load failed: {e}"),
+        // Save/Load and edit history
+        if save {
+            let message = match save_load::save_to_file(&sim, &camera, "genesis_save.bin") {
+                Ok(()) => "Saved to genesis_save.bin".to_string(),
+                Err(e) => format!("Save failed: {e}"),
+            };
+            eprintln!("[GENESIS] {message}");
+            ui_state.notifications.log.post(NoticeKind::Save, sim.tick_count, message, None);
+        }
+        if undo || redo {
+            // Undo/redo can reorder walls, so wall indices are no longer meaningful
+            ui_state.wall_edit.cancel(&mut sim.environment.walls);
+        }
+        if undo && !ui_state.edit_history.undo(&mut sim) {
+            eprintln!("[GENESIS] Nothing to undo");
+        }
+        if redo && !ui_state.edit_history.redo(&mut sim) {
+            eprintln!("[GENESIS] Nothing to redo");
+        }
+        if load {
+            let message = match save_load::load_from_file("genesis_save.bin") {
+                Ok((loaded, view)) => {
+                    install_loaded(&mut sim, &mut camera, &mut ui_state, &mut neuron_recorder, loaded, view);
+                    format!("Loaded from genesis_save.bin (tick {})", sim.tick_count)
                 }
-            }
+                Err(e) => format!("Load failed: {e}"),
+            };
+            eprintln!("[GENESIS] {message}");
+            ui_state.notifications.log.post(NoticeKind::Save, sim.tick_count, message, None);
         }
 
         let alpha = if !sim.paused {
@@ -153,33 +754,161 @@ async fn main() {
             1.0
         };
 
-        // Render scene (with or without bloom)
-        if let Some(ref mut b) = bloom {
-            b.check_resize();
-            renderer::draw_with_bloom(&sim, &camera, alpha, b);
+        // Render scene (with or without bloom); fast-forward only draws a progress overlay
+        if fast_forward {
+            let (_, avg_generation) = stats::averages(&sim);
+            renderer::draw_fast_forward(&sim, fast_forward_speed as f32, avg_generation, ui_state.run_until.as_ref());
         } else {
-            renderer::draw(&sim, &camera, alpha);
+            if let Some(ref mut b) = bloom {
+                b.set_settings(ui_state.graphics);
+                b.check_resize();
+                renderer::draw_with_bloom(&sim, &camera, alpha, b);
+            } else {
+                renderer::draw(&sim, &camera, alpha);
+            }
+            if let Some(start) = ui_state.wall_drag_start {
+                renderer::draw_wall_preview(&camera, start, mouse_world);
+            }
+            if let Some(start) = ui_state.portal_drag_start {
+                renderer::draw_portal_preview(&camera, start, mouse_world);
+            }
+            if let Some(start) = ui_state.select_drag_start {
+                renderer::draw_selection_box(&camera, start, mouse_world);
+            }
+            renderer::draw_region(&camera, &ui_state.region, mouse_world);
+            if ui_state.tool_mode == ToolMode::ToxicZone && !egui_wants_pointer {
+                renderer::draw_toxic_brush(&camera, mouse_world, &ui_state.toxic_brush);
+            }
+            if ui_state.tool_mode == ToolMode::Erase && !egui_wants_pointer {
+                renderer::draw_erase_brush(&camera, mouse_world, &ui_state.erase_brush, erase_walls);
+            }
+            if ui_state.tool_mode == ToolMode::EditWalls {
+                renderer::draw_wall_edit(&camera, &sim.environment.walls, &ui_state.wall_edit);
+            }
+            renderer::draw_entity_labels(&camera, &sim.arena, alpha);
+            if ui_state.show_intent_bubble {
+                renderer::draw_intent_bubble(&camera, &sim, alpha);
+            }
+            renderer::draw_selection_markers(&camera, &sim.arena, &ui_state.selection.entities);
+            if let Some(id) = sim.held {
+                renderer::draw_selection_markers(&camera, &sim.arena, &[id]);
+            }
+            if ui_state.show_follow_inset {
+                follow_inset.draw(&sim, &camera, alpha);
+            }
+        }
+
+        // Snapshot frames show the world without the UI
+        if let Some(ref mut run) = snapshots {
+            if run.is_due(sim.tick_count) {
+                let path = run.capture(&sim);
+                eprintln!("[GENESIS] Snapshot {path} (tick {})", sim.tick_count);
+            }
+            if run.is_finished() {
+                match run.write_report(&sim.report_header(), &sim.run_info) {
+                    Ok(path) => eprintln!("[GENESIS] Snapshot report → {path}"),
+                    Err(e) => eprintln!("[GENESIS] Snapshot report failed: {e}"),
+                }
+                save_load::end_session();
+                break;
+            }
         }
 
         // Draw egui UI on top
-        ui::draw_ui(&mut sim, &mut camera, &mut ui_state, &sim_stats);
+        ui::draw_ui(&mut sim, &mut camera, &mut ui_state, &mut sim_stats, &mut neuron_recorder);
+
+        // Recorded frames include the UI; the indicator is drawn after the capture so it stays out
+        if let Some(mut rec) = recording.take() {
+            match rec.capture() {
+                Ok(()) => {
+                    renderer::draw_recording_indicator(rec.frames, rec.elapsed());
+                    recording = Some(rec);
+                }
+                Err(e) => stop_recording(rec, Err(e), &mut ui_state, sim.tick_count),
+            }
+        }
+
+        // Persist visual and UI preferences whenever one changes
+        let prefs = Preferences::capture(&sim, &mut ui_state);
+        if prefs != saved_prefs {
+            if let Err(e) = prefs.save(preferences::PREFERENCES_PATH) {
+                eprintln!("[GENESIS] Preferences save failed: {e}");
+            }
+            saved_prefs = prefs;
+        }
 
         next_frame().await;
     }
-}
 
-fn compute_averages(sim: &SimState) -> (f32, f32) {
-    let mut total_energy = 0.0f32;
-    let mut total_gen = 0.0f32;
-    let mut count = 0u32;
-    for (_idx, e) in sim.arena.iter_alive() {
-        total_energy += e.energy;
-        total_gen += e.generation_depth as f32;
-        count += 1;
+    if let Some(rec) = recording {
+        stop_recording(rec, Ok(()), &mut ui_state, sim.tick_count);
     }
-    if count > 0 {
-        (total_energy / count as f32, total_gen / count as f32)
-    } else {
-        (0.0, 0.0)
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(db) = run_db {
+        match db.finish() {
+            Ok(()) => eprintln!("[GENESIS] Run database closed"),
+            Err(e) => eprintln!("[GENESIS] Run database failed: {e}"),
+        }
     }
 }
+
+/// Start a new recording take with the command-line record settings.
+fn start_recording(
+    recording: &mut Option<capture::Recording>,
+    settings: &capture::RecordSettings,
+    run_info: &manifest::RunInfo,
+    ui_state: &mut UiState,
+    tick: u64,
+) {
+    let message = match capture::Recording::start(settings) {
+        Ok(rec) => {
+            write_manifest(&rec.dir, "recording", run_info, None);
+            let message = format!("Recording to {}/", rec.dir);
+            *recording = Some(rec);
+            message
+        }
+        Err(e) => format!("Recording failed: {e}"),
+    };
+    eprintln!("[GENESIS] {message}");
+    ui_state.notifications.log.post(NoticeKind::Save, tick, message, None);
+}
+
+/// Finish a recording, reporting the error that ended it early if there was one.
+fn stop_recording(rec: capture::Recording, ended_by: Result<(), String>, ui_state: &mut UiState, tick: u64) {
+    let finished = rec.finish();
+    let message = match (ended_by, finished) {
+        (Ok(()), Ok(summary)) => format!("Recording stopped: {summary}"),
+        (Err(e), Ok(summary)) => format!("Recording stopped ({e}): {summary}"),
+        (_, Err(e)) => format!("Recording failed: {e}"),
+    };
+    eprintln!("[GENESIS] {message}");
+    ui_state.notifications.log.post(NoticeKind::Save, tick, message, None);
+}
+
+/// Replace the running simulation with a loaded one. The hall of fame and visual preferences
+/// are kept across loads.
+fn install_loaded(
+    sim: &mut SimState,
+    camera: &mut CameraController,
+    ui_state: &mut UiState,
+    recorder: &mut NeuronRecorder,
+    loaded: SimState,
+    view: save_load::SavedView,
+) {
+    recorder.stop();
+    let hall_of_fame = std::mem::take(&mut sim.hall_of_fame);
+    let (ray_mode, heatmap_layer, heatmap_opacity, color_mode) =
+        (sim.ray_mode, sim.heatmap_layer, sim.heatmap_opacity, sim.color_mode);
+    *sim = loaded;
+    sim.hall_of_fame = hall_of_fame;
+    sim.ray_mode = ray_mode;
+    sim.heatmap_layer = heatmap_layer;
+    sim.heatmap_opacity = heatmap_opacity;
+    sim.color_mode = color_mode;
+    *camera = CameraController::new(sim.world.center());
+    view.apply(camera, &sim.arena);
+    ui_state.edit_history.clear();
+    ui_state.selection.entities.clear();
+    // A run-until carries on from the loaded tick
+    ui_state.run_until = ui_state.run_until.map(|run| run_target::RunUntil::start(sim, run.target));
+}
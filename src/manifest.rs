@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cli::RunConfig;
+use crate::config;
+use crate::environment::WorldPreset;
+use crate::reporting::json_number;
+use crate::simulation::SimState;
+use crate::storage;
+
+/// File written next to the output of every QA, benchmark, snapshot and experiment run, and
+/// into every recording take.
+pub const RUN_MANIFEST_FILE: &str = "run_manifest.json";
+
+/// Provenance of a run: what built it and how it was configured. Captured once at startup and
+/// attached to its outputs (saves, snapshot reports, QA reports, benchmark reports and run
+/// manifests), so any of them can be traced back to the command that produced it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunInfo {
+    pub version: String,
+    /// Commit the binary was built from, or empty outside a git checkout.
+    pub git_hash: String,
+    /// Command-line arguments, without the program name.
+    pub args: Vec<String>,
+    /// The resolved run configuration as JSON, with defaults filled in.
+    pub config: String,
+    pub seed: u64,
+    pub terrain_seed: u32,
+    pub started_at_unix: u64,
+}
+
+impl RunInfo {
+    pub fn new(run_config: &RunConfig) -> Self {
+        let seed = run_config.seed.unwrap_or(42);
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: env!("GENESIS_GIT_HASH").to_string(),
+            args: std::env::args().skip(1).collect(),
+            config: config_json(run_config),
+            seed,
+            terrain_seed: run_config.terrain_seed.unwrap_or(seed as u32),
+            started_at_unix: unix_now(),
+        }
+    }
+
+    pub fn git_hash(&self) -> &str {
+        if self.git_hash.is_empty() {
+            "unknown"
+        } else {
+            &self.git_hash
+        }
+    }
+
+    /// JSON object with every field; `config` is embedded as an object.
+    pub fn to_json(&self) -> String {
+        let args: Vec<String> = self.args.iter().map(|a| json_string(a)).collect();
+        format!(
+            "{{\"genesis_version\": {}, \"git_hash\": {}, \"args\": [{}], \"config\": {}, \"seed\": {}, \"terrain_seed\": {}, \"started_at\": {}, \"started_at_unix\": {}}}",
+            json_string(&self.version),
+            json_string(self.git_hash()),
+            args.join(", "),
+            if self.config.is_empty() { "null" } else { &self.config },
+            self.seed,
+            self.terrain_seed,
+            json_string(&utc_timestamp(self.started_at_unix)),
+            self.started_at_unix
+        )
+    }
+
+    /// One line for logs and report headers.
+    pub fn summary(&self) -> String {
+        format!(
+            "genesis {} ({}) seed={} terrain_seed={} started={} args=[{}]",
+            self.version,
+            self.git_hash(),
+            self.seed,
+            self.terrain_seed,
+            utc_timestamp(self.started_at_unix),
+            self.args.join(" ")
+        )
+    }
+}
+
+/// Write `dir/run_manifest.json`: the run's provenance (command line, resolved configuration,
+/// genesis version and commit, start time) and the platform, so the run can be reproduced.
+/// `sim` is the freshly built simulation the run uses, if it builds one up front (QA scenarios
+/// build their own). Returns the path written.
+pub fn write_run_manifest(dir: &str, mode: &str, run_info: &RunInfo, sim: Option<&SimState>) -> Result<String, String> {
+    let args: Vec<String> = run_info.args.iter().map(|a| json_string(a)).collect();
+    let json = format!(
+        "{{\n  \"mode\": {},\n  \"genesis_version\": {},\n  \"git_hash\": {},\n  \"started_at\": {},\n  \"started_at_unix\": {},\n  \"platform\": {{\"os\": {}, \"arch\": {}, \"family\": {}}},\n  \"args\": [{}],\n  \"config\": {},\n  \"world\": {}\n}}\n",
+        json_string(mode),
+        json_string(&run_info.version),
+        json_string(run_info.git_hash()),
+        json_string(&utc_timestamp(run_info.started_at_unix)),
+        run_info.started_at_unix,
+        json_string(std::env::consts::OS),
+        json_string(std::env::consts::ARCH),
+        json_string(std::env::consts::FAMILY),
+        args.join(", "),
+        run_info.config,
+        sim.map_or("null".to_string(), world_json),
+    );
+    let path = format!("{dir}/{RUN_MANIFEST_FILE}");
+    storage::write(&path, json).map_err(|e| format!("Write error: {e}"))?;
+    Ok(path)
+}
+
+/// Every RunConfig option, with the defaults the run falls back to filled in.
+fn config_json(rc: &RunConfig) -> String {
+    let seed = rc.seed.unwrap_or(42);
+    let optional = |v: Option<String>| v.unwrap_or_else(|| "null".to_string());
+    let benchmark = rc.benchmark.as_ref().map(|b| {
+        format!(
+            "{{\"ticks\": {}, \"warmup_ticks\": {}, \"percentiles\": [{}], \"tick_buckets_ms\": [{}], \"scenario\": {}}}",
+            b.ticks,
+            config::BENCHMARK_WARMUP_TICKS,
+            number_list(&b.percentiles),
+            number_list(&b.tick_buckets_ms),
+            optional(b.scenario.map(|s| json_string(s.id())))
+        )
+    });
+    let snapshot = rc.snapshot.as_ref().map(|p| {
+        let ticks: Vec<String> = p.ticks.iter().map(|t| t.to_string()).collect();
+        format!(
+            "{{\"ticks\": [{}], \"every\": {}, \"until\": {}, \"captures\": {}}}",
+            ticks.join(", "),
+            optional(p.every.map(|v| v.to_string())),
+            optional(p.until.map(|v| v.to_string())),
+            p.targets().len()
+        )
+    });
+    let record = format!(
+        "{{\"out\": {}, \"every\": {}, \"ffmpeg\": {}}}",
+        optional(rc.record.out.as_deref().map(json_string)),
+        rc.record.every,
+        rc.record.ffmpeg
+    );
+    format!(
+        "{{\"scenario\": {}, \"world_preset\": {}, \"seed\": {seed}, \"terrain_seed\": {}, \"sim_hz\": {}, \"energy_audit\": {}, \"qa\": {}, \"benchmark\": {}, \"snapshot\": {}, \"experiment\": {}, \"db\": {}, \"telemetry_port\": {}, \"control_port\": {}, \"run_until\": {}, \"record\": {record}}}",
+        optional(rc.scenario.as_deref().map(json_string)),
+        json_string(rc.world_preset.unwrap_or(WorldPreset::Default).id()),
+        rc.terrain_seed.unwrap_or(seed as u32),
+        json_number(rc.sim_hz.unwrap_or(config::BASE_SIM_HZ) as f64),
+        rc.energy_audit,
+        optional(rc.qa.map(|q| json_string(q.id()))),
+        optional(benchmark),
+        optional(snapshot),
+        optional(rc.experiment.as_deref().map(json_string)),
+        optional(rc.db.as_deref().map(json_string)),
+        optional(rc.telemetry_port.map(|p| p.to_string())),
+        optional(rc.control_port.map(|p| p.to_string())),
+        optional(rc.run_until.map(|t| json_string(&t.describe())))
+    )
+}
+
+/// The simulation as built for the run, including the constants that shape it.
+fn world_json(sim: &SimState) -> String {
+    format!(
+        "{{\"seed\": {}, \"terrain_seed\": {}, \"preset\": {}, \"sim_hz\": {}, \"width\": {}, \"height\": {}, \"toroidal\": {}, \"initial_entities\": {}, \"entity_capacity\": {}, \"max_entities\": {}, \"initial_food\": {}}}",
+        sim.seed,
+        sim.environment.terrain_seed,
+        json_string(sim.environment.world_preset.id()),
+        json_number(sim.sim_hz() as f64),
+        json_number(sim.world.width as f64),
+        json_number(sim.world.height as f64),
+        sim.world.toroidal,
+        sim.arena.count,
+        sim.arena.capacity(),
+        config::MAX_ENTITY_COUNT,
+        sim.food.len()
+    )
+}
+
+fn number_list(values: &[f64]) -> String {
+    values.iter().map(|v| json_number(*v)).collect::<Vec<_>>().join(", ")
+}
+
+/// JSON string literal with quotes, backslashes and control characters escaped.
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Seconds since the Unix epoch. `SystemTime::now` panics on wasm32, so the browser build
+/// reads the page's clock through miniquad instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn unix_now() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn unix_now() -> u64 {
+    macroquad::miniquad::date::now() as u64
+}
+
+/// ISO 8601 UTC timestamp for seconds since the Unix epoch.
+pub fn utc_timestamp(secs: u64) -> String {
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
@@ -0,0 +1,86 @@
+// Notification bus: significant events posted by the simulation and the app, kept in a
+// bounded log that feeds the on-screen toasts and the event log panel.
+
+use std::collections::VecDeque;
+
+use macroquad::prelude::*;
+
+use crate::config;
+
+/// Category of a notice, used for coloring and filtering.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoticeKind {
+    /// The population reached a new generation milestone.
+    Milestone,
+    /// A species (or the whole population) died out.
+    Extinction,
+    Storm,
+    /// Saves, loads and autosaves.
+    Save,
+}
+
+impl NoticeKind {
+    pub const ALL: [NoticeKind; 4] = [
+        NoticeKind::Milestone,
+        NoticeKind::Extinction,
+        NoticeKind::Storm,
+        NoticeKind::Save,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoticeKind::Milestone => "Milestones",
+            NoticeKind::Extinction => "Extinctions",
+            NoticeKind::Storm => "Storms",
+            NoticeKind::Save => "Saves",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Notice {
+    pub kind: NoticeKind,
+    pub tick: u64,
+    pub message: String,
+    /// Where it happened, if anywhere in particular; the UI can jump the camera there.
+    pub pos: Option<Vec2>,
+}
+
+/// Bounded log of notices, oldest first, holding at most NOTIFICATION_LOG_SIZE entries.
+#[derive(Default)]
+pub struct NoticeLog {
+    pub entries: VecDeque<Notice>,
+    /// Notices ever posted, including dropped ones, so readers can tell which are new.
+    pub total: u64,
+}
+
+impl NoticeLog {
+    pub fn post(&mut self, kind: NoticeKind, tick: u64, message: impl Into<String>, pos: Option<Vec2>) {
+        self.push(Notice {
+            kind,
+            tick,
+            message: message.into(),
+            pos,
+        });
+    }
+
+    pub fn push(&mut self, notice: Notice) {
+        if self.entries.len() >= config::NOTIFICATION_LOG_SIZE {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(notice);
+        self.total += 1;
+    }
+
+    /// Move every notice into `other`, leaving this log empty.
+    pub fn drain_into(&mut self, other: &mut NoticeLog) {
+        for notice in self.entries.drain(..) {
+            other.push(notice);
+        }
+    }
+}
+
+/// Highest generation milestone at or below a generation depth.
+pub fn generation_milestone(depth: u32) -> u32 {
+    depth / config::GENERATION_MILESTONE_STEP * config::GENERATION_MILESTONE_STEP
+}
@@ -0,0 +1,60 @@
+use macroquad::prelude::*;
+
+use crate::config;
+
+/// Nutrients returned to the soil by carcasses that decayed uneaten, per terrain cell in
+/// [0, 1]. They fade over time and boost food spawning and plant growth where they lie, so
+/// places where many died become briefly richer.
+pub struct NutrientGrid {
+    pub cells: Vec<f32>,
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: f32,
+}
+
+impl NutrientGrid {
+    pub fn new(world_w: f32, world_h: f32, cell_size: f32) -> Self {
+        let width = (world_w / cell_size).ceil() as usize;
+        let height = (world_h / cell_size).ceil() as usize;
+        Self {
+            cells: vec![0.0; width * height],
+            width,
+            height,
+            cell_size,
+        }
+    }
+
+    fn index(&self, pos: Vec2) -> usize {
+        let x = ((pos.x / self.cell_size).max(0.0) as usize).min(self.width - 1);
+        let y = ((pos.y / self.cell_size).max(0.0) as usize).min(self.height - 1);
+        y * self.width + x
+    }
+
+    /// Return a decayed carcass holding `energy` to the cell under `pos`.
+    pub fn deposit(&mut self, pos: Vec2, energy: f32) {
+        let i = self.index(pos);
+        let cell = &mut self.cells[i];
+        *cell = (*cell + energy / config::MEAT_ENERGY * config::NUTRIENTS_PER_CARCASS).min(1.0);
+    }
+
+    /// Nutrients leach away at a constant rate.
+    pub fn decay(&mut self, dt: f32) {
+        let loss = config::NUTRIENT_DECAY_RATE * dt;
+        self.cells.iter_mut().for_each(|n| *n = (*n - loss).max(0.0));
+    }
+
+    pub fn at(&self, pos: Vec2) -> f32 {
+        self.cells[self.index(pos)]
+    }
+
+    /// Food spawn multiplier from the nutrients at a position.
+    pub fn food_multiplier(&self, pos: Vec2) -> f32 {
+        1.0 + config::NUTRIENT_FOOD_BOOST * self.at(pos)
+    }
+
+    /// Share of cells holding noticeable nutrients.
+    pub fn enriched_fraction(&self) -> f32 {
+        let enriched = self.cells.iter().filter(|&&n| n > 0.05).count();
+        enriched as f32 / self.cells.len().max(1) as f32
+    }
+}
@@ -43,6 +43,11 @@ impl ParticleSystem {
         self.emit_burst(pos, 10, Color::new(1.0, 0.7, 0.1, 1.0), 50.0, 0.5);
     }
 
+    /// Dust burst when a shelter is built (brown).
+    pub fn emit_build(&mut self, pos: Vec2) {
+        self.emit_burst(pos, 10, Color::new(0.75, 0.6, 0.35, 1.0), 35.0, 0.6);
+    }
+
     fn emit_burst(&mut self, pos: Vec2, count: usize, color: Color, speed: f32, lifetime: f32) {
         for i in 0..count {
             if self.particles.len() >= MAX_PARTICLES {
@@ -2,62 +2,64 @@ use macroquad::prelude::*;
 
 use crate::config;
 use crate::entity::EntityArena;
+use crate::environment::{TerrainGrid, TerrainType, Wall};
 use crate::spatial_hash::SpatialHash;
 use crate::world::World;
 
 /// Apply random wander movement (Phase 1 placeholder — replaced by brain output in Phase 2).
 pub fn random_wander(arena: &mut EntityArena, rng: &mut impl ::rand::Rng, dt: f32) {
-    for slot in arena.entities.iter_mut() {
-        if let Some(entity) = slot {
-            // Random turn
-            entity.heading += rng.gen_range(-1.5..1.5) * dt;
+    for (_, entity) in arena.iter_alive_mut() {
+        // Random turn
+        entity.heading += rng.gen_range(-1.5..1.5) * dt;
 
-            // Constant forward drive
-            let dir = Vec2::from_angle(entity.heading);
-            let target_vel = dir * config::ENTITY_MAX_SPEED * 0.5 * entity.speed_multiplier;
+        // Constant forward drive
+        let dir = Vec2::from_angle(entity.heading);
+        let target_vel = dir * config::ENTITY_MAX_SPEED * 0.5 * entity.speed_multiplier;
 
-            // Smooth velocity toward target (simple friction model)
-            entity.velocity +=
-                (target_vel - entity.velocity) * (config::ENTITY_FRICTION * dt).min(1.0);
-        }
+        // Smooth velocity toward target (simple friction model)
+        entity.velocity +=
+            (target_vel - entity.velocity) * (config::ENTITY_FRICTION * dt).min(1.0);
     }
 }
 
 /// Apply brain-driven motor outputs to entity movement.
+/// Top speed depends on whether the entity is swimming and on its aquatic adaptation.
 pub fn apply_motor_outputs(
     arena: &mut EntityArena,
     motor_outputs: &[(f32, f32)], // (forward_drive [0,1], turn [-1,1]) indexed by slot
+    terrain: &TerrainGrid,
     dt: f32,
 ) {
-    for (idx, slot) in arena.entities.iter_mut().enumerate() {
-        if let Some(entity) = slot {
-            if idx < motor_outputs.len() {
-                let (forward, turn) = motor_outputs[idx];
+    for (idx, entity) in arena.iter_alive_mut() {
+        if idx < motor_outputs.len() {
+            let (forward, turn) = motor_outputs[idx];
 
-                // Turn
-                entity.heading += turn * config::ENTITY_TURN_RATE * dt;
+            // Turn
+            entity.heading += turn * config::ENTITY_TURN_RATE * dt;
 
-                // Forward drive
-                let dir = Vec2::from_angle(entity.heading);
-                let max_speed = config::ENTITY_MAX_SPEED * entity.speed_multiplier;
-                let target_vel = dir * forward * max_speed;
+            // Forward drive
+            let dir = Vec2::from_angle(entity.heading);
+            let medium_mult = if terrain.get_at(entity.pos) == TerrainType::Water {
+                entity.water_speed_mult()
+            } else {
+                entity.land_speed_mult()
+            };
+            let max_speed = config::ENTITY_MAX_SPEED * entity.effective_speed() * medium_mult;
+            let target_vel = dir * forward * max_speed;
 
-                entity.velocity +=
-                    (target_vel - entity.velocity) * (config::ENTITY_FRICTION * dt).min(1.0);
-            }
+            entity.velocity +=
+                (target_vel - entity.velocity) * (config::ENTITY_FRICTION * dt).min(1.0);
         }
     }
 }
 
 /// Integrate positions from velocities and wrap to world bounds.
 pub fn integrate(arena: &mut EntityArena, world: &World, dt: f32) {
-    for slot in arena.entities.iter_mut() {
-        if let Some(entity) = slot {
-            entity.prev_pos = entity.pos;
-            entity.pos += entity.velocity * dt;
-            entity.pos = world.wrap(entity.pos);
-            entity.age += dt;
-        }
+    for (_, entity) in arena.iter_alive_mut() {
+        entity.prev_pos = entity.pos;
+        entity.pos += entity.velocity * dt;
+        entity.pos = world.wrap(entity.pos);
+        entity.age += dt;
     }
 }
 
@@ -66,15 +68,16 @@ pub fn resolve_collisions(arena: &mut EntityArena, spatial: &SpatialHash, world:
     let max_radius = config::ENTITY_BASE_RADIUS * 2.0;
     let query_radius = max_radius * 2.5;
 
-    // Collect positions and radii first to avoid borrow conflicts
-    let entity_data: Vec<Option<(Vec2, f32)>> = arena
-        .entities
-        .iter()
-        .map(|slot| slot.as_ref().map(|e| (e.pos, e.radius)))
-        .collect();
+    // Collect positions, radii and masses first to avoid borrow conflicts
+    let mut entity_data: Vec<Option<(Vec2, f32, f32)>> = vec![None; arena.entities.len()];
+    let mut living = Vec::with_capacity(arena.count);
+    for (idx, e) in arena.iter_alive() {
+        entity_data[idx] = Some((e.pos, e.radius, e.mass()));
+        living.push(idx);
+    }
 
-    for (idx_a, slot_a) in entity_data.iter().enumerate() {
-        if let Some((pos_a, radius_a)) = slot_a {
+    for idx_a in living {
+        if let Some((pos_a, radius_a, mass_a)) = &entity_data[idx_a] {
             let neighbors =
                 spatial.query_radius_excluding(*pos_a, query_radius, idx_a as u32, world, arena);
 
@@ -83,7 +86,7 @@ pub fn resolve_collisions(arena: &mut EntityArena, spatial: &SpatialHash, world:
                 if idx_b <= idx_a {
                     continue; // avoid double-processing pairs
                 }
-                if let Some((pos_b, radius_b)) = entity_data[idx_b] {
+                if let Some((pos_b, radius_b, mass_b)) = entity_data[idx_b] {
                     let delta = world.delta(*pos_a, pos_b);
                     let dist_sq = delta.length_squared();
                     let min_dist = radius_a + radius_b;
@@ -91,13 +94,15 @@ pub fn resolve_collisions(arena: &mut EntityArena, spatial: &SpatialHash, world:
                     if dist_sq < min_dist * min_dist && dist_sq > 0.001 {
                         let dist = dist_sq.sqrt();
                         let overlap = min_dist - dist;
-                        let push = delta / dist * (overlap * 0.5);
+                        // The lighter entity gives way more
+                        let push = delta / dist * overlap;
+                        let share_a = mass_b / (mass_a + mass_b);
 
                         if let Some(ea) = arena.get_mut_by_index(idx_a) {
-                            ea.pos = world.wrap(ea.pos - push);
+                            ea.pos = world.wrap(ea.pos - push * share_a);
                         }
                         if let Some(eb) = arena.get_mut_by_index(idx_b) {
-                            eb.pos = world.wrap(eb.pos + push);
+                            eb.pos = world.wrap(eb.pos + push * (1.0 - share_a));
                         }
                     }
                 }
@@ -105,3 +110,31 @@ pub fn resolve_collisions(arena: &mut EntityArena, spatial: &SpatialHash, world:
         }
     }
 }
+
+/// Push entities out of user-placed walls and cancel velocity into them.
+pub fn resolve_wall_collisions(arena: &mut EntityArena, walls: &[Wall], world: &World) {
+    if walls.is_empty() {
+        return;
+    }
+    let half_thickness = config::WALL_THICKNESS * 0.5;
+    for (_, entity) in arena.iter_alive_mut() {
+        for wall in walls {
+            let closest = wall.closest_point(entity.pos);
+            let delta = entity.pos - closest;
+            let min_dist = entity.radius + half_thickness;
+            let dist_sq = delta.length_squared();
+            if dist_sq < min_dist * min_dist {
+                let normal = if dist_sq > 1e-6 {
+                    delta / dist_sq.sqrt()
+                } else {
+                    (wall.b - wall.a).perp().normalize_or_zero()
+                };
+                entity.pos = world.wrap(closest + normal * min_dist);
+                let into_wall = entity.velocity.dot(normal);
+                if into_wall < 0.0 {
+                    entity.velocity -= normal * into_wall;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+// Plant ecology: persistent plant patches that grow, seed neighbouring cells and get grazed down.
+// Food spawns in proportion to plant cover, so good foraging grounds persist and move.
+
+use macroquad::prelude::*;
+use ::rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::environment::{EnvironmentState, TerrainType};
+use crate::world::World;
+
+/// How respawning food is distributed over the world.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FoodModel {
+    /// Food appears anywhere, biased only by terrain, latitude and rain.
+    Uniform,
+    /// Food grows from plant patches that spread, regrow and are depleted by grazing.
+    Patches,
+}
+
+impl FoodModel {
+    pub const ALL: [FoodModel; 2] = [FoodModel::Uniform, FoodModel::Patches];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FoodModel::Uniform => "Uniform",
+            FoodModel::Patches => "Plant patches",
+        }
+    }
+
+    /// Identifier used in save files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            FoodModel::Uniform => "uniform",
+            FoodModel::Patches => "patches",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<FoodModel> {
+        FoodModel::ALL.iter().copied().find(|m| m.id() == id)
+    }
+}
+
+/// Kind of plant food. Grass is poor but keeps; berries and fruit are richer but spoil.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FoodKind {
+    Grass,
+    Berry,
+    Fruit,
+}
+
+impl FoodKind {
+    pub const ALL: [FoodKind; 3] = [FoodKind::Grass, FoodKind::Berry, FoodKind::Fruit];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FoodKind::Grass => "Grass",
+            FoodKind::Berry => "Berry",
+            FoodKind::Fruit => "Fruit",
+        }
+    }
+
+    /// Energy of a fresh item.
+    pub fn energy(&self) -> f32 {
+        config::FOOD_ENERGY
+            * match self {
+                FoodKind::Grass => config::GRASS_ENERGY_MULT,
+                FoodKind::Berry => config::BERRY_ENERGY_MULT,
+                FoodKind::Fruit => config::FRUIT_ENERGY_MULT,
+            }
+    }
+
+    /// Seconds until an uneaten item rots away; None if it keeps.
+    pub fn spoil_time(&self) -> Option<f32> {
+        match self {
+            FoodKind::Grass => None,
+            FoodKind::Berry => Some(config::BERRY_SPOIL_TIME),
+            FoodKind::Fruit => Some(config::FRUIT_SPOIL_TIME),
+        }
+    }
+
+    /// Position on the taste axis: a taste gene at this value digests the kind fully.
+    pub fn taste(&self) -> f32 {
+        match self {
+            FoodKind::Grass => 0.0,
+            FoodKind::Berry => 0.5,
+            FoodKind::Fruit => 1.0,
+        }
+    }
+
+    /// Fraction of the energy an entity with taste gene `taste` gets out of this kind.
+    pub fn digestion(&self, taste: f32) -> f32 {
+        1.0 - (taste - self.taste()).abs() * config::TASTE_DIGESTION_PENALTY
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            FoodKind::Grass => Color::new(0.55, 0.85, 0.25, 0.9),
+            FoodKind::Berry => Color::new(0.75, 0.25, 0.65, 0.9),
+            FoodKind::Fruit => Color::new(0.95, 0.6, 0.15, 0.9),
+        }
+    }
+
+    /// Relative spawn weights (grass, berry, fruit) on a terrain: forests bear fruit and
+    /// berries, open and dry ground mostly grass.
+    fn biome_weights(terrain: TerrainType) -> [f32; 3] {
+        match terrain {
+            TerrainType::Plains => [0.6, 0.3, 0.1],
+            TerrainType::Forest => [0.15, 0.4, 0.45],
+            TerrainType::Desert => [0.85, 0.15, 0.0],
+            TerrainType::Water => [1.0, 0.0, 0.0],
+            TerrainType::Toxic => [0.5, 0.4, 0.1],
+        }
+    }
+
+    /// Draw the kind of a newly spawned item from the biome weights of its terrain.
+    pub fn pick(terrain: TerrainType, rng: &mut impl Rng) -> FoodKind {
+        let weights = Self::biome_weights(terrain);
+        let mut roll = rng.gen::<f32>() * weights.iter().sum::<f32>();
+        for (kind, weight) in Self::ALL.into_iter().zip(weights) {
+            if roll < weight {
+                return kind;
+            }
+            roll -= weight;
+        }
+        FoodKind::Grass
+    }
+}
+
+/// Plant cover per cell in [0, 1].
+pub struct PlantGrid {
+    pub model: FoodModel,
+    pub cover: Vec<f32>,
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: f32,
+    scratch: Vec<f32>,
+}
+
+impl PlantGrid {
+    pub fn new(world_w: f32, world_h: f32, cell_size: f32) -> Self {
+        let width = (world_w / cell_size).ceil() as usize;
+        let height = (world_h / cell_size).ceil() as usize;
+        Self {
+            model: FoodModel::Patches,
+            cover: vec![0.0; width * height],
+            width,
+            height,
+            cell_size,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Scatter initial patches over fertile ground.
+    pub fn seed(&mut self, environment: &EnvironmentState, world: &World, rng: &mut impl Rng) {
+        for i in 0..self.cover.len() {
+            let capacity = fertility(environment, self.cell_center(i), world).min(1.0);
+            self.cover[i] = if rng.gen::<f32>() < config::PLANT_INITIAL_COVERAGE {
+                capacity * rng.gen_range(0.3..1.0)
+            } else {
+                0.0
+            };
+        }
+    }
+
+    fn cell_index(&self, pos: Vec2) -> usize {
+        let x = ((pos.x / self.cell_size).max(0.0) as usize).min(self.width - 1);
+        let y = ((pos.y / self.cell_size).max(0.0) as usize).min(self.height - 1);
+        y * self.width + x
+    }
+
+    fn cell_center(&self, i: usize) -> Vec2 {
+        vec2((i % self.width) as f32 + 0.5, (i / self.width) as f32 + 0.5) * self.cell_size
+    }
+
+    pub fn cover_at(&self, pos: Vec2) -> f32 {
+        self.cover[self.cell_index(pos)]
+    }
+
+    /// Food spawn multiplier from plant cover at a position (1 under the uniform model).
+    pub fn food_multiplier(&self, pos: Vec2) -> f32 {
+        match self.model {
+            FoodModel::Uniform => 1.0,
+            FoodModel::Patches => self.cover_at(pos) * config::PLANT_FOOD_BOOST,
+        }
+    }
+
+    /// Eating food crops the plants it grew from.
+    pub fn graze(&mut self, pos: Vec2) {
+        if self.model == FoodModel::Patches {
+            let i = self.cell_index(pos);
+            self.cover[i] = (self.cover[i] - config::PLANT_GRAZE_COST).max(0.0);
+        }
+    }
+
+    /// Fraction of cells with noticeable plant cover.
+    pub fn covered_fraction(&self) -> f32 {
+        let covered = self.cover.iter().filter(|&&c| c > 0.1).count();
+        covered as f32 / self.cover.len().max(1) as f32
+    }
+}
+
+/// How well plants grow at a position: terrain fertility times seasonal latitude, rain and
+/// carcass nutrients.
+fn fertility(environment: &EnvironmentState, pos: Vec2, world: &World) -> f32 {
+    environment.terrain.get_at(pos).food_spawn_mult()
+        * environment.latitude_food_multiplier(pos.y, world.height)
+        * environment.weather.food_multiplier(pos)
+        * environment.nutrients.food_multiplier(pos)
+}
+
+/// Grow each cell logistically toward the carrying capacity of its ground. Bare cells are
+/// seeded from their neighbours (and rarely germinate on their own); cells over capacity,
+/// such as patches on ground that turned toxic, die back.
+pub fn grow_plants(grid: &mut PlantGrid, environment: &EnvironmentState, world: &World, dt: f32) {
+    if grid.model != FoodModel::Patches {
+        return;
+    }
+    let season = environment.season.food_multiplier();
+    let (w, h) = (grid.width, grid.height);
+    grid.scratch.clone_from(&grid.cover);
+    for i in 0..grid.cover.len() {
+        let (x, y) = (i % w, i / w);
+        let neighbours = grid.scratch[y * w + (x + w - 1) % w]
+            + grid.scratch[y * w + (x + 1) % w]
+            + grid.scratch[((y + h - 1) % h) * w + x]
+            + grid.scratch[((y + 1) % h) * w + x];
+        let fert = fertility(environment, grid.cell_center(i), world);
+        let capacity = fert.min(1.0);
+        let c = grid.scratch[i];
+        let stock = c + config::PLANT_SEED_RATE * neighbours * 0.25 + config::PLANT_GERMINATION;
+        let rate = config::PLANT_GROWTH_RATE * season * fert.max(config::PLANT_MIN_GROWTH);
+        grid.cover[i] = (c + rate * stock * (capacity - c) * dt).clamp(0.0, 1.0);
+    }
+}
+
+/// Faint green tint over cells with plant cover.
+pub fn draw_plants(grid: &PlantGrid) {
+    if grid.model != FoodModel::Patches {
+        return;
+    }
+    for (i, &c) in grid.cover.iter().enumerate() {
+        if c > 0.05 {
+            let x = (i % grid.width) as f32 * grid.cell_size;
+            let y = (i / grid.width) as f32 * grid.cell_size;
+            draw_rectangle(x, y, grid.cell_size, grid.cell_size, Color::new(0.2, 0.6, 0.15, c * 0.12));
+        }
+    }
+}
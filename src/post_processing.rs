@@ -1,17 +1,106 @@
+use macroquad::miniquad::{BlendFactor, BlendState, Equation};
 use macroquad::prelude::*;
 
+/// Rendering quality preset: sets the bloom resolution and how many blur passes it runs.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum VisualQuality {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Ultra,
+}
+
+impl VisualQuality {
+    pub const ALL: [VisualQuality; 4] = [
+        VisualQuality::Low,
+        VisualQuality::Medium,
+        VisualQuality::High,
+        VisualQuality::Ultra,
+    ];
+
+    /// Identifier used in the preferences file.
+    pub fn id(&self) -> &'static str {
+        match self {
+            VisualQuality::Low => "low",
+            VisualQuality::Medium => "medium",
+            VisualQuality::High => "high",
+            VisualQuality::Ultra => "ultra",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<VisualQuality> {
+        VisualQuality::ALL.iter().copied().find(|q| q.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VisualQuality::Low => "Low",
+            VisualQuality::Medium => "Medium",
+            VisualQuality::High => "High",
+            VisualQuality::Ultra => "Ultra",
+        }
+    }
+
+    /// Bloom buffers are the screen size divided by this.
+    fn bloom_divisor(&self) -> u32 {
+        match self {
+            VisualQuality::Low => 4,
+            VisualQuality::Medium | VisualQuality::High => 2,
+            VisualQuality::Ultra => 1,
+        }
+    }
+
+    /// Horizontal + vertical blur passes over the bright pixels; more passes spread the glow wider.
+    fn blur_passes(&self) -> usize {
+        match self {
+            VisualQuality::Low | VisualQuality::Medium => 1,
+            VisualQuality::High => 2,
+            VisualQuality::Ultra => 3,
+        }
+    }
+}
+
+/// Post-processing settings chosen in the Settings panel.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GraphicsSettings {
+    pub quality: VisualQuality,
+    /// Smooth jagged edges with an FXAA pass over the final image.
+    pub fxaa: bool,
+}
+
+/// A soft circular light for the night lighting pass, in world coordinates.
+pub struct PointLight {
+    pub pos: Vec2,
+    pub radius: f32,
+    /// Light added at the centre, fading to nothing at the radius.
+    pub color: Color,
+}
+
 /// Simple bloom post-processing using render targets.
 /// Renders the scene to an offscreen target, extracts bright pixels,
-/// blurs them at half resolution, and composites additively.
+/// blurs them at a quality-dependent resolution, and composites additively,
+/// optionally followed by an FXAA pass. The composite also multiplies the scene by a light
+/// buffer (ambient light plus additive point lights) so night darkens the world everywhere
+/// except around its light sources.
 pub struct BloomPipeline {
     scene_target: RenderTarget,
     bright_target: RenderTarget,
     blur_h_target: RenderTarget,
     blur_v_target: RenderTarget,
+    /// Light buffer at bloom resolution; the low resolution softens the edges of light pools.
+    light_target: RenderTarget,
+    /// Bloom-composited frame the FXAA pass reads; only allocated while FXAA is on.
+    fxaa_target: Option<RenderTarget>,
     bright_material: Material,
     blur_h_material: Material,
     blur_v_material: Material,
     combine_material: Material,
+    fxaa_material: Material,
+    light_material: Material,
+    /// Radial falloff drawn for every point light.
+    light_sprite: Texture2D,
+    settings: GraphicsSettings,
     width: u32,
     height: u32,
 }
@@ -85,32 +174,84 @@ precision lowp float;
 varying lowp vec2 uv;
 uniform sampler2D Texture;
 uniform sampler2D bloom_texture;
+uniform sampler2D light_texture;
 uniform float bloom_intensity;
 void main() {
     vec4 scene = texture2D(Texture, uv);
     vec4 bloom = texture2D(bloom_texture, uv);
-    gl_FragColor = scene + bloom * bloom_intensity;
+    vec4 light = texture2D(light_texture, uv);
+    gl_FragColor = vec4(scene.rgb * light.rgb, scene.a) + bloom * bloom_intensity;
 }
 "#;
 
-impl BloomPipeline {
-    pub fn new() -> Option<Self> {
-        let width = screen_width() as u32;
-        let height = screen_height() as u32;
-        let half_w = width / 2;
-        let half_h = height / 2;
+const LIGHT_VERT: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+varying lowp vec2 uv;
+varying lowp vec4 color;
+uniform mat4 Model;
+uniform mat4 Projection;
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    uv = texcoord;
+    color = color0 / 255.0;
+}
+"#;
 
-        let scene_target = render_target(width, height);
-        scene_target.texture.set_filter(FilterMode::Linear);
+// Drawn with additive blending: overlapping lights add up (and saturate at white).
+const LIGHT_FRAG: &str = r#"#version 100
+precision lowp float;
+varying lowp vec2 uv;
+varying lowp vec4 color;
+uniform sampler2D Texture;
+void main() {
+    float falloff = texture2D(Texture, uv).a;
+    gl_FragColor = vec4(color.rgb * color.a * falloff, 1.0);
+}
+"#;
 
-        let bright_target = render_target(half_w, half_h);
-        bright_target.texture.set_filter(FilterMode::Linear);
+// FXAA after Lottes: blend along the local edge direction found from the luma of the corners.
+const FXAA_FRAG: &str = r#"#version 100
+precision mediump float;
+varying lowp vec2 uv;
+uniform sampler2D Texture;
+uniform vec2 texel_size;
+void main() {
+    vec3 luma = vec3(0.299, 0.587, 0.114);
+    vec3 rgb_m = texture2D(Texture, uv).rgb;
+    float nw = dot(texture2D(Texture, uv + vec2(-1.0, -1.0) * texel_size).rgb, luma);
+    float ne = dot(texture2D(Texture, uv + vec2( 1.0, -1.0) * texel_size).rgb, luma);
+    float sw = dot(texture2D(Texture, uv + vec2(-1.0,  1.0) * texel_size).rgb, luma);
+    float se = dot(texture2D(Texture, uv + vec2( 1.0,  1.0) * texel_size).rgb, luma);
+    float m = dot(rgb_m, luma);
+    float luma_min = min(m, min(min(nw, ne), min(sw, se)));
+    float luma_max = max(m, max(max(nw, ne), max(sw, se)));
 
-        let blur_h_target = render_target(half_w, half_h);
-        blur_h_target.texture.set_filter(FilterMode::Linear);
+    vec2 dir = vec2(-((nw + ne) - (sw + se)), (nw + sw) - (ne + se));
+    float reduce = max((nw + ne + sw + se) * (0.25 / 8.0), 1.0 / 128.0);
+    float rcp_min = 1.0 / (min(abs(dir.x), abs(dir.y)) + reduce);
+    dir = clamp(dir * rcp_min, vec2(-8.0), vec2(8.0)) * texel_size;
 
-        let blur_v_target = render_target(half_w, half_h);
-        blur_v_target.texture.set_filter(FilterMode::Linear);
+    vec3 rgb_a = 0.5 * (texture2D(Texture, uv + dir * (1.0 / 3.0 - 0.5)).rgb
+                      + texture2D(Texture, uv + dir * (2.0 / 3.0 - 0.5)).rgb);
+    vec3 rgb_b = rgb_a * 0.5 + 0.25 * (texture2D(Texture, uv - dir * 0.5).rgb
+                                     + texture2D(Texture, uv + dir * 0.5).rgb);
+    float luma_b = dot(rgb_b, luma);
+    if (luma_b < luma_min || luma_b > luma_max) {
+        gl_FragColor = vec4(rgb_a, 1.0);
+    } else {
+        gl_FragColor = vec4(rgb_b, 1.0);
+    }
+}
+"#;
+
+impl BloomPipeline {
+    pub fn new() -> Option<Self> {
+        let settings = GraphicsSettings::default();
+        let width = screen_width() as u32;
+        let height = screen_height() as u32;
+        let (bloom_w, bloom_h) = bloom_size(width, height, settings.quality);
 
         let bright_material = load_material(
             ShaderSource::Glsl {
@@ -160,20 +301,53 @@ impl BloomPipeline {
                 uniforms: vec![
                     UniformDesc::new("bloom_intensity", UniformType::Float1),
                 ],
-                textures: vec!["bloom_texture".to_string()],
+                textures: vec!["bloom_texture".to_string(), "light_texture".to_string()],
+                ..Default::default()
+            },
+        ).ok()?;
+
+        let light_material = load_material(
+            ShaderSource::Glsl {
+                vertex: LIGHT_VERT,
+                fragment: LIGHT_FRAG,
+            },
+            MaterialParams {
+                pipeline_params: PipelineParams {
+                    color_blend: Some(BlendState::new(Equation::Add, BlendFactor::One, BlendFactor::One)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ).ok()?;
+
+        let fxaa_material = load_material(
+            ShaderSource::Glsl {
+                vertex: BRIGHT_EXTRACT_VERT,
+                fragment: FXAA_FRAG,
+            },
+            MaterialParams {
+                uniforms: vec![
+                    UniformDesc::new("texel_size", UniformType::Float2),
+                ],
                 ..Default::default()
             },
         ).ok()?;
 
         Some(Self {
-            scene_target,
-            bright_target,
-            blur_h_target,
-            blur_v_target,
+            scene_target: linear_target(width, height),
+            bright_target: linear_target(bloom_w, bloom_h),
+            blur_h_target: linear_target(bloom_w, bloom_h),
+            blur_v_target: linear_target(bloom_w, bloom_h),
+            light_target: linear_target(bloom_w, bloom_h),
+            fxaa_target: None,
             bright_material,
             blur_h_material,
             blur_v_material,
             combine_material,
+            fxaa_material,
+            light_material,
+            light_sprite: light_sprite(),
+            settings,
             width,
             height,
         })
@@ -198,85 +372,94 @@ impl BloomPipeline {
         self.scene_target.clone()
     }
 
-    /// Process the rendered scene: extract bright, blur, combine.
-    pub fn apply(&self) {
-        let half_w = self.width as f32 / 2.0;
-        let half_h = self.height as f32 / 2.0;
+    /// Get the render target for the world camera to draw lights into.
+    pub fn light_render_target(&self) -> RenderTarget {
+        self.light_target.clone()
+    }
 
-        // Step 1: Extract bright pixels to half-res target
-        set_camera(&Camera2D {
-            render_target: Some(self.bright_target.clone()),
-            ..Camera2D::from_display_rect(Rect::new(0.0, 0.0, half_w, half_h))
-        });
-        clear_background(BLACK);
-        self.bright_material.set_uniform("threshold", 0.6f32);
-        gl_use_material(&self.bright_material);
-        draw_texture_ex(
-            &self.scene_target.texture,
-            0.0, 0.0,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(half_w, half_h)),
-                ..Default::default()
-            },
-        );
+    /// Fill the light buffer: `ambient` everywhere plus each light, through `camera` (a world
+    /// camera rendering into `light_render_target`). With white ambient, lights change nothing.
+    pub fn draw_lights(&self, camera: &Camera2D, ambient: Color, lights: &[PointLight]) {
+        set_camera(camera);
+        clear_background(ambient);
+        if ambient.r >= 1.0 && ambient.g >= 1.0 && ambient.b >= 1.0 {
+            return;
+        }
+        gl_use_material(&self.light_material);
+        for light in lights {
+            let size = light.radius * 2.0;
+            draw_texture_ex(
+                &self.light_sprite,
+                light.pos.x - light.radius,
+                light.pos.y - light.radius,
+                light.color,
+                DrawTextureParams {
+                    dest_size: Some(vec2(size, size)),
+                    ..Default::default()
+                },
+            );
+        }
         gl_use_default_material();
+    }
 
-        // Step 2: Horizontal blur
-        set_camera(&Camera2D {
-            render_target: Some(self.blur_h_target.clone()),
-            ..Camera2D::from_display_rect(Rect::new(0.0, 0.0, half_w, half_h))
-        });
-        clear_background(BLACK);
-        self.blur_h_material.set_uniform("texel_size", vec2(1.0 / half_w, 1.0 / half_h));
-        gl_use_material(&self.blur_h_material);
-        draw_texture_ex(
-            &self.bright_target.texture,
-            0.0, 0.0,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(half_w, half_h)),
-                ..Default::default()
-            },
-        );
-        gl_use_default_material();
+    /// Process the rendered scene: extract bright, blur, combine, and optionally anti-alias.
+    pub fn apply(&self) {
+        let (bloom_w, bloom_h) = bloom_size(self.width, self.height, self.settings.quality);
+        let (bloom_w, bloom_h) = (bloom_w as f32, bloom_h as f32);
+        let texel = vec2(1.0 / bloom_w, 1.0 / bloom_h);
 
-        // Step 3: Vertical blur
-        set_camera(&Camera2D {
-            render_target: Some(self.blur_v_target.clone()),
-            ..Camera2D::from_display_rect(Rect::new(0.0, 0.0, half_w, half_h))
+        // Step 1: Extract bright pixels to the bloom-resolution target
+        draw_pass(&self.bright_target, &self.scene_target, bloom_w, bloom_h, || {
+            self.bright_material.set_uniform("threshold", 0.6f32);
+            gl_use_material(&self.bright_material);
         });
-        clear_background(BLACK);
-        self.blur_v_material.set_uniform("texel_size", vec2(1.0 / half_w, 1.0 / half_h));
-        gl_use_material(&self.blur_v_material);
-        draw_texture_ex(
-            &self.blur_h_target.texture,
-            0.0, 0.0,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(half_w, half_h)),
-                ..Default::default()
-            },
-        );
-        gl_use_default_material();
 
-        // Step 4: Combine scene + bloom
+        // Steps 2-3: Horizontal then vertical blur, repeated per quality level
+        for pass in 0..self.settings.quality.blur_passes() {
+            let source = if pass == 0 { &self.bright_target } else { &self.blur_v_target };
+            draw_pass(&self.blur_h_target, source, bloom_w, bloom_h, || {
+                self.blur_h_material.set_uniform("texel_size", texel);
+                gl_use_material(&self.blur_h_material);
+            });
+            draw_pass(&self.blur_v_target, &self.blur_h_target, bloom_w, bloom_h, || {
+                self.blur_v_material.set_uniform("texel_size", texel);
+                gl_use_material(&self.blur_v_material);
+            });
+        }
+
+        // Step 4: Combine scene + bloom, into the FXAA input if anti-aliasing is on
+        let (w, h) = (self.width as f32, self.height as f32);
+        let use_combine = || {
+            self.combine_material.set_uniform("bloom_intensity", 0.4f32);
+            self.combine_material.set_texture("bloom_texture", self.blur_v_target.texture.clone());
+            self.combine_material.set_texture("light_texture", self.light_target.texture.clone());
+            gl_use_material(&self.combine_material);
+        };
+        let Some(fxaa_target) = &self.fxaa_target else {
+            set_default_camera();
+            use_combine();
+            draw_full(&self.scene_target.texture, screen_width(), screen_height());
+            gl_use_default_material();
+            return;
+        };
+        draw_pass(fxaa_target, &self.scene_target, w, h, use_combine);
+
+        // Step 5: FXAA to screen
         set_default_camera();
-        self.combine_material.set_uniform("bloom_intensity", 0.4f32);
-        self.combine_material.set_texture("bloom_texture", self.blur_v_target.texture.clone());
-        gl_use_material(&self.combine_material);
-        draw_texture_ex(
-            &self.scene_target.texture,
-            0.0, 0.0,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(screen_width(), screen_height())),
-                ..Default::default()
-            },
-        );
+        self.fxaa_material.set_uniform("texel_size", vec2(1.0 / w, 1.0 / h));
+        gl_use_material(&self.fxaa_material);
+        draw_full(&fxaa_target.texture, screen_width(), screen_height());
         gl_use_default_material();
     }
 
+    /// Switch quality or FXAA, rebuilding the buffers that depend on them.
+    pub fn set_settings(&mut self, settings: GraphicsSettings) {
+        if settings != self.settings {
+            self.settings = settings;
+            self.rebuild_targets();
+        }
+    }
+
     /// Check if window was resized and rebuild targets if needed.
     pub fn check_resize(&mut self) {
         let w = screen_width() as u32;
@@ -284,20 +467,69 @@ impl BloomPipeline {
         if w != self.width || h != self.height {
             self.width = w;
             self.height = h;
-            let half_w = w / 2;
-            let half_h = h / 2;
-
-            self.scene_target = render_target(w, h);
-            self.scene_target.texture.set_filter(FilterMode::Linear);
+            self.scene_target = linear_target(w, h);
+            self.rebuild_targets();
+        }
+    }
 
-            self.bright_target = render_target(half_w, half_h);
-            self.bright_target.texture.set_filter(FilterMode::Linear);
+    fn rebuild_targets(&mut self) {
+        let (bloom_w, bloom_h) = bloom_size(self.width, self.height, self.settings.quality);
+        self.bright_target = linear_target(bloom_w, bloom_h);
+        self.blur_h_target = linear_target(bloom_w, bloom_h);
+        self.blur_v_target = linear_target(bloom_w, bloom_h);
+        self.light_target = linear_target(bloom_w, bloom_h);
+        self.fxaa_target = self.settings.fxaa.then(|| linear_target(self.width, self.height));
+    }
+}
 
-            self.blur_h_target = render_target(half_w, half_h);
-            self.blur_h_target.texture.set_filter(FilterMode::Linear);
+fn bloom_size(width: u32, height: u32, quality: VisualQuality) -> (u32, u32) {
+    let divisor = quality.bloom_divisor();
+    ((width / divisor).max(1), (height / divisor).max(1))
+}
 
-            self.blur_v_target = render_target(half_w, half_h);
-            self.blur_v_target.texture.set_filter(FilterMode::Linear);
+/// White disc whose alpha falls off smoothly from 1 at the centre to 0 at the edge.
+fn light_sprite() -> Texture2D {
+    const SIZE: u16 = 64;
+    let mut image = Image::gen_image_color(SIZE, SIZE, Color::new(1.0, 1.0, 1.0, 0.0));
+    let half = SIZE as f32 / 2.0;
+    for y in 0..SIZE as u32 {
+        for x in 0..SIZE as u32 {
+            let d = vec2(x as f32 + 0.5 - half, y as f32 + 0.5 - half).length() / half;
+            let falloff = (1.0 - d).clamp(0.0, 1.0);
+            image.set_pixel(x, y, Color::new(1.0, 1.0, 1.0, falloff * falloff));
         }
     }
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Linear);
+    texture
+}
+
+fn linear_target(width: u32, height: u32) -> RenderTarget {
+    let target = render_target(width, height);
+    target.texture.set_filter(FilterMode::Linear);
+    target
+}
+
+/// Draw `source` stretched over `target` (of size `w` x `h`) with the material `use_material` selects.
+fn draw_pass(target: &RenderTarget, source: &RenderTarget, w: f32, h: f32, use_material: impl FnOnce()) {
+    set_camera(&Camera2D {
+        render_target: Some(target.clone()),
+        ..Camera2D::from_display_rect(Rect::new(0.0, 0.0, w, h))
+    });
+    clear_background(BLACK);
+    use_material();
+    draw_full(&source.texture, w, h);
+    gl_use_default_material();
+}
+
+fn draw_full(texture: &Texture2D, w: f32, h: f32) {
+    draw_texture_ex(
+        texture,
+        0.0, 0.0,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(vec2(w, h)),
+            ..Default::default()
+        },
+    );
 }
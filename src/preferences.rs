@@ -0,0 +1,222 @@
+use crate::audio::AudioSettings;
+use crate::coloring::ColorMode;
+use crate::heatmap::HeatmapLayer;
+use crate::post_processing::VisualQuality;
+use crate::sensory::RayMode;
+use crate::simulation::SimState;
+use crate::storage;
+use crate::ui::UiState;
+
+pub const PREFERENCES_PATH: &str = "preferences.toml";
+
+/// One preference value. The file is a flat subset of TOML: sections of `key = value` lines.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PrefValue {
+    Bool(bool),
+    Number(f32),
+    Text(String),
+}
+
+impl PrefValue {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        match s {
+            "true" => Ok(PrefValue::Bool(true)),
+            "false" => Ok(PrefValue::Bool(false)),
+            _ if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') => {
+                Ok(PrefValue::Text(s[1..s.len() - 1].to_string()))
+            }
+            _ => s.parse().map(PrefValue::Number).map_err(|_| format!("invalid value `{s}`")),
+        }
+    }
+
+    fn to_text(&self) -> String {
+        match self {
+            PrefValue::Bool(b) => b.to_string(),
+            PrefValue::Number(n) => format!("{n}"),
+            PrefValue::Text(t) => format!("\"{t}\""),
+        }
+    }
+}
+
+/// Visual and UI settings that persist across launches, independent of simulation saves.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Preferences {
+    /// (section, key, value) in file order.
+    pub entries: Vec<(String, String, PrefValue)>,
+}
+
+/// Panel visibility flags by preference key.
+fn panel_flags(ui: &mut UiState) -> [(&'static str, &mut bool); 13] {
+    [
+        ("inspector", &mut ui.show_inspector),
+        ("brain", &mut ui.show_neural_viz),
+        ("graphs", &mut ui.show_graphs),
+        ("generations", &mut ui.show_generations),
+        ("minimap", &mut ui.show_minimap),
+        ("settings", &mut ui.show_settings),
+        ("breeding", &mut ui.show_breeding),
+        ("hall_of_fame", &mut ui.show_hall_of_fame),
+        ("kill_feed", &mut ui.show_kill_feed),
+        ("follow_inset", &mut ui.show_follow_inset),
+        ("intent_bubble", &mut ui.show_intent_bubble),
+        ("event_log", &mut ui.show_event_log),
+        ("toasts", &mut ui.show_toasts),
+    ]
+}
+
+/// Audio toggles by preference key (the volume is handled separately).
+fn audio_flags(audio: &mut AudioSettings) -> [(&'static str, &mut bool); 6] {
+    [
+        ("enabled", &mut audio.enabled),
+        ("births", &mut audio.births),
+        ("deaths", &mut audio.deaths),
+        ("combat", &mut audio.combat),
+        ("storms", &mut audio.storms),
+        ("ui_clicks", &mut audio.ui_clicks),
+    ]
+}
+
+impl Preferences {
+    /// Current preferences of the running app.
+    pub fn capture(sim: &SimState, ui: &mut UiState) -> Self {
+        let mut prefs = Preferences::default();
+        let layer = sim.heatmap_layer.map_or("none", |l| l.id());
+        prefs.push("visuals", "heatmap_layer", PrefValue::Text(layer.to_string()));
+        prefs.push("visuals", "heatmap_opacity", PrefValue::Number(sim.heatmap_opacity));
+        prefs.push("visuals", "color_mode", PrefValue::Text(sim.color_mode.id().to_string()));
+        prefs.push("visuals", "sensor_rays", PrefValue::Text(sim.ray_mode.id().to_string()));
+        prefs.push("visuals", "quality", PrefValue::Text(ui.graphics.quality.id().to_string()));
+        prefs.push("visuals", "fxaa", PrefValue::Bool(ui.graphics.fxaa));
+        for (key, flag) in panel_flags(ui) {
+            prefs.push("panels", key, PrefValue::Bool(*flag));
+        }
+        for (key, _, flag) in ui.minimap.layers.flags() {
+            prefs.push("minimap", key, PrefValue::Bool(*flag));
+        }
+        prefs.push("audio", "master_volume", PrefValue::Number(ui.audio.master_volume));
+        for (key, flag) in audio_flags(&mut ui.audio) {
+            prefs.push("audio", key, PrefValue::Bool(*flag));
+        }
+        prefs
+    }
+
+    fn push(&mut self, section: &str, key: &str, value: PrefValue) {
+        self.entries.push((section.to_string(), key.to_string(), value));
+    }
+
+    /// Apply every recognized entry; unknown keys and mistyped values are reported and skipped.
+    pub fn apply(&self, sim: &mut SimState, ui: &mut UiState) {
+        for (section, key, value) in &self.entries {
+            let applied = match (section.as_str(), key.as_str(), value) {
+                ("visuals", "heatmap_layer", PrefValue::Text(id)) => {
+                    sim.heatmap_layer = HeatmapLayer::from_id(id);
+                    sim.heatmap_layer.is_some() || id == "none"
+                }
+                ("visuals", "heatmap_opacity", PrefValue::Number(n)) => {
+                    sim.heatmap_opacity = n.clamp(0.05, 1.0);
+                    true
+                }
+                ("visuals", "color_mode", PrefValue::Text(id)) => match ColorMode::from_id(id) {
+                    Some(mode) => {
+                        sim.color_mode = mode;
+                        true
+                    }
+                    None => false,
+                },
+                // Written by older versions before the other color modes existed
+                ("visuals", "color_by_species", PrefValue::Bool(b)) => {
+                    sim.color_mode = if *b { ColorMode::Species } else { ColorMode::Genome };
+                    true
+                }
+                ("visuals", "sensor_rays", PrefValue::Text(id)) => match RayMode::from_id(id) {
+                    Some(mode) => {
+                        sim.ray_mode = mode;
+                        true
+                    }
+                    None => false,
+                },
+                ("visuals", "quality", PrefValue::Text(id)) => match VisualQuality::from_id(id) {
+                    Some(quality) => {
+                        ui.graphics.quality = quality;
+                        true
+                    }
+                    None => false,
+                },
+                ("visuals", "fxaa", PrefValue::Bool(b)) => {
+                    ui.graphics.fxaa = *b;
+                    true
+                }
+                ("panels", key, PrefValue::Bool(b)) => set_flag(panel_flags(ui), key, *b),
+                ("minimap", key, PrefValue::Bool(b)) => {
+                    let flags = ui.minimap.layers.flags().map(|(key, _, flag)| (key, flag));
+                    set_flag(flags, key, *b)
+                }
+                ("audio", "master_volume", PrefValue::Number(n)) => {
+                    ui.audio.master_volume = n.clamp(0.0, 1.0);
+                    true
+                }
+                ("audio", key, PrefValue::Bool(b)) => set_flag(audio_flags(&mut ui.audio), key, *b),
+                _ => false,
+            };
+            if !applied {
+                eprintln!("[GENESIS] Preferences: ignoring {section}.{key} = {}", value.to_text());
+            }
+        }
+    }
+
+    /// Parse a preferences file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut prefs = Preferences::default();
+        let mut section = String::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Line {}: expected `key = value`", line_no + 1))?;
+            let value = PrefValue::parse(value).map_err(|e| format!("Line {}: {e}", line_no + 1))?;
+            prefs.entries.push((section.clone(), key.trim().to_string(), value));
+        }
+        Ok(prefs)
+    }
+
+    /// Render as a preferences file.
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# GENESIS preferences, saved automatically whenever a setting changes.\n");
+        let mut section = None;
+        for (s, key, value) in &self.entries {
+            if section != Some(s) {
+                text.push_str(&format!("\n[{s}]\n"));
+                section = Some(s);
+            }
+            text.push_str(&format!("{key} = {}\n", value.to_text()));
+        }
+        text
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = storage::read_to_string(path).map_err(|e| format!("Read error: {e}"))?;
+        Self::parse(&text)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        storage::write(path, self.to_text()).map_err(|e| format!("Write error: {e}"))
+    }
+}
+
+fn set_flag<const N: usize>(flags: [(&'static str, &mut bool); N], key: &str, value: bool) -> bool {
+    match flags.into_iter().find(|(k, _)| *k == key) {
+        Some((_, flag)) => {
+            *flag = value;
+            true
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,424 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use macroquad::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::camera::CameraController;
+use crate::config;
+use crate::environment::{Portal, ToxicZone, Wall};
+use crate::journal::InterventionKind;
+use crate::manifest::{json_string, RunInfo};
+use crate::save_load;
+use crate::simulation::SimState;
+use crate::storage;
+
+/// Where every QA run writes its PASS/FAIL entries.
+pub const QA_REPORT_PATH: &str = "qa_report.json";
+
+/// Scripted end-to-end checks, run headless with `--qa <name>`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QaScenario {
+    /// Save mid-run, mutate the world, reload, and check the restored state and its future
+    /// match the original exactly.
+    SaveLoadRoundTrip,
+}
+
+impl QaScenario {
+    pub const ALL: [QaScenario; 1] = [QaScenario::SaveLoadRoundTrip];
+
+    /// Identifier used on the command line.
+    pub fn id(&self) -> &'static str {
+        match self {
+            QaScenario::SaveLoadRoundTrip => "save-load-round-trip",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<QaScenario> {
+        QaScenario::ALL.iter().copied().find(|s| s.id() == id)
+    }
+
+    pub fn run(&self, seed: u64, run_info: &RunInfo) -> QaReport {
+        let mut report = QaReport::new(*self, run_info.clone());
+        match self {
+            QaScenario::SaveLoadRoundTrip => save_load_round_trip(seed, run_info, &mut report),
+        }
+        report
+    }
+}
+
+/// One named check and, if it failed, what was wrong.
+pub struct QaCheck {
+    pub name: String,
+    pub failure: Option<String>,
+}
+
+/// Outcome of a QA scenario: every check that ran, with the provenance of the run that
+/// produced it.
+pub struct QaReport {
+    pub scenario: QaScenario,
+    pub checks: Vec<QaCheck>,
+    pub run_info: RunInfo,
+}
+
+impl QaReport {
+    fn new(scenario: QaScenario, run_info: RunInfo) -> Self {
+        Self {
+            scenario,
+            checks: Vec::new(),
+            run_info,
+        }
+    }
+
+    fn check(&mut self, name: impl Into<String>, ok: bool, failure: impl FnOnce() -> String) {
+        self.checks.push(QaCheck {
+            name: name.into(),
+            failure: (!ok).then(failure),
+        });
+    }
+
+    fn failures(&self) -> impl Iterator<Item = &QaCheck> {
+        self.checks.iter().filter(|c| c.failure.is_some())
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Write QA_REPORT_PATH: the verdict, one PASS/FAIL entry per check and the run's
+    /// provenance.
+    pub fn write_json(&self) -> Result<String, String> {
+        let checks: Vec<String> = self
+            .checks
+            .iter()
+            .map(|c| {
+                format!(
+                    "    {{\"name\": {}, \"result\": \"{}\", \"detail\": {}}}",
+                    json_string(&c.name),
+                    if c.failure.is_some() { "FAIL" } else { "PASS" },
+                    c.failure.as_deref().map_or("null".to_string(), json_string)
+                )
+            })
+            .collect();
+        let json = format!(
+            "{{\n  \"scenario\": \"{}\",\n  \"result\": \"{}\",\n  \"checks\": [\n{}\n  ],\n  \"run_info\": {}\n}}\n",
+            self.scenario.id(),
+            if self.passed() { "PASS" } else { "FAIL" },
+            checks.join(",\n"),
+            self.run_info.to_json()
+        );
+        storage::write(QA_REPORT_PATH, json).map_err(|e| format!("Write error: {e}"))?;
+        Ok(QA_REPORT_PATH.to_string())
+    }
+
+    /// Print the report and return the process exit code (0 on success).
+    pub fn print(&self) -> i32 {
+        eprintln!("[GENESIS] QA {}: {}", self.scenario.id(), self.run_info.summary());
+        for check in self.failures() {
+            eprintln!(
+                "[GENESIS] QA {}: FAIL {}: {}",
+                self.scenario.id(),
+                check.name,
+                check.failure.as_deref().unwrap_or_default()
+            );
+        }
+        let verdict = if self.passed() { "PASS" } else { "FAIL" };
+        eprintln!(
+            "[GENESIS] QA {}: {verdict} ({} checks, {} failed)",
+            self.scenario.id(),
+            self.checks.len(),
+            self.failures().count()
+        );
+        if self.passed() {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+/// One category of simulation state flattened to floats, `stride` values per item.
+struct Section {
+    name: &'static str,
+    stride: usize,
+    values: Vec<f32>,
+}
+
+/// Exact copy of the state a save must preserve, for bit-for-bit comparison.
+struct Snapshot {
+    tick: u64,
+    sections: Vec<Section>,
+    rng: ChaCha8Rng,
+}
+
+impl Snapshot {
+    fn capture(sim: &SimState) -> Self {
+        let mut entities = Vec::new();
+        let mut brains = Vec::new();
+        let mut genomes = Vec::new();
+        for (idx, e) in sim.arena.iter_alive() {
+            entities.extend([
+                idx as f32,
+                e.uid as f32,
+                e.pos.x,
+                e.pos.y,
+                e.velocity.x,
+                e.velocity.y,
+                e.heading,
+                e.energy,
+                e.health,
+                e.age,
+            ]);
+            let b = &sim.brains;
+            brains.extend(b.states[idx]);
+            brains.extend(b.outputs[idx]);
+            brains.extend(b.biases[idx]);
+            brains.extend(b.tau_inv[idx]);
+            brains.extend(b.weights[idx].iter().flatten());
+            if let Some(Some(genome)) = sim.genomes.get(idx) {
+                genomes.extend(&genome.genes);
+            }
+        }
+        let n = config::BRAIN_NEURONS;
+        let env = &sim.environment;
+        let sections = vec![
+            Section { name: "entities", stride: 10, values: entities },
+            Section { name: "brains", stride: 4 * n + n * n, values: brains },
+            Section { name: "genomes", stride: crate::genome::TOTAL_GENOME_SIZE, values: genomes },
+            Section {
+                name: "food",
+                stride: 5,
+                values: sim.food.iter().flat_map(|f| [f.pos.x, f.pos.y, f.energy, f.kind as u8 as f32, f.age]).collect(),
+            },
+            Section {
+                name: "meat",
+                stride: 4,
+                values: sim.meat.iter().flat_map(|m| [m.pos.x, m.pos.y, m.energy, m.decay_timer]).collect(),
+            },
+            Section {
+                name: "projectiles",
+                stride: 6,
+                values: sim
+                    .projectiles
+                    .iter()
+                    .flat_map(|p| [p.pos.x, p.pos.y, p.velocity.x, p.velocity.y, p.damage, p.life])
+                    .collect(),
+            },
+            Section {
+                name: "shelters",
+                stride: 4,
+                values: sim.shelters.iter().flat_map(|s| [s.pos.x, s.pos.y, s.radius, s.durability]).collect(),
+            },
+            Section { name: "walls", stride: 4, values: env.walls.iter().flat_map(|w| [w.a.x, w.a.y, w.b.x, w.b.y]).collect() },
+            Section {
+                name: "toxic zones",
+                stride: 4,
+                values: env.toxic_zones.iter().flat_map(|z| [z.pos.x, z.pos.y, z.radius, z.intensity]).collect(),
+            },
+            Section { name: "portals", stride: 4, values: env.portals.iter().flat_map(|p| [p.a.x, p.a.y, p.b.x, p.b.y]).collect() },
+            Section {
+                name: "storm",
+                stride: 8,
+                values: env
+                    .storm
+                    .iter()
+                    .flat_map(|s| [s.center.x, s.center.y, s.radius, s.velocity.x, s.velocity.y, s.timer, s.strength, s.eye])
+                    .collect(),
+            },
+            Section {
+                name: "rain clouds",
+                stride: 4,
+                values: env.weather.clouds.iter().flat_map(|c| [c.center.x, c.center.y, c.radius, c.timer]).collect(),
+            },
+            Section {
+                name: "clock",
+                stride: 5,
+                values: vec![env.time_of_day, env.day_progress, env.season_progress, env.storm_cooldown, sim.food_spawner.accumulator],
+            },
+            Section { name: "pheromones", stride: 1, values: sim.pheromone_grid.cells.clone() },
+            Section { name: "plants", stride: 1, values: sim.plants.cover.clone() },
+            Section { name: "nutrients", stride: 1, values: env.nutrients.cells.clone() },
+            Section {
+                name: "journal",
+                stride: 2,
+                values: sim.journal.entries.iter().flat_map(|j| [j.tick as f32, j.detail.len() as f32]).collect(),
+            },
+        ];
+        Self {
+            tick: sim.tick_count,
+            sections,
+            rng: sim.rng.clone(),
+        }
+    }
+
+    /// Hash of every captured bit, for cheap per-tick determinism checks.
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.tick.hash(&mut hasher);
+        self.rng.get_word_pos().hash(&mut hasher);
+        for section in &self.sections {
+            for v in &section.values {
+                v.to_bits().hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Compare with `other` part by part: the tick, each section and the RNG, each with the
+    /// first difference found, if any.
+    fn diff(&self, other: &Snapshot) -> Vec<(String, Option<String>)> {
+        let mut out = Vec::new();
+        out.push((
+            "tick".to_string(),
+            (self.tick != other.tick).then(|| format!("{} vs {}", self.tick, other.tick)),
+        ));
+        for (a, b) in self.sections.iter().zip(&other.sections) {
+            let difference = if a.values.len() != b.values.len() {
+                Some(format!("{} items vs {}", a.values.len() / a.stride, b.values.len() / b.stride))
+            } else {
+                (0..a.values.len()).find(|&i| a.values[i].to_bits() != b.values[i].to_bits()).map(|i| {
+                    format!("item {} field {} is {} vs {}", i / a.stride, i % a.stride, a.values[i], b.values[i])
+                })
+            };
+            out.push((a.name.to_string(), difference));
+        }
+        out.push((
+            "rng".to_string(),
+            (self.rng != other.rng).then(|| {
+                format!("word position {} vs {}", self.rng.get_word_pos(), other.rng.get_word_pos())
+            }),
+        ));
+        out
+    }
+}
+
+/// Checksum of every living entity's genome, by UID in slot order.
+fn genome_checksums(sim: &SimState) -> Vec<(u64, u64)> {
+    sim.arena
+        .iter_alive()
+        .filter_map(|(idx, e)| {
+            let genome = sim.genomes.get(idx)?.as_ref()?;
+            let mut hasher = DefaultHasher::new();
+            for gene in &genome.genes {
+                gene.to_bits().hash(&mut hasher);
+            }
+            Some((e.uid, hasher.finish()))
+        })
+        .collect()
+}
+
+const ROUND_TRIP_PATH: &str = "genesis_qa_roundtrip.bin";
+const ROUND_TRIP_WARMUP_TICKS: u64 = 300;
+const ROUND_TRIP_DETERMINISM_TICKS: u64 = 100;
+
+fn save_load_round_trip(seed: u64, run_info: &RunInfo, report: &mut QaReport) {
+    // Build up a busy world with user-placed objects
+    let mut sim = SimState::new(config::INITIAL_ENTITY_COUNT, seed);
+    sim.run_info = run_info.clone();
+    let center = sim.world.center();
+    sim.environment.walls.push(Wall {
+        a: center - vec2(200.0, 0.0),
+        b: center + vec2(200.0, 0.0),
+    });
+    sim.environment.toxic_zones.push(ToxicZone {
+        pos: center + vec2(0.0, 300.0),
+        radius: 80.0,
+        intensity: 1.5,
+    });
+    sim.environment.portals.push(Portal {
+        a: center - vec2(400.0, 300.0),
+        b: center + vec2(400.0, 300.0),
+    });
+    sim.journal.record(sim.tick_count, InterventionKind::Edit, "add wall, hazard and portal");
+    for _ in 0..ROUND_TRIP_WARMUP_TICKS {
+        sim.tick();
+    }
+
+    let before = Snapshot::capture(&sim);
+    let population = sim.arena.count;
+    let checksums = genome_checksums(&sim);
+    let camera = CameraController::new(center);
+    if let Err(e) = save_load::save_to_file(&sim, &camera, ROUND_TRIP_PATH) {
+        report.check("save", false, || e);
+        return;
+    }
+
+    // Reference future of the original run
+    let mut reference = Vec::new();
+    for _ in 0..ROUND_TRIP_DETERMINISM_TICKS {
+        sim.tick();
+        reference.push(Snapshot::capture(&sim).fingerprint());
+    }
+    let reference_end = Snapshot::capture(&sim);
+
+    // Mutate the live world so nothing of it can leak into the reload
+    for entity in sim.arena.entities.iter_mut().flatten() {
+        entity.alive = false;
+    }
+    sim.food.clear();
+    sim.journal.record(sim.tick_count, InterventionKind::Delete, "everything");
+    sim.environment.walls.clear();
+    sim.environment.portals.clear();
+    sim.environment.toxic_zones.push(ToxicZone {
+        pos: center,
+        radius: 500.0,
+        intensity: 4.0,
+    });
+    sim.tick();
+    drop(sim);
+
+    let loaded = save_load::load_from_file(ROUND_TRIP_PATH);
+    if let Err(e) = storage::remove(ROUND_TRIP_PATH) {
+        eprintln!("[GENESIS] Could not remove {ROUND_TRIP_PATH}: {e}");
+    }
+    let mut restored = match loaded {
+        Ok((restored, _view)) => restored,
+        Err(e) => {
+            report.check("load", false, || e);
+            return;
+        }
+    };
+
+    report.check("population", restored.arena.count == population, || {
+        format!("{} vs {population}", restored.arena.count)
+    });
+    report.check("run info", restored.run_info == *run_info, || {
+        format!("{} vs {}", restored.run_info.summary(), run_info.summary())
+    });
+    let restored_checksums = genome_checksums(&restored);
+    let mismatch = checksums.iter().zip(&restored_checksums).find(|(a, b)| a != b);
+    report.check(
+        "genome checksums",
+        checksums.len() == restored_checksums.len() && mismatch.is_none(),
+        || match mismatch {
+            Some(((uid, _), (restored_uid, _))) => format!("first mismatch at #{uid} (restored #{restored_uid})"),
+            None => format!("{} genomes vs {}", restored_checksums.len(), checksums.len()),
+        },
+    );
+    let after = Snapshot::capture(&restored);
+    for (part, difference) in before.diff(&after) {
+        report.check(format!("restored {part}"), difference.is_none(), || difference.unwrap_or_default());
+    }
+
+    // The restored run must follow the original's future exactly
+    let mut diverged = None;
+    for (i, expected) in reference.iter().enumerate() {
+        restored.tick();
+        if diverged.is_none() && Snapshot::capture(&restored).fingerprint() != *expected {
+            diverged = Some(i + 1);
+        }
+    }
+    let end = Snapshot::capture(&restored);
+    report.check("determinism", diverged.is_none(), || {
+        let differences: Vec<String> = reference_end
+            .diff(&end)
+            .into_iter()
+            .filter_map(|(part, difference)| Some(format!("{part}: {}", difference?)))
+            .collect();
+        format!(
+            "diverged {} ticks after loading; after {ROUND_TRIP_DETERMINISM_TICKS} ticks: {}",
+            diverged.unwrap_or(0),
+            differences.join("; ")
+        )
+    });
+}
@@ -0,0 +1,182 @@
+use std::collections::VecDeque;
+use std::io::Write;
+
+use crate::brain::{self, MotorOutputs};
+use crate::config;
+use crate::entity::EntityId;
+use crate::genome::N;
+use crate::simulation::SimState;
+use crate::storage;
+
+const MOTOR_CSV_HEADER: &str =
+    ",motor_forward,motor_turn,motor_attack,motor_signal,motor_build,motor_call,motor_trail,motor_cohesion,motor_alignment";
+
+fn motor_csv(m: &MotorOutputs) -> String {
+    format!(
+        ",{:.5},{:.5},{:.5},{:.5},{:.5},{:.5},{:.5},{:.5},{:.5}",
+        m.forward, m.turn, m.attack, m.signal, m.build, m.call, m.trail, m.cohesion, m.alignment
+    )
+}
+
+/// One tick of a brain's activity. Sensor neuron states are the sensor inputs.
+#[derive(Clone, Copy)]
+pub struct BrainSample {
+    pub tick: u64,
+    pub states: [f32; N],
+    pub outputs: [f32; N],
+    pub motors: MotorOutputs,
+}
+
+/// Ring buffer of the followed entity's recent brain activity (the last BRAIN_HISTORY_LENGTH ticks).
+#[derive(Default)]
+pub struct BrainHistory {
+    pub target: Option<EntityId>,
+    pub uid: u64,
+    pub samples: VecDeque<BrainSample>,
+    pub last_export: Option<String>,
+}
+
+impl BrainHistory {
+    /// Append the current tick for the followed entity. Following a different entity starts
+    /// a new history; losing the entity keeps the old one for export.
+    pub fn record(&mut self, sim: &SimState, following: Option<EntityId>) {
+        let Some(id) = following else { return };
+        let slot = id.index as usize;
+        let Some(entity) = sim.arena.get(id) else { return };
+        if !sim.brains.active.get(slot).copied().unwrap_or(false) {
+            return;
+        }
+        if self.target != Some(id) {
+            self.target = Some(id);
+            self.uid = entity.uid;
+            self.samples.clear();
+            self.last_export = None;
+        }
+        if self.samples.len() >= config::BRAIN_HISTORY_LENGTH {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(BrainSample {
+            tick: sim.tick_count,
+            states: sim.brains.states[slot],
+            outputs: sim.brains.outputs[slot],
+            motors: sim.brains.motor_outputs(slot),
+        });
+    }
+
+    /// Write the buffered history as CSV: sensor inputs, neuron states and motor outputs per tick.
+    pub fn export_csv(&self, path: &str, run_header: &str) -> Result<usize, String> {
+        let mut writer = storage::create(path).map_err(|e| format!("Create error: {e}"))?;
+        writeln!(writer, "{run_header}").map_err(|e| format!("Write error: {e}"))?;
+
+        let mut header = String::from("tick");
+        for i in 0..N {
+            let label = brain::neuron_label(i);
+            let kind = if i < config::BRAIN_SENSOR_NEURONS { "input" } else { "state" };
+            header.push_str(&format!(",{kind}_{label}"));
+        }
+        header.push_str(MOTOR_CSV_HEADER);
+        writeln!(writer, "{header}").map_err(|e| format!("Write error: {e}"))?;
+
+        for sample in &self.samples {
+            let mut row = sample.tick.to_string();
+            for v in &sample.states {
+                row.push_str(&format!(",{v:.5}"));
+            }
+            row.push_str(&motor_csv(&sample.motors));
+            writeln!(writer, "{row}").map_err(|e| format!("Write error: {e}"))?;
+        }
+        writer.flush().map_err(|e| format!("Write error: {e}"))?;
+        Ok(self.samples.len())
+    }
+}
+
+/// Streams one entity's neuron states, activations and motor outputs to CSV, one row per tick.
+pub struct NeuronRecorder {
+    writer: Option<storage::Writer>,
+    pub target: Option<EntityId>,
+    pub path: String,
+    pub rows: u64,
+    /// Recent activity of the followed entity, kept whether or not a CSV stream is open.
+    pub history: BrainHistory,
+}
+
+impl NeuronRecorder {
+    pub fn new() -> Self {
+        Self {
+            writer: None,
+            target: None,
+            path: String::new(),
+            rows: 0,
+            history: BrainHistory::default(),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// Start recording the given entity to `neurons_uid<uid>_tick<tick>.csv`.
+    pub fn start(&mut self, id: EntityId, uid: u64, tick: u64, run_header: &str) -> Result<(), String> {
+        self.stop();
+        let path = format!("neurons_uid{}_tick{}.csv", uid, tick);
+        let mut writer = storage::create(&path).map_err(|e| format!("Create error: {e}"))?;
+        writeln!(writer, "{run_header}").map_err(|e| format!("Write error: {e}"))?;
+
+        let mut header = String::from("tick");
+        for i in 0..N {
+            header.push_str(&format!(",state_{}", brain::neuron_label(i)));
+        }
+        for i in 0..N {
+            header.push_str(&format!(",out_{}", brain::neuron_label(i)));
+        }
+        header.push_str(MOTOR_CSV_HEADER);
+        writeln!(writer, "{header}").map_err(|e| format!("Write error: {e}"))?;
+
+        self.writer = Some(writer);
+        self.target = Some(id);
+        self.path = path;
+        self.rows = 0;
+        Ok(())
+    }
+
+    /// Flush and close the current recording, if any.
+    pub fn stop(&mut self) {
+        if let Some(mut writer) = self.writer.take() {
+            if let Err(e) = writer.flush() {
+                eprintln!("[GENESIS] Neuron recording flush failed: {e}");
+            } else {
+                eprintln!("[GENESIS] Recorded {} ticks to {}", self.rows, self.path);
+            }
+        }
+        self.target = None;
+    }
+
+    /// Append the current tick for the recorded entity. Stops automatically when it dies.
+    pub fn record(&mut self, sim: &SimState) {
+        let (Some(writer), Some(id)) = (self.writer.as_mut(), self.target) else {
+            return;
+        };
+        let slot = id.index as usize;
+        let alive = sim.arena.get(id).is_some() && sim.brains.active.get(slot).copied().unwrap_or(false);
+        if !alive {
+            self.stop();
+            return;
+        }
+
+        let mut row = sim.tick_count.to_string();
+        for v in &sim.brains.states[slot] {
+            row.push_str(&format!(",{v:.5}"));
+        }
+        for v in &sim.brains.outputs[slot] {
+            row.push_str(&format!(",{v:.5}"));
+        }
+        row.push_str(&motor_csv(&sim.brains.motor_outputs(slot)));
+
+        if let Err(e) = writeln!(writer, "{row}") {
+            eprintln!("[GENESIS] Neuron recording write failed: {e}");
+            self.stop();
+            return;
+        }
+        self.rows += 1;
+    }
+}
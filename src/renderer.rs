@@ -1,23 +1,36 @@
 use macroquad::prelude::*;
 
-use crate::camera::CameraController;
-use crate::combat::MeatItem;
-use crate::entity::EntityArena;
-use crate::environment;
-use crate::sensory::{EntityRays, HitType};
-use crate::signals::{self, SignalState};
+use crate::camera::{CameraController, EntityDetail};
+use crate::combat::{self, MeatItem};
+use crate::config;
+use crate::editing::{EraseBrush, ToxicBrush, WallEditState};
+use crate::entity::{self, EntityArena, EntityId};
+use crate::environment::{self, EnvironmentState, TerrainGrid, TerrainType, Wall};
+use crate::heatmap;
+use crate::plants;
+use crate::post_processing::PointLight;
+use crate::run_target::RunUntil;
+use crate::sensory::{EntityRays, HitType, RayMode};
+use crate::shelter;
+use crate::acoustics;
+use crate::signals;
 use crate::simulation::{FoodItem, SimState};
+use crate::ui::region::{Region, RegionState};
+use crate::weather;
 use crate::world::World;
 
 const BG_COLOR: Color = Color::new(0.02, 0.03, 0.08, 1.0);
 
 /// Draw the world scene (everything that should be affected by bloom).
 /// If render_target is Some, renders into that target; otherwise renders to screen.
+/// `night_overlay` darkens the scene with a flat tint at night; the bloom path leaves it off
+/// and lights the scene in post-processing instead.
 pub fn draw_world_scene(
     sim: &SimState,
     camera: &CameraController,
     alpha: f32,
     render_target: Option<RenderTarget>,
+    night_overlay: bool,
 ) {
     if let Some(ref rt) = render_target {
         // Render to offscreen target
@@ -45,11 +58,21 @@ pub fn draw_world_scene(
 
     // Terrain
     environment::draw_terrain(&sim.environment.terrain);
+    plants::draw_plants(&sim.plants);
+    environment::draw_toxic_zones(&sim.environment.toxic_zones);
 
     // Pheromone overlay (under everything)
     signals::draw_pheromone_overlay(&sim.pheromone_grid, &sim.world);
 
-    draw_food(&sim.food);
+    // Activity heatmap
+    if let Some(layer) = sim.heatmap_layer {
+        heatmap::draw_heatmap(sim.heatmaps.layer(layer), layer, sim.heatmap_opacity);
+    }
+
+    shelter::draw_shelters(&sim.shelters);
+    environment::draw_walls(&sim.environment.walls);
+    environment::draw_portals(&sim.environment.portals, sim.environment.day_progress);
+    draw_food(&sim.food, &sim.environment.terrain);
     draw_meat(&sim.meat);
 
     // Draw signal auras behind entities
@@ -58,17 +81,23 @@ pub fn draw_world_scene(
             let pos = entity.prev_pos.lerp(entity.pos, alpha);
             signals::draw_signal_aura(pos, entity.radius, &sim.signals[idx]);
         }
+        if let Some(&level) = sim.calls.get(idx) {
+            let pos = entity.prev_pos.lerp(entity.pos, alpha);
+            acoustics::draw_call_ring(pos, entity.radius, level, get_time() as f32);
+        }
     }
 
-    draw_entities(&sim.arena, &sim.signals, alpha);
+    draw_entities(sim, alpha, camera.detail);
 
     // Draw sensor rays if enabled
-    if sim.show_rays {
+    if sim.ray_mode != RayMode::Off {
         draw_sensor_rays(&sim.last_rays);
     }
 
-    // Draw combat lines
-    for event in &sim.combat_events {
+    combat::draw_projectiles(&sim.projectiles);
+
+    // Draw combat lines (spit hits show only their particles)
+    for event in sim.combat_events.iter().filter(|e| !e.ranged) {
         draw_line(
             event.attacker_pos.x, event.attacker_pos.y,
             event.target_pos.x, event.target_pos.y,
@@ -79,31 +108,366 @@ pub fn draw_world_scene(
     // Particles
     sim.particles.draw();
 
-    // Storm visual
+    // Storm and rain visuals
     if let Some(ref storm) = sim.environment.storm {
-        environment::draw_storm(storm);
+        environment::draw_storm(storm, sim.environment.day_progress);
     }
+    weather::draw_rain_clouds(&sim.environment.weather.clouds, sim.environment.day_progress);
 
     // Day/night tint overlay
-    environment::draw_day_night_overlay(sim.environment.day_brightness());
+    if night_overlay {
+        environment::draw_day_night_overlay(sim.environment.day_brightness());
+    }
 }
 
 /// Standard draw (no bloom): renders directly to screen.
 pub fn draw(sim: &SimState, camera: &CameraController, alpha: f32) {
     clear_background(BG_COLOR);
 
-    draw_world_scene(sim, camera, alpha, None);
+    draw_world_scene(sim, camera, alpha, None, true);
 
     set_default_camera();
-    draw_hud(
-        &sim.arena,
-        sim.tick_count,
-        sim.paused,
+    draw_hud(&sim.arena, sim.tick_count, sim.paused, sim.food.len(), &sim.environment);
+}
+
+/// Fast-forward progress overlay, drawn instead of the world: target and achieved speed,
+/// simulated time, a few population figures and the run-until target, if any.
+pub fn draw_fast_forward(sim: &SimState, achieved_speed: f32, avg_generation: f32, run_until: Option<&RunUntil>) {
+    clear_background(BG_COLOR);
+    let tc = Color::new(0.7, 0.75, 0.8, 1.0);
+    let (cx, cy) = (screen_width() * 0.5, screen_height() * 0.5);
+    let x = cx - 180.0;
+
+    draw_text(&format!("FAST-FORWARD {}x", sim.speed_multiplier), x, cy - 60.0, 32.0, Color::new(0.9, 0.8, 0.4, 1.0));
+
+    // Achieved speed as a fraction of the requested speed
+    let frac = (achieved_speed / sim.speed_multiplier).clamp(0.0, 1.0);
+    draw_rectangle(x, cy - 44.0, 360.0, 10.0, Color::new(0.15, 0.15, 0.2, 1.0));
+    draw_rectangle(x, cy - 44.0, 360.0 * frac, 10.0, Color::new(0.4, 0.7, 1.0, 1.0));
+    let ticks_per_sec = achieved_speed * sim.sim_hz();
+    draw_text(&format!("Achieved {achieved_speed:.0}x ({ticks_per_sec:.0} ticks/s)"), x, cy - 14.0, 18.0, tc);
+
+    let secs = (sim.tick_count as f32 * sim.dt) as u64;
+    let time = format!("{}h {:02}m {:02}s", secs / 3600, secs % 3600 / 60, secs % 60);
+    draw_text(&format!("Tick {} | simulated {time}", sim.tick_count), x, cy + 8.0, 18.0, tc);
+    let population = format!(
+        "Entities {} | Food {} | Species {} | Avg generation {avg_generation:.1}",
+        sim.arena.count,
         sim.food.len(),
-        sim.environment.season.name(),
-        sim.environment.is_day(),
-        sim.environment.storm.is_some(),
+        sim.species.species.len()
     );
+    draw_text(&population, x, cy + 30.0, 18.0, tc);
+    draw_text(
+        &format!("Drop below {}x to watch the world", config::FAST_FORWARD_MIN_SPEED),
+        x,
+        cy + 56.0,
+        16.0,
+        Color::new(0.5, 0.55, 0.6, 1.0),
+    );
+
+    if let Some(run) = run_until {
+        let target_color = Color::new(0.55, 0.9, 0.6, 1.0);
+        let label = format!("Running until {}", run.target.describe());
+        match run.progress(sim) {
+            Some(frac) => {
+                draw_text(&format!("{label} ({:.0}%)", frac * 100.0), x, cy + 90.0, 18.0, target_color);
+                draw_rectangle(x, cy + 98.0, 360.0, 8.0, Color::new(0.15, 0.15, 0.2, 1.0));
+                draw_rectangle(x, cy + 98.0, 360.0 * frac, 8.0, target_color);
+            }
+            None => {
+                draw_text(&label, x, cy + 90.0, 18.0, target_color);
+            }
+        }
+    }
+}
+
+/// Blinking "REC" badge at the top centre of the screen while frames are being recorded.
+pub fn draw_recording_indicator(frames: u64, elapsed: f64) {
+    let secs = elapsed as u64;
+    let label = format!("REC {:02}:{:02}  {frames} frames", secs / 60, secs % 60);
+    let x = screen_width() * 0.5 - 80.0;
+    draw_rectangle(x - 8.0, 6.0, 176.0, 24.0, Color::new(0.0, 0.0, 0.0, 0.6));
+    if elapsed.fract() < 0.5 {
+        draw_circle(x + 6.0, 18.0, 6.0, Color::new(0.95, 0.2, 0.2, 1.0));
+    }
+    draw_text(&label, x + 18.0, 24.0, 18.0, Color::new(0.95, 0.9, 0.9, 1.0));
+}
+
+/// Draw the wall being dragged out by the wall tool (world space, on top of the scene).
+pub fn draw_wall_preview(camera: &CameraController, from: Vec2, to: Vec2) {
+    set_camera(&camera.to_macroquad_camera());
+    let valid = from.distance(to) >= config::WALL_MIN_LENGTH;
+    let color = if valid {
+        Color::new(0.8, 0.8, 0.9, 0.7)
+    } else {
+        Color::new(0.9, 0.3, 0.3, 0.7)
+    };
+    draw_line(from.x, from.y, to.x, to.y, config::WALL_THICKNESS, color);
+    set_default_camera();
+}
+
+/// Draw the portal being dragged out by the portal tool; red while the endpoints are too close.
+pub fn draw_portal_preview(camera: &CameraController, from: Vec2, to: Vec2) {
+    set_camera(&camera.to_macroquad_camera());
+    if from.distance(to) >= config::PORTAL_MIN_DISTANCE {
+        environment::draw_portal(from, to, get_time() as f32, 0.5);
+    } else {
+        draw_circle_lines(from.x, from.y, config::PORTAL_RADIUS, 2.0, Color::new(0.9, 0.3, 0.3, 0.7));
+        draw_circle_lines(to.x, to.y, config::PORTAL_RADIUS, 2.0, Color::new(0.9, 0.3, 0.3, 0.7));
+    }
+    set_default_camera();
+}
+
+/// Preview of the toxic zone the hazard tool would place at `pos`.
+pub fn draw_toxic_brush(camera: &CameraController, pos: Vec2, brush: &ToxicBrush) {
+    set_camera(&camera.to_macroquad_camera());
+    environment::draw_toxic_gradient(pos, brush.radius, brush.intensity, 0.5);
+    set_default_camera();
+}
+
+/// Outline of the erase tool; red when walls will be erased too.
+pub fn draw_erase_brush(camera: &CameraController, pos: Vec2, brush: &EraseBrush, walls: bool) {
+    set_camera(&camera.to_macroquad_camera());
+    let color = if walls { Color::new(1.0, 0.35, 0.3, 0.9) } else { Color::new(0.9, 0.9, 1.0, 0.8) };
+    draw_circle(pos.x, pos.y, brush.radius, Color::new(color.r, color.g, color.b, 0.08));
+    draw_circle_lines(pos.x, pos.y, brush.radius, 1.5 / camera.smooth_zoom, color);
+    set_default_camera();
+}
+
+/// Highlight the hovered and selected walls for the wall-edit tool, with endpoint handles.
+pub fn draw_wall_edit(camera: &CameraController, walls: &[Wall], state: &WallEditState) {
+    set_camera(&camera.to_macroquad_camera());
+    let line = 2.0 / camera.smooth_zoom;
+    if let Some(w) = state.hovered.filter(|&i| Some(i) != state.selected).and_then(|i| walls.get(i)) {
+        draw_line(w.a.x, w.a.y, w.b.x, w.b.y, config::WALL_THICKNESS + line * 2.0, Color::new(0.9, 0.9, 1.0, 0.35));
+    }
+    if let Some(w) = state.selected.and_then(|i| walls.get(i)) {
+        let color = Color::new(0.4, 0.7, 1.0, 0.9);
+        draw_line(w.a.x, w.a.y, w.b.x, w.b.y, config::WALL_THICKNESS + line * 2.0, Color::new(0.4, 0.7, 1.0, 0.4));
+        let handle = 6.0 / camera.smooth_zoom;
+        for p in [w.a, w.b] {
+            draw_circle(p.x, p.y, handle, Color::new(0.1, 0.15, 0.25, 0.9));
+            draw_circle_lines(p.x, p.y, handle, line, color);
+        }
+    }
+    set_default_camera();
+}
+
+/// Draw the rubber-band selection rectangle (world space, on top of the scene).
+pub fn draw_selection_box(camera: &CameraController, from: Vec2, to: Vec2) {
+    set_camera(&camera.to_macroquad_camera());
+    let (x, y) = (from.x.min(to.x), from.y.min(to.y));
+    let (w, h) = ((from.x - to.x).abs(), (from.y - to.y).abs());
+    draw_rectangle(x, y, w, h, Color::new(0.4, 0.7, 1.0, 0.12));
+    draw_rectangle_lines(x, y, w, h, 2.0 / camera.smooth_zoom, Color::new(0.4, 0.7, 1.0, 0.8));
+    set_default_camera();
+}
+
+/// Draw the marked region, plus the rectangle or polygon being drawn with the region tool.
+pub fn draw_region(camera: &CameraController, state: &RegionState, mouse: Vec2) {
+    let fill = Color::new(1.0, 0.8, 0.3, 0.08);
+    let line = Color::new(1.0, 0.8, 0.3, 0.8);
+    let width = 2.0 / camera.smooth_zoom;
+    set_camera(&camera.to_macroquad_camera());
+    match &state.region {
+        Some(Region::Rect(r)) => {
+            draw_rectangle(r.x, r.y, r.w, r.h, fill);
+            draw_rectangle_lines(r.x, r.y, r.w, r.h, width, line);
+        }
+        Some(Region::Polygon(points)) => {
+            // Fan triangulation; concave polygons get overlapping fill but an exact outline
+            for pair in points[1..].windows(2) {
+                draw_triangle(points[0], pair[0], pair[1], fill);
+            }
+            for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+                draw_line(a.x, a.y, b.x, b.y, width, line);
+            }
+        }
+        None => {}
+    }
+    if let Some(start) = state.drag_start {
+        let (x, y) = (start.x.min(mouse.x), start.y.min(mouse.y));
+        draw_rectangle_lines(x, y, (start.x - mouse.x).abs(), (start.y - mouse.y).abs(), width, line);
+    }
+    if let Some(last) = state.polygon.last() {
+        for pair in state.polygon.windows(2) {
+            draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, width, line);
+        }
+        draw_line(last.x, last.y, mouse.x, mouse.y, width, Color::new(1.0, 0.8, 0.3, 0.4));
+        let first = state.polygon[0];
+        draw_circle_lines(first.x, first.y, 6.0 / camera.smooth_zoom, width, line);
+    }
+    set_default_camera();
+}
+
+/// Draw rings around multi-selected entities.
+pub fn draw_selection_markers(camera: &CameraController, arena: &EntityArena, selection: &[EntityId]) {
+    set_camera(&camera.to_macroquad_camera());
+    for entity in selection.iter().filter_map(|id| arena.get(*id)) {
+        draw_circle_lines(
+            entity.pos.x,
+            entity.pos.y,
+            entity.radius + 5.0,
+            2.0 / camera.smooth_zoom,
+            Color::new(0.4, 0.7, 1.0, 0.9),
+        );
+    }
+    set_default_camera();
+}
+
+/// Names and tags above labelled entities, in screen space. Hidden when zoomed out.
+pub fn draw_entity_labels(camera: &CameraController, arena: &EntityArena, alpha: f32) {
+    if camera.smooth_zoom < config::ENTITY_LABEL_MIN_ZOOM {
+        return;
+    }
+    let font_size = 16.0;
+    for (_idx, entity) in arena.iter_alive() {
+        if entity.name.is_none() && entity.tag.is_none() {
+            continue;
+        }
+        let pos = entity.prev_pos.lerp(entity.pos, alpha);
+        // Above the energy bar
+        let anchor = camera.world_to_screen(pos - vec2(0.0, entity.radius * 2.0));
+        let mut y = anchor.y - 6.0;
+        if let Some(ref tag) = entity.tag {
+            let size = measure_text(tag, None, font_size as u16, 1.0);
+            let x = anchor.x - size.width * 0.5;
+            let color = entity::tag_color(tag);
+            let (bx, by, bw, bh) = (x - 3.0, y - size.offset_y - 1.0, size.width + 6.0, size.height + 2.0);
+            draw_rectangle(bx, by, bw, bh, Color::new(0.0, 0.0, 0.0, 0.6));
+            draw_rectangle_lines(bx, by, bw, bh, 1.0, color);
+            draw_text(tag, x, y, font_size, color);
+            y -= size.height + 6.0;
+        }
+        if let Some(ref name) = entity.name {
+            let size = measure_text(name, None, font_size as u16, 1.0);
+            let x = anchor.x - size.width * 0.5;
+            draw_text(name, x + 1.0, y + 1.0, font_size, Color::new(0.0, 0.0, 0.0, 0.6));
+            draw_text(name, x, y, font_size, Color::new(0.9, 0.95, 1.0, 0.95));
+        }
+    }
+}
+
+/// Motor intents shown in the thought bubble.
+#[derive(Clone, Copy)]
+enum Intent {
+    Forage,
+    Turn,
+    Attack,
+    Share,
+    Reproduce,
+}
+
+impl Intent {
+    fn label(self) -> &'static str {
+        match self {
+            Intent::Forage => "forage",
+            Intent::Turn => "turn",
+            Intent::Attack => "attack",
+            Intent::Share => "share",
+            Intent::Reproduce => "mate",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Intent::Forage => Color::new(0.4, 0.9, 0.4, 1.0),
+            Intent::Turn => Color::new(0.5, 0.75, 1.0, 1.0),
+            Intent::Attack => Color::new(1.0, 0.3, 0.25, 1.0),
+            Intent::Share => Color::new(1.0, 0.85, 0.3, 1.0),
+            Intent::Reproduce => Color::new(1.0, 0.5, 0.8, 1.0),
+        }
+    }
+
+    /// Small glyph centred on (x, y).
+    fn draw_icon(self, x: f32, y: f32, turn: f32) {
+        let c = self.color();
+        match self {
+            Intent::Forage => {
+                draw_circle(x, y, 4.5, c);
+                draw_line(x, y - 4.5, x + 2.5, y - 7.0, 1.5, c);
+            }
+            Intent::Turn => {
+                let d = if turn < 0.0 { -1.0 } else { 1.0 };
+                draw_circle_lines(x, y, 4.5, 1.5, c);
+                draw_triangle(vec2(x + 4.5 * d, y - 3.0), vec2(x + 1.5 * d, y - 7.0), vec2(x + 7.5 * d, y - 6.0), c);
+            }
+            Intent::Attack => {
+                for dx in [-4.0, 0.0, 4.0] {
+                    draw_triangle(vec2(x + dx - 2.0, y + 4.0), vec2(x + dx + 2.0, y + 4.0), vec2(x + dx, y - 5.0), c);
+                }
+            }
+            Intent::Share => {
+                draw_circle(x, y, 2.5, c);
+                for k in 0..4 {
+                    let a = k as f32 * std::f32::consts::FRAC_PI_2 + std::f32::consts::FRAC_PI_4;
+                    let dir = vec2(a.cos(), a.sin());
+                    draw_line(x + dir.x * 4.0, y + dir.y * 4.0, x + dir.x * 6.5, y + dir.y * 6.5, 1.5, c);
+                }
+            }
+            Intent::Reproduce => {
+                draw_circle(x - 2.5, y, 3.5, c);
+                draw_circle(x + 2.5, y, 3.5, c);
+            }
+        }
+    }
+}
+
+/// Thought bubble above the followed entity with its strongest motor intents as icons and
+/// intensity bars, read from the brain outputs of the last tick. Reproduce is the energy
+/// fraction of the entity's current reproduction threshold, since breeding has no motor.
+pub fn draw_intent_bubble(camera: &CameraController, sim: &SimState, alpha: f32) {
+    let Some(id) = camera.following else { return };
+    let Some(entity) = sim.arena.get(id) else { return };
+    let idx = id.index as usize;
+    if !sim.brains.active.get(idx).copied().unwrap_or(false) {
+        return;
+    }
+    let motors = sim.brains.motor_outputs(idx);
+    let reproduce = if entity.fertility() > 0.0 {
+        (entity.energy / entity.reproduction_threshold()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let mut intents = [
+        (Intent::Forage, motors.forward),
+        (Intent::Turn, motors.turn.abs()),
+        (Intent::Attack, motors.attack),
+        (Intent::Share, motors.signal),
+        (Intent::Reproduce, reproduce),
+    ];
+    intents.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let shown: Vec<_> = intents
+        .iter()
+        .filter(|(_, v)| *v >= config::INTENT_BUBBLE_MIN)
+        .take(config::INTENT_BUBBLE_ROWS)
+        .collect();
+    if shown.is_empty() {
+        return;
+    }
+
+    let pos = entity.prev_pos.lerp(entity.pos, alpha);
+    let anchor = camera.world_to_screen(pos + vec2(entity.radius, -entity.radius));
+    let (row_h, bar_w, font_size) = (16.0, 44.0, 14.0);
+    let (w, h) = (108.0, shown.len() as f32 * row_h + 8.0);
+    let x = anchor.x + 14.0;
+    let y = anchor.y - 18.0 - h;
+    let bg = Color::new(0.05, 0.07, 0.1, 0.8);
+    let edge = Color::new(0.8, 0.85, 0.95, 0.6);
+    // Trailing puffs from the entity up to the bubble
+    draw_circle(anchor.x + 3.0, anchor.y - 3.0, 2.5, bg);
+    draw_circle(anchor.x + 9.0, anchor.y - 11.0, 4.0, bg);
+    draw_rectangle(x, y, w, h, bg);
+    draw_rectangle_lines(x, y, w, h, 1.0, edge);
+
+    for (row, (intent, value)) in shown.iter().enumerate() {
+        let cy = y + 4.0 + row_h * (row as f32 + 0.5);
+        intent.draw_icon(x + 11.0, cy, motors.turn);
+        draw_text(intent.label(), x + 21.0, cy + 4.0, font_size, Color::new(0.9, 0.92, 0.95, 0.95));
+        let bx = x + w - bar_w - 6.0;
+        draw_rectangle(bx, cy - 3.0, bar_w, 6.0, Color::new(1.0, 1.0, 1.0, 0.12));
+        draw_rectangle(bx, cy - 3.0, bar_w * value.clamp(0.0, 1.0), 6.0, intent.color());
+    }
 }
 
 /// Draw with bloom pipeline.
@@ -113,22 +477,45 @@ pub fn draw_with_bloom(
     alpha: f32,
     bloom: &crate::post_processing::BloomPipeline,
 ) {
-    // Render world scene to bloom's scene render target
-    draw_world_scene(sim, camera, alpha, Some(bloom.scene_render_target()));
+    // Render world scene to bloom's scene render target, and its lights to the light buffer
+    draw_world_scene(sim, camera, alpha, Some(bloom.scene_render_target()), false);
+    let light_camera = Camera2D {
+        render_target: Some(bloom.light_render_target()),
+        ..camera.to_macroquad_camera()
+    };
+    let ambient = environment::night_ambient(sim.environment.day_brightness());
+    bloom.draw_lights(&light_camera, ambient, &collect_lights(sim, alpha));
 
     // Run bloom post-processing and composite to screen
     bloom.apply();
 
     // Draw HUD on top (after bloom, in screen space)
-    draw_hud(
-        &sim.arena,
-        sim.tick_count,
-        sim.paused,
-        sim.food.len(),
-        sim.environment.season.name(),
-        sim.environment.is_day(),
-        sim.environment.storm.is_some(),
-    );
+    draw_hud(&sim.arena, sim.tick_count, sim.paused, sim.food.len(), &sim.environment);
+}
+
+/// Lights for the night lighting pass: a glow around every creature, tinted by its colour, and
+/// a wider one in its signal colour while it signals.
+fn collect_lights(sim: &SimState, alpha: f32) -> Vec<PointLight> {
+    let mut lights = Vec::new();
+    for (idx, entity) in sim.arena.iter_alive() {
+        let pos = entity.prev_pos.lerp(entity.pos, alpha);
+        let color = sim.display_color(idx, entity);
+        let s = config::NIGHT_LIGHT_ENTITY_STRENGTH;
+        lights.push(PointLight {
+            pos,
+            radius: entity.radius * config::NIGHT_LIGHT_ENTITY_RADIUS,
+            color: Color::new((0.5 + color.r * 0.5) * s, (0.5 + color.g * 0.5) * s, (0.5 + color.b * 0.5) * s, 1.0),
+        });
+        if let Some(signal) = sim.signals.get(idx).filter(|s| s.intensity > 0.05) {
+            let s = config::NIGHT_LIGHT_SIGNAL_STRENGTH * signal.intensity;
+            lights.push(PointLight {
+                pos,
+                radius: entity.radius * (2.0 + signal.intensity * 2.0) * config::NIGHT_LIGHT_SIGNAL_RADIUS,
+                color: Color::new(signal.color.r * s, signal.color.g * s, signal.color.b * s, 1.0),
+            });
+        }
+    }
+    lights
 }
 
 fn draw_world_background(world: &World, camera: &CameraController) {
@@ -155,10 +542,19 @@ fn draw_world_background(world: &World, camera: &CameraController) {
     }
 }
 
-fn draw_food(food: &[FoodItem]) {
+/// Food is green; algae (food lying in water, edible only by swimmers) is teal.
+fn draw_food(food: &[FoodItem], terrain: &TerrainGrid) {
     for item in food {
-        draw_circle(item.pos.x, item.pos.y, 6.0, Color::new(0.1, 0.5, 0.1, 0.3));
-        draw_circle(item.pos.x, item.pos.y, 3.5, Color::new(0.2, 0.85, 0.2, 0.9));
+        if terrain.get_at(item.pos) == TerrainType::Water {
+            draw_circle(item.pos.x, item.pos.y, 6.0, Color::new(0.05, 0.45, 0.45, 0.3));
+            draw_circle(item.pos.x, item.pos.y, 3.5, Color::new(0.2, 0.8, 0.75, 0.9));
+        } else {
+            // Berries and fruit fade over their last third before spoiling
+            let fade = item.kind.spoil_time().map_or(1.0, |t| ((t - item.age) / t * 3.0).clamp(0.3, 1.0));
+            let color = item.kind.color();
+            draw_circle(item.pos.x, item.pos.y, 6.0, Color::new(color.r * 0.5, color.g * 0.5, color.b * 0.5, 0.3 * fade));
+            draw_circle(item.pos.x, item.pos.y, 3.5, Color { a: color.a * fade, ..color });
+        }
     }
 }
 
@@ -170,23 +566,62 @@ fn draw_meat(meat: &[MeatItem]) {
     }
 }
 
-fn draw_entities(arena: &EntityArena, _signals: &[SignalState], alpha: f32) {
-    for (_idx, entity) in arena.iter_alive() {
+fn draw_entities(sim: &SimState, alpha: f32, detail: EntityDetail) {
+    for (idx, entity) in sim.arena.iter_alive() {
         let pos = entity.prev_pos.lerp(entity.pos, alpha);
-        draw_entity_shape(pos, entity.heading, entity.radius, entity.color, entity.energy);
+        let color = sim.display_color(idx, entity);
+        draw_entity_shape(pos, entity.heading, entity.radius, color, entity.energy, detail);
+        let intent = sim.attack_intents.get(idx).copied().unwrap_or(0.0);
+        if intent >= config::ATTACK_INTENT_THRESHOLD {
+            draw_aggression_glyph(pos, entity.heading, entity.radius, intent);
+        }
+        if entity.protection_ticks > 0 {
+            // Shield ring for entities protected by the breeding tool
+            draw_circle_lines(pos.x, pos.y, entity.radius * 1.9, 1.0, Color::new(0.5, 0.85, 1.0, 0.6));
+        }
     }
 }
 
-fn draw_entity_shape(pos: Vec2, heading: f32, radius: f32, color: Color, energy: f32) {
+/// Red spikes ahead of an entity that is trying to attack, brighter as the intent nears 1.
+fn draw_aggression_glyph(pos: Vec2, heading: f32, radius: f32, intent: f32) {
+    let strength = (intent - config::ATTACK_INTENT_THRESHOLD) / (1.0 - config::ATTACK_INTENT_THRESHOLD);
+    let color = Color::new(1.0, 0.25, 0.15, 0.5 + 0.5 * strength.clamp(0.0, 1.0));
+    for offset in [-0.5f32, 0.0, 0.5] {
+        let dir = Vec2::from_angle(heading + offset);
+        let perp = Vec2::new(-dir.y, dir.x);
+        let base = pos + dir * radius * 1.7;
+        let tip = pos + dir * radius * 2.4;
+        draw_triangle(tip, base + perp * radius * 0.2, base - perp * radius * 0.2, color);
+    }
+}
+
+/// A creature at the given level of detail. Body is two triangles; Basic adds the body disc,
+/// eyes and energy bar; Full adds fins, a dorsal stripe and pupils looking ahead.
+fn draw_entity_shape(pos: Vec2, heading: f32, radius: f32, color: Color, energy: f32, detail: EntityDetail) {
     let dir = Vec2::from_angle(heading);
     let perp = Vec2::new(-dir.y, dir.x);
 
     let front = pos + dir * radius * 1.6;
     let back_left = pos - dir * radius * 0.8 + perp * radius * 0.9;
     let back_right = pos - dir * radius * 0.8 - perp * radius * 0.9;
-    draw_triangle(front, back_left, back_right, color);
-
+    if detail == EntityDetail::Body {
+        // Notched dart: two triangles meeting at a point behind the centre
+        let notch = pos - dir * radius * 0.4;
+        draw_triangle(front, back_left, notch, color);
+        draw_triangle(front, notch, back_right, color);
+        return;
+    }
     let body_color = Color::new(color.r * 0.85, color.g * 0.85, color.b * 0.85, 1.0);
+    if detail == EntityDetail::Full {
+        // Fins trailing from the flanks
+        let fin_color = Color::new(color.r * 0.7, color.g * 0.7, color.b * 0.7, 0.9);
+        for side in [1.0, -1.0] {
+            let root = pos - dir * radius * 0.2 + perp * side * radius * 0.5;
+            let tip = pos - dir * radius * 0.9 + perp * side * radius * 1.2;
+            draw_triangle(root, root - dir * radius * 0.6, tip, fin_color);
+        }
+    }
+    draw_triangle(front, back_left, back_right, color);
     draw_circle(pos.x, pos.y, radius * 0.55, body_color);
 
     let eye_offset = radius * 0.35;
@@ -195,6 +630,16 @@ fn draw_entity_shape(pos: Vec2, heading: f32, radius: f32, color: Color, energy:
     let eye_r = eye_pos - perp * eye_offset;
     draw_circle(eye_l.x, eye_l.y, radius * 0.12, Color::new(0.9, 0.95, 1.0, 0.9));
     draw_circle(eye_r.x, eye_r.y, radius * 0.12, Color::new(0.9, 0.95, 1.0, 0.9));
+    if detail == EntityDetail::Full {
+        let stripe_color = Color::new(color.r * 0.55, color.g * 0.55, color.b * 0.55, 0.9);
+        let tail = pos - dir * radius * 0.7;
+        let head = pos + dir * radius * 0.25;
+        draw_line(tail.x, tail.y, head.x, head.y, radius * 0.12, stripe_color);
+        for eye in [eye_l, eye_r] {
+            let pupil = eye + dir * radius * 0.04;
+            draw_circle(pupil.x, pupil.y, radius * 0.06, Color::new(0.05, 0.05, 0.08, 1.0));
+        }
+    }
 
     // Energy bar
     let bar_width = radius * 2.0;
@@ -229,6 +674,7 @@ fn draw_sensor_rays(all_rays: &[Option<EntityRays>]) {
                     HitType::Entity => Color::new(1.0, 0.3, 0.3, 0.4),
                     HitType::Food => Color::new(0.3, 1.0, 0.3, 0.4),
                     HitType::Wall => Color::new(0.5, 0.5, 0.8, 0.4),
+                    HitType::Portal => Color::new(0.7, 0.5, 1.0, 0.5),
                 };
                 draw_line(start.x, start.y, end.x, end.y, 1.0, color);
             }
@@ -236,15 +682,14 @@ fn draw_sensor_rays(all_rays: &[Option<EntityRays>]) {
     }
 }
 
-fn draw_hud(
-    arena: &EntityArena,
-    tick_count: u64,
-    paused: bool,
-    food_count: usize,
-    season: &str,
-    is_day: bool,
-    storm_active: bool,
-) {
+/// Compass direction the wind blows toward (north is up, y = 0).
+fn compass(dir: Vec2) -> &'static str {
+    const POINTS: [&str; 8] = ["E", "SE", "S", "SW", "W", "NW", "N", "NE"];
+    let angle = dir.y.atan2(dir.x).rem_euclid(std::f32::consts::TAU);
+    POINTS[((angle / std::f32::consts::FRAC_PI_4).round() as usize) % 8]
+}
+
+fn draw_hud(arena: &EntityArena, tick_count: u64, paused: bool, food_count: usize, env: &EnvironmentState) {
     let tc = Color::new(0.7, 0.75, 0.8, 1.0);
     let sh = Color::new(0.0, 0.0, 0.0, 0.5);
 
@@ -264,11 +709,29 @@ fn draw_hud(
     draw_text(&tick_text, 11.0, 81.0, 18.0, sh);
     draw_text(&tick_text, 10.0, 80.0, 18.0, tc);
 
-    let day_str = if is_day { "Day" } else { "Night" };
-    let env_text = format!("{} | {} {}", season, day_str, if storm_active { "| STORM" } else { "" });
+    let day_str = if env.is_day() { "Day" } else { "Night" };
+    let rain_str = if env.weather.is_raining() { " | Rain" } else { "" };
+    let storm_str = if env.storm.is_some() { " | STORM" } else { "" };
+    let env_text = format!("{} | {}{}{}", env.season.name(), day_str, rain_str, storm_str);
     draw_text(&env_text, 11.0, 101.0, 18.0, sh);
     draw_text(&env_text, 10.0, 100.0, 18.0, tc);
 
+    // Weather forecast: wind and next storm
+    let wind = env.wind();
+    let forecast = match (env.storm.as_ref(), env.storm_eta()) {
+        (Some(storm), _) => format!("Storm (strength {:.1}) passing: {:.0}s left", storm.strength, storm.timer.max(0.0)),
+        (None, Some(eta)) => format!("Next storm in {}:{:02}", eta as u32 / 60, eta as u32 % 60),
+        (None, None) => String::new(),
+    };
+    let weather_text = format!(
+        "Wind {:.0} u/s {} | {}",
+        wind.length(),
+        compass(wind),
+        forecast
+    );
+    draw_text(&weather_text, 11.0, 121.0, 18.0, sh);
+    draw_text(&weather_text, 10.0, 120.0, 18.0, tc);
+
     if paused {
         let pause_text = "PAUSED (Space to resume)";
         let tw = measure_text(pause_text, None, 24, 1.0).width;
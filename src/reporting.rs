@@ -0,0 +1,295 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+use ::rand::Rng;
+use macroquad::prelude::{vec2, Vec2};
+
+use crate::config;
+use crate::environment::Storm;
+use crate::genome::{Genome, BODY_ATTACK};
+use crate::plants::FoodKind;
+use crate::simulation::{FoodItem, SimState};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::storage;
+
+/// Collects samples of one metric and summarizes them as mean, percentiles and a histogram.
+pub struct MetricAggregator {
+    pub name: &'static str,
+    /// Upper bounds of the histogram buckets, ascending. Samples above the last bound fall
+    /// into a final overflow bucket.
+    pub buckets: Vec<f64>,
+    samples: Vec<f64>,
+}
+
+impl MetricAggregator {
+    pub fn new(name: &'static str, buckets: &[f64]) -> Self {
+        let mut buckets = buckets.to_vec();
+        buckets.sort_by(|a, b| a.total_cmp(b));
+        buckets.dedup();
+        Self {
+            name,
+            buckets,
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, value: f64) {
+        self.samples.push(value);
+    }
+
+    pub fn count(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.samples.iter().sum::<f64>() / self.samples.len().max(1) as f64
+    }
+
+    pub fn min(&self) -> f64 {
+        self.samples.iter().copied().fold(f64::INFINITY, f64::min)
+    }
+
+    pub fn max(&self) -> f64 {
+        self.samples.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Nearest-rank percentiles for each `q` in [0, 100] (e.g. 99.9), in the order given.
+    pub fn percentiles(&self, qs: &[f64]) -> Vec<(f64, f64)> {
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        qs.iter()
+            .map(|&q| {
+                if sorted.is_empty() {
+                    return (q, 0.0);
+                }
+                let rank = (q.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+                (q, sorted[rank.clamp(1, sorted.len()) - 1])
+            })
+            .collect()
+    }
+
+    /// Sample count per bucket: one entry per upper bound, then the overflow bucket.
+    pub fn histogram(&self) -> Vec<u64> {
+        let mut counts = vec![0u64; self.buckets.len() + 1];
+        for &v in &self.samples {
+            let bucket = self.buckets.partition_point(|&upper| upper < v);
+            counts[bucket] += 1;
+        }
+        counts
+    }
+
+    /// JSON object with the summary statistics, the requested percentiles and the histogram.
+    pub fn to_json(&self, percentiles: &[f64]) -> String {
+        let percentiles: Vec<String> = self
+            .percentiles(percentiles)
+            .iter()
+            .map(|(q, v)| format!("\"p{q}\": {}", json_number(*v)))
+            .collect();
+        let histogram: Vec<String> = self
+            .histogram()
+            .iter()
+            .enumerate()
+            .map(|(i, count)| match self.buckets.get(i) {
+                Some(upper) => format!("{{\"le\": {}, \"count\": {count}}}", json_number(*upper)),
+                None => format!("{{\"le\": \"+Inf\", \"count\": {count}}}"),
+            })
+            .collect();
+        format!(
+            "{{\"count\": {}, \"mean\": {}, \"min\": {}, \"max\": {}, \"percentiles\": {{{}}}, \"histogram\": [{}]}}",
+            self.count(),
+            json_number(self.mean()),
+            json_number(self.min()),
+            json_number(self.max()),
+            percentiles.join(", "),
+            histogram.join(", ")
+        )
+    }
+}
+
+/// JSON has no NaN or infinity; report them (empty aggregators) as null.
+pub fn json_number(v: f64) -> String {
+    if v.is_finite() {
+        format!("{v:.4}")
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Deterministic stress events injected into the measured window of a benchmark
+/// (`--benchmark-scenario`), so the report covers worst-case tick times.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BenchmarkScenario {
+    /// A storm BENCHMARK_STORM_RADIUS_MULT times the normal size over the world centre.
+    StormSurge,
+    /// A dense cluster of aggressive random entities.
+    CombatHeavy,
+    /// BENCHMARK_FOOD_FLOOD food items scattered across the world.
+    FoodBloom,
+}
+
+impl BenchmarkScenario {
+    pub const ALL: [BenchmarkScenario; 3] = [
+        BenchmarkScenario::StormSurge,
+        BenchmarkScenario::CombatHeavy,
+        BenchmarkScenario::FoodBloom,
+    ];
+
+    /// Identifier used on the command line and in the report.
+    pub fn id(&self) -> &'static str {
+        match self {
+            BenchmarkScenario::StormSurge => "storm-surge",
+            BenchmarkScenario::CombatHeavy => "combat-heavy",
+            BenchmarkScenario::FoodBloom => "food-bloom",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<BenchmarkScenario> {
+        BenchmarkScenario::ALL.iter().copied().find(|s| s.id() == id)
+    }
+
+    /// Inject one stress event, drawing any randomness from the simulation RNG. Returns a
+    /// description for the report.
+    fn inject(&self, sim: &mut SimState) -> String {
+        let center = vec2(sim.world.width, sim.world.height) * 0.5;
+        match self {
+            BenchmarkScenario::StormSurge => {
+                let radius = config::STORM_RADIUS * config::BENCHMARK_STORM_RADIUS_MULT;
+                let mut storm = Storm::new(center, radius, &mut sim.rng);
+                storm.strength = config::STORM_STRENGTH_MAX;
+                sim.environment.storm = Some(storm);
+                format!("storm of radius {radius:.0}")
+            }
+            BenchmarkScenario::CombatHeavy => {
+                let mut spawned = 0;
+                for _ in 0..config::BENCHMARK_MASS_SPAWN {
+                    let mut genome = Genome::random(&mut sim.rng);
+                    genome.set_body_gene(BODY_ATTACK, 1.0);
+                    let angle = sim.rng.gen_range(0.0..std::f32::consts::TAU);
+                    let dist = sim.rng.gen_range(0.0..config::BENCHMARK_MASS_SPAWN_RADIUS);
+                    if sim.inject_genome(&genome, center + Vec2::from_angle(angle) * dist).is_none() {
+                        break;
+                    }
+                    spawned += 1;
+                }
+                format!("{spawned} aggressive entities spawned")
+            }
+            BenchmarkScenario::FoodBloom => {
+                for _ in 0..config::BENCHMARK_FOOD_FLOOD {
+                    let pos = vec2(
+                        sim.rng.gen_range(0.0..sim.world.width),
+                        sim.rng.gen_range(0.0..sim.world.height),
+                    );
+                    sim.food.push(FoodItem::new(pos, FoodKind::Berry));
+                }
+                format!("{} food items dropped", config::BENCHMARK_FOOD_FLOOD)
+            }
+        }
+    }
+}
+
+/// Settings for a headless benchmark run (`--benchmark`).
+#[derive(Debug)]
+pub struct BenchmarkConfig {
+    /// Measured ticks, after BENCHMARK_WARMUP_TICKS unmeasured ones.
+    pub ticks: u64,
+    /// Percentiles reported for every metric, in [0, 100].
+    pub percentiles: Vec<f64>,
+    /// Histogram bucket upper bounds for tick times, in milliseconds.
+    pub tick_buckets_ms: Vec<f64>,
+    /// Stress events to inject during the measured window, if any.
+    pub scenario: Option<BenchmarkScenario>,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            ticks: config::BENCHMARK_TICKS,
+            percentiles: config::BENCHMARK_PERCENTILES.to_vec(),
+            tick_buckets_ms: config::BENCHMARK_TICK_BUCKETS_MS.to_vec(),
+            scenario: None,
+        }
+    }
+}
+
+pub const BENCHMARK_REPORT_PATH: &str = "benchmark_report.json";
+
+/// Time every simulation tick of `sim` and write the report to BENCHMARK_REPORT_PATH.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_benchmark(mut sim: SimState, bench: &BenchmarkConfig) -> Result<String, String> {
+    for _ in 0..config::BENCHMARK_WARMUP_TICKS {
+        sim.tick();
+    }
+
+    let mut tick_ms = MetricAggregator::new("tick_ms", &bench.tick_buckets_ms);
+    let mut event_tick_ms = MetricAggregator::new("event_tick_ms", &bench.tick_buckets_ms);
+    let population_buckets: Vec<f64> = (1..=8).map(|i| (config::MAX_ENTITY_COUNT * i / 8) as f64).collect();
+    let mut population = MetricAggregator::new("population", &population_buckets);
+    let fraction_buckets: Vec<f64> = (1..=8).map(|i| i as f64 / 8.0).collect();
+    let mut occupancy = MetricAggregator::new("arena_occupancy", &fraction_buckets);
+    let mut fragmentation = MetricAggregator::new("arena_fragmentation", &fraction_buckets);
+    let event_ticks: Vec<u64> = match bench.scenario {
+        Some(_) => config::BENCHMARK_EVENT_FRACTIONS.iter().map(|f| (bench.ticks as f64 * f) as u64).collect(),
+        None => Vec::new(),
+    };
+    let mut events: Vec<String> = Vec::new();
+    let start = Instant::now();
+    for tick in 0..bench.ticks {
+        let tick_start = Instant::now();
+        if let Some(scenario) = bench.scenario.filter(|_| event_ticks.contains(&tick)) {
+            let description = scenario.inject(&mut sim);
+            events.push(format!("    {{\"tick\": {tick}, \"event\": \"{description}\"}}"));
+        }
+        sim.tick();
+        let ms = tick_start.elapsed().as_secs_f64() * 1000.0;
+        tick_ms.record(ms);
+        if event_ticks.iter().any(|&t| (t..t + config::BENCHMARK_EVENT_WINDOW).contains(&tick)) {
+            event_tick_ms.record(ms);
+        }
+        population.record(sim.arena.count as f64);
+        let slots = sim.arena.occupancy();
+        occupancy.record(slots.occupancy() as f64);
+        fragmentation.record(slots.fragmentation() as f64);
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let mut measured = vec![&tick_ms, &population, &occupancy, &fragmentation];
+    if bench.scenario.is_some() {
+        measured.insert(1, &event_tick_ms);
+    }
+    let metrics: Vec<String> = measured
+        .iter()
+        .map(|m| format!("    \"{}\": {}", m.name, m.to_json(&bench.percentiles)))
+        .collect();
+    let scenario = bench.scenario.map_or("null".to_string(), |s| format!("\"{}\"", s.id()));
+    let json = format!(
+        "{{\n  \"seed\": {},\n  \"terrain_seed\": {},\n  \"preset\": \"{}\",\n  \"sim_hz\": {},\n  \"warmup_ticks\": {},\n  \"ticks\": {},\n  \"seconds\": {},\n  \"ticks_per_second\": {},\n  \"scenario\": {scenario},\n  \"events\": [{}],\n  \"metrics\": {{\n{}\n  }},\n  \"run_info\": {}\n}}\n",
+        sim.seed,
+        sim.environment.terrain_seed,
+        sim.environment.world_preset.id(),
+        json_number(sim.sim_hz() as f64),
+        config::BENCHMARK_WARMUP_TICKS,
+        bench.ticks,
+        json_number(elapsed),
+        json_number(bench.ticks as f64 / elapsed.max(1e-9)),
+        if events.is_empty() { String::new() } else { format!("\n{}\n  ", events.join(",\n")) },
+        metrics.join(",\n"),
+        sim.run_info.to_json()
+    );
+    storage::write(BENCHMARK_REPORT_PATH, json).map_err(|e| format!("Write error: {e}"))?;
+
+    let summary: Vec<String> = tick_ms
+        .percentiles(&bench.percentiles)
+        .iter()
+        .map(|(q, v)| format!("p{q} {v:.3}ms"))
+        .collect();
+    let stress = match bench.scenario {
+        Some(s) => format!(" ({} x{}, worst {:.3}ms)", s.id(), events.len(), tick_ms.max()),
+        None => String::new(),
+    };
+    Ok(format!(
+        "{} ticks in {elapsed:.1}s{stress}, mean {:.3}ms, {} → {BENCHMARK_REPORT_PATH}",
+        bench.ticks,
+        tick_ms.mean(),
+        summary.join(", ")
+    ))
+}
@@ -3,82 +3,108 @@ use ::rand::Rng;
 
 use crate::brain::BrainStorage;
 use crate::config;
-use crate::entity::{Entity, EntityArena, EntityId};
+use crate::energy_audit::EnergyLedger;
+use crate::entity::{Entity, EntityArena, EntityEventKind, EntityId};
 use crate::genome::Genome;
 use crate::world::World;
 
 /// Pending birth record (to avoid borrow conflicts during iteration).
-struct Birth {
+pub struct Birth {
     parent_idx: usize,
     child_pos: Vec2,
     child_genome: Genome,
     parent_generation_depth: u32,
     parent_id: EntityId,
+    parent_uid: u64,
 }
 
-/// Check all entities for reproduction eligibility and spawn offspring.
-/// Returns positions of newly born entities.
-pub fn check_and_spawn(
-    arena: &mut EntityArena,
-    brains: &mut BrainStorage,
-    genomes: &mut Vec<Option<Genome>>,
+/// Check all entities for reproduction eligibility and draw their offspring's genomes and
+/// positions, up to MAX_ENTITY_COUNT. Reserve slots for the result before `spawn_births`.
+pub fn collect_births(
+    arena: &EntityArena,
+    brains: &BrainStorage,
+    genomes: &[Option<Genome>],
     world: &World,
     rng: &mut impl Rng,
-    tick: u64,
-) -> Vec<Vec2> {
-    let mut birth_positions = Vec::new();
-
+) -> Vec<Birth> {
+    let mut births: Vec<Birth> = Vec::new();
     if arena.count >= config::MAX_ENTITY_COUNT {
-        return birth_positions;
+        return births;
     }
 
-    // Collect birth events
-    let mut births: Vec<Birth> = Vec::new();
-
-    for (idx, entity) in arena.entities.iter().enumerate() {
-        if let Some(e) = entity {
-            if e.energy < config::REPRODUCTION_THRESHOLD {
-                continue;
-            }
-            if arena.count + births.len() >= config::MAX_ENTITY_COUNT {
-                break;
-            }
+    for (idx, e) in arena.iter_alive() {
+        // Fertility declines after the prime age: older entities need a larger
+        // energy surplus to reproduce and become infertile near DEATH_AGE.
+        let fertility = e.fertility();
+        if fertility <= 0.0 {
+            continue;
+        }
+        if e.energy < e.reproduction_threshold() {
+            continue;
+        }
+        if arena.count + births.len() >= config::MAX_ENTITY_COUNT {
+            break;
+        }
 
-            if let Some(ref genome) = genomes[idx] {
-                let child_genome = genome.mutate(rng);
-                let offset_angle = rng.gen_range(0.0..std::f32::consts::TAU);
-                let offset_dist = e.radius * 3.0;
-                let child_pos = world.wrap(e.pos + Vec2::from_angle(offset_angle) * offset_dist);
+        if let Some(ref genome) = genomes[idx] {
+            let mut inherited = genome.clone();
+            brains.write_back_learned(idx, &mut inherited);
+            let child_genome = inherited.mutate(rng);
+            let offset_angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let offset_dist = e.radius * 3.0;
+            let child_pos = world.wrap(e.pos + Vec2::from_angle(offset_angle) * offset_dist);
 
-                births.push(Birth {
-                    parent_idx: idx,
-                    child_pos,
-                    child_genome,
-                    parent_generation_depth: e.generation_depth,
-                    parent_id: EntityId {
-                        index: idx as u32,
-                        generation: arena.generations[idx],
-                    },
-                });
-            }
+            births.push(Birth {
+                parent_idx: idx,
+                child_pos,
+                child_genome,
+                parent_generation_depth: e.generation_depth,
+                parent_id: EntityId {
+                    index: idx as u32,
+                    generation: arena.generations[idx],
+                },
+                parent_uid: e.uid,
+            });
         }
     }
 
-    // Deduct energy from parents and spawn children
-    for birth in births {
-        if let Some(parent) = &mut arena.entities[birth.parent_idx] {
-            parent.energy -= config::REPRODUCTION_COST;
-            parent.offspring_count += 1;
-        }
+    births
+}
 
+/// Spawn the children collected by `collect_births`, charging each parent only once its child
+/// is in the arena. Returns positions of newly born entities.
+pub fn spawn_births(
+    births: Vec<Birth>,
+    arena: &mut EntityArena,
+    brains: &mut BrainStorage,
+    genomes: &mut Vec<Option<Genome>>,
+    rng: &mut impl Rng,
+    tick: u64,
+    ledger: &mut EnergyLedger,
+) -> Vec<Vec2> {
+    let mut birth_positions = Vec::new();
+    for birth in births {
         let mut child = Entity::new_from_genome_rng(&birth.child_genome, birth.child_pos, tick, rng);
         child.energy = config::INITIAL_ENTITY_ENERGY * config::OFFSPRING_ENERGY_FRACTION;
+        child.set_growth(0.0);
+        child.health = child.effective_max_health();
         child.generation_depth = birth.parent_generation_depth + 1;
         child.parent_id = Some(birth.parent_id);
+        child.parent_uid = Some(birth.parent_uid);
 
+        let child_energy = child.energy;
         if let Some(id) = arena.spawn(child) {
+            ledger.offspring += child_energy as f64;
+            let child_uid = arena.get(id).map_or(0, |c| c.uid);
+            if let Some(parent) = &mut arena.entities[birth.parent_idx] {
+                parent.energy -= config::REPRODUCTION_COST;
+                parent.offspring_count += 1;
+                ledger.actions += config::REPRODUCTION_COST as f64;
+                parent.log_event(EntityEventKind::Reproduced(child_uid));
+            }
             let slot = id.index as usize;
             brains.init_from_genome(slot, &birth.child_genome);
+            brains.inherit_state(slot, birth.parent_idx);
 
             // Ensure genomes vec is large enough
             if slot >= genomes.len() {
@@ -0,0 +1,336 @@
+// Run database: streams per-tick aggregates, births, deaths and periodic genome samples into
+// an SQLite file, so multi-hour runs can be queried (and compared across runs) with SQL.
+// Rows are buffered in memory and inserted through prepared statements of the bundled SQLite
+// library, one transaction per DB_FLUSH_INTERVAL ticks.
+
+use rusqlite::{params, Connection};
+
+use crate::config;
+use crate::entity::Entity;
+use crate::manifest;
+use crate::simulation::SimState;
+
+const SCHEMA: &str = "\
+PRAGMA journal_mode = WAL;
+PRAGMA synchronous = NORMAL;
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY, started TEXT, version TEXT, seed INTEGER, terrain_seed INTEGER,
+    preset TEXT, sim_hz REAL, start_tick INTEGER, args TEXT
+);
+CREATE TABLE IF NOT EXISTS ticks (
+    run INTEGER, tick INTEGER, population INTEGER, food INTEGER, avg_energy REAL,
+    avg_generation REAL, max_generation INTEGER, species INTEGER, births INTEGER, deaths INTEGER,
+    PRIMARY KEY (run, tick)
+) WITHOUT ROWID;
+CREATE TABLE IF NOT EXISTS births (
+    run INTEGER, tick INTEGER, uid INTEGER, parent_uid INTEGER, generation INTEGER, x REAL, y REAL
+);
+CREATE TABLE IF NOT EXISTS deaths (
+    run INTEGER, tick INTEGER, uid INTEGER, age REAL, generation INTEGER, offspring INTEGER,
+    kills INTEGER, x REAL, y REAL
+);
+CREATE TABLE IF NOT EXISTS genomes (
+    run INTEGER, tick INTEGER, uid INTEGER, generation INTEGER, species INTEGER, genes BLOB
+);
+CREATE INDEX IF NOT EXISTS births_run_tick ON births (run, tick);
+CREATE INDEX IF NOT EXISTS deaths_run_tick ON deaths (run, tick);
+CREATE INDEX IF NOT EXISTS genomes_run_tick ON genomes (run, tick);
+";
+
+/// Last seen state of the entity in an arena slot, kept to detect births and deaths.
+#[derive(Clone, Copy)]
+struct Tracked {
+    uid: u64,
+    age: f32,
+    generation: u32,
+    offspring: u32,
+    kills: u32,
+    x: f32,
+    y: f32,
+}
+
+impl Tracked {
+    fn of(e: &Entity) -> Self {
+        Self {
+            uid: e.uid,
+            age: e.age,
+            generation: e.generation_depth,
+            offspring: e.offspring_count,
+            kills: e.kills,
+            x: e.pos.x,
+            y: e.pos.y,
+        }
+    }
+}
+
+/// A row waiting for the next flush. Rows follow the `Run` that opened their stretch of ticks.
+enum Row {
+    Run {
+        started: String,
+        seed: u64,
+        terrain_seed: u32,
+        preset: &'static str,
+        sim_hz: f32,
+        start_tick: u64,
+        args: String,
+    },
+    Tick {
+        tick: u64,
+        population: usize,
+        food: usize,
+        avg_energy: f32,
+        avg_generation: f32,
+        max_generation: u32,
+        species: usize,
+        births: u32,
+        deaths: u32,
+    },
+    Birth {
+        tick: u64,
+        uid: u64,
+        parent_uid: Option<u64>,
+        generation: u32,
+        x: f32,
+        y: f32,
+    },
+    Death {
+        tick: u64,
+        dead: Tracked,
+    },
+    Genome {
+        tick: u64,
+        uid: u64,
+        generation: u32,
+        species: Option<u32>,
+        /// Genes as little-endian f32s.
+        genes: Vec<u8>,
+    },
+}
+
+pub struct RunDatabase {
+    pub path: String,
+    conn: Connection,
+    /// Rows recorded since the last flush.
+    pending: Vec<Row>,
+    /// Id of the `runs` row the committed rows belong to.
+    run_id: Option<i64>,
+    /// Entity in each arena slot as of the last recorded tick.
+    slots: Vec<Option<Tracked>>,
+    /// Tick recorded last; a jump (after loading a save) starts a new run row.
+    last_tick: Option<u64>,
+    /// Ticks since the last flush.
+    unflushed: u64,
+}
+
+impl RunDatabase {
+    /// Open (or create) the database at `path` and create the tables if the file is new.
+    pub fn open(path: &str) -> Result<Self, String> {
+        let conn = Connection::open(path).map_err(|e| format!("could not open {path}: {e}"))?;
+        // Outside a transaction: the journal mode cannot change inside one
+        conn.execute_batch(SCHEMA).map_err(|e| format!("schema failed: {e}"))?;
+        Ok(Self {
+            path: path.to_string(),
+            conn,
+            pending: Vec::new(),
+            run_id: None,
+            slots: Vec::new(),
+            last_tick: None,
+            unflushed: 0,
+        })
+    }
+
+    /// Record the tick the simulation just finished. Flushes every DB_FLUSH_INTERVAL ticks.
+    pub fn record(&mut self, sim: &SimState) -> Result<(), String> {
+        if self.last_tick.is_none_or(|t| sim.tick_count != t + 1) {
+            self.begin_run(sim);
+        }
+        self.last_tick = Some(sim.tick_count);
+        let tick = sim.tick_count;
+
+        let (mut births, mut deaths) = (0u32, 0u32);
+        self.slots.resize(sim.arena.entities.len().max(self.slots.len()), None);
+        for (idx, slot) in sim.arena.entities.iter().enumerate() {
+            let current = slot.as_ref().filter(|e| e.alive);
+            let previous = self.slots[idx];
+            if let Some(prev) = previous.filter(|p| current.is_none_or(|e| e.uid != p.uid)) {
+                deaths += 1;
+                self.pending.push(Row::Death { tick, dead: prev });
+            }
+            self.slots[idx] = current.map(|e| {
+                if previous.is_none_or(|p| p.uid != e.uid) {
+                    births += 1;
+                    self.pending.push(Row::Birth {
+                        tick,
+                        uid: e.uid,
+                        parent_uid: e.parent_uid,
+                        generation: e.generation_depth,
+                        x: e.pos.x,
+                        y: e.pos.y,
+                    });
+                }
+                Tracked::of(e)
+            });
+        }
+
+        let alive = sim.arena.count.max(1) as f32;
+        let (energy, generation) = sim
+            .arena
+            .iter_alive()
+            .fold((0.0f32, 0.0f32), |(en, gen), (_, e)| (en + e.energy, gen + e.generation_depth as f32));
+        self.pending.push(Row::Tick {
+            tick,
+            population: sim.arena.count,
+            food: sim.food.len(),
+            avg_energy: energy / alive,
+            avg_generation: generation / alive,
+            max_generation: sim.arena.deepest_generation(),
+            species: sim.species.species.len(),
+            births,
+            deaths,
+        });
+
+        if tick.is_multiple_of(config::DB_GENOME_SAMPLE_INTERVAL) {
+            self.sample_genomes(sim);
+        }
+
+        self.unflushed += 1;
+        if self.unflushed >= config::DB_FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Add a run row for a new continuous stretch of ticks and take the current population
+    /// as already born, so it is not logged as a burst of births.
+    fn begin_run(&mut self, sim: &SimState) {
+        let started = manifest::unix_now();
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        self.pending.push(Row::Run {
+            started: manifest::utc_timestamp(started),
+            seed: sim.seed,
+            terrain_seed: sim.environment.terrain_seed,
+            preset: sim.environment.world_preset.id(),
+            sim_hz: sim.sim_hz(),
+            start_tick: sim.tick_count,
+            args: args.join(" "),
+        });
+        self.slots.clear();
+        self.slots.resize(sim.arena.entities.len(), None);
+        for (idx, e) in sim.arena.iter_alive() {
+            self.slots[idx] = Some(Tracked::of(e));
+        }
+    }
+
+    /// Store the genomes of up to DB_GENOME_SAMPLE_SIZE living entities, spread evenly over
+    /// the arena, as little-endian f32 blobs.
+    fn sample_genomes(&mut self, sim: &SimState) {
+        let alive: Vec<usize> = sim.arena.iter_alive().map(|(idx, _)| idx).collect();
+        let step = alive.len().div_ceil(config::DB_GENOME_SAMPLE_SIZE).max(1);
+        for &idx in alive.iter().step_by(step) {
+            let (Some(e), Some(Some(genome))) = (sim.arena.get_by_index(idx), sim.genomes.get(idx)) else {
+                continue;
+            };
+            self.pending.push(Row::Genome {
+                tick: sim.tick_count,
+                uid: e.uid,
+                generation: e.generation_depth,
+                species: sim.species.species_of(idx),
+                genes: genome.genes.iter().flat_map(|g| g.to_le_bytes()).collect(),
+            });
+        }
+    }
+
+    /// Write the pending rows in one transaction. If any insert fails, the whole batch is
+    /// rolled back and SQLite's error is returned.
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.unflushed = 0;
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let rows = std::mem::take(&mut self.pending);
+        let tx = self.conn.transaction().map_err(|e| format!("could not begin a transaction: {e}"))?;
+        let mut run = self.run_id;
+        for row in &rows {
+            let result = match row {
+                Row::Run { started, seed, terrain_seed, preset, sim_hz, start_tick, args } => tx
+                    .prepare_cached(
+                        "INSERT INTO runs (started, version, seed, terrain_seed, preset, sim_hz, start_tick, args) \
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    )
+                    .and_then(|mut insert| {
+                        let version = env!("CARGO_PKG_VERSION");
+                        insert.execute(params![started, version, seed, terrain_seed, preset, finite(*sim_hz), start_tick, args])
+                    })
+                    .map(|_| run = Some(tx.last_insert_rowid())),
+                Row::Tick {
+                    tick,
+                    population,
+                    food,
+                    avg_energy,
+                    avg_generation,
+                    max_generation,
+                    species,
+                    births,
+                    deaths,
+                } => tx
+                    .prepare_cached("INSERT OR REPLACE INTO ticks VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)")
+                    .and_then(|mut insert| {
+                        insert.execute(params![
+                            run,
+                            tick,
+                            population,
+                            food,
+                            finite(*avg_energy),
+                            finite(*avg_generation),
+                            max_generation,
+                            species,
+                            births,
+                            deaths
+                        ])
+                    })
+                    .map(|_| ()),
+                Row::Birth { tick, uid, parent_uid, generation, x, y } => tx
+                    .prepare_cached("INSERT INTO births VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)")
+                    .and_then(|mut insert| {
+                        insert.execute(params![run, tick, uid, parent_uid, generation, finite(*x), finite(*y)])
+                    })
+                    .map(|_| ()),
+                Row::Death { tick, dead } => tx
+                    .prepare_cached("INSERT INTO deaths VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)")
+                    .and_then(|mut insert| {
+                        insert.execute(params![
+                            run,
+                            tick,
+                            dead.uid,
+                            finite(dead.age),
+                            dead.generation,
+                            dead.offspring,
+                            dead.kills,
+                            finite(dead.x),
+                            finite(dead.y)
+                        ])
+                    })
+                    .map(|_| ()),
+                Row::Genome { tick, uid, generation, species, genes } => tx
+                    .prepare_cached("INSERT INTO genomes VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
+                    .and_then(|mut insert| insert.execute(params![run, tick, uid, generation, species, genes.as_slice()]))
+                    .map(|_| ()),
+            };
+            result.map_err(|e| format!("insert failed: {e}"))?;
+        }
+        tx.commit().map_err(|e| format!("commit failed: {e}"))?;
+        self.run_id = run;
+        Ok(())
+    }
+
+    /// Flush the remaining rows and close the database.
+    pub fn finish(mut self) -> Result<(), String> {
+        self.flush()?;
+        self.conn.close().map_err(|(_, e)| format!("close failed: {e}"))
+    }
+}
+
+/// A float for a REAL column; NaN and infinities are stored as NULL.
+fn finite(v: f32) -> Option<f64> {
+    v.is_finite().then_some(v as f64)
+}
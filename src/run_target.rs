@@ -0,0 +1,123 @@
+// Run-until targets: fast-forward the simulation until a tick, a generation depth or the next
+// notice of some kind (a storm, an extinction, a generation milestone), then pause, so long
+// stretches of evolution can be skipped without watching the clock.
+
+use crate::config;
+use crate::journal::InterventionKind;
+use crate::notifications::NoticeKind;
+use crate::simulation::SimState;
+
+/// Where a run-until stops.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunTarget {
+    Tick(u64),
+    /// The deepest living lineage reaches this generation.
+    Generation(u32),
+    /// The simulation posts a notice of this kind.
+    Event(NoticeKind),
+}
+
+impl RunTarget {
+    /// Notice kinds the simulation posts itself (saves come from the app).
+    pub const EVENTS: [NoticeKind; 3] = [NoticeKind::Milestone, NoticeKind::Extinction, NoticeKind::Storm];
+
+    /// Parse `tick:N`, `generation:N` or `event:milestone|extinction|storm`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (kind, value) = spec.split_once(':').ok_or(format!("Invalid run-until target: {spec}"))?;
+        match kind.trim() {
+            "tick" => value.trim().parse().map(RunTarget::Tick).map_err(|_| format!("Invalid tick: {value}")),
+            "generation" | "gen" => {
+                value.trim().parse().map(RunTarget::Generation).map_err(|_| format!("Invalid generation: {value}"))
+            }
+            "event" => Self::EVENTS
+                .into_iter()
+                .find(|k| event_id(*k) == value.trim())
+                .map(RunTarget::Event)
+                .ok_or(format!("Unknown event: {value} (milestone, extinction or storm)")),
+            other => Err(format!("Unknown run-until target: {other} (tick, generation or event)")),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            RunTarget::Tick(tick) => format!("tick {tick}"),
+            RunTarget::Generation(generation) => format!("generation {generation}"),
+            RunTarget::Event(kind) => format!("next {}", event_id(*kind)),
+        }
+    }
+}
+
+pub fn event_id(kind: NoticeKind) -> &'static str {
+    match kind {
+        NoticeKind::Milestone => "milestone",
+        NoticeKind::Extinction => "extinction",
+        NoticeKind::Storm => "storm",
+        NoticeKind::Save => "save",
+    }
+}
+
+/// A run-until in progress.
+#[derive(Clone, Copy, Debug)]
+pub struct RunUntil {
+    pub target: RunTarget,
+    start_tick: u64,
+    start_generation: u32,
+    /// Speed to return to once the target is reached.
+    resume_speed: f32,
+}
+
+impl RunUntil {
+    /// Unpause and fast-forward at RUN_UNTIL_SPEED toward `target`.
+    pub fn start(sim: &mut SimState, target: RunTarget) -> Self {
+        let run = Self {
+            target,
+            start_tick: sim.tick_count,
+            start_generation: sim.arena.deepest_generation(),
+            resume_speed: if sim.speed_multiplier < config::FAST_FORWARD_MIN_SPEED { sim.speed_multiplier } else { 1.0 },
+        };
+        let detail = format!("run until {} at {}x", target.describe(), config::RUN_UNTIL_SPEED);
+        sim.journal.record(sim.tick_count, InterventionKind::Speed, detail);
+        sim.paused = false;
+        sim.speed_multiplier = config::RUN_UNTIL_SPEED;
+        eprintln!("[GENESIS] Running until {} from tick {}", target.describe(), sim.tick_count);
+        run
+    }
+
+    /// Whether the tick the simulation just finished meets the target.
+    pub fn reached(&self, sim: &SimState) -> bool {
+        match self.target {
+            RunTarget::Tick(tick) => sim.tick_count >= tick,
+            RunTarget::Generation(generation) => sim.arena.deepest_generation() >= generation,
+            RunTarget::Event(kind) => sim.notices.entries.iter().any(|n| n.kind == kind && n.tick > self.start_tick),
+        }
+    }
+
+    /// Fraction of the way to a tick or generation target; None for events.
+    pub fn progress(&self, sim: &SimState) -> Option<f32> {
+        let fraction = |from: f32, now: f32, to: f32| ((now - from) / (to - from).max(1.0)).clamp(0.0, 1.0);
+        match self.target {
+            RunTarget::Tick(tick) => Some(fraction(self.start_tick as f32, sim.tick_count as f32, tick as f32)),
+            RunTarget::Generation(generation) => Some(fraction(
+                self.start_generation as f32,
+                sim.arena.deepest_generation() as f32,
+                generation as f32,
+            )),
+            RunTarget::Event(_) => None,
+        }
+    }
+
+    /// Give up on the target and drop back to a watchable speed without pausing.
+    pub fn cancel(self, sim: &mut SimState) {
+        sim.speed_multiplier = self.resume_speed;
+        eprintln!("[GENESIS] Run until {} cancelled at tick {}", self.target.describe(), sim.tick_count);
+    }
+
+    /// Pause at the target and drop back to a watchable speed.
+    pub fn finish(self, sim: &mut SimState) {
+        sim.paused = true;
+        sim.speed_multiplier = self.resume_speed;
+        let message = format!("Reached {} at tick {}", self.target.describe(), sim.tick_count);
+        eprintln!("[GENESIS] {message}");
+        sim.notices.post(NoticeKind::Milestone, sim.tick_count, message, None);
+    }
+}
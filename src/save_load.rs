@@ -1,15 +1,42 @@
+use std::collections::VecDeque;
+
 use macroquad::prelude::*;
 use serde::{Serialize, Deserialize};
 
+use crate::audio::SoundEvents;
 use crate::brain::BrainStorage;
-use crate::combat::MeatItem;
+use crate::camera::{CameraBookmark, CameraBookmarks, CameraController, NUM_BOOKMARKS};
+use crate::care::JuvenileCounts;
+use crate::coloring::ColorMode;
+use crate::combat::{MeatItem, Projectile};
 use crate::config;
+use crate::energy_audit::EnergyAudit;
 use crate::entity::{Entity, EntityArena, EntityId};
-use crate::environment::{EnvironmentState, Season, Storm, TerrainType};
-use crate::genome::{Genome, N};
+use crate::environment::{EnvironmentState, Portal, Season, Storm, TerrainType, ToxicZone, Wall, WorldPreset};
+use crate::genome::{Genome, GENOME_LAYOUT_VERSION, N, TOTAL_GENOME_SIZE};
+use crate::hall_of_fame::HallOfFame;
+use crate::heatmap::Heatmaps;
+use crate::journal::{Intervention, InterventionJournal};
+use crate::lineage::{LineageLog, LineageRecord};
+use crate::manifest::RunInfo;
+use crate::notifications::{self, NoticeLog};
 use crate::particles::ParticleSystem;
+use crate::plants::{FoodKind, FoodModel, PlantGrid};
+use crate::sensory::RayMode;
+use crate::shelter::Shelter;
 use crate::signals::{PheromoneGrid, SignalState};
-use crate::simulation::{FoodItem, SimState};
+use crate::species::SpeciesTracker;
+use crate::storage;
+use crate::weather::RainCloud;
+use crate::simulation::{FoodItem, SharingTotals, SimState};
+
+/// First bytes of every save file, ahead of the format and genome layout versions.
+const SAVE_MAGIC: &[u8; 8] = b"GENESIS\0";
+/// Version of the serialized SaveState. bincode is positional, so bump this whenever a field is
+/// added, removed or reordered in SaveState or any type it contains; older saves are then
+/// refused with a clear error rather than misread.
+const SAVE_FORMAT_VERSION: u32 = 1;
+const SAVE_HEADER_LEN: usize = SAVE_MAGIC.len() + 8;
 
 // Serde-friendly wrapper types for macroquad primitives
 
@@ -54,11 +81,14 @@ impl From<SerdColor> for Color {
 // Serializable entity
 #[derive(Serialize, Deserialize)]
 struct SerdEntity {
+    uid: u64,
     pos: SerdVec2,
     prev_pos: SerdVec2,
     velocity: SerdVec2,
     heading: f32,
     radius: f32,
+    adult_radius: f32,
+    growth: f32,
     color: SerdColor,
     energy: f32,
     health: f32,
@@ -70,8 +100,28 @@ struct SerdEntity {
     generation_depth: u32,
     parent_idx: Option<u32>,
     parent_gen: Option<u32>,
+    parent_uid: Option<u64>,
     offspring_count: u32,
+    kills: u32,
+    energy_donated: f32,
+    energy_received: f32,
+    name: Option<String>,
+    tag: Option<String>,
     tick_born: u64,
+    aquatic: f32,
+    prime_age: f32,
+    care: f32,
+    attack_power: f32,
+    armor: f32,
+    ranged: f32,
+    taste: f32,
+    circadian: f32,
+    flocking: f32,
+    nepotism: f32,
+    toxin_resistance: f32,
+    storm_resistance: f32,
+    spit_cooldown: f32,
+    protection_ticks: u32,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -94,6 +144,8 @@ struct SerdBrain {
 struct SerdFood {
     pos: SerdVec2,
     energy: f32,
+    kind: FoodKind,
+    age: f32,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -103,12 +155,57 @@ struct SerdMeat {
     decay_timer: f32,
 }
 
+#[derive(Serialize, Deserialize)]
+struct SerdProjectile {
+    pos: SerdVec2,
+    velocity: SerdVec2,
+    damage: f32,
+    owner_slot: usize,
+    owner_uid: u64,
+    life: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerdShelter {
+    pos: SerdVec2,
+    radius: f32,
+    durability: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerdWall {
+    a: SerdVec2,
+    b: SerdVec2,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerdToxicZone {
+    pos: SerdVec2,
+    radius: f32,
+    intensity: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerdPortal {
+    a: SerdVec2,
+    b: SerdVec2,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerdRainCloud {
+    center: SerdVec2,
+    radius: f32,
+    timer: f32,
+}
+
 #[derive(Serialize, Deserialize)]
 struct SerdStorm {
     center: SerdVec2,
     radius: f32,
     velocity: SerdVec2,
     timer: f32,
+    strength: f32,
+    eye: f32,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -143,6 +240,7 @@ pub struct SaveState {
     entities: Vec<Option<SerdEntity>>,
     generations: Vec<u32>,
     arena_count: usize,
+    next_uid: u64,
 
     // Brains (only active slots)
     active_brain_slots: Vec<usize>,
@@ -154,9 +252,15 @@ pub struct SaveState {
     // Food + meat
     food: Vec<SerdFood>,
     meat: Vec<SerdMeat>,
+    projectiles: Vec<SerdProjectile>,
+    shelters: Vec<SerdShelter>,
 
     // Pheromone grid
     pheromone_cells: Vec<f32>,
+    // Per-slot signal and call state carried over between ticks
+    signals: Vec<(SerdColor, f32)>,
+    calls: Vec<f32>,
+    fear: Vec<f32>,
 
     // Environment
     time_of_day: f32,
@@ -165,25 +269,74 @@ pub struct SaveState {
     season_progress: f32,
     storm: Option<SerdStorm>,
     storm_cooldown: f32,
+    walls: Vec<SerdWall>,
+    toxic_zones: Vec<SerdToxicZone>,
+    portals: Vec<SerdPortal>,
+    wind_gust: SerdVec2,
+    wind_gust_target: SerdVec2,
+    wind_gust_timer: f32,
+    rain_clouds: Vec<SerdRainCloud>,
+    rain_cooldown: f32,
+    moisture: Vec<f32>,
+    nutrients: Vec<f32>,
     terrain_cells: Vec<u8>, // stored as u8 indices
+    world_preset: String,
+    terrain_seed: u32,
 
     // RNG state
     rng_seed_state: Vec<u8>,
+    seed: u64,
 
     // Sim state
     tick_count: u64,
+    food_spawn_accumulator: f32,
+    food_model: String,
+    food_spoilage: bool,
+    plant_cover: Vec<f32>,
     speed_multiplier: f32,
+    juveniles_matured: u64,
+    juveniles_died: u64,
+    shared_to_kin: f64,
+    shared_to_strangers: f64,
+    dt: f32,
+    // User interventions so far
+    journal: Vec<Intervention>,
+    // Family records, oldest first
+    lineage: Vec<LineageRecord>,
+    // Provenance of the run that created the world
+    run_info: RunInfo,
+
+    // Camera bookmarks (target, zoom) per slot, and the UID of the followed entity
+    camera_bookmarks: Vec<Option<(SerdVec2, f32)>>,
+    followed_uid: Option<u64>,
+}
+
+/// Camera view state stored alongside the simulation.
+pub struct SavedView {
+    pub bookmarks: CameraBookmarks,
+    pub followed_uid: Option<u64>,
+}
+
+impl SavedView {
+    /// Restore bookmarks and resume following the saved entity, if it is still alive.
+    pub fn apply(&self, camera: &mut CameraController, arena: &EntityArena) {
+        camera.bookmarks = self.bookmarks;
+        camera.following = self.followed_uid.and_then(|uid| arena.find_by_uid(uid));
+    }
 }
 
 impl SaveState {
-    pub fn from_sim(sim: &SimState) -> Self {
+    pub fn from_sim(sim: &SimState, camera: &CameraController) -> Self {
         let entities: Vec<Option<SerdEntity>> = sim.arena.entities.iter().map(|slot| {
             slot.as_ref().map(|e| SerdEntity {
+                uid: e.uid,
                 pos: e.pos.into(),
                 prev_pos: e.prev_pos.into(),
                 velocity: e.velocity.into(),
                 heading: e.heading,
                 radius: e.radius,
+                adult_radius: e.adult_radius,
+                growth: e.growth,
                 color: e.color.into(),
                 energy: e.energy,
                 health: e.health,
@@ -195,8 +348,28 @@ impl SaveState {
                 generation_depth: e.generation_depth,
                 parent_idx: e.parent_id.map(|id| id.index),
                 parent_gen: e.parent_id.map(|id| id.generation),
+                parent_uid: e.parent_uid,
                 offspring_count: e.offspring_count,
+                kills: e.kills,
+                energy_donated: e.energy_donated,
+                energy_received: e.energy_received,
+                name: e.name.clone(),
+                tag: e.tag.clone(),
                 tick_born: e.tick_born,
+                aquatic: e.aquatic,
+                prime_age: e.prime_age,
+                care: e.care,
+                attack_power: e.attack_power,
+                armor: e.armor,
+                ranged: e.ranged,
+                taste: e.taste,
+                circadian: e.circadian,
+                flocking: e.flocking,
+                nepotism: e.nepotism,
+                toxin_resistance: e.toxin_resistance,
+                storm_resistance: e.storm_resistance,
+                spit_cooldown: e.spit_cooldown,
+                protection_ticks: e.protection_ticks,
             })
         }).collect();
 
@@ -222,6 +395,8 @@ impl SaveState {
         let food: Vec<SerdFood> = sim.food.iter().map(|f| SerdFood {
             pos: f.pos.into(),
             energy: f.energy,
+            kind: f.kind,
+            age: f.age,
         }).collect();
 
         let meat: Vec<SerdMeat> = sim.meat.iter().map(|m| SerdMeat {
@@ -230,6 +405,21 @@ impl SaveState {
             decay_timer: m.decay_timer,
         }).collect();
 
+        let projectiles: Vec<SerdProjectile> = sim.projectiles.iter().map(|p| SerdProjectile {
+            pos: p.pos.into(),
+            velocity: p.velocity.into(),
+            damage: p.damage,
+            owner_slot: p.owner_slot,
+            owner_uid: p.owner_uid,
+            life: p.life,
+        }).collect();
+
+        let shelters: Vec<SerdShelter> = sim.shelters.iter().map(|s| SerdShelter {
+            pos: s.pos.into(),
+            radius: s.radius,
+            durability: s.durability,
+        }).collect();
+
         let terrain_cells: Vec<u8> = sim.environment.terrain.cells.iter().map(|t| match t {
             TerrainType::Plains => 0,
             TerrainType::Forest => 1,
@@ -243,6 +433,8 @@ impl SaveState {
             radius: s.radius,
             velocity: s.velocity.into(),
             timer: s.timer,
+            strength: s.strength,
+            eye: s.eye,
         });
 
         // Serialize RNG state via bincode
@@ -252,22 +444,88 @@ impl SaveState {
             entities,
             generations: sim.arena.generations.clone(),
             arena_count: sim.arena.count,
+            next_uid: sim.arena.next_uid,
             active_brain_slots,
             brains,
             genomes,
             food,
             meat,
+            projectiles,
+            shelters,
             pheromone_cells: sim.pheromone_grid.cells.clone(),
+            signals: sim.signals.iter().map(|s| (s.color.into(), s.intensity)).collect(),
+            calls: sim.calls.clone(),
+            fear: sim.fear.clone(),
             time_of_day: sim.environment.time_of_day,
             day_progress: sim.environment.day_progress,
             season: sim.environment.season.into(),
             season_progress: sim.environment.season_progress,
             storm,
             storm_cooldown: sim.environment.storm_cooldown,
+            walls: sim.environment.walls.iter().map(|w| SerdWall {
+                a: w.a.into(),
+                b: w.b.into(),
+            }).collect(),
+            toxic_zones: sim.environment.toxic_zones.iter().map(|z| SerdToxicZone {
+                pos: z.pos.into(),
+                radius: z.radius,
+                intensity: z.intensity,
+            }).collect(),
+            portals: sim.environment.portals.iter().map(|p| SerdPortal {
+                a: p.a.into(),
+                b: p.b.into(),
+            }).collect(),
+            wind_gust: sim.environment.weather.gust.into(),
+            wind_gust_target: sim.environment.weather.gust_target.into(),
+            wind_gust_timer: sim.environment.weather.gust_timer,
+            rain_clouds: sim.environment.weather.clouds.iter().map(|c| SerdRainCloud {
+                center: c.center.into(),
+                radius: c.radius,
+                timer: c.timer,
+            }).collect(),
+            rain_cooldown: sim.environment.weather.rain_cooldown,
+            moisture: sim.environment.weather.moisture.clone(),
+            nutrients: sim.environment.nutrients.cells.clone(),
             terrain_cells,
+            world_preset: sim.environment.world_preset.id().to_string(),
+            terrain_seed: sim.environment.terrain_seed,
+            seed: sim.seed,
+            food_spawn_accumulator: sim.food_spawner.accumulator,
+            food_spoilage: sim.food_spoilage,
+            food_model: sim.plants.model.id().to_string(),
+            plant_cover: sim.plants.cover.clone(),
             rng_seed_state,
             tick_count: sim.tick_count,
             speed_multiplier: sim.speed_multiplier,
+            juveniles_matured: sim.juveniles.matured,
+            juveniles_died: sim.juveniles.died,
+            shared_to_kin: sim.sharing.to_kin,
+            shared_to_strangers: sim.sharing.to_strangers,
+            dt: sim.dt,
+            journal: sim.journal.entries.clone(),
+            lineage: sim.lineage.records(),
+            run_info: sim.run_info.clone(),
+            camera_bookmarks: camera
+                .bookmarks
+                .iter()
+                .map(|b| b.map(|b| (b.target.into(), b.zoom)))
+                .collect(),
+            followed_uid: camera.following.and_then(|id| sim.arena.get(id)).map(|e| e.uid),
+        }
+    }
+
+    /// Camera view (bookmarks and followed entity) stored alongside the simulation.
+    pub fn view(&self) -> SavedView {
+        let mut bookmarks: CameraBookmarks = [None; NUM_BOOKMARKS];
+        for (slot, b) in self.camera_bookmarks.iter().enumerate().take(NUM_BOOKMARKS) {
+            bookmarks[slot] = b.as_ref().map(|(target, zoom)| CameraBookmark {
+                target: target.clone().into(),
+                zoom: *zoom,
+            });
+        }
+        SavedView {
+            bookmarks,
+            followed_uid: self.followed_uid,
         }
     }
 
@@ -288,11 +546,14 @@ impl SaveState {
                     _ => None,
                 };
                 Entity {
+                    uid: e.uid,
                     pos: e.pos.clone().into(),
                     prev_pos: e.prev_pos.clone().into(),
                     velocity: e.velocity.clone().into(),
                     heading: e.heading,
                     radius: e.radius,
+                    adult_radius: e.adult_radius,
+                    growth: e.growth,
                     color: e.color.clone().into(),
                     energy: e.energy,
                     health: e.health,
@@ -304,26 +565,37 @@ impl SaveState {
                     metabolic_rate: e.metabolic_rate,
                     generation_depth: e.generation_depth,
                     parent_id,
+                    parent_uid: e.parent_uid,
                     offspring_count: e.offspring_count,
+                    kills: e.kills,
+                    energy_donated: e.energy_donated,
+                    energy_received: e.energy_received,
+                    name: e.name.clone(),
+                    tag: e.tag.clone(),
                     tick_born: e.tick_born,
+                    aquatic: e.aquatic,
+                    prime_age: e.prime_age,
+                    care: e.care,
+                    attack_power: e.attack_power,
+                    armor: e.armor,
+                    ranged: e.ranged,
+                    taste: e.taste,
+                    circadian: e.circadian,
+                    flocking: e.flocking,
+                    nepotism: e.nepotism,
+                    toxin_resistance: e.toxin_resistance,
+                    storm_resistance: e.storm_resistance,
+                    spit_cooldown: e.spit_cooldown,
+                    sheltered: false,
+                    protection_ticks: e.protection_ticks,
+                    in_storm: false,
+                    events: VecDeque::new(),
                 }
             })
         }).collect();
 
         let capacity = entities.len();
-        let mut free_list: Vec<u32> = Vec::new();
-        for (i, slot) in entities.iter().enumerate().rev() {
-            if slot.is_none() {
-                free_list.push(i as u32);
-            }
-        }
-
-        let arena = EntityArena {
-            entities,
-            generations: self.generations.clone(),
-            free_list,
-            count: self.arena_count,
-        };
+        let arena = EntityArena::from_slots(entities, self.generations.clone(), self.next_uid);
 
         // Restore brains
         let mut brains = BrainStorage::new(capacity);
@@ -339,15 +611,24 @@ impl SaveState {
             }
         }
 
-        // Restore genomes
-        let genomes: Vec<Option<Genome>> = self.genomes.iter().map(|g| {
-            g.as_ref().map(|genes| Genome { genes: genes.clone() })
-        }).collect();
+        // Restore genomes (the file header guarantees the current gene layout)
+        let genomes: Vec<Option<Genome>> = self
+            .genomes
+            .iter()
+            .map(|g| g.as_ref().map(|genes| Genome { genes: genes.clone() }))
+            .collect();
+        for (slot, genome) in genomes.iter().enumerate().take(capacity) {
+            if let Some(genome) = genome {
+                brains.learning_rates[slot] = genome.learning_rate();
+            }
+        }
 
         // Restore food + meat
         let food: Vec<FoodItem> = self.food.iter().map(|f| FoodItem {
             pos: f.pos.clone().into(),
             energy: f.energy,
+            kind: f.kind,
+            age: f.age,
         }).collect();
 
         let meat: Vec<MeatItem> = self.meat.iter().map(|m| MeatItem {
@@ -356,10 +637,27 @@ impl SaveState {
             decay_timer: m.decay_timer,
         }).collect();
 
+        let projectiles: Vec<Projectile> = self.projectiles.iter().map(|p| Projectile {
+            pos: p.pos.clone().into(),
+            velocity: p.velocity.clone().into(),
+            damage: p.damage,
+            owner_slot: p.owner_slot,
+            owner_uid: p.owner_uid,
+            life: p.life,
+        }).collect();
+
+        let shelters: Vec<Shelter> = self.shelters.iter().map(|s| Shelter {
+            pos: s.pos.clone().into(),
+            radius: s.radius,
+            durability: s.durability,
+        }).collect();
+
         // Restore pheromone grid
         let mut pheromone_grid = PheromoneGrid::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, 32.0);
         if self.pheromone_cells.len() == pheromone_grid.cells.len() {
             pheromone_grid.cells = self.pheromone_cells.clone();
+        } else {
+            eprintln!("[GENESIS] Load: pheromone grid size mismatch, starting with an empty grid");
         }
 
         // Restore terrain
@@ -377,59 +675,284 @@ impl SaveState {
         environment.season = self.season.clone().into();
         environment.season_progress = self.season_progress;
         environment.storm_cooldown = self.storm_cooldown;
+        environment.walls = self.walls.iter().map(|w| Wall {
+            a: w.a.clone().into(),
+            b: w.b.clone().into(),
+        }).collect();
+        environment.toxic_zones = self.toxic_zones.iter().map(|z| ToxicZone {
+            pos: z.pos.clone().into(),
+            radius: z.radius,
+            intensity: z.intensity,
+        }).collect();
+        environment.portals = self.portals.iter().map(|p| Portal {
+            a: p.a.clone().into(),
+            b: p.b.clone().into(),
+        }).collect();
         environment.storm = self.storm.as_ref().map(|s| Storm {
             center: s.center.clone().into(),
             radius: s.radius,
             velocity: s.velocity.clone().into(),
             timer: s.timer,
+            strength: s.strength,
+            eye: s.eye,
         });
 
+        let weather = &mut environment.weather;
+        weather.gust = self.wind_gust.clone().into();
+        weather.gust_target = self.wind_gust_target.clone().into();
+        weather.gust_timer = self.wind_gust_timer;
+        weather.clouds = self.rain_clouds.iter().map(|c| RainCloud {
+            center: c.center.clone().into(),
+            radius: c.radius,
+            timer: c.timer,
+        }).collect();
+        weather.rain_cooldown = self.rain_cooldown;
+        if self.moisture.len() == weather.moisture.len() {
+            weather.moisture = self.moisture.clone();
+        } else {
+            eprintln!("[GENESIS] Load: moisture grid size mismatch, starting dry");
+        }
+        if self.nutrients.len() == environment.nutrients.cells.len() {
+            environment.nutrients.cells = self.nutrients.clone();
+        } else {
+            eprintln!("[GENESIS] Load: nutrient grid size mismatch, starting without nutrients");
+        }
+
         if terrain_cells.len() == environment.terrain.cells.len() {
             environment.terrain.cells = terrain_cells;
+        } else {
+            eprintln!("[GENESIS] Load: terrain size mismatch, using generated terrain");
         }
+        environment.world_preset = WorldPreset::from_id(&self.world_preset).unwrap_or(WorldPreset::Default);
+        environment.terrain_seed = self.terrain_seed;
 
         // Restore RNG
-        let rng: ChaCha8Rng = bincode::deserialize(&self.rng_seed_state)
-            .unwrap_or_else(|_| ChaCha8Rng::seed_from_u64(42));
+        let rng: ChaCha8Rng = bincode::deserialize(&self.rng_seed_state).unwrap_or_else(|e| {
+            eprintln!("[GENESIS] Load: RNG state unreadable ({e}), reseeding; the run will not be reproducible");
+            ChaCha8Rng::seed_from_u64(self.seed)
+        });
 
         let spatial_hash = SpatialHash::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, config::SPATIAL_CELL_SIZE);
-        let signals = vec![SignalState::default(); capacity];
+        let mut signals = vec![SignalState::default(); capacity];
+        for (signal, (color, intensity)) in signals.iter_mut().zip(&self.signals) {
+            *signal = SignalState {
+                color: color.clone().into(),
+                intensity: *intensity,
+            };
+        }
+        let mut calls = self.calls.clone();
+        calls.resize(capacity, 0.0);
+        let mut fear = self.fear.clone();
+        fear.resize(capacity, 0.0);
+
+        let mut plants = PlantGrid::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, config::PLANT_CELL_SIZE);
+        plants.model = FoodModel::from_id(&self.food_model).unwrap_or(FoodModel::Patches);
+        if self.plant_cover.len() == plants.cover.len() {
+            plants.cover = self.plant_cover.clone();
+        } else {
+            eprintln!("[GENESIS] Load: plant grid size mismatch, starting bare");
+        }
 
-        SimState {
+        let mut sim = SimState {
             arena,
             brains,
             genomes,
             world,
             spatial_hash,
             food,
-            food_spawner: FoodSpawner::new(),
+            food_spawner: FoodSpawner {
+                accumulator: self.food_spawn_accumulator,
+            },
+            food_spoilage: self.food_spoilage,
+            plants,
             meat,
+            projectiles,
+            shelters,
             signals,
+            calls,
+            fear,
             pheromone_grid,
             combat_events: Vec::new(),
+            attack_intents: Vec::new(),
+            kill_feed: VecDeque::new(),
             particles: ParticleSystem::new(),
             environment,
             rng,
             tick_count: self.tick_count,
             paused: false,
             speed_multiplier: self.speed_multiplier,
-            show_rays: false,
+            ray_mode: RayMode::Off,
+            ray_targets: Vec::new(),
+            color_mode: ColorMode::Genome,
             last_rays: Vec::new(),
-        }
+            heatmaps: Heatmaps::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, config::HEATMAP_CELL_SIZE),
+            heatmap_layer: None,
+            heatmap_opacity: config::HEATMAP_OPACITY,
+            species: SpeciesTracker::new(),
+            hall_of_fame: HallOfFame::default(),
+            sound_events: SoundEvents::default(),
+            births_total: 0,
+            deaths_total: 0,
+            notices: NoticeLog::default(),
+            generation_milestone: 0,
+            deepest_generation: 0,
+            ceiling_announced: false,
+            energy_audit: EnergyAudit::default(),
+            held: None,
+            juveniles: JuvenileCounts {
+                matured: self.juveniles_matured,
+                died: self.juveniles_died,
+            },
+            sharing: SharingTotals {
+                to_kin: self.shared_to_kin,
+                to_strangers: self.shared_to_strangers,
+            },
+            journal: InterventionJournal {
+                entries: self.journal.clone(),
+            },
+            lineage: LineageLog::from_records(self.lineage.clone()),
+            run_info: self.run_info.clone(),
+            seed: self.seed,
+            dt: self.dt,
+        };
+        // Species are re-clustered from scratch (IDs are not preserved across loads)
+        sim.species.recluster(&sim.arena, &sim.genomes, sim.tick_count);
+        // Milestones already passed were announced in the run that was saved
+        sim.deepest_generation = sim.arena.deepest_generation();
+        sim.generation_milestone = notifications::generation_milestone(sim.deepest_generation);
+        sim
     }
 }
 
-/// Save the simulation state to a file.
-pub fn save_to_file(sim: &SimState, path: &str) -> Result<(), String> {
-    let state = SaveState::from_sim(sim);
-    let bytes = bincode::serialize(&state).map_err(|e| format!("Serialize error: {e}"))?;
-    std::fs::write(path, bytes).map_err(|e| format!("Write error: {e}"))?;
+/// Number of rotating autosave files kept (slot 0 is the newest).
+pub const AUTOSAVE_SLOTS: usize = 3;
+
+/// Marker file present while a session is running. Finding it at startup means the
+/// previous session did not exit cleanly.
+pub const SESSION_MARKER_PATH: &str = "genesis_session.lock";
+
+pub fn autosave_path(slot: usize) -> String {
+    format!("genesis_autosave_{slot}.bin")
+}
+
+/// Save the simulation state (and camera view) to a file. The data is written to a temporary
+/// file first and renamed into place, so a crash mid-write never leaves a truncated save.
+pub fn save_to_file(sim: &SimState, camera: &CameraController, path: &str) -> Result<(), String> {
+    let state = SaveState::from_sim(sim, camera);
+    let mut bytes = Vec::with_capacity(SAVE_HEADER_LEN);
+    bytes.extend_from_slice(SAVE_MAGIC);
+    bytes.extend_from_slice(&SAVE_FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&GENOME_LAYOUT_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut bytes, &state).map_err(|e| format!("Serialize error: {e}"))?;
+    let tmp = format!("{path}.tmp");
+    storage::write(&tmp, bytes).map_err(|e| format!("Write error: {e}"))?;
+    storage::rename(&tmp, path).map_err(|e| format!("Rename error: {e}"))?;
     Ok(())
 }
 
-/// Load simulation state from a file.
-pub fn load_from_file(path: &str) -> Result<SimState, String> {
-    let bytes = std::fs::read(path).map_err(|e| format!("Read error: {e}"))?;
-    let state: SaveState = bincode::deserialize(&bytes).map_err(|e| format!("Deserialize error: {e}"))?;
-    Ok(state.restore())
+/// Write a new autosave into slot 0, shifting older autosaves down and dropping the oldest.
+/// Returns the path written.
+pub fn rotate_autosave(sim: &SimState, camera: &CameraController) -> Result<String, String> {
+    // Write first so a failed save never costs an existing autosave
+    let tmp = format!("{}.new", autosave_path(0));
+    save_to_file(sim, camera, &tmp)?;
+    for slot in (0..AUTOSAVE_SLOTS - 1).rev() {
+        let from = autosave_path(slot);
+        if storage::exists(&from) {
+            storage::rename(&from, &autosave_path(slot + 1)).map_err(|e| format!("Rotate error: {e}"))?;
+        }
+    }
+    let path = autosave_path(0);
+    storage::rename(&tmp, &path).map_err(|e| format!("Rename error: {e}"))?;
+    Ok(path)
+}
+
+/// The newest autosave that loads successfully, skipping missing or corrupt slots.
+pub fn latest_valid_autosave() -> Option<(String, SimState, SavedView)> {
+    for slot in 0..AUTOSAVE_SLOTS {
+        let path = autosave_path(slot);
+        if !storage::exists(&path) {
+            continue;
+        }
+        match load_from_file(&path) {
+            Ok((sim, view)) => return Some((path, sim, view)),
+            Err(e) => eprintln!("[GENESIS] Skipping autosave {path}: {e}"),
+        }
+    }
+    None
+}
+
+/// Mark a session as running. Returns true if the previous session crashed
+/// (its marker was never removed).
+pub fn begin_session() -> bool {
+    let crashed = storage::exists(SESSION_MARKER_PATH);
+    if let Err(e) = storage::write(SESSION_MARKER_PATH, "running") {
+        eprintln!("[GENESIS] Could not write session marker: {e}");
+    }
+    crashed
+}
+
+/// Remove the session marker on a clean exit.
+pub fn end_session() {
+    if let Err(e) = storage::remove(SESSION_MARKER_PATH) {
+        eprintln!("[GENESIS] Could not remove session marker: {e}");
+    }
+}
+
+/// Load simulation state and camera view from a file.
+pub fn load_from_file(path: &str) -> Result<(SimState, SavedView), String> {
+    let bytes = storage::read(path).map_err(|e| format!("Read error: {e}"))?;
+    let body = check_save_header(&bytes)?;
+    let state: SaveState = bincode::deserialize(body).map_err(|e| format!("Deserialize error: {e}"))?;
+    Ok((state.restore(), state.view()))
+}
+
+/// Check the magic and versions at the start of a save, returning the serialized state after them.
+fn check_save_header(bytes: &[u8]) -> Result<&[u8], String> {
+    if bytes.len() < SAVE_HEADER_LEN || &bytes[..SAVE_MAGIC.len()] != SAVE_MAGIC {
+        return Err("Incompatible save version: the file predates versioned saves".to_string());
+    }
+    let version_at = |offset: usize| {
+        let field: [u8; 4] = bytes[offset..offset + 4].try_into().expect("4-byte slice");
+        u32::from_le_bytes(field)
+    };
+    let format = version_at(SAVE_MAGIC.len());
+    if format != SAVE_FORMAT_VERSION {
+        return Err(format!("Incompatible save version {format} (this build reads version {SAVE_FORMAT_VERSION})"));
+    }
+    let layout = version_at(SAVE_MAGIC.len() + 4);
+    if layout != GENOME_LAYOUT_VERSION {
+        return Err(format!(
+            "Incompatible save version: genome layout {layout} (this build uses layout {GENOME_LAYOUT_VERSION})"
+        ));
+    }
+    Ok(&bytes[SAVE_HEADER_LEN..])
+}
+
+/// Write the genomes of the given entities as CSV (one row per entity: uid, generation, name,
+/// tag, genes).
+/// Returns the number of rows written.
+pub fn export_genomes_csv(sim: &SimState, ids: &[EntityId], path: &str) -> Result<usize, String> {
+    let mut csv = format!("{}\nuid,generation,name,tag", sim.report_header());
+    for i in 0..TOTAL_GENOME_SIZE {
+        csv.push_str(&format!(",gene_{i}"));
+    }
+    csv.push('\n');
+
+    let mut rows = 0;
+    for id in ids {
+        let Some(entity) = sim.arena.get(*id) else { continue };
+        let Some(Some(genome)) = sim.genomes.get(id.index as usize) else { continue };
+        let name = entity.name.as_deref().unwrap_or("").replace([',', '\n'], " ");
+        let tag = entity.tag.as_deref().unwrap_or("").replace([',', '\n'], " ");
+        csv.push_str(&format!("{},{},{},{}", entity.uid, entity.generation_depth, name, tag));
+        for g in &genome.genes {
+            csv.push_str(&format!(",{g}"));
+        }
+        csv.push('\n');
+        rows += 1;
+    }
+
+    storage::write(path, csv).map_err(|e| format!("Write error: {e}"))?;
+    Ok(rows)
 }
@@ -0,0 +1,297 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use macroquad::prelude::*;
+use ::rand::Rng;
+use rhai::{CallFnOptions, Dynamic, Engine, Scope, AST};
+
+use crate::config;
+use crate::environment::{Portal, Season, Storm, ToxicZone, Wall};
+use crate::plants::FoodKind;
+use crate::simulation::{FoodItem, SimState};
+use crate::storage;
+
+/// An intervention requested by a scenario script, applied after the callback returns.
+#[derive(Clone, Debug)]
+enum Command {
+    SpawnFood { pos: Vec2, count: usize, radius: f32 },
+    TriggerStorm { pos: Vec2 },
+    AddWall(Wall),
+    AddToxicZone(ToxicZone),
+    AddPortal(Portal),
+    SetSeason(Season),
+    SetPaused(bool),
+}
+
+/// A script callback registered with `at(tick, "fn")` or `every(interval, "fn")`.
+struct Scheduled {
+    next_tick: u64,
+    /// Repeat interval in ticks; None for one-shot callbacks.
+    interval: Option<u64>,
+    callback: String,
+    done: bool,
+}
+
+/// Read-only view of the simulation exposed to scripts, refreshed before each callback.
+#[derive(Clone, Default)]
+struct View {
+    tick: u64,
+    population: usize,
+    food: usize,
+    avg_energy: f32,
+    avg_generation: f32,
+    species: usize,
+    season: &'static str,
+    storm_active: bool,
+}
+
+/// A loaded rhai scenario: schedules callbacks at chosen ticks and applies their interventions.
+pub struct Scenario {
+    pub path: String,
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    schedule: Rc<RefCell<Vec<Scheduled>>>,
+    commands: Rc<RefCell<Vec<Command>>>,
+    view: Rc<RefCell<View>>,
+}
+
+impl Scenario {
+    /// Compile a script and run its top level, which registers the callback schedule.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let source = storage::read_to_string(path).map_err(|e| format!("Read error: {e}"))?;
+
+        let schedule: Rc<RefCell<Vec<Scheduled>>> = Rc::new(RefCell::new(Vec::new()));
+        let commands: Rc<RefCell<Vec<Command>>> = Rc::new(RefCell::new(Vec::new()));
+        let view: Rc<RefCell<View>> = Rc::new(RefCell::new(View::default()));
+
+        let mut engine = Engine::new();
+        register_api(&mut engine, &schedule, &commands, &view);
+
+        let ast = engine.compile(&source).map_err(|e| format!("Compile error: {e}"))?;
+        let mut scope = Scope::new();
+        engine
+            .run_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| format!("Script error: {e}"))?;
+
+        let count = schedule.borrow().len();
+        eprintln!("[GENESIS] Scenario {path} loaded ({count} scheduled callbacks)");
+
+        Ok(Self {
+            path: path.to_string(),
+            engine,
+            ast,
+            scope,
+            schedule,
+            commands,
+            view,
+        })
+    }
+
+    /// Run callbacks due at the current tick and apply the interventions they request.
+    /// Callbacks registered while these run are first considered on the next tick, even if
+    /// they are already due, so one that keeps rescheduling itself cannot stall the tick.
+    pub fn tick(&mut self, sim: &mut SimState) {
+        let tick = sim.tick_count;
+        let due: Vec<(usize, String)> = self
+            .schedule
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| !s.done && s.next_tick <= tick)
+            .map(|(i, s)| (i, s.callback.clone()))
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+
+        *self.view.borrow_mut() = View::from_sim(sim);
+        // The top level already ran in `load`; re-running it would duplicate the schedule.
+        for (_, callback) in &due {
+            let result = self.engine.call_fn_with_options::<Dynamic>(
+                CallFnOptions::new().eval_ast(false),
+                &mut self.scope,
+                &self.ast,
+                callback,
+                (),
+            );
+            if let Err(e) = result {
+                eprintln!("[GENESIS] Scenario callback '{callback}' failed at tick {tick}: {e}");
+            }
+        }
+
+        let mut schedule = self.schedule.borrow_mut();
+        for &(i, _) in &due {
+            let s = &mut schedule[i];
+            match s.interval {
+                Some(interval) => s.next_tick = tick + interval,
+                None => s.done = true,
+            }
+        }
+        drop(schedule);
+
+        let commands: Vec<Command> = self.commands.borrow_mut().drain(..).collect();
+        for command in commands {
+            apply(sim, command);
+        }
+    }
+
+    /// Whether any callback is still scheduled to run.
+    pub fn is_finished(&self) -> bool {
+        self.schedule.borrow().iter().all(|s| s.done)
+    }
+}
+
+impl View {
+    fn from_sim(sim: &SimState) -> Self {
+        let mut total_energy = 0.0;
+        let mut total_gen = 0.0;
+        for (_idx, e) in sim.arena.iter_alive() {
+            total_energy += e.energy;
+            total_gen += e.generation_depth as f32;
+        }
+        let n = sim.arena.count.max(1) as f32;
+        Self {
+            tick: sim.tick_count,
+            population: sim.arena.count,
+            food: sim.food.len(),
+            avg_energy: total_energy / n,
+            avg_generation: total_gen / n,
+            species: sim.species.species.len(),
+            season: sim.environment.season.name(),
+            storm_active: sim.environment.storm.is_some(),
+        }
+    }
+}
+
+fn register_api(
+    engine: &mut Engine,
+    schedule: &Rc<RefCell<Vec<Scheduled>>>,
+    commands: &Rc<RefCell<Vec<Command>>>,
+    view: &Rc<RefCell<View>>,
+) {
+    // Scheduling
+    let s = schedule.clone();
+    engine.register_fn("at", move |tick: i64, callback: &str| {
+        s.borrow_mut().push(Scheduled {
+            next_tick: tick.max(0) as u64,
+            interval: None,
+            callback: callback.to_string(),
+            done: false,
+        });
+    });
+    let s = schedule.clone();
+    engine.register_fn("every", move |interval: i64, callback: &str| {
+        let interval = interval.max(1) as u64;
+        s.borrow_mut().push(Scheduled {
+            next_tick: interval,
+            interval: Some(interval),
+            callback: callback.to_string(),
+            done: false,
+        });
+    });
+
+    // Interventions
+    let c = commands.clone();
+    engine.register_fn("spawn_food", move |x: f64, y: f64, count: i64, radius: f64| {
+        c.borrow_mut().push(Command::SpawnFood {
+            pos: vec2(x as f32, y as f32),
+            count: count.clamp(0, config::CONTROL_FOOD_MAX as i64) as usize,
+            radius: radius as f32,
+        });
+    });
+    let c = commands.clone();
+    engine.register_fn("trigger_storm", move |x: f64, y: f64| {
+        c.borrow_mut().push(Command::TriggerStorm { pos: vec2(x as f32, y as f32) });
+    });
+    let c = commands.clone();
+    engine.register_fn("add_wall", move |x1: f64, y1: f64, x2: f64, y2: f64| {
+        c.borrow_mut().push(Command::AddWall(Wall {
+            a: vec2(x1 as f32, y1 as f32),
+            b: vec2(x2 as f32, y2 as f32),
+        }));
+    });
+    let c = commands.clone();
+    engine.register_fn("add_portal", move |x1: f64, y1: f64, x2: f64, y2: f64| {
+        c.borrow_mut().push(Command::AddPortal(Portal {
+            a: vec2(x1 as f32, y1 as f32),
+            b: vec2(x2 as f32, y2 as f32),
+        }));
+    });
+    let c = commands.clone();
+    engine.register_fn("add_toxic_zone", move |x: f64, y: f64, radius: f64| {
+        c.borrow_mut().push(Command::AddToxicZone(ToxicZone {
+            pos: vec2(x as f32, y as f32),
+            radius: radius as f32,
+            intensity: 1.0,
+        }));
+    });
+    let c = commands.clone();
+    engine.register_fn("add_toxic_zone", move |x: f64, y: f64, radius: f64, intensity: f64| {
+        c.borrow_mut().push(Command::AddToxicZone(ToxicZone {
+            pos: vec2(x as f32, y as f32),
+            radius: radius as f32,
+            intensity: intensity as f32,
+        }));
+    });
+    let c = commands.clone();
+    engine.register_fn("set_season", move |name: &str| -> Result<(), Box<rhai::EvalAltResult>> {
+        let season = match name.to_ascii_lowercase().as_str() {
+            "spring" => Season::Spring,
+            "summer" => Season::Summer,
+            "autumn" | "fall" => Season::Autumn,
+            "winter" => Season::Winter,
+            other => return Err(format!("Unknown season: {other}").into()),
+        };
+        c.borrow_mut().push(Command::SetSeason(season));
+        Ok(())
+    });
+    let c = commands.clone();
+    engine.register_fn("pause", move || c.borrow_mut().push(Command::SetPaused(true)));
+    engine.register_fn("log", |msg: &str| eprintln!("[SCENARIO] {msg}"));
+
+    // Simulation state
+    let v = view.clone();
+    engine.register_fn("tick", move || v.borrow().tick as i64);
+    let v = view.clone();
+    engine.register_fn("population", move || v.borrow().population as i64);
+    let v = view.clone();
+    engine.register_fn("food_count", move || v.borrow().food as i64);
+    let v = view.clone();
+    engine.register_fn("avg_energy", move || v.borrow().avg_energy as f64);
+    let v = view.clone();
+    engine.register_fn("avg_generation", move || v.borrow().avg_generation as f64);
+    let v = view.clone();
+    engine.register_fn("species_count", move || v.borrow().species as i64);
+    let v = view.clone();
+    engine.register_fn("season", move || v.borrow().season.to_string());
+    let v = view.clone();
+    engine.register_fn("storm_active", move || v.borrow().storm_active);
+    engine.register_fn("world_width", || config::WORLD_WIDTH as f64);
+    engine.register_fn("world_height", || config::WORLD_HEIGHT as f64);
+}
+
+fn apply(sim: &mut SimState, command: Command) {
+    match command {
+        Command::SpawnFood { pos, count, radius } => {
+            for _ in 0..count {
+                let angle = sim.rng.gen_range(0.0..std::f32::consts::TAU);
+                let dist = sim.rng.gen_range(0.0..radius.max(1.0));
+                let pos = sim.world.wrap(pos + Vec2::from_angle(angle) * dist);
+                sim.food.push(FoodItem::new(pos, FoodKind::Berry));
+            }
+        }
+        Command::TriggerStorm { pos } => {
+            sim.environment.storm = Some(Storm::new(sim.world.wrap(pos), config::STORM_RADIUS, &mut sim.rng));
+            sim.sound_events.storm_started = true;
+        }
+        Command::AddWall(wall) => sim.environment.walls.push(wall),
+        Command::AddToxicZone(zone) => sim.environment.toxic_zones.push(zone),
+        Command::AddPortal(portal) => sim.environment.portals.push(portal),
+        Command::SetSeason(season) => {
+            sim.environment.season = season;
+            sim.environment.season_progress = 0.0;
+        }
+        Command::SetPaused(paused) => sim.paused = paused,
+    }
+}
@@ -3,6 +3,7 @@ use macroquad::prelude::*;
 use crate::config;
 use crate::entity::EntityArena;
 use crate::environment::{EnvironmentState, TerrainType};
+use crate::shelter::Shelter;
 use crate::spatial_hash::SpatialHash;
 use crate::world::World;
 
@@ -13,6 +14,7 @@ pub enum HitType {
     Entity,
     Food,
     Wall,
+    Portal,
 }
 
 /// Result of a single raycast.
@@ -22,6 +24,50 @@ pub struct RayHit {
     pub hit_type: HitType,
 }
 
+/// Which entities' sensor rays are collected and drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RayMode {
+    #[default]
+    Off,
+    /// The followed entity and the multi-selection only.
+    Selected,
+    All,
+}
+
+impl RayMode {
+    pub const ALL: [RayMode; 3] = [RayMode::Off, RayMode::Selected, RayMode::All];
+
+    /// Identifier used in the preferences file.
+    pub fn id(&self) -> &'static str {
+        match self {
+            RayMode::Off => "off",
+            RayMode::Selected => "selected",
+            RayMode::All => "all",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<RayMode> {
+        RayMode::ALL.iter().copied().find(|m| m.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RayMode::Off => "Off",
+            RayMode::Selected => "Selected only",
+            RayMode::All => "All entities",
+        }
+    }
+
+    /// The mode after this one, for the toggle key.
+    pub fn next(&self) -> RayMode {
+        match self {
+            RayMode::Off => RayMode::Selected,
+            RayMode::Selected => RayMode::All,
+            RayMode::All => RayMode::Off,
+        }
+    }
+}
+
 /// Per-entity ray data for visualization.
 #[derive(Clone, Debug)]
 pub struct EntityRays {
@@ -30,30 +76,30 @@ pub struct EntityRays {
 
 /// Compute sensor inputs for all entities.
 /// Returns a Vec of sensor arrays, indexed by entity slot.
-/// Also returns ray data for visualization if requested.
+/// Also returns ray data for visualization for the slots flagged in `ray_mask` (empty = none).
 pub fn compute_all_sensors(
     arena: &EntityArena,
     food_positions: &[Vec2],
     spatial: &SpatialHash,
     world: &World,
     environment: &EnvironmentState,
-    collect_rays: bool,
+    shelters: &[Shelter],
+    ray_mask: &[bool],
 ) -> (Vec<[f32; config::BRAIN_SENSOR_NEURONS]>, Vec<Option<EntityRays>>) {
     let capacity = arena.entities.len();
+    let darkness = environment.darkness();
     let mut all_inputs = vec![[0.0f32; config::BRAIN_SENSOR_NEURONS]; capacity];
-    let mut all_rays: Vec<Option<EntityRays>> = if collect_rays {
+    let mut all_rays: Vec<Option<EntityRays>> = if !ray_mask.is_empty() {
         vec![None; capacity]
     } else {
         Vec::new()
     };
 
-    for (idx, entity) in arena.entities.iter().enumerate() {
-        let entity = match entity {
-            Some(e) => e,
-            None => continue,
-        };
+    for (idx, entity) in arena.iter_alive() {
 
-        let ray_length = config::SENSOR_RAY_LENGTH * entity.sensor_range;
+        let collect_rays = ray_mask.get(idx).copied().unwrap_or(false);
+        let circadian = 1.0 + config::CIRCADIAN_SENSOR_SWING * entity.circadian_activity(darkness);
+        let ray_length = config::SENSOR_RAY_LENGTH * entity.effective_sensor_range() * circadian;
         let num_rays = config::NUM_SENSOR_RAYS;
         let arc = config::SENSOR_ARC;
         let step_angle = arc / (num_rays - 1).max(1) as f32;
@@ -72,7 +118,7 @@ pub fn compute_all_sensors(
             let angle = start_angle + step_angle * ray_i as f32;
             let dir = Vec2::from_angle(angle);
 
-            let hit = raycast(
+            let mut hit = raycast(
                 entity.pos,
                 dir,
                 ray_length,
@@ -83,6 +129,31 @@ pub fn compute_all_sensors(
                 world,
             );
 
+            // User-placed walls occlude anything behind them
+            for wall in &environment.walls {
+                if let Some(t) = wall.ray_intersection(entity.pos, dir, ray_length) {
+                    let norm = t / ray_length;
+                    if norm < hit.distance_norm {
+                        hit = RayHit {
+                            distance_norm: norm,
+                            hit_type: HitType::Wall,
+                        };
+                    }
+                }
+            }
+            // Portal endpoints show up as their own kind of obstacle
+            for portal in &environment.portals {
+                if let Some(t) = portal.ray_intersection(entity.pos, dir, ray_length) {
+                    let norm = t / ray_length;
+                    if norm < hit.distance_norm {
+                        hit = RayHit {
+                            distance_norm: norm,
+                            hit_type: HitType::Portal,
+                        };
+                    }
+                }
+            }
+
             ray_distances[ray_i] = hit.distance_norm;
             ray_types[ray_i] = hit.hit_type;
 
@@ -103,6 +174,11 @@ pub fn compute_all_sensors(
         // [3]: entity proximity (min distance to entity ray, inverted)
         // [4]: own energy level normalized [0,1]
         // [5]: environment signal: terrain danger + day/night combined
+        // [6]: shelter proximity (nearest shelter within sensor range, inverted; 1 = inside)
+        // [7], [8]: sound bearing and loudness, filled in afterwards from acoustics::hear
+        // [9]: fear (recent combat damage and nearby attacks), filled in afterwards from SimState::fear
+        // [10]: daylight (0 at midnight, 1 at noon)
+        // [11], [12]: flock density and heading, filled in afterwards from flocking::sense_flocks
 
         let left_prox = 1.0
             - (ray_distances[0] + ray_distances[1] + ray_distances[2] + ray_distances[3]) * 0.25;
@@ -127,6 +203,7 @@ pub fn compute_all_sensors(
         // Day/night: adds 0.0 (full day) to 0.3 (full night)
         let terrain = environment.terrain.get_at(entity.pos);
         let terrain_danger = match terrain {
+            _ if environment.in_toxic_zone(entity.pos, world) => 1.0,
             TerrainType::Plains => 0.0,
             TerrainType::Forest => 0.2,
             TerrainType::Desert => 0.4,
@@ -136,7 +213,24 @@ pub fn compute_all_sensors(
         let night_signal = 1.0 - environment.day_brightness(); // 0 at day, 0.7 at night
         let env_signal = (terrain_danger * 0.7 + night_signal * 0.3).clamp(0.0, 1.0);
 
-        all_inputs[idx] = [left_prox, right_prox, food_prox, entity_prox, energy_norm, env_signal];
+        let shelter_prox = shelters
+            .iter()
+            .map(|s| {
+                let dist = (world.distance_sq(entity.pos, s.pos).sqrt() - s.radius).max(0.0);
+                1.0 - (dist / ray_length).min(1.0)
+            })
+            .fold(0.0f32, f32::max);
+
+        all_inputs[idx][10] = 1.0 - darkness;
+        all_inputs[idx][..7].copy_from_slice(&[
+            left_prox,
+            right_prox,
+            food_prox,
+            entity_prox,
+            energy_norm,
+            env_signal,
+            shelter_prox,
+        ]);
     }
 
     (all_inputs, all_rays)
@@ -0,0 +1,91 @@
+use macroquad::prelude::*;
+
+use crate::config;
+use crate::energy_audit::EnergyLedger;
+use crate::entity::EntityArena;
+use crate::world::World;
+
+/// A shelter built by an entity. Protects occupants from storms and lowers resting metabolism.
+#[derive(Clone, Debug)]
+pub struct Shelter {
+    pub pos: Vec2,
+    pub radius: f32,
+    /// Seconds until the shelter collapses. Building at an existing shelter repairs it.
+    pub durability: f32,
+}
+
+impl Shelter {
+    pub fn contains(&self, pos: Vec2, world: &World) -> bool {
+        world.distance_sq(self.pos, pos) < self.radius * self.radius
+    }
+}
+
+/// Entities with build intent above the threshold spend energy to construct a shelter at their
+/// position, or to repair the shelter they are standing in. Returns positions of new shelters.
+pub fn process_building(
+    arena: &mut EntityArena,
+    build_intents: &[f32], // indexed by slot, [0,1]
+    shelters: &mut Vec<Shelter>,
+    world: &World,
+    ledger: &mut EnergyLedger,
+) -> Vec<Vec2> {
+    let mut built = Vec::new();
+
+    for (idx, entity) in arena.iter_alive_mut() {
+        if build_intents.get(idx).copied().unwrap_or(0.0) < config::BUILD_THRESHOLD {
+            continue;
+        }
+        if entity.energy < config::SHELTER_BUILD_COST * 2.0 {
+            continue;
+        }
+
+        if let Some(existing) = shelters.iter_mut().find(|s| s.contains(entity.pos, world)) {
+            // Repair only when noticeably worn, so repair costs are not paid every tick
+            if existing.durability < config::SHELTER_LIFETIME * 0.5 {
+                existing.durability = config::SHELTER_LIFETIME;
+                entity.energy -= config::SHELTER_BUILD_COST * 0.5;
+                ledger.actions += (config::SHELTER_BUILD_COST * 0.5) as f64;
+            }
+            continue;
+        }
+
+        if shelters.len() >= config::MAX_SHELTERS {
+            continue;
+        }
+
+        entity.energy -= config::SHELTER_BUILD_COST;
+        ledger.actions += config::SHELTER_BUILD_COST as f64;
+        shelters.push(Shelter {
+            pos: entity.pos,
+            radius: config::SHELTER_RADIUS,
+            durability: config::SHELTER_LIFETIME,
+        });
+        built.push(entity.pos);
+    }
+
+    built
+}
+
+/// Wear down shelters and remove collapsed ones.
+pub fn decay_shelters(shelters: &mut Vec<Shelter>, dt: f32) {
+    for s in shelters.iter_mut() {
+        s.durability -= dt;
+    }
+    shelters.retain(|s| s.durability > 0.0);
+}
+
+/// Flag entities standing inside any shelter.
+pub fn update_occupancy(arena: &mut EntityArena, shelters: &[Shelter], world: &World) {
+    for (_, entity) in arena.iter_alive_mut() {
+        entity.sheltered = shelters.iter().any(|s| s.contains(entity.pos, world));
+    }
+}
+
+/// Draw shelters as domes that fade as they wear down.
+pub fn draw_shelters(shelters: &[Shelter]) {
+    for s in shelters {
+        let wear = (s.durability / config::SHELTER_LIFETIME).clamp(0.0, 1.0);
+        draw_circle(s.pos.x, s.pos.y, s.radius, Color::new(0.45, 0.35, 0.2, 0.12 + 0.12 * wear));
+        draw_circle_lines(s.pos.x, s.pos.y, s.radius, 2.0, Color::new(0.75, 0.6, 0.35, 0.3 + 0.4 * wear));
+    }
+}
@@ -1,5 +1,7 @@
 use macroquad::prelude::*;
 
+use crate::config;
+use crate::energy_audit::EnergyLedger;
 use crate::entity::EntityArena;
 use crate::world::World;
 
@@ -10,6 +12,9 @@ pub struct PheromoneGrid {
     pub height: usize,
     pub cell_size: f32,
     inv_cell_size: f32,
+    /// Next row to process in the amortized diffusion/advection pass.
+    next_row: usize,
+    scratch: Vec<f32>,
 }
 
 impl PheromoneGrid {
@@ -22,6 +27,8 @@ impl PheromoneGrid {
             height,
             cell_size,
             inv_cell_size: 1.0 / cell_size,
+            next_row: 0,
+            scratch: Vec::new(),
         }
     }
 
@@ -56,6 +63,56 @@ impl PheromoneGrid {
         vec2(dx, dy) * 0.5
     }
 
+    /// Cell value with toroidal wrapping.
+    fn cell_wrapped(&self, x: i32, y: i32) -> f32 {
+        let x = x.rem_euclid(self.width as i32) as usize;
+        let y = y.rem_euclid(self.height as i32) as usize;
+        self.cells[y * self.width + x]
+    }
+
+    /// Bilinear sample in cell coordinates (cell centers at integer coordinates).
+    fn sample_bilinear(&self, fx: f32, fy: f32) -> f32 {
+        let x0 = fx.floor();
+        let y0 = fy.floor();
+        let tx = fx - x0;
+        let ty = fy - y0;
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let top = self.cell_wrapped(x0, y0) * (1.0 - tx) + self.cell_wrapped(x0 + 1, y0) * tx;
+        let bottom = self.cell_wrapped(x0, y0 + 1) * (1.0 - tx) + self.cell_wrapped(x0 + 1, y0 + 1) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+
+    /// Diffuse and advect one horizontal strip of the grid. Each call advances to the next strip,
+    /// so the full grid is updated once every `strips` ticks with a correspondingly larger step.
+    pub fn diffuse_advect_strip(&mut self, wind: Vec2, diffusion: f32, strips: usize, dt: f32) {
+        let strips = strips.clamp(1, self.height.max(1));
+        let rows_per_strip = self.height.div_ceil(strips);
+        let step = dt * strips as f32;
+        // Explicit diffusion is only stable up to a quarter exchange per step
+        let k = (diffusion * step).min(0.25);
+        // Backtrace offset in cells
+        let shift = wind * step * self.inv_cell_size;
+
+        let start = self.next_row;
+        let end = (start + rows_per_strip).min(self.height);
+        self.scratch.clear();
+        for y in start..end {
+            for x in 0..self.width {
+                let (xi, yi) = (x as i32, y as i32);
+                let advected = self.sample_bilinear(x as f32 - shift.x, y as f32 - shift.y);
+                let neighbors = self.cell_wrapped(xi - 1, yi)
+                    + self.cell_wrapped(xi + 1, yi)
+                    + self.cell_wrapped(xi, yi - 1)
+                    + self.cell_wrapped(xi, yi + 1);
+                let center = self.cells[y * self.width + x];
+                self.scratch.push((advected + k * (neighbors - 4.0 * center)).max(0.0));
+            }
+        }
+        self.cells[start * self.width..end * self.width].copy_from_slice(&self.scratch);
+
+        self.next_row = if end >= self.height { 0 } else { end };
+    }
+
     /// Exponential decay of all pheromones.
     pub fn decay(&mut self, rate: f32, dt: f32) {
         let factor = 1.0 - rate * dt;
@@ -82,12 +139,35 @@ impl Default for SignalState {
     }
 }
 
-/// Update signals and pheromones for all entities.
+/// Lay pheromone trails under control of the trail motor output. Output above
+/// PHEROMONE_DEPOSIT_THRESHOLD deposits (and costs energy) in proportion to the excess.
+pub fn lay_trails(
+    arena: &mut EntityArena,
+    trail_intents: &[f32],
+    pheromone_grid: &mut PheromoneGrid,
+    ledger: &mut EnergyLedger,
+    dt: f32,
+) {
+    let span = 1.0 - config::PHEROMONE_DEPOSIT_THRESHOLD;
+    for (idx, entity) in arena.iter_alive_mut() {
+        let intent = trail_intents.get(idx).copied().unwrap_or(0.0);
+        let strength = (intent - config::PHEROMONE_DEPOSIT_THRESHOLD) / span;
+        if strength <= 0.0 {
+            continue;
+        }
+        pheromone_grid.deposit(entity.pos, config::PHEROMONE_DEPOSIT_RATE * strength * dt);
+        entity.energy -= config::PHEROMONE_DEPOSIT_COST * strength * dt;
+        ledger.actions += (config::PHEROMONE_DEPOSIT_COST * strength * dt) as f64;
+    }
+}
+
+/// Update signals and decay the pheromone grid for all entities.
 pub fn update_signals(
     arena: &EntityArena,
     signal_intensities: &[f32], // brain output [0,1] per slot
     signals: &mut Vec<SignalState>,
     pheromone_grid: &mut PheromoneGrid,
+    wind: Vec2,
     dt: f32,
 ) {
     // Ensure signals vec is large enough
@@ -95,34 +175,27 @@ pub fn update_signals(
         signals.resize(arena.entities.len(), SignalState::default());
     }
 
-    for (idx, entity) in arena.entities.iter().enumerate() {
-        if let Some(e) = entity {
-            let intensity = if idx < signal_intensities.len() {
-                signal_intensities[idx]
-            } else {
-                0.0
-            };
-
-            signals[idx] = SignalState {
-                color: e.color,
-                intensity,
-            };
-
-            // Deposit pheromone proportional to movement speed
-            let speed = e.velocity.length();
-            let deposit_amount = speed * 0.01 * dt;
-            if deposit_amount > 0.001 {
-                pheromone_grid.deposit(e.pos, deposit_amount);
-            }
+    // Empty slots between the living are reset as the walk passes them
+    let mut next = 0;
+    for (idx, e) in arena.iter_alive() {
+        signals[next..idx].fill(SignalState::default());
+        next = idx + 1;
+        let intensity = if idx < signal_intensities.len() {
+            signal_intensities[idx]
         } else {
-            if idx < signals.len() {
-                signals[idx] = SignalState::default();
-            }
-        }
+            0.0
+        };
+
+        signals[idx] = SignalState {
+            color: e.color,
+            intensity,
+        };
     }
+    signals[next..].fill(SignalState::default());
 
-    // Decay pheromones
-    pheromone_grid.decay(0.5, dt); // ~2 second half-life
+    // Spread and drift trails, then decay
+    pheromone_grid.diffuse_advect_strip(wind, config::PHEROMONE_DIFFUSION, config::PHEROMONE_STRIPS, dt);
+    pheromone_grid.decay(config::PHEROMONE_DECAY_RATE, dt);
 }
 
 /// Draw signal auras around entities (called from renderer).
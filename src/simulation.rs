@@ -1,20 +1,39 @@
+use std::collections::VecDeque;
+
 use macroquad::prelude::*;
 use ::rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
+use crate::acoustics;
+use crate::audio::SoundEvents;
+use crate::care::{self, JuvenileCounts};
 use crate::brain::BrainStorage;
-use crate::combat::{self, CombatEvent, MeatItem};
+use crate::coloring::{self, ColorMode};
+use crate::combat::{self, CombatEvent, KillRecord, MeatItem, Projectile};
 use crate::config;
 use crate::energy::{self, FoodSpawner};
-use crate::entity::EntityArena;
+use crate::energy_audit::{self, EnergyAudit, EnergyLedger};
+use crate::flocking;
+use crate::entity::{Entity, EntityArena, EntityEventKind, EntityId};
 use crate::environment::{self, EnvironmentState};
 use crate::genome::Genome;
+use crate::hall_of_fame::HallOfFame;
+use crate::heatmap::{self, HeatmapLayer, Heatmaps};
+use crate::journal::InterventionJournal;
+use crate::lineage::LineageLog;
+use crate::manifest::RunInfo;
+use crate::notifications::{self, NoticeKind, NoticeLog};
 use crate::particles::ParticleSystem;
 use crate::physics;
+use crate::plants::{self, FoodKind, PlantGrid};
 use crate::reproduction;
-use crate::sensory::{self, EntityRays};
+use crate::sensory::{self, EntityRays, RayMode};
+use crate::shelter::{self, Shelter};
 use crate::signals::{self, PheromoneGrid, SignalState};
+use crate::species::{self, SpeciesTracker};
 use crate::spatial_hash::SpatialHash;
+use crate::stats;
+use crate::weather;
 use crate::world::World;
 
 /// Food item in the world.
@@ -22,6 +41,28 @@ use crate::world::World;
 pub struct FoodItem {
     pub pos: Vec2,
     pub energy: f32,
+    pub kind: FoodKind,
+    /// Seconds since the item appeared; berries and fruit spoil with age.
+    pub age: f32,
+}
+
+impl FoodItem {
+    pub fn new(pos: Vec2, kind: FoodKind) -> Self {
+        Self {
+            pos,
+            energy: kind.energy(),
+            kind,
+            age: 0.0,
+        }
+    }
+}
+
+/// Energy given away through food sharing since the start of the run, split by whether the
+/// recipient was kin (within SHARE_KIN_DISTANCE of the giver's genome).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SharingTotals {
+    pub to_kin: f64,
+    pub to_strangers: f64,
 }
 
 pub struct SimState {
@@ -32,27 +73,86 @@ pub struct SimState {
     pub spatial_hash: SpatialHash,
     pub food: Vec<FoodItem>,
     pub food_spawner: FoodSpawner,
+    /// Whether berries and fruit rot away when left uneaten.
+    pub food_spoilage: bool,
+    /// Plant cover that food grows from under the patch food model.
+    pub plants: PlantGrid,
     pub meat: Vec<MeatItem>,
+    /// Spit in flight.
+    pub projectiles: Vec<Projectile>,
+    pub shelters: Vec<Shelter>,
     pub signals: Vec<SignalState>,
+    /// Loudness of each slot's call in [0, 1], fading once the call motor drops.
+    pub calls: Vec<f32>,
+    /// Per-slot fear level in [0, 1] from recent combat, read by the fear sensor.
+    pub fear: Vec<f32>,
     pub pheromone_grid: PheromoneGrid,
     pub combat_events: Vec<CombatEvent>,
+    /// Attack motor output per slot from the last tick (0 for empty slots). Not saved.
+    pub attack_intents: Vec<f32>,
+    /// Most recent combat deaths, oldest first, bounded by KILL_FEED_SIZE. Not saved.
+    pub kill_feed: VecDeque<KillRecord>,
     pub particles: ParticleSystem,
     pub environment: EnvironmentState,
     pub rng: ChaCha8Rng,
     pub tick_count: u64,
     pub paused: bool,
     pub speed_multiplier: f32,
-    pub show_rays: bool,
+    pub ray_mode: RayMode,
+    /// Entities whose rays are collected in RayMode::Selected (set by the UI each frame).
+    pub ray_targets: Vec<EntityId>,
+    /// What entities are colored by.
+    pub color_mode: ColorMode,
     pub last_rays: Vec<Option<EntityRays>>,
+    pub heatmaps: Heatmaps,
+    pub heatmap_layer: Option<HeatmapLayer>,
+    pub heatmap_opacity: f32,
+    pub species: SpeciesTracker,
+    /// Archive of the best genomes seen this run.
+    pub hall_of_fame: HallOfFame,
+    /// Sound-worthy events since the audio system last drained them.
+    pub sound_events: SoundEvents,
+    /// Births by reproduction and deaths (of any cause) since this state was created or loaded.
+    /// Manual and scripted spawns are not births.
+    pub births_total: u64,
+    pub deaths_total: u64,
+    /// Notable events since the UI last drained them into its event log.
+    pub notices: NoticeLog,
+    /// Highest generation milestone already announced.
+    pub generation_milestone: u32,
+    /// Highest generation depth among the living, refreshed every species update.
+    pub deepest_generation: u32,
+    /// Whether the population ceiling (MAX_ENTITY_COUNT) has been announced. Not saved.
+    pub ceiling_announced: bool,
+    /// Debug energy-conservation check, run each tick while enabled.
+    pub energy_audit: EnergyAudit,
+    /// Entity picked up with the drag tool. Physics leaves it where it is while held.
+    pub held: Option<EntityId>,
+    /// Juvenile outcomes since the start of the run, for the survival-rate graph.
+    pub juveniles: JuvenileCounts,
+    /// Food sharing totals, for the altruism graph. Stored in saves.
+    pub sharing: SharingTotals,
+    /// Everything the user did to the world, with tick timestamps. Stored in saves.
+    pub journal: InterventionJournal,
+    /// Births and deaths kept for the inspector's family tree. Stored in saves.
+    pub lineage: LineageLog,
+    /// Provenance of the run that created this world. Stored in saves, so a loaded world keeps
+    /// the configuration it was started with.
+    pub run_info: RunInfo,
+    /// Seed the population and RNG were created from. The terrain has its own seed.
+    pub seed: u64,
+    /// Simulated seconds per tick. Set through `set_sim_hz`.
+    pub dt: f32,
 }
 
 impl SimState {
     pub fn new(entity_count: usize, seed: u64) -> Self {
         let world = World::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, config::WORLD_TOROIDAL);
         let mut rng = ChaCha8Rng::seed_from_u64(seed);
-        let mut arena = EntityArena::new(config::MAX_ENTITY_COUNT);
-        let mut brains = BrainStorage::new(config::MAX_ENTITY_COUNT);
-        let mut genomes: Vec<Option<Genome>> = vec![None; config::MAX_ENTITY_COUNT];
+        let capacity = config::INITIAL_ENTITY_CAPACITY.max(entity_count);
+        let mut arena = EntityArena::new(capacity);
+        let mut brains = BrainStorage::new(capacity);
+        let mut genomes: Vec<Option<Genome>> = vec![None; capacity];
 
         for _ in 0..entity_count {
             let pos = vec2(
@@ -68,19 +168,18 @@ impl SimState {
             }
         }
 
+        let environment = EnvironmentState::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, seed as u32);
         let mut food = Vec::with_capacity(config::INITIAL_FOOD_COUNT * 2);
         for _ in 0..config::INITIAL_FOOD_COUNT {
-            food.push(FoodItem {
-                pos: vec2(rng.gen_range(0.0..world.width), rng.gen_range(0.0..world.height)),
-                energy: config::FOOD_ENERGY,
-            });
+            let pos = vec2(rng.gen_range(0.0..world.width), rng.gen_range(0.0..world.height));
+            food.push(FoodItem::new(pos, FoodKind::pick(environment.terrain.get_at(pos), &mut rng)));
         }
 
         let spatial_hash =
             SpatialHash::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, config::SPATIAL_CELL_SIZE);
         let pheromone_grid = PheromoneGrid::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, 32.0);
 
-        Self {
+        let mut sim = Self {
             arena,
             brains,
             genomes,
@@ -88,18 +187,86 @@ impl SimState {
             spatial_hash,
             food,
             food_spawner: FoodSpawner::new(),
+            food_spoilage: true,
+            plants: PlantGrid::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, config::PLANT_CELL_SIZE),
             meat: Vec::new(),
-            signals: vec![SignalState::default(); config::MAX_ENTITY_COUNT],
+            projectiles: Vec::new(),
+            shelters: Vec::new(),
+            signals: vec![SignalState::default(); capacity],
+            calls: vec![0.0; capacity],
+            fear: vec![0.0; capacity],
             pheromone_grid,
             combat_events: Vec::new(),
+            attack_intents: Vec::new(),
+            kill_feed: VecDeque::new(),
             particles: ParticleSystem::new(),
-            environment: EnvironmentState::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, seed as u32),
+            environment,
             rng,
             tick_count: 0,
             paused: false,
             speed_multiplier: 1.0,
-            show_rays: false,
+            ray_mode: RayMode::Off,
+            ray_targets: Vec::new(),
+            color_mode: ColorMode::Genome,
             last_rays: Vec::new(),
+            heatmaps: Heatmaps::new(config::WORLD_WIDTH, config::WORLD_HEIGHT, config::HEATMAP_CELL_SIZE),
+            heatmap_layer: None,
+            heatmap_opacity: config::HEATMAP_OPACITY,
+            species: SpeciesTracker::new(),
+            hall_of_fame: HallOfFame::default(),
+            sound_events: SoundEvents::default(),
+            births_total: 0,
+            deaths_total: 0,
+            notices: NoticeLog::default(),
+            generation_milestone: 0,
+            deepest_generation: 0,
+            ceiling_announced: false,
+            energy_audit: EnergyAudit::default(),
+            held: None,
+            juveniles: JuvenileCounts::default(),
+            sharing: SharingTotals::default(),
+            journal: InterventionJournal::default(),
+            lineage: LineageLog::default(),
+            run_info: RunInfo::default(),
+            seed,
+            dt: config::FIXED_DT,
+        };
+        sim.plants.seed(&sim.environment, &sim.world, &mut sim.rng);
+        sim.species.recluster(&sim.arena, &sim.genomes, 0);
+        sim
+    }
+
+    /// Ticks per simulated second.
+    pub fn sim_hz(&self) -> f32 {
+        1.0 / self.dt
+    }
+
+    /// Change the tick rate. Every per-tick effect scales with `dt`, so the rate only trades
+    /// precision for speed and leaves behavior per simulated second unchanged.
+    pub fn set_sim_hz(&mut self, hz: f32) {
+        self.dt = 1.0 / hz.clamp(config::SIM_HZ_MIN, config::SIM_HZ_MAX);
+    }
+
+    /// Color the entity in `slot` is drawn with under the current color mode.
+    pub fn display_color(&self, slot: usize, entity: &Entity) -> Color {
+        match self.color_mode {
+            ColorMode::Genome => entity.color,
+            ColorMode::Species => self
+                .species
+                .species_of(slot)
+                .map_or(species::UNASSIGNED_COLOR, species::species_color),
+            ColorMode::Energy => coloring::ramp(entity.energy / config::MAX_ENTITY_ENERGY),
+            ColorMode::Age => coloring::ramp(entity.age / config::DEATH_AGE),
+            ColorMode::Generation => {
+                coloring::ramp(entity.generation_depth as f32 / self.deepest_generation.max(1) as f32)
+            }
+            ColorMode::Diet => coloring::meat_share(entity).map_or(coloring::NO_DIET_COLOR, coloring::diet_color),
+            ColorMode::BrainSize => match self.genomes.get(slot) {
+                Some(Some(genome)) => coloring::ramp(
+                    stats::interneurons_in_use(genome) as f32 / config::BRAIN_INTERNEURONS as f32,
+                ),
+                _ => entity.color,
+            },
         }
     }
 
@@ -107,113 +274,319 @@ impl SimState {
         self.food.iter().map(|f| f.pos).collect()
     }
 
+    /// Chance that a food spawn attempt at `pos` succeeds: terrain, seasonal latitude, recent
+    /// rain, carcass nutrients and plant cover.
+    pub fn local_food_multiplier(&self, pos: Vec2) -> f32 {
+        self.environment.terrain.get_at(pos).food_spawn_mult()
+            * self.environment.latitude_food_multiplier(pos.y, self.world.height)
+            * self.environment.weather.food_multiplier(pos)
+            * self.environment.nutrients.food_multiplier(pos)
+            * self.plants.food_multiplier(pos)
+    }
+
+    /// Recompute the productivity heatmap: the global spawn rate (preset, season, time of day)
+    /// times the local multiplier at each cell centre.
+    pub fn sample_productivity(&mut self) {
+        let global = self.environment.food_rate_multiplier();
+        let grid = &self.heatmaps.productivity;
+        let cells: Vec<f32> = (0..grid.cells.len())
+            .map(|i| global * self.local_food_multiplier(grid.cell_center(i)))
+            .collect();
+        self.heatmaps.productivity.cells = cells;
+    }
+
     pub fn tick(&mut self) {
-        let dt = config::FIXED_DT;
+        let dt = self.dt;
+        let had_population = self.arena.count > 0;
+        let mut ledger = EnergyLedger::default();
+        let opening_stock = self
+            .energy_audit
+            .enabled
+            .then(|| energy_audit::energy_stock(&self.arena, &self.food, &self.meat));
+
+        self.reserve_entity_slots(0);
 
         // Rebuild spatial hash
         self.spatial_hash.rebuild(&self.arena);
 
         // Sensory + Brain
         let food_pos = self.food_positions();
-        let (sensor_inputs, rays) = sensory::compute_all_sensors(
+        let (mut sensor_inputs, rays) = sensory::compute_all_sensors(
             &self.arena,
             &food_pos,
             &self.spatial_hash,
             &self.world,
             &self.environment,
-            self.show_rays,
+            &self.shelters,
+            &self.ray_mask(),
         );
         self.last_rays = rays;
+        let hearing = acoustics::hear(
+            &self.arena,
+            &self.calls,
+            &self.spatial_hash,
+            &self.world,
+            &self.environment.walls,
+        );
+        let flocks = flocking::sense_flocks(&self.arena, &self.signals, &self.spatial_hash, &self.world);
+        for (slot, (inputs, [bearing, loudness])) in sensor_inputs.iter_mut().zip(hearing).enumerate() {
+            inputs[7] = bearing;
+            inputs[8] = loudness;
+            inputs[9] = self.fear.get(slot).copied().unwrap_or(0.0);
+            if let Some(flock) = flocks.get(slot) {
+                inputs[11] = flock.density;
+                inputs[12] = flock.heading;
+            }
+        }
         self.brains.step_all(&sensor_inputs, dt);
 
         // Extract all motor outputs at once
         let entity_count = self.arena.entities.len();
         let mut motor_pairs = Vec::with_capacity(entity_count);
-        let mut attack_intents = Vec::with_capacity(entity_count);
+        self.attack_intents.clear();
         let mut signal_intensities = Vec::with_capacity(entity_count);
+        let mut build_intents = Vec::with_capacity(entity_count);
+        let mut call_intents = Vec::with_capacity(entity_count);
+        let mut trail_intents = Vec::with_capacity(entity_count);
 
-        for slot in 0..entity_count {
+        // One flock view per arena slot
+        for (slot, flock) in flocks.iter().enumerate() {
             if self.brains.active.get(slot).copied().unwrap_or(false) {
-                let (fwd, turn, attack, signal) = self.brains.motor_outputs(slot);
-                motor_pairs.push((fwd, turn));
-                attack_intents.push(attack);
-                signal_intensities.push(signal);
+                let m = self.brains.motor_outputs(slot);
+                let flocking = self.arena.entities[slot].as_ref().map_or(0.0, |e| e.flocking);
+                let turn = flocking::steer(m.turn, m.cohesion, m.alignment, flocking, flock);
+                motor_pairs.push((m.forward, turn));
+                self.attack_intents.push(m.attack);
+                signal_intensities.push(m.signal);
+                build_intents.push(m.build);
+                call_intents.push(m.call);
+                trail_intents.push(m.trail);
             } else {
                 motor_pairs.push((0.0, 0.0));
-                attack_intents.push(0.0);
+                self.attack_intents.push(0.0);
                 signal_intensities.push(0.0);
+                build_intents.push(0.0);
+                call_intents.push(0.0);
+                trail_intents.push(0.0);
             }
         }
 
-        // Physics
-        physics::apply_motor_outputs(&mut self.arena, &motor_pairs, dt);
+        // Physics (a held entity stays pinned where the drag tool put it)
+        let held_pos = self.held.and_then(|id| self.arena.get(id)).map(|e| e.pos);
+        physics::apply_motor_outputs(&mut self.arena, &motor_pairs, &self.environment.terrain, dt);
         physics::integrate(&mut self.arena, &self.world, dt);
+        environment::apply_portals(&mut self.arena, &self.environment.portals, &self.world);
         self.spatial_hash.rebuild(&self.arena);
         physics::resolve_collisions(&mut self.arena, &self.spatial_hash, &self.world);
+        physics::resolve_wall_collisions(&mut self.arena, &self.environment.walls, &self.world);
+        if let (Some(id), Some(pos)) = (self.held, held_pos) {
+            self.teleport_entity(id, pos);
+        }
 
         // Combat
         self.combat_events = combat::resolve_combat(
             &mut self.arena,
-            &attack_intents,
+            &self.attack_intents,
             &self.spatial_hash,
             &self.world,
             &mut self.meat,
+            &mut ledger,
+            dt,
+        );
+        combat::fire_spit(
+            &mut self.arena,
+            &self.attack_intents,
+            &self.spatial_hash,
+            &self.world,
+            &mut self.projectiles,
+            &mut ledger,
+            dt,
+        );
+        let spit_hits = combat::move_projectiles(
+            &mut self.projectiles,
+            &self.arena,
+            &self.spatial_hash,
+            &self.environment.walls,
+            &self.world,
+            dt,
+        );
+        let spit_events =
+            combat::apply_spit_hits(&mut self.arena, &mut self.projectiles, &spit_hits, &mut self.meat, &mut ledger);
+        self.combat_events.extend(spit_events);
+        combat::update_fear(
+            &mut self.fear,
+            &self.combat_events,
+            &self.arena,
+            &self.spatial_hash,
+            &self.world,
+            dt,
         );
 
         // Emit combat particles
+        self.sound_events.combat_hits += self.combat_events.len() as u32;
         for event in &self.combat_events {
             self.particles.emit_combat(event.target_pos);
+            self.heatmaps.combat.add(event.target_pos, 1.0);
+            if event.killed {
+                let killer = self.arena.get_by_index(event.attacker_slot);
+                let victim = self.arena.get_by_index(event.target_slot);
+                if let (Some(killer), Some(victim)) = (killer, victim) {
+                    if self.kill_feed.len() >= config::KILL_FEED_SIZE {
+                        self.kill_feed.pop_front();
+                    }
+                    self.kill_feed.push_back(KillRecord {
+                        tick: self.tick_count,
+                        killer_uid: killer.uid,
+                        killer: killer.display_name(),
+                        victim_uid: victim.uid,
+                        victim: victim.display_name(),
+                    });
+                }
+            }
         }
 
         // Meat consumption and decay
-        combat::consume_meat(&mut self.arena, &mut self.meat, &self.world);
-        combat::decay_meat(&mut self.meat, dt);
+        combat::consume_meat(&mut self.arena, &mut self.meat, &self.world, &mut ledger);
+        combat::decay_meat(&mut self.meat, &mut self.environment.nutrients, &mut ledger, dt);
 
-        // Energy: metabolism, food consumption, starvation
-        energy::deduct_metabolism(&mut self.arena, dt);
-        let eaten_positions = energy::consume_food(&mut self.arena, &mut self.food, &self.world);
+        // Shelters: construction, wear, occupancy
+        let built = shelter::process_building(
+            &mut self.arena,
+            &build_intents,
+            &mut self.shelters,
+            &self.world,
+            &mut ledger,
+        );
+        for pos in &built {
+            self.particles.emit_build(*pos);
+        }
+        shelter::decay_shelters(&mut self.shelters, dt);
+        shelter::update_occupancy(&mut self.arena, &self.shelters, &self.world);
+
+        // Energy: metabolism, food consumption, healing, starvation and fatal injuries
+        energy::deduct_metabolism(&mut self.arena, &mut ledger, self.environment.darkness(), dt);
+        energy::grow_juveniles(&mut self.arena, &mut ledger, dt);
+        if self.food_spoilage {
+            energy::spoil_food(&mut self.food, &mut ledger, dt);
+        }
+        let eaten_positions = energy::consume_food(
+            &mut self.arena,
+            &mut self.food,
+            &self.world,
+            &self.environment.terrain,
+            &mut ledger,
+        );
         for pos in &eaten_positions {
             self.particles.emit_eat(*pos);
+            self.plants.graze(*pos);
         }
+        energy::apply_senescence(&mut self.arena);
+        energy::regenerate_health(&mut self.arena, &mut ledger, dt);
         energy::kill_starved(&mut self.arena);
 
         // Food sharing: entities with high signal and adjacent neighbor share energy
-        self.process_food_sharing();
+        self.process_food_sharing(&mut ledger, dt);
 
         // Signals and pheromones
+        signals::lay_trails(&mut self.arena, &trail_intents, &mut self.pheromone_grid, &mut ledger, dt);
         signals::update_signals(
             &self.arena,
             &signal_intensities,
             &mut self.signals,
             &mut self.pheromone_grid,
+            self.environment.wind(),
             dt,
         );
+        acoustics::update_calls(&mut self.arena, &call_intents, &mut self.calls, &mut ledger, dt);
+
+        // Parental care: hungry juveniles beg, nearby parents feed them
+        care::apply_parental_care(&mut self.arena, &self.world, &mut self.calls, &mut ledger, dt);
 
         // Reproduction
-        let birth_positions = reproduction::check_and_spawn(
+        let births = reproduction::collect_births(&self.arena, &self.brains, &self.genomes, &self.world, &mut self.rng);
+        self.reserve_entity_slots(births.len());
+        let birth_positions = reproduction::spawn_births(
+            births,
             &mut self.arena,
             &mut self.brains,
             &mut self.genomes,
-            &self.world,
             &mut self.rng,
             self.tick_count,
+            &mut ledger,
         );
+        self.sound_events.births += birth_positions.len() as u32;
+        self.births_total += birth_positions.len() as u64;
         for pos in &birth_positions {
             self.particles.emit_birth(*pos);
+            self.heatmaps.births.add(*pos, 1.0);
+        }
+
+        // Count down breeding protection
+        for (_, entity) in self.arena.iter_alive_mut() {
+            entity.protection_ticks = entity.protection_ticks.saturating_sub(1);
+        }
+
+        // Family records: births since the last tick, then deaths before their carriers are swept
+        self.lineage.record_births(&self.arena, self.tick_count);
+        self.lineage.record_deaths(&self.arena, self.tick_count);
+
+        // Archive notable genomes before their carriers are swept
+        for (idx, entity) in self.arena.iter_alive() {
+            if let Some(Some(genome)) = self.genomes.get(idx) {
+                if !entity.alive {
+                    self.hall_of_fame.consider(entity, genome, self.tick_count);
+                }
+            }
         }
 
-        // Sweep dead entities
+        self.juveniles.observe(&self.arena, dt);
+
+        // Sweep dead entities (whatever energy they still hold leaves the system)
+        ledger.deaths += self
+            .arena
+            .iter_alive()
+            .filter(|(_, e)| !e.alive)
+            .map(|(_, e)| e.energy as f64)
+            .sum::<f64>();
         let dead = self.arena.sweep_dead();
+        self.sound_events.deaths += dead.len() as u32;
+        self.deaths_total += dead.len() as u64;
         for (idx, pos) in &dead {
             self.brains.deactivate(*idx);
             if *idx < self.genomes.len() {
                 self.genomes[*idx] = None;
             }
             self.particles.emit_death(*pos);
+            self.heatmaps.deaths.add(*pos, 1.0);
+        }
+        self.heatmaps.sample_density(&self.arena, self.tick_count);
+        self.heatmaps.sample_population(&self.arena, self.tick_count);
+        if self.tick_count.is_multiple_of(heatmap::PRODUCTIVITY_SAMPLE_INTERVAL) {
+            self.sample_productivity();
+        }
+        for species in self.species.update(&self.arena, &self.genomes, self.tick_count) {
+            let lifetime = self.tick_count.saturating_sub(species.first_seen_tick) as f32 * dt;
+            if lifetime >= config::EXTINCTION_NOTICE_MIN_AGE && species.peak_member_count >= config::EXTINCTION_NOTICE_MIN_PEAK {
+                let message = format!(
+                    "Species S{} went extinct after {:.0}s (peak {} members)",
+                    species.id, lifetime, species.peak_member_count
+                );
+                self.notices.post(NoticeKind::Extinction, self.tick_count, message, None);
+            }
+        }
+        if self.tick_count.is_multiple_of(config::SPECIES_UPDATE_INTERVAL) {
+            self.deepest_generation = self.arena.deepest_generation();
+            let milestone = notifications::generation_milestone(self.deepest_generation);
+            if milestone > self.generation_milestone {
+                self.generation_milestone = milestone;
+                self.notices.post(NoticeKind::Milestone, self.tick_count, format!("Generation {milestone} reached"), None);
+            }
         }
 
         // Environment: terrain, storms, day/night, seasons
-        environment::apply_terrain_effects(&mut self.arena, &self.environment.terrain, &self.world, dt);
+        environment::apply_terrain_effects(&mut self.arena, &self.environment.terrain, &mut ledger, dt);
+        environment::apply_toxic_zones(&mut self.arena, &self.environment.toxic_zones, &self.world, dt);
+        weather::apply_wind(&mut self.arena, self.environment.wind(), dt);
         if let Some(ref storm) = self.environment.storm {
             let storm_clone = storm.clone();
             environment::apply_storm_effects(
@@ -221,10 +594,23 @@ impl SimState {
                 &storm_clone,
                 &self.world,
                 &self.environment.terrain,
+                &mut ledger,
                 dt,
             );
+        } else {
+            for (_, entity) in self.arena.iter_alive_mut() {
+                entity.in_storm = false;
+            }
         }
+        let had_storm = self.environment.storm.is_some();
         self.environment.tick(dt, &self.world, &mut self.rng);
+        if let Some(storm) = self.environment.storm.as_ref().filter(|_| !had_storm) {
+            self.sound_events.storm_started = true;
+            let message = format!("Storm made landfall (strength {:.1})", storm.strength);
+            self.notices.post(NoticeKind::Storm, self.tick_count, message, Some(storm.center));
+        }
+
+        plants::grow_plants(&mut self.plants, &self.environment, &self.world, dt);
 
         // Respawn food (modulated by environment)
         let food_rate_mult = self.environment.food_rate_multiplier();
@@ -235,38 +621,200 @@ impl SimState {
                 self.rng.gen_range(0.0..self.world.width),
                 self.rng.gen_range(0.0..self.world.height),
             );
-            // Bias food spawning by terrain
             let terrain = self.environment.terrain.get_at(pos);
-            if self.rng.gen::<f32>() < terrain.food_spawn_mult() {
-                self.food.push(FoodItem {
-                    pos,
-                    energy: config::FOOD_ENERGY,
-                });
+            if self.rng.gen::<f32>() < self.local_food_multiplier(pos) {
+                let item = FoodItem::new(pos, FoodKind::pick(terrain, &mut self.rng));
+                ledger.food_spawned += item.energy as f64;
+                self.food.push(item);
             }
             self.food_spawner.accumulator -= 1.0;
         }
 
+        if let Some(opening) = opening_stock {
+            let closing = energy_audit::energy_stock(&self.arena, &self.food, &self.meat);
+            self.energy_audit.check(opening, closing, ledger, self.tick_count);
+        }
+
+        if had_population && self.arena.count == 0 {
+            self.notices.post(NoticeKind::Extinction, self.tick_count, "The population died out", None);
+        }
+
         // Update particles
         self.particles.update(dt);
 
         self.tick_count += 1;
     }
 
+    /// Force-breed two entities: the child genome is a crossover of both parents, mutated,
+    /// and spawned at `pos`. The child cannot die for `protection_ticks` ticks.
+    /// Returns None if either parent is gone or the population is at capacity.
+    pub fn breed(
+        &mut self,
+        parent_a: EntityId,
+        parent_b: EntityId,
+        pos: Vec2,
+        protection_ticks: u32,
+    ) -> Option<EntityId> {
+        if self.arena.count >= config::MAX_ENTITY_COUNT {
+            return None;
+        }
+        self.reserve_entity_slots(1);
+        let (depth_a, depth_b, uid_a) = match (self.arena.get(parent_a), self.arena.get(parent_b)) {
+            (Some(a), Some(b)) => (a.generation_depth, b.generation_depth, a.uid),
+            _ => return None,
+        };
+        let mut genome_a = self.genomes.get(parent_a.index as usize)?.clone()?;
+        let mut genome_b = self.genomes.get(parent_b.index as usize)?.clone()?;
+        self.brains.write_back_learned(parent_a.index as usize, &mut genome_a);
+        self.brains.write_back_learned(parent_b.index as usize, &mut genome_b);
+        let child_genome = Genome::crossover(&genome_a, &genome_b, &mut self.rng).mutate(&mut self.rng);
+
+        let pos = self.world.wrap(pos);
+        let mut child = Entity::new_from_genome_rng(&child_genome, pos, self.tick_count, &mut self.rng);
+        child.generation_depth = depth_a.max(depth_b) + 1;
+        child.set_growth(0.0);
+        child.health = child.effective_max_health();
+        child.parent_id = Some(parent_a);
+        child.parent_uid = Some(uid_a);
+        // Protection is given in base-rate ticks; convert so it lasts as long at any rate
+        child.protection_ticks = (protection_ticks as f32 / (self.dt * config::BASE_SIM_HZ)).round() as u32;
+
+        let id = self.arena.spawn(child)?;
+        let slot = id.index as usize;
+        self.brains.init_from_genome(slot, &child_genome);
+        self.brains.inherit_state(slot, parent_a.index as usize);
+        if slot >= self.genomes.len() {
+            self.genomes.resize(slot + 1, None);
+        }
+        self.genomes[slot] = Some(child_genome);
+
+        let child_uid = self.arena.get(id).map_or(0, |c| c.uid);
+        for parent in [parent_a, parent_b] {
+            if let Some(p) = self.arena.get_mut(parent) {
+                p.offspring_count += 1;
+                p.log_event(EntityEventKind::Reproduced(child_uid));
+            }
+        }
+        self.particles.emit_birth(pos);
+        Some(id)
+    }
+
+    /// Spawn a fresh entity from an existing genome (e.g. one from the hall of fame)
+    /// without mutation. Returns None if the population is at capacity.
+    pub fn inject_genome(&mut self, genome: &Genome, pos: Vec2) -> Option<EntityId> {
+        if self.arena.count >= config::MAX_ENTITY_COUNT {
+            return None;
+        }
+        self.reserve_entity_slots(1);
+        let pos = self.world.wrap(pos);
+        let entity = Entity::new_from_genome_rng(genome, pos, self.tick_count, &mut self.rng);
+        let id = self.arena.spawn(entity)?;
+        let slot = id.index as usize;
+        self.brains.init_from_genome(slot, genome);
+        if slot >= self.genomes.len() {
+            self.genomes.resize(slot + 1, None);
+        }
+        self.genomes[slot] = Some(genome.clone());
+        self.particles.emit_birth(pos);
+        Some(id)
+    }
+
+    /// Grow the entity slots and every per-slot store (brains, genomes, signals, calls, fear) in
+    /// whole ENTITY_CAPACITY_CHUNKs once fewer than ENTITY_CAPACITY_HEADROOM slots (or fewer than
+    /// `births`, the spawns about to happen) are free, so births land in slots allocated ahead of
+    /// time. Growth stops at MAX_ENTITY_COUNT; the first time the population reaches that
+    /// ceiling it is announced.
+    pub fn reserve_entity_slots(&mut self, births: usize) {
+        let capacity = self.arena.capacity();
+        if self.arena.count >= config::MAX_ENTITY_COUNT && !self.ceiling_announced {
+            self.ceiling_announced = true;
+            let message = format!("Population reached the {}-entity ceiling", config::MAX_ENTITY_COUNT);
+            self.notices.post(NoticeKind::Milestone, self.tick_count, message, None);
+        }
+        let free = capacity - self.arena.count;
+        if (free >= config::ENTITY_CAPACITY_HEADROOM && free >= births) || capacity >= config::MAX_ENTITY_COUNT {
+            return;
+        }
+        let needed = capacity.max(self.arena.count + births) + 1;
+        let capacity = needed.next_multiple_of(config::ENTITY_CAPACITY_CHUNK).min(config::MAX_ENTITY_COUNT);
+        self.arena.grow(capacity);
+        self.brains.ensure_capacity(capacity);
+        self.genomes.resize(capacity, None);
+        self.signals.resize(capacity, SignalState::default());
+        self.calls.resize(capacity, 0.0);
+        self.fear.resize(capacity, 0.0);
+    }
+
+    /// Per-slot flags for the entities whose sensor rays should be collected this tick.
+    fn ray_mask(&self) -> Vec<bool> {
+        match self.ray_mode {
+            RayMode::Off => Vec::new(),
+            RayMode::All => vec![true; self.arena.entities.len()],
+            RayMode::Selected => {
+                let mut mask = vec![false; self.arena.entities.len()];
+                for id in &self.ray_targets {
+                    if self.arena.get(*id).is_some() {
+                        mask[id.index as usize] = true;
+                    }
+                }
+                mask
+            }
+        }
+    }
+
+    /// Comment line identifying this run, written at the top of CSV reports.
+    pub fn report_header(&self) -> String {
+        format!(
+            "# genesis seed={} terrain_seed={} preset={} tick={}",
+            self.seed,
+            self.environment.terrain_seed,
+            self.environment.world_preset.id(),
+            self.tick_count
+        )
+    }
+
+    /// Move an entity to `pos` (wrapped to the world) and stop it. Returns false if the
+    /// entity is no longer alive.
+    pub fn teleport_entity(&mut self, id: EntityId, pos: Vec2) -> bool {
+        let pos = self.world.wrap(pos);
+        match self.arena.get_mut(id) {
+            Some(entity) => {
+                entity.pos = pos;
+                entity.prev_pos = pos;
+                entity.velocity = Vec2::ZERO;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Overwrite one body gene of a living entity and re-derive its body attributes.
+    /// Returns false if the entity is no longer alive.
+    pub fn set_body_gene(&mut self, id: EntityId, offset: usize, value: f32) -> bool {
+        let slot = id.index as usize;
+        let (Some(entity), Some(Some(genome))) = (self.arena.get_mut(id), self.genomes.get_mut(slot)) else {
+            return false;
+        };
+        genome.set_body_gene(offset, value);
+        entity.apply_genome(genome);
+        // The brain keeps its own copy of the learning rate, taken at spawn
+        if slot < self.brains.learning_rates.len() {
+            self.brains.learning_rates[slot] = genome.learning_rate();
+        }
+        true
+    }
+
     /// Food sharing: entities with signal intensity > 0.7 share energy with closest neighbor
-    fn process_food_sharing(&mut self) {
+    fn process_food_sharing(&mut self, ledger: &mut EnergyLedger, dt: f32) {
         let share_range = config::ATTACK_RANGE * 2.0; // slightly larger than attack range
-        let share_amount = 5.0;
+        let base_share = 5.0; // per tick at the base rate
+        let share_amount = base_share * dt * config::BASE_SIM_HZ;
         let signal_threshold = 0.7;
 
-        // Collect sharing intents: (giver_idx, receiver_idx)
-        let mut shares: Vec<(usize, usize)> = Vec::new();
-
-        for (idx, entity) in self.arena.entities.iter().enumerate() {
-            let entity = match entity {
-                Some(e) => e,
-                None => continue,
-            };
+        // Collect sharing intents: (giver_idx, receiver_idx, receiver is kin)
+        let mut shares: Vec<(usize, usize, bool)> = Vec::new();
 
+        for (idx, entity) in self.arena.iter_alive() {
             // Check if entity's signal output is high enough to indicate sharing intent
             if idx >= self.signals.len() {
                 continue;
@@ -278,11 +826,11 @@ impl SimState {
             }
 
             // Must have enough energy to share
-            if entity.energy < share_amount * 2.0 {
+            if entity.energy < base_share * 2.0 {
                 continue;
             }
 
-            // Find closest neighbor
+            // Share with the closest neighbor; nepotists count strangers as farther away than kin
             let neighbors = self.spatial_hash.query_radius_excluding(
                 entity.pos,
                 share_range,
@@ -290,24 +838,45 @@ impl SimState {
                 &self.world,
                 &self.arena,
             );
-
-            if let Some(&neighbor_idx) = neighbors.first() {
-                shares.push((idx, neighbor_idx as usize));
+            let genome = self.genomes.get(idx).and_then(|g| g.as_ref());
+            let recipient = neighbors
+                .iter()
+                .filter_map(|&n| {
+                    let neighbor = self.arena.entities.get(n as usize)?.as_ref()?;
+                    let genetic_distance = match (genome, self.genomes.get(n as usize).and_then(|g| g.as_ref())) {
+                        (Some(a), Some(b)) => a.distance(b) / config::SPECIES_DISTANCE_THRESHOLD,
+                        _ => 1.0,
+                    };
+                    let bias = config::SHARE_NEPOTISM_BIAS * entity.nepotism * genetic_distance;
+                    let score = self.world.distance(entity.pos, neighbor.pos) * (1.0 + bias);
+                    Some((n as usize, score, genetic_distance < config::SHARE_KIN_DISTANCE))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((neighbor_idx, _, kin)) = recipient {
+                shares.push((idx, neighbor_idx, kin));
             }
         }
 
         // Apply shares (two-pass to avoid double mutable borrow)
-        for (giver, receiver) in shares {
+        for (giver, receiver, kin) in shares {
             let can_give = self.arena.entities.get(giver)
                 .and_then(|e| e.as_ref())
-                .map(|e| e.energy > share_amount * 2.0)
+                .map(|e| e.energy > base_share * 2.0)
                 .unwrap_or(false);
             if can_give {
                 if let Some(Some(giver_e)) = self.arena.entities.get_mut(giver) {
                     giver_e.energy -= share_amount;
+                    giver_e.energy_donated += share_amount;
+                }
+                if kin {
+                    self.sharing.to_kin += share_amount as f64;
+                } else {
+                    self.sharing.to_strangers += share_amount as f64;
                 }
                 if let Some(Some(receiver_e)) = self.arena.entities.get_mut(receiver) {
-                    receiver_e.energy = (receiver_e.energy + share_amount).min(config::MAX_ENTITY_ENERGY);
+                    receiver_e.energy_received += share_amount;
+                    ledger.capped += energy_audit::add_capped(&mut receiver_e.energy, share_amount) as f64;
                 }
             }
         }
@@ -0,0 +1,139 @@
+use std::fmt::Write as _;
+
+use macroquad::prelude::get_screen_data;
+
+use crate::config;
+use crate::manifest::RunInfo;
+use crate::simulation::SimState;
+use crate::stats;
+use crate::storage;
+
+/// Directory receiving the numbered frames and the report of a snapshot run.
+pub const SNAPSHOT_DIR: &str = "snapshots";
+
+/// When a snapshot run captures: explicit ticks (`--snapshot-ticks 600,1200`) and/or a regular
+/// interval (`--snapshot-every 600 --snapshot-until 36000`).
+#[derive(Debug, Default)]
+pub struct SnapshotPlan {
+    pub ticks: Vec<u64>,
+    /// Capture every N ticks...
+    pub every: Option<u64>,
+    /// ...up to and including this tick.
+    pub until: Option<u64>,
+}
+
+impl SnapshotPlan {
+    /// All capture ticks, ascending and without duplicates.
+    pub fn targets(&self) -> Vec<u64> {
+        let mut targets = self.ticks.clone();
+        if let (Some(every), Some(until)) = (self.every, self.until) {
+            targets.extend((1..=until / every).map(|i| i * every));
+        }
+        targets.sort_unstable();
+        targets.dedup();
+        targets
+    }
+}
+
+/// A windowed run that fast-forwards to each capture tick, saves the screen as a numbered PNG
+/// and samples the population, then exits after the last capture.
+pub struct SnapshotRun {
+    every: Option<u64>,
+    until: Option<u64>,
+    targets: Vec<u64>,
+    next: usize,
+    last_tick: u64,
+    rows: Vec<String>,
+}
+
+impl SnapshotRun {
+    pub fn new(plan: &SnapshotPlan) -> Result<Self, String> {
+        storage::create_dir_all(SNAPSHOT_DIR).map_err(|e| format!("Cannot create {SNAPSHOT_DIR}/: {e}"))?;
+        Ok(Self {
+            every: plan.every,
+            until: plan.until,
+            targets: plan.targets(),
+            next: 0,
+            last_tick: 0,
+            rows: Vec::new(),
+        })
+    }
+
+    /// Ticks to run this frame: up to the next capture, at most SNAPSHOT_MAX_TICKS_PER_FRAME.
+    pub fn ticks_this_frame(&self, tick: u64) -> u64 {
+        self.targets
+            .get(self.next)
+            .map_or(0, |&target| target.saturating_sub(tick).min(config::SNAPSHOT_MAX_TICKS_PER_FRAME))
+    }
+
+    pub fn is_due(&self, tick: u64) -> bool {
+        self.targets.get(self.next).is_some_and(|&target| target <= tick)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.targets.len()
+    }
+
+    /// Save the current screen as the next frame and record a sample row. Returns the file path.
+    pub fn capture(&mut self, sim: &SimState) -> String {
+        let frame = self.next + 1;
+        let path = format!("{SNAPSHOT_DIR}/frame_{frame:05}.png");
+        if let Err(e) = crate::capture::write_png(&path, &get_screen_data()) {
+            eprintln!("[GENESIS] Snapshot frame failed: {e}");
+        }
+
+        let (mut energy, mut generation) = (0.0f32, 0.0f32);
+        for (_idx, e) in sim.arena.iter_alive() {
+            energy += e.energy;
+            generation += e.generation_depth as f32;
+        }
+        let n = sim.arena.count.max(1) as f32;
+        let interneurons = stats::interneuron_distribution(&sim.arena, &sim.genomes);
+        let weighted: u32 = interneurons.iter().enumerate().map(|(k, &c)| k as u32 * c).sum();
+        let mut row = format!(
+            "{frame},{},{:.2},{},{path},{},{},{:.2},{:.2},{}",
+            sim.tick_count,
+            sim.tick_count as f32 * sim.dt,
+            sim.tick_count - self.last_tick,
+            sim.arena.count,
+            sim.food.len(),
+            energy / n,
+            generation / n,
+            sim.species.species.len()
+        );
+        for count in &interneurons {
+            let _ = write!(row, ",{count}");
+        }
+        let _ = write!(row, ",{:.3}", weighted as f32 / n);
+        self.rows.push(row);
+        self.last_tick = sim.tick_count;
+        self.next += 1;
+        path
+    }
+
+    /// Write the sample report next to the frames, with the run's provenance in its header.
+    /// Returns its path.
+    pub fn write_report(&self, header: &str, run_info: &RunInfo) -> Result<String, String> {
+        let path = format!("{SNAPSHOT_DIR}/snapshots.csv");
+        let interval = match (self.every, self.until) {
+            (Some(every), Some(until)) => format!("every={every} until={until}"),
+            _ => "every=none until=none".to_string(),
+        };
+        let mut csv = format!(
+            "{header}\n# run_info {}\n# snapshot {interval} frames={}\n",
+            run_info.to_json(),
+            self.rows.len()
+        );
+        csv.push_str("frame,tick,sim_seconds,interval_ticks,file,population,food,avg_energy,avg_generation,species");
+        for k in 0..=config::BRAIN_INTERNEURONS {
+            let _ = write!(csv, ",interneurons_{k}");
+        }
+        csv.push_str(",mean_interneurons\n");
+        for row in &self.rows {
+            csv.push_str(row);
+            csv.push('\n');
+        }
+        storage::write(&path, csv).map_err(|e| format!("Write error: {e}"))?;
+        Ok(path)
+    }
+}
@@ -30,12 +30,10 @@ impl SpatialHash {
         for cell in &mut self.cells {
             cell.clear();
         }
-        for (idx, entity) in arena.entities.iter().enumerate() {
-            if let Some(e) = entity {
-                let cx = ((e.pos.x * self.inv_cell_size) as usize).min(self.cols - 1);
-                let cy = ((e.pos.y * self.inv_cell_size) as usize).min(self.rows - 1);
-                self.cells[cy * self.cols + cx].push(idx as u32);
-            }
+        for (idx, e) in arena.iter_alive() {
+            let cx = ((e.pos.x * self.inv_cell_size) as usize).min(self.cols - 1);
+            let cy = ((e.pos.y * self.inv_cell_size) as usize).min(self.rows - 1);
+            self.cells[cy * self.cols + cx].push(idx as u32);
         }
     }
 
@@ -85,6 +83,40 @@ impl SpatialHash {
         result
     }
 
+    /// Query all entity indices inside `rect`, in world coordinates (regions do not wrap).
+    pub fn query_rect(&self, rect: Rect, arena: &EntityArena) -> Vec<u32> {
+        let mut result = Vec::new();
+        // One cell of margin catches entities that crossed a cell border since the last rebuild
+        let cell = |v: f32, n: usize| ((v.max(0.0) * self.inv_cell_size) as usize).min(n - 1);
+        let (x0, x1) = (cell(rect.x - self.cell_size, self.cols), cell(rect.right() + self.cell_size, self.cols));
+        let (y0, y1) = (cell(rect.y - self.cell_size, self.rows), cell(rect.bottom() + self.cell_size, self.rows));
+        for gy in y0..=y1 {
+            for gx in x0..=x1 {
+                for &entity_idx in &self.cells[gy * self.cols + gx] {
+                    if let Some(e) = arena.get_by_index(entity_idx as usize) {
+                        if rect.contains(e.pos) {
+                            result.push(entity_idx);
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Query all entity indices inside the polygon with vertices `points` (in order, implicitly
+    /// closed). Self-intersecting polygons use the even-odd rule.
+    pub fn query_polygon(&self, points: &[Vec2], arena: &EntityArena) -> Vec<u32> {
+        if points.len() < 3 {
+            return Vec::new();
+        }
+        let min = points.iter().copied().fold(Vec2::splat(f32::INFINITY), Vec2::min);
+        let max = points.iter().copied().fold(Vec2::splat(f32::NEG_INFINITY), Vec2::max);
+        let mut result = self.query_rect(Rect::new(min.x, min.y, max.x - min.x, max.y - min.y), arena);
+        result.retain(|&idx| arena.get_by_index(idx as usize).is_some_and(|e| point_in_polygon(e.pos, points)));
+        result
+    }
+
     /// Query all entity indices within `radius` of `pos`, excluding a specific index.
     pub fn query_radius_excluding(
         &self,
@@ -99,3 +131,17 @@ impl SpatialHash {
         result
     }
 }
+
+/// Whether `p` lies inside the polygon `points` (even-odd rule).
+pub fn point_in_polygon(p: Vec2, points: &[Vec2]) -> bool {
+    let mut inside = false;
+    let mut j = points.len().wrapping_sub(1);
+    for (i, a) in points.iter().enumerate() {
+        let b = points[j];
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
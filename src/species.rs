@@ -0,0 +1,114 @@
+use macroquad::prelude::*;
+
+use crate::config;
+use crate::entity::EntityArena;
+use crate::genome::Genome;
+
+/// A cluster of genetically similar entities, identified by a stable ID.
+pub struct Species {
+    pub id: u32,
+    /// Genome of the founding member; new entities join the first species within range of it.
+    pub representative: Genome,
+    pub member_count: usize,
+    /// Largest member count seen at any update.
+    pub peak_member_count: usize,
+    pub first_seen_tick: u64,
+}
+
+/// Palette color of a species. Hues step by the golden ratio, so consecutive IDs stay far
+/// apart and a species keeps its color for as long as it exists.
+pub fn species_color(id: u32) -> Color {
+    let hue = (id as f32 * 0.618_034).fract();
+    macroquad::color::hsl_to_rgb(hue, 0.75, 0.55)
+}
+
+/// Color of entities born since the last species update.
+pub const UNASSIGNED_COLOR: Color = Color::new(0.55, 0.55, 0.6, 1.0);
+
+/// Leader-clustering species assignment, refreshed every SPECIES_UPDATE_INTERVAL ticks.
+pub struct SpeciesTracker {
+    pub species: Vec<Species>,
+    /// Species ID per entity slot (None for empty slots or entities born since the last update).
+    pub assignments: Vec<Option<u32>>,
+    next_id: u32,
+}
+
+impl SpeciesTracker {
+    pub fn new() -> Self {
+        Self {
+            species: Vec::new(),
+            assignments: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Re-cluster the population if an update is due, returning the species that went extinct.
+    pub fn update(&mut self, arena: &EntityArena, genomes: &[Option<Genome>], tick: u64) -> Vec<Species> {
+        if !tick.is_multiple_of(config::SPECIES_UPDATE_INTERVAL) {
+            return Vec::new();
+        }
+        self.recluster(arena, genomes, tick)
+    }
+
+    /// Assign every alive entity to the first species whose representative is within
+    /// SPECIES_DISTANCE_THRESHOLD, founding a new species otherwise. Empty species go extinct
+    /// and are returned.
+    pub fn recluster(&mut self, arena: &EntityArena, genomes: &[Option<Genome>], tick: u64) -> Vec<Species> {
+        self.assignments.clear();
+        self.assignments.resize(arena.entities.len(), None);
+        for s in &mut self.species {
+            s.member_count = 0;
+        }
+
+        for (idx, _entity) in arena.iter_alive() {
+            let Some(Some(genome)) = genomes.get(idx) else { continue };
+            let existing = self
+                .species
+                .iter_mut()
+                .find(|s| s.representative.distance(genome) < config::SPECIES_DISTANCE_THRESHOLD);
+            let id = match existing {
+                Some(s) => {
+                    s.member_count += 1;
+                    s.id
+                }
+                None => {
+                    let id = self.next_id;
+                    self.next_id += 1;
+                    self.species.push(Species {
+                        id,
+                        representative: genome.clone(),
+                        member_count: 1,
+                        peak_member_count: 1,
+                        first_seen_tick: tick,
+                    });
+                    id
+                }
+            };
+            self.assignments[idx] = Some(id);
+        }
+
+        for s in &mut self.species {
+            s.peak_member_count = s.peak_member_count.max(s.member_count);
+        }
+        let (alive, extinct) = std::mem::take(&mut self.species).into_iter().partition(|s| s.member_count > 0);
+        self.species = alive;
+        extinct
+    }
+
+    pub fn species_of(&self, slot: usize) -> Option<u32> {
+        self.assignments.get(slot).copied().flatten()
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Species> {
+        self.species.iter().find(|s| s.id == id)
+    }
+
+    /// Positions of all alive members of a species.
+    pub fn member_positions(&self, arena: &EntityArena, id: u32) -> Vec<Vec2> {
+        arena
+            .iter_alive()
+            .filter(|(idx, _)| self.species_of(*idx) == Some(id))
+            .map(|(_, e)| e.pos)
+            .collect()
+    }
+}
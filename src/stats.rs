@@ -1,5 +1,15 @@
 /// Rolling statistics for population tracking and graph display.
 
+use std::collections::VecDeque;
+
+use crate::care::JuvenileCounts;
+use crate::config;
+use crate::entity::EntityArena;
+use crate::genetics::GeneticsStats;
+use crate::genome::Genome;
+use crate::simulation::{SharingTotals, SimState};
+use crate::storage;
+
 /// Ring buffer that stores the last N samples of a metric.
 pub struct RingBuffer {
     data: Vec<f32>,
@@ -40,6 +50,11 @@ impl RingBuffer {
         self.len
     }
 
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
     pub fn last(&self) -> Option<f32> {
         if self.len == 0 {
             None
@@ -50,6 +65,287 @@ impl RingBuffer {
     }
 }
 
+/// Distribution of one per-entity metric over time: min, median, 95th percentile and max
+/// of the living population at each sample.
+pub struct QuantileSeries {
+    pub min: RingBuffer,
+    pub p50: RingBuffer,
+    pub p95: RingBuffer,
+    pub max: RingBuffer,
+}
+
+impl QuantileSeries {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            min: RingBuffer::new(capacity),
+            p50: RingBuffer::new(capacity),
+            p95: RingBuffer::new(capacity),
+            max: RingBuffer::new(capacity),
+        }
+    }
+
+    /// Push the quantiles of `values` (sorted in place). An empty sample records zeros.
+    pub fn push(&mut self, values: &mut [f32]) {
+        values.sort_by(|a, b| a.total_cmp(b));
+        let at = |q: f32| {
+            if values.is_empty() {
+                0.0
+            } else {
+                values[((values.len() - 1) as f32 * q).round() as usize]
+            }
+        };
+        self.min.push(at(0.0));
+        self.p50.push(at(0.5));
+        self.p95.push(at(0.95));
+        self.max.push(at(1.0));
+    }
+}
+
+/// Distribution of one genetic body trait over a fixed value range.
+pub struct TraitHistogram {
+    pub name: &'static str,
+    pub min: f32,
+    pub max: f32,
+    /// Entities per bin; bins split [min, max] evenly.
+    pub counts: Vec<u32>,
+    pub mean: f32,
+}
+
+impl TraitHistogram {
+    fn new(name: &'static str, min: f32, max: f32, bins: usize) -> Self {
+        Self {
+            name,
+            min,
+            max,
+            counts: vec![0; bins],
+            mean: 0.0,
+        }
+    }
+
+    fn fill(&mut self, values: impl Iterator<Item = f32>) {
+        self.counts.fill(0);
+        let (mut sum, mut n) = (0.0, 0);
+        let bins = self.counts.len();
+        for v in values {
+            let t = (v - self.min) / (self.max - self.min);
+            let bin = ((t * bins as f32) as usize).min(bins - 1);
+            self.counts[bin] += 1;
+            sum += v;
+            n += 1;
+        }
+        self.mean = sum / n.max(1) as f32;
+    }
+}
+
+/// Live histograms of the population's genetic body traits, refreshed every
+/// TRAIT_HISTOGRAM_INTERVAL ticks. The ranges are the traits' full genetic ranges, so shifts
+/// in the distribution show up as movement across a fixed axis.
+pub struct TraitHistograms {
+    /// Tick of the last sample.
+    pub tick: u64,
+    pub traits: Vec<TraitHistogram>,
+}
+
+impl Default for TraitHistograms {
+    fn default() -> Self {
+        let bins = config::TRAIT_HISTOGRAM_BINS;
+        let interneurons = config::BRAIN_INTERNEURONS;
+        Self {
+            tick: 0,
+            traits: vec![
+                TraitHistogram::new("Size", 0.6, 1.6, bins),
+                TraitHistogram::new("Speed", 0.5, 1.5, bins),
+                TraitHistogram::new("Sensor range", 0.5, 1.5, bins),
+                TraitHistogram::new("Metabolic rate", 0.5, 1.5, bins),
+                TraitHistogram::new("Interneurons in use", 0.0, (interneurons + 1) as f32, interneurons + 1),
+            ],
+        }
+    }
+}
+
+impl TraitHistograms {
+    pub fn record(&mut self, arena: &EntityArena, genomes: &[Option<Genome>], tick: u64) {
+        if !tick.is_multiple_of(config::TRAIT_HISTOGRAM_INTERVAL) {
+            return;
+        }
+        self.tick = tick;
+        let living: Vec<&Genome> = arena
+            .iter_alive()
+            .filter_map(|(idx, _)| genomes.get(idx).and_then(|g| g.as_ref()))
+            .collect();
+        let decoders: [fn(&Genome) -> f32; 5] = [
+            Genome::body_size,
+            Genome::max_speed,
+            Genome::sensor_range,
+            Genome::metabolic_rate,
+            |g| interneurons_in_use(g) as f32,
+        ];
+        for (histogram, decode) in self.traits.iter_mut().zip(decoders) {
+            histogram.fill(living.iter().map(|g| decode(g)));
+        }
+    }
+}
+
+/// Interneurons whose mean absolute weight onto the motor neurons reaches TRAIT_INTERNEURON_WEIGHT.
+/// The brain always has BRAIN_INTERNEURONS of them; this counts the ones evolution has wired in.
+pub fn interneurons_in_use(genome: &Genome) -> usize {
+    let inter_start = config::BRAIN_SENSOR_NEURONS;
+    let motor_start = inter_start + config::BRAIN_INTERNEURONS;
+    (inter_start..motor_start)
+        .filter(|&i| {
+            let total: f32 = (motor_start..config::BRAIN_NEURONS).map(|m| genome.weight(m, i).abs()).sum();
+            total / config::BRAIN_MOTOR_NEURONS as f32 >= config::TRAIT_INTERNEURON_WEIGHT
+        })
+        .count()
+}
+
+/// Living entities with each number of interneurons in use (index = count).
+pub fn interneuron_distribution(arena: &EntityArena, genomes: &[Option<Genome>]) -> Vec<u32> {
+    let mut counts = vec![0; config::BRAIN_INTERNEURONS + 1];
+    for (idx, _) in arena.iter_alive() {
+        if let Some(Some(genome)) = genomes.get(idx) {
+            counts[interneurons_in_use(genome)] += 1;
+        }
+    }
+    counts
+}
+
+/// Interneuron counts of one species at the last sample.
+pub struct SpeciesInterneurons {
+    pub species: u32,
+    /// Members with each number of interneurons in use (index = count).
+    pub counts: Vec<u32>,
+}
+
+impl SpeciesInterneurons {
+    pub fn mean(&self) -> f32 {
+        let weighted: u32 = self.counts.iter().enumerate().map(|(n, &c)| n as u32 * c).sum();
+        weighted as f32 / self.counts.iter().sum::<u32>().max(1) as f32
+    }
+}
+
+/// How many interneurons brains have wired into their motors, over time and per species,
+/// sampled every INTERNEURON_SAMPLE_INTERVAL ticks, to show whether brains are growing or
+/// shrinking.
+pub struct InterneuronStats {
+    /// Percentage of the population with each number of interneurons in use (index = count).
+    pub shares: Vec<RingBuffer>,
+    pub mean: RingBuffer,
+    /// Latest breakdown for each species, largest first.
+    pub per_species: Vec<SpeciesInterneurons>,
+    /// Tick of the last sample.
+    pub tick: u64,
+}
+
+impl InterneuronStats {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            shares: (0..=config::BRAIN_INTERNEURONS).map(|_| RingBuffer::new(capacity)).collect(),
+            mean: RingBuffer::new(capacity),
+            per_species: Vec::new(),
+            tick: 0,
+        }
+    }
+
+    pub fn record(&mut self, sim: &SimState) {
+        if !sim.tick_count.is_multiple_of(config::INTERNEURON_SAMPLE_INTERVAL) {
+            return;
+        }
+        self.tick = sim.tick_count;
+        let counts = interneuron_distribution(&sim.arena, &sim.genomes);
+        let total = counts.iter().sum::<u32>().max(1) as f32;
+        for (share, &count) in self.shares.iter_mut().zip(&counts) {
+            share.push(count as f32 / total * 100.0);
+        }
+        let weighted: u32 = counts.iter().enumerate().map(|(n, &c)| n as u32 * c).sum();
+        self.mean.push(weighted as f32 / total);
+
+        self.per_species.clear();
+        for (idx, _) in sim.arena.iter_alive() {
+            let (Some(species), Some(Some(genome))) = (sim.species.species_of(idx), sim.genomes.get(idx)) else {
+                continue;
+            };
+            let row = match self.per_species.iter().position(|s| s.species == species) {
+                Some(i) => &mut self.per_species[i],
+                None => {
+                    self.per_species.push(SpeciesInterneurons {
+                        species,
+                        counts: vec![0; config::BRAIN_INTERNEURONS + 1],
+                    });
+                    self.per_species.last_mut().unwrap()
+                }
+            };
+            row.counts[interneurons_in_use(genome)] += 1;
+        }
+        self.per_species
+            .sort_by(|a, b| b.counts.iter().sum::<u32>().cmp(&a.counts.iter().sum::<u32>()).then(a.species.cmp(&b.species)));
+    }
+}
+
+/// Population count per generation depth, refreshed every GENERATION_HISTOGRAM_INTERVAL ticks.
+#[derive(Default)]
+pub struct GenerationHistogram {
+    /// Tick of the last sample.
+    pub tick: u64,
+    /// Entities at each generation depth (index = depth).
+    pub counts: Vec<u32>,
+    pub last_export: Option<String>,
+}
+
+impl GenerationHistogram {
+    pub fn record(&mut self, arena: &EntityArena, tick: u64) {
+        if !tick.is_multiple_of(config::GENERATION_HISTOGRAM_INTERVAL) {
+            return;
+        }
+        self.tick = tick;
+        self.counts.clear();
+        for (_, e) in arena.iter_alive() {
+            let depth = e.generation_depth as usize;
+            if depth >= self.counts.len() {
+                self.counts.resize(depth + 1, 0);
+            }
+            self.counts[depth] += 1;
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+
+    pub fn mean(&self) -> f32 {
+        let weighted: u64 = self.counts.iter().enumerate().map(|(d, &c)| d as u64 * c as u64).sum();
+        weighted as f32 / self.total().max(1) as f32
+    }
+
+    /// Generation depth below which half the population lies.
+    pub fn median(&self) -> usize {
+        let half = self.total().div_ceil(2);
+        let mut seen = 0;
+        for (depth, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= half {
+                return depth;
+            }
+        }
+        0
+    }
+
+    /// Share of the population that is an original spawn (depth 0), in [0, 1].
+    pub fn fresh_fraction(&self) -> f32 {
+        self.counts.first().copied().unwrap_or(0) as f32 / self.total().max(1) as f32
+    }
+
+    /// Write `generation,count` rows, with the run header as the first line.
+    pub fn export_csv(&self, path: &str, header: &str) -> Result<usize, String> {
+        let mut csv = format!("{header}\ngeneration,count\n");
+        for (depth, count) in self.counts.iter().enumerate() {
+            csv.push_str(&format!("{depth},{count}\n"));
+        }
+        storage::write(path, csv).map_err(|e| format!("Write error: {e}"))?;
+        Ok(self.counts.len())
+    }
+}
+
 /// All tracked simulation statistics.
 pub struct SimStats {
     pub population: RingBuffer,
@@ -58,6 +354,51 @@ pub struct SimStats {
     pub births: RingBuffer,
     pub deaths: RingBuffer,
     pub avg_generation: RingBuffer,
+    pub energy_quantiles: QuantileSeries,
+    /// Age in seconds.
+    pub age_quantiles: QuantileSeries,
+    /// Body size multiplier (radius relative to the base radius).
+    pub size_quantiles: QuantileSeries,
+    /// Gene-pool diversity, sampled on its own interval.
+    pub genetics: GeneticsStats,
+    /// Percentage of bred juveniles reaching CARE_DURATION, over the last
+    /// JUVENILE_SURVIVAL_WINDOW samples.
+    pub juvenile_survival: RingBuffer,
+    /// Mean care gene of the population, in percent.
+    pub avg_care: RingBuffer,
+    /// Mean attack power multiplier of the population.
+    pub avg_attack: RingBuffer,
+    /// Mean armor gene of the population [0, 1].
+    pub avg_armor: RingBuffer,
+    /// Mean ranged gene of the population [0, 1].
+    pub avg_ranged: RingBuffer,
+    /// Percentage of shared energy given to kin over the last SHARING_WINDOW samples.
+    pub kin_sharing: RingBuffer,
+    /// Energy shared per simulated second over the last SHARING_WINDOW samples.
+    pub sharing_rate: RingBuffer,
+    /// Mean nepotism gene of the population, in percent.
+    pub avg_nepotism: RingBuffer,
+    /// Mean toxin resistance gene of the population, in percent.
+    pub avg_toxin_resistance: RingBuffer,
+    /// Mean storm resistance gene of the population, in percent.
+    pub avg_storm_resistance: RingBuffer,
+    /// Juvenile outcome totals at each of the last JUVENILE_SURVIVAL_WINDOW samples.
+    juvenile_history: VecDeque<JuvenileCounts>,
+    /// Sharing totals at each of the last SHARING_WINDOW samples.
+    sharing_history: VecDeque<SharingTotals>,
+    /// Generation-depth histogram, sampled on its own interval.
+    pub generations: GenerationHistogram,
+    /// Body-trait histograms, sampled on their own interval.
+    pub traits: TraitHistograms,
+    /// Interneuron counts over time and per species.
+    pub interneurons: InterneuronStats,
+    /// Average energy of the tagged entities matching `tag_filter` (0 when there are none).
+    pub tagged_energy: RingBuffer,
+    pub tagged_count: RingBuffer,
+    /// Tag the tagged series follow; None follows every tagged entity.
+    pub tag_filter: Option<String>,
+    /// Distinct tags in the population at the last sample, sorted.
+    pub known_tags: Vec<String>,
 
     // Per-tick accumulators
     pub births_this_tick: u32,
@@ -67,6 +408,15 @@ pub struct SimStats {
 }
 
 impl SimStats {
+    /// Follow a different tag. The tagged series restart, since their history was of the old filter.
+    pub fn set_tag_filter(&mut self, filter: Option<String>) {
+        if filter != self.tag_filter {
+            self.tag_filter = filter;
+            self.tagged_energy.clear();
+            self.tagged_count.clear();
+        }
+    }
+
     pub fn new(capacity: usize) -> Self {
         Self {
             population: RingBuffer::new(capacity),
@@ -75,6 +425,29 @@ impl SimStats {
             births: RingBuffer::new(capacity),
             deaths: RingBuffer::new(capacity),
             avg_generation: RingBuffer::new(capacity),
+            energy_quantiles: QuantileSeries::new(capacity),
+            age_quantiles: QuantileSeries::new(capacity),
+            size_quantiles: QuantileSeries::new(capacity),
+            genetics: GeneticsStats::new(capacity),
+            juvenile_survival: RingBuffer::new(capacity),
+            avg_care: RingBuffer::new(capacity),
+            avg_attack: RingBuffer::new(capacity),
+            avg_armor: RingBuffer::new(capacity),
+            avg_ranged: RingBuffer::new(capacity),
+            kin_sharing: RingBuffer::new(capacity),
+            sharing_rate: RingBuffer::new(capacity),
+            avg_nepotism: RingBuffer::new(capacity),
+            avg_toxin_resistance: RingBuffer::new(capacity),
+            avg_storm_resistance: RingBuffer::new(capacity),
+            juvenile_history: VecDeque::new(),
+            sharing_history: VecDeque::new(),
+            generations: GenerationHistogram::default(),
+            traits: TraitHistograms::default(),
+            interneurons: InterneuronStats::new(capacity),
+            tagged_energy: RingBuffer::new(capacity),
+            tagged_count: RingBuffer::new(capacity),
+            tag_filter: None,
+            known_tags: Vec::new(),
             births_this_tick: 0,
             deaths_this_tick: 0,
             sample_interval: 10, // sample every N ticks
@@ -83,17 +456,14 @@ impl SimStats {
     }
 
     /// Record a sample from the current simulation state.
-    pub fn record(
-        &mut self,
-        entity_count: usize,
-        avg_energy: f32,
-        food_count: usize,
-        avg_generation: f32,
-    ) {
+    pub fn record(&mut self, sim: &SimState) {
         self.tick_counter += 1;
         if self.tick_counter % self.sample_interval != 0 {
             return;
         }
+        let (avg_energy, avg_generation) = averages(sim);
+        let (entity_count, food_count, arena) = (sim.arena.count, sim.food.len(), &sim.arena);
+        let (juveniles, sharing, dt) = (sim.juveniles, sim.sharing, sim.dt);
 
         self.population.push(entity_count as f32);
         self.avg_energy.push(avg_energy);
@@ -102,7 +472,91 @@ impl SimStats {
         self.deaths.push(self.deaths_this_tick as f32);
         self.avg_generation.push(avg_generation);
 
+        let alive: Vec<_> = arena.iter_alive().map(|(_, e)| e).collect();
+        let mut values: Vec<f32> = alive.iter().map(|e| e.energy).collect();
+        self.energy_quantiles.push(&mut values);
+        let mut values: Vec<f32> = alive.iter().map(|e| e.age).collect();
+        self.age_quantiles.push(&mut values);
+        let mut values: Vec<f32> = alive.iter().map(|e| e.radius / config::ENTITY_BASE_RADIUS).collect();
+        self.size_quantiles.push(&mut values);
+
+        let care: f32 = alive.iter().map(|e| e.care).sum();
+        self.avg_care.push(care / alive.len().max(1) as f32 * 100.0);
+        let attack: f32 = alive.iter().map(|e| e.attack_power).sum();
+        self.avg_attack.push(attack / alive.len().max(1) as f32);
+        let armor: f32 = alive.iter().map(|e| e.armor).sum();
+        self.avg_armor.push(armor / alive.len().max(1) as f32);
+        let ranged: f32 = alive.iter().map(|e| e.ranged).sum();
+        self.avg_ranged.push(ranged / alive.len().max(1) as f32);
+        let toxin: f32 = alive.iter().map(|e| e.toxin_resistance).sum();
+        self.avg_toxin_resistance.push(toxin / alive.len().max(1) as f32 * 100.0);
+        let storm: f32 = alive.iter().map(|e| e.storm_resistance).sum();
+        self.avg_storm_resistance.push(storm / alive.len().max(1) as f32 * 100.0);
+        if self.juvenile_history.len() >= config::JUVENILE_SURVIVAL_WINDOW {
+            self.juvenile_history.pop_front();
+        }
+        self.juvenile_history.push_back(juveniles);
+        let oldest = self.juvenile_history.front().copied().unwrap_or(juveniles);
+        // Counts restart when a different save is loaded
+        let matured = juveniles.matured.saturating_sub(oldest.matured);
+        let died = juveniles.died.saturating_sub(oldest.died);
+        if matured + died > 0 {
+            self.juvenile_survival.push(matured as f32 / (matured + died) as f32 * 100.0);
+        } else {
+            self.juvenile_survival.push(self.juvenile_survival.last().unwrap_or(0.0));
+        }
+
+        let nepotism: f32 = alive.iter().map(|e| e.nepotism).sum();
+        self.avg_nepotism.push(nepotism / alive.len().max(1) as f32 * 100.0);
+        if self.sharing_history.len() >= config::SHARING_WINDOW {
+            self.sharing_history.pop_front();
+        }
+        self.sharing_history.push_back(sharing);
+        let oldest = self.sharing_history.front().copied().unwrap_or(sharing);
+        // Totals restart when a different save is loaded
+        let to_kin = (sharing.to_kin - oldest.to_kin).max(0.0);
+        let to_strangers = (sharing.to_strangers - oldest.to_strangers).max(0.0);
+        let window_secs = (self.sharing_history.len().saturating_sub(1) * self.sample_interval as usize) as f32 * dt;
+        self.sharing_rate.push(((to_kin + to_strangers) as f32 / window_secs.max(dt)).max(0.0));
+        if to_kin + to_strangers > 0.0 {
+            self.kin_sharing.push((to_kin / (to_kin + to_strangers) * 100.0) as f32);
+        } else {
+            self.kin_sharing.push(self.kin_sharing.last().unwrap_or(0.0));
+        }
+
+        let tagged: Vec<f32> = alive
+            .iter()
+            .filter(|e| match (&e.tag, &self.tag_filter) {
+                (Some(tag), Some(filter)) => tag == filter,
+                (Some(_), None) => true,
+                (None, _) => false,
+            })
+            .map(|e| e.energy)
+            .collect();
+        self.tagged_count.push(tagged.len() as f32);
+        self.tagged_energy.push(tagged.iter().sum::<f32>() / tagged.len().max(1) as f32);
+        self.known_tags = alive.iter().filter_map(|e| e.tag.clone()).collect();
+        self.known_tags.sort();
+        self.known_tags.dedup();
+
         self.births_this_tick = 0;
         self.deaths_this_tick = 0;
     }
 }
+
+/// Mean energy and generation depth of the living (zeros when empty).
+pub fn averages(sim: &SimState) -> (f32, f32) {
+    let mut total_energy = 0.0f32;
+    let mut total_gen = 0.0f32;
+    let mut count = 0u32;
+    for (_idx, e) in sim.arena.iter_alive() {
+        total_energy += e.energy;
+        total_gen += e.generation_depth as f32;
+        count += 1;
+    }
+    if count > 0 {
+        (total_energy / count as f32, total_gen / count as f32)
+    } else {
+        (0.0, 0.0)
+    }
+}
@@ -0,0 +1,163 @@
+// File storage used for saves, preferences, libraries and exports. Native builds use the file
+// system; wasm32 builds keep every "file" in the browser's localStorage, keyed by path, through
+// the `genesis_storage` plugin in `web/genesis_storage.js`.
+
+use std::io;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    use std::fs::File;
+    use std::io::{self, BufWriter};
+
+    /// Buffered writer for a file being created.
+    pub type Writer = BufWriter<File>;
+
+    pub fn read(path: &str) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    pub fn write(path: &str, bytes: &[u8]) -> io::Result<()> {
+        std::fs::write(path, bytes)
+    }
+
+    pub fn create(path: &str) -> io::Result<Writer> {
+        File::create(path).map(BufWriter::new)
+    }
+
+    pub fn rename(from: &str, to: &str) -> io::Result<()> {
+        std::fs::rename(from, to)
+    }
+
+    pub fn remove(path: &str) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    pub fn exists(path: &str) -> bool {
+        std::path::Path::new(path).exists()
+    }
+
+    pub fn create_dir_all(path: &str) -> io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    use std::io::{self, Write};
+
+    extern "C" {
+        /// Length of the stored value, or -1 if there is none.
+        fn genesis_storage_len(key: *const u8, key_len: usize) -> i32;
+        /// Copy the stored value into `out` (which holds at least `genesis_storage_len` bytes).
+        fn genesis_storage_get(key: *const u8, key_len: usize, out: *mut u8);
+        /// Returns 0 if the browser refused the write (e.g. quota exceeded).
+        fn genesis_storage_set(key: *const u8, key_len: usize, value: *const u8, value_len: usize) -> i32;
+        fn genesis_storage_remove(key: *const u8, key_len: usize);
+    }
+
+    fn not_found(path: &str) -> io::Error {
+        io::Error::new(io::ErrorKind::NotFound, format!("{path} not in browser storage"))
+    }
+
+    /// Writes are buffered in memory and stored as a whole on flush (and when dropped).
+    pub struct Writer {
+        path: String,
+        bytes: Vec<u8>,
+    }
+
+    impl Write for Writer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.bytes.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            write(&self.path, &self.bytes)
+        }
+    }
+
+    impl Drop for Writer {
+        fn drop(&mut self) {
+            let _ = self.flush();
+        }
+    }
+
+    pub fn read(path: &str) -> io::Result<Vec<u8>> {
+        let len = unsafe { genesis_storage_len(path.as_ptr(), path.len()) };
+        if len < 0 {
+            return Err(not_found(path));
+        }
+        let mut bytes = vec![0u8; len as usize];
+        unsafe { genesis_storage_get(path.as_ptr(), path.len(), bytes.as_mut_ptr()) };
+        Ok(bytes)
+    }
+
+    pub fn write(path: &str, bytes: &[u8]) -> io::Result<()> {
+        match unsafe { genesis_storage_set(path.as_ptr(), path.len(), bytes.as_ptr(), bytes.len()) } {
+            0 => Err(io::Error::new(io::ErrorKind::Other, "browser storage is full")),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn create(path: &str) -> io::Result<Writer> {
+        Ok(Writer {
+            path: path.to_string(),
+            bytes: Vec::new(),
+        })
+    }
+
+    pub fn rename(from: &str, to: &str) -> io::Result<()> {
+        let bytes = read(from)?;
+        write(to, &bytes)?;
+        remove(from)
+    }
+
+    pub fn remove(path: &str) -> io::Result<()> {
+        unsafe { genesis_storage_remove(path.as_ptr(), path.len()) };
+        Ok(())
+    }
+
+    pub fn exists(path: &str) -> bool {
+        unsafe { genesis_storage_len(path.as_ptr(), path.len()) >= 0 }
+    }
+
+    /// Keys are flat, so directories need no creating.
+    pub fn create_dir_all(_path: &str) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub use backend::Writer;
+
+pub fn read(path: &str) -> io::Result<Vec<u8>> {
+    backend::read(path)
+}
+
+pub fn read_to_string(path: &str) -> io::Result<String> {
+    String::from_utf8(read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn write(path: &str, bytes: impl AsRef<[u8]>) -> io::Result<()> {
+    backend::write(path, bytes.as_ref())
+}
+
+/// Create (or truncate) `path` for buffered writing.
+pub fn create(path: &str) -> io::Result<Writer> {
+    backend::create(path)
+}
+
+pub fn rename(from: &str, to: &str) -> io::Result<()> {
+    backend::rename(from, to)
+}
+
+pub fn remove(path: &str) -> io::Result<()> {
+    backend::remove(path)
+}
+
+pub fn exists(path: &str) -> bool {
+    backend::exists(path)
+}
+
+pub fn create_dir_all(path: &str) -> io::Result<()> {
+    backend::create_dir_all(path)
+}
@@ -0,0 +1,451 @@
+// Live telemetry: a small WebSocket server (`--telemetry-port 9000`) that streams tick stats
+// and periodic population summaries as JSON text messages, so dashboards and notebooks can
+// watch a long run without touching the UI or files. Sockets are non-blocking and serviced
+// once per frame; a client that falls too far behind is disconnected.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use crate::config;
+use crate::genome::{BODY_GENE_NAMES, BODY_PARAMS_COUNT};
+use crate::manifest::json_string;
+use crate::reporting::json_number;
+use crate::simulation::SimState;
+use crate::stats;
+
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// Largest HTTP upgrade request accepted before the handshake is abandoned.
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+/// Largest client frame accepted. Clients only send control frames (at most 125 bytes).
+const MAX_FRAME_BYTES: usize = 1024;
+/// Unparsed bytes a client may have pending before it is disconnected.
+const MAX_INBOX_BYTES: usize = 16 * 1024;
+/// Connections served at once, open or still handshaking; further clients are turned away.
+const MAX_CLIENTS: usize = 16;
+/// Time a connection has to complete the WebSocket handshake before it is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+/// WebSocket close status: message too big.
+const CLOSE_TOO_BIG: u16 = 1009;
+
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+struct Client {
+    stream: TcpStream,
+    addr: SocketAddr,
+    /// Bytes received but not yet parsed.
+    inbox: Vec<u8>,
+    /// Bytes queued for sending.
+    outbox: Vec<u8>,
+    /// Whether the WebSocket handshake has completed.
+    open: bool,
+    /// Drop the connection once the outbox is flushed.
+    closing: bool,
+    accepted: Instant,
+}
+
+pub struct TelemetryServer {
+    pub port: u16,
+    listener: TcpListener,
+    clients: Vec<Client>,
+    /// `births_total` and `deaths_total` at the last tick message, to count births and deaths
+    /// between messages. None until the first tick is recorded.
+    last_totals: Option<(u64, u64)>,
+}
+
+impl TelemetryServer {
+    /// Listen on `127.0.0.1:port`.
+    pub fn bind(port: u16) -> Result<Self, String> {
+        let listener =
+            TcpListener::bind(("127.0.0.1", port)).map_err(|e| format!("could not listen on port {port}: {e}"))?;
+        listener.set_nonblocking(true).map_err(|e| format!("could not configure the socket: {e}"))?;
+        Ok(Self {
+            port,
+            listener,
+            clients: Vec::new(),
+            last_totals: None,
+        })
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.iter().filter(|c| c.open).count()
+    }
+
+    /// Accept new connections, complete handshakes, answer pings and send queued messages.
+    /// Call once per frame.
+    pub fn poll(&mut self, sim: &SimState) {
+        loop {
+            match self.listener.accept() {
+                Ok((mut stream, addr)) => {
+                    if self.clients.len() >= MAX_CLIENTS {
+                        // Best effort: the socket is still blocking, so this write is immediate
+                        let _ = stream.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                        continue;
+                    }
+                    if stream.set_nonblocking(true).is_ok() {
+                        let _ = stream.set_nodelay(true);
+                        self.clients.push(Client {
+                            stream,
+                            addr,
+                            inbox: Vec::new(),
+                            outbox: Vec::new(),
+                            open: false,
+                            closing: false,
+                            accepted: Instant::now(),
+                        });
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    eprintln!("[GENESIS] Telemetry accept failed: {e}");
+                    break;
+                }
+            }
+        }
+
+        self.clients.retain_mut(|client| {
+            let connected = client.service(sim);
+            if !connected && client.open {
+                eprintln!("[GENESIS] Telemetry client {} disconnected", client.addr);
+            }
+            connected
+        });
+    }
+
+    /// Queue the messages for the tick the simulation just finished: a `tick` message every
+    /// TELEMETRY_TICK_INTERVAL ticks and a `summary` every TELEMETRY_SUMMARY_INTERVAL.
+    pub fn record(&mut self, sim: &SimState) {
+        if !sim.tick_count.is_multiple_of(config::TELEMETRY_TICK_INTERVAL) {
+            return;
+        }
+        // The totals restart when a save is loaded, hence the saturating deltas
+        let last = self.last_totals.replace((sim.births_total, sim.deaths_total));
+        let Some((last_births, last_deaths)) = last else {
+            return;
+        };
+        if self.client_count() == 0 {
+            return;
+        }
+        let births = sim.births_total.saturating_sub(last_births);
+        let deaths = sim.deaths_total.saturating_sub(last_deaths);
+        self.broadcast(&tick_json(sim, births, deaths));
+        if sim.tick_count.is_multiple_of(config::TELEMETRY_SUMMARY_INTERVAL) {
+            self.broadcast(&summary_json(sim));
+        }
+    }
+
+    fn broadcast(&mut self, message: &str) {
+        for client in self.clients.iter_mut().filter(|c| c.open && !c.closing) {
+            push_frame(&mut client.outbox, OPCODE_TEXT, message.as_bytes());
+        }
+    }
+}
+
+impl Client {
+    /// Read, handle and write whatever the socket allows without blocking. Returns false
+    /// once the connection should be dropped.
+    fn service(&mut self, sim: &SimState) -> bool {
+        let mut buf = [0u8; 4096];
+        while self.inbox.len() <= MAX_INBOX_BYTES {
+            match self.stream.read(&mut buf) {
+                Ok(0) => return false,
+                Ok(n) => self.inbox.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => return false,
+            }
+        }
+        if self.inbox.len() > MAX_INBOX_BYTES && self.open {
+            eprintln!("[GENESIS] Telemetry client {} sent too much data, disconnecting", self.addr);
+            return false;
+        }
+
+        // Covers clients turned away with 426 too, in case they never read the reply
+        if !self.open && self.accepted.elapsed() >= HANDSHAKE_TIMEOUT {
+            return false;
+        }
+        if !self.open && !self.closing {
+            let Some(end) = self.inbox.windows(4).position(|w| w == b"\r\n\r\n") else {
+                return self.inbox.len() <= MAX_REQUEST_BYTES;
+            };
+            let request = String::from_utf8_lossy(&self.inbox[..end]).into_owned();
+            self.inbox.drain(..end + 4);
+            match websocket_key(&request) {
+                Some(key) => {
+                    let accept = base64(&sha1(format!("{key}{WEBSOCKET_GUID}").as_bytes()));
+                    let response = format!(
+                        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {accept}\r\n\r\n"
+                    );
+                    self.outbox.extend_from_slice(response.as_bytes());
+                    push_frame(&mut self.outbox, OPCODE_TEXT, hello_json(sim).as_bytes());
+                    self.open = true;
+                    eprintln!("[GENESIS] Telemetry client {} connected", self.addr);
+                }
+                None => {
+                    let body = "genesis telemetry: connect with a WebSocket client\n";
+                    let response = format!(
+                        "HTTP/1.1 426 Upgrade Required\r\nUpgrade: websocket\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    self.outbox.extend_from_slice(response.as_bytes());
+                    self.closing = true;
+                }
+            }
+        }
+
+        while self.open && !self.closing {
+            let (opcode, payload, used) = match parse_frame(&self.inbox) {
+                Frame::Complete { opcode, payload, used } => (opcode, payload, used),
+                Frame::TooLarge => {
+                    push_frame(&mut self.outbox, OPCODE_CLOSE, &CLOSE_TOO_BIG.to_be_bytes());
+                    self.closing = true;
+                    break;
+                }
+                Frame::Incomplete => break,
+            };
+            self.inbox.drain(..used);
+            match opcode {
+                OPCODE_CLOSE => {
+                    push_frame(&mut self.outbox, OPCODE_CLOSE, &payload[..payload.len().min(2)]);
+                    self.closing = true;
+                }
+                OPCODE_PING => push_frame(&mut self.outbox, OPCODE_PONG, &payload),
+                // Clients have nothing to say; other messages are ignored
+                _ => {}
+            }
+        }
+
+        while !self.outbox.is_empty() {
+            match self.stream.write(&self.outbox) {
+                Ok(0) => return false,
+                Ok(n) => {
+                    self.outbox.drain(..n);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => return false,
+            }
+        }
+        if self.outbox.len() > config::TELEMETRY_MAX_BACKLOG {
+            eprintln!("[GENESIS] Telemetry client {} is too slow, disconnecting", self.addr);
+            return false;
+        }
+        !(self.closing && self.outbox.is_empty())
+    }
+}
+
+/// The Sec-WebSocket-Key of an HTTP upgrade request.
+fn websocket_key(request: &str) -> Option<&str> {
+    request.lines().skip(1).find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("sec-websocket-key").then(|| value.trim())
+    })
+}
+
+/// Append an unmasked, unfragmented server frame.
+fn push_frame(out: &mut Vec<u8>, opcode: u8, payload: &[u8]) {
+    out.push(0x80 | opcode);
+    match payload.len() {
+        n if n < 126 => out.push(n as u8),
+        n if n <= u16::MAX as usize => {
+            out.push(126);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            out.push(127);
+            out.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(payload);
+}
+
+/// Result of decoding the start of a client's inbox.
+enum Frame {
+    /// Opcode, unmasked payload and the bytes the frame used.
+    Complete { opcode: u8, payload: Vec<u8>, used: usize },
+    /// Longer than MAX_FRAME_BYTES.
+    TooLarge,
+    /// More bytes are needed.
+    Incomplete,
+}
+
+/// Decode the first client frame in `data`.
+fn parse_frame(data: &[u8]) -> Frame {
+    parse_frame_inner(data).unwrap_or(Frame::Incomplete)
+}
+
+fn parse_frame_inner(data: &[u8]) -> Option<Frame> {
+    let (&b0, &b1) = (data.first()?, data.get(1)?);
+    let mut pos = 2;
+    let len = match b1 & 0x7f {
+        126 => {
+            pos += 2;
+            u16::from_be_bytes(data.get(2..4)?.try_into().ok()?) as usize
+        }
+        127 => {
+            pos += 8;
+            u64::from_be_bytes(data.get(2..10)?.try_into().ok()?) as usize
+        }
+        n => n as usize,
+    };
+    if len > MAX_FRAME_BYTES {
+        return Some(Frame::TooLarge);
+    }
+    let mask: Option<[u8; 4]> = if b1 & 0x80 != 0 {
+        let key = data.get(pos..pos + 4)?.try_into().ok()?;
+        pos += 4;
+        Some(key)
+    } else {
+        None
+    };
+    let mut payload = data.get(pos..pos.checked_add(len)?)?.to_vec();
+    if let Some(mask) = mask {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+    Some(Frame::Complete {
+        opcode: b0 & 0x0f,
+        payload,
+        used: pos + len,
+    })
+}
+
+/// SHA-1 digest (only used for the WebSocket handshake).
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+    for chunk in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in chunk.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &wi) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(wi);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = t;
+        }
+        for (hi, v) in h.iter_mut().zip([a, b, c, d, e]) {
+            *hi = hi.wrapping_add(v);
+        }
+    }
+    let mut out = [0u8; 20];
+    for (i, v) in h.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&v.to_be_bytes());
+    }
+    out
+}
+
+/// Standard base64 with padding.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// First message on every connection: what run this is.
+fn hello_json(sim: &SimState) -> String {
+    format!(
+        "{{\"type\": \"hello\", \"genesis_version\": {}, \"seed\": {}, \"terrain_seed\": {}, \"preset\": {}, \"sim_hz\": {}, \"width\": {}, \"height\": {}, \"tick\": {}, \"tick_interval\": {}, \"summary_interval\": {}}}",
+        json_string(env!("CARGO_PKG_VERSION")),
+        sim.seed,
+        sim.environment.terrain_seed,
+        json_string(sim.environment.world_preset.id()),
+        json_number(sim.sim_hz() as f64),
+        json_number(sim.world.width as f64),
+        json_number(sim.world.height as f64),
+        sim.tick_count,
+        config::TELEMETRY_TICK_INTERVAL,
+        config::TELEMETRY_SUMMARY_INTERVAL
+    )
+}
+
+fn tick_json(sim: &SimState, births: u64, deaths: u64) -> String {
+    let alive = sim.arena.count.max(1) as f64;
+    let (energy, generation) = sim
+        .arena
+        .iter_alive()
+        .fold((0.0f64, 0.0f64), |(en, gen), (_, e)| (en + e.energy as f64, gen + e.generation_depth as f64));
+    format!(
+        "{{\"type\": \"tick\", \"tick\": {}, \"sim_seconds\": {}, \"population\": {}, \"food\": {}, \"meat\": {}, \"avg_energy\": {}, \"avg_generation\": {}, \"max_generation\": {}, \"species\": {}, \"births\": {births}, \"deaths\": {deaths}, \"paused\": {}, \"speed\": {}}}",
+        sim.tick_count,
+        json_number(sim.tick_count as f64 * sim.dt as f64),
+        sim.arena.count,
+        sim.food.len(),
+        sim.meat.len(),
+        json_number(energy / alive),
+        json_number(generation / alive),
+        sim.arena.deepest_generation(),
+        sim.species.species.len(),
+        sim.paused,
+        json_number(sim.speed_multiplier as f64)
+    )
+}
+
+/// Population summary: largest species, mean body genes and the interneuron distribution.
+pub fn summary_json(sim: &SimState) -> String {
+    let mut species: Vec<_> = sim.species.species.iter().collect();
+    species.sort_by(|a, b| b.member_count.cmp(&a.member_count).then(a.id.cmp(&b.id)));
+    let species: Vec<String> = species
+        .iter()
+        .take(config::TELEMETRY_SUMMARY_SPECIES)
+        .map(|s| format!("{{\"id\": {}, \"members\": {}, \"first_seen_tick\": {}}}", s.id, s.member_count, s.first_seen_tick))
+        .collect();
+
+    let mut sums = [0.0f64; BODY_PARAMS_COUNT];
+    let mut n = 0usize;
+    for (idx, _) in sim.arena.iter_alive() {
+        if let Some(Some(genome)) = sim.genomes.get(idx) {
+            for (i, sum) in sums.iter_mut().enumerate() {
+                *sum += genome.body_gene(i) as f64;
+            }
+            n += 1;
+        }
+    }
+    let genes: Vec<String> = BODY_GENE_NAMES
+        .iter()
+        .zip(sums)
+        .map(|(name, sum)| format!("{}: {}", json_string(name), json_number(sum / n.max(1) as f64)))
+        .collect();
+    let interneurons: Vec<String> =
+        stats::interneuron_distribution(&sim.arena, &sim.genomes).iter().map(|c| c.to_string()).collect();
+
+    format!(
+        "{{\"type\": \"summary\", \"tick\": {}, \"population\": {}, \"species\": [{}], \"mean_body_genes\": {{{}}}, \"interneurons\": [{}], \"interventions\": {}}}",
+        sim.tick_count,
+        sim.arena.count,
+        species.join(", "),
+        genes.join(", "),
+        interneurons.join(", "),
+        sim.journal.entries.len()
+    )
+}
@@ -0,0 +1,147 @@
+use egui;
+use macroquad::prelude::*;
+
+use crate::camera::CameraController;
+use crate::entity::EntityId;
+use crate::journal::InterventionKind;
+use crate::simulation::SimState;
+
+/// Where the breeding tool places the offspring.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SpawnLocation {
+    /// Halfway between the two parents.
+    Midpoint,
+    /// A world position picked by clicking on the map.
+    Chosen,
+}
+
+/// State of the interactive breeding tool.
+pub struct BreedingState {
+    pub parent_a: Option<EntityId>,
+    pub parent_b: Option<EntityId>,
+    pub spawn_location: SpawnLocation,
+    pub chosen_pos: Option<Vec2>,
+    /// When true, the next left click in the world sets `chosen_pos` instead of selecting.
+    pub picking_location: bool,
+    pub protect: bool,
+    pub protection_ticks: u32,
+    pub last_result: Option<String>,
+}
+
+impl Default for BreedingState {
+    fn default() -> Self {
+        Self {
+            parent_a: None,
+            parent_b: None,
+            spawn_location: SpawnLocation::Midpoint,
+            chosen_pos: None,
+            picking_location: false,
+            protect: true,
+            protection_ticks: 600,
+            last_result: None,
+        }
+    }
+}
+
+/// Breeding panel: mark two parents, pick a spawn location and force-breed them.
+pub fn draw_breeding(
+    ctx: &egui::Context,
+    sim: &mut SimState,
+    camera: &CameraController,
+    state: &mut BreedingState,
+) {
+    egui::Window::new("Breeding")
+        .default_pos(egui::pos2(300.0, 60.0))
+        .default_size(egui::vec2(260.0, 240.0))
+        .resizable(true)
+        .show(ctx, |ui| {
+            // Forget parents that have died
+            if state.parent_a.is_some_and(|id| sim.arena.get(id).is_none()) {
+                state.parent_a = None;
+            }
+            if state.parent_b.is_some_and(|id| sim.arena.get(id).is_none()) {
+                state.parent_b = None;
+            }
+
+            ui.label(format!("Parent A: {}", parent_label(sim, state.parent_a)));
+            ui.label(format!("Parent B: {}", parent_label(sim, state.parent_b)));
+
+            ui.horizontal(|ui| {
+                let selected = camera.following;
+                if ui.add_enabled(selected.is_some(), egui::Button::new("Mark selected as A")).clicked() {
+                    state.parent_a = selected;
+                }
+                if ui.add_enabled(selected.is_some(), egui::Button::new("Mark selected as B")).clicked() {
+                    state.parent_b = selected;
+                }
+            });
+
+            ui.separator();
+
+            ui.label("Spawn offspring at:");
+            ui.radio_value(&mut state.spawn_location, SpawnLocation::Midpoint, "Midpoint of parents");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut state.spawn_location, SpawnLocation::Chosen, "Chosen location");
+                let pick_label = if state.picking_location { "Click map..." } else { "Pick" };
+                if ui.button(pick_label).clicked() {
+                    state.spawn_location = SpawnLocation::Chosen;
+                    state.picking_location = !state.picking_location;
+                }
+            });
+            if let Some(pos) = state.chosen_pos {
+                ui.label(format!("Chosen: ({:.0}, {:.0})", pos.x, pos.y));
+            }
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut state.protect, "Protect for");
+                ui.add_enabled(
+                    state.protect,
+                    egui::DragValue::new(&mut state.protection_ticks).range(1..=36000),
+                );
+                ui.label("ticks");
+            });
+
+            ui.separator();
+
+            let spawn_pos = match state.spawn_location {
+                SpawnLocation::Midpoint => match (state.parent_a, state.parent_b) {
+                    (Some(a), Some(b)) => match (sim.arena.get(a), sim.arena.get(b)) {
+                        (Some(ea), Some(eb)) => Some(ea.pos + sim.world.delta(ea.pos, eb.pos) * 0.5),
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                SpawnLocation::Chosen => state.chosen_pos,
+            };
+
+            let can_breed = state.parent_a.is_some() && state.parent_b.is_some() && spawn_pos.is_some();
+            if ui.add_enabled(can_breed, egui::Button::new("Breed")).clicked() {
+                if let (Some(a), Some(b), Some(pos)) = (state.parent_a, state.parent_b, spawn_pos) {
+                    let protection = if state.protect { state.protection_ticks } else { 0 };
+                    state.last_result = Some(match sim.breed(a, b, pos, protection) {
+                        Some(child) => match sim.arena.get(child) {
+                            Some(e) => {
+                                let uid = |id| sim.arena.get(id).map_or(0, |p| p.uid);
+                                let detail = format!("#{} from #{} x #{} at ({:.0}, {:.0})", e.uid, uid(a), uid(b), e.pos.x, e.pos.y);
+                                sim.journal.record(sim.tick_count, InterventionKind::Breed, detail);
+                                format!("Spawned offspring #{}", e.uid)
+                            }
+                            None => "Spawned offspring".to_string(),
+                        },
+                        None => "Breeding failed (population at capacity?)".to_string(),
+                    });
+                }
+            }
+
+            if let Some(ref msg) = state.last_result {
+                ui.label(msg);
+            }
+        });
+}
+
+fn parent_label(sim: &SimState, id: Option<EntityId>) -> String {
+    match id.and_then(|id| sim.arena.get(id)) {
+        Some(e) => format!("#{}", e.uid),
+        None => "(none)".to_string(),
+    }
+}
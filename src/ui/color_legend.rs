@@ -0,0 +1,64 @@
+use egui;
+
+use crate::coloring::{self, ColorMode};
+use crate::config;
+use crate::simulation::SimState;
+use crate::ui::species_legend::swatch;
+
+const BAR_WIDTH: f32 = 160.0;
+const BAR_STEPS: usize = 32;
+
+/// Legend for the attribute color modes: the gradient with its end values.
+pub fn draw_color_legend(ctx: &egui::Context, sim: &SimState) {
+    let mode = sim.color_mode;
+    let corner = ctx.available_rect().left_bottom() + egui::vec2(12.0, -12.0);
+    egui::Area::new(egui::Id::new("color_legend"))
+        .pivot(egui::Align2::LEFT_BOTTOM)
+        .fixed_pos(corner)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(egui::RichText::new(format!("Color: {}", mode.name())).strong());
+                if mode == ColorMode::BrainSize {
+                    // Only a few discrete levels, so list them
+                    for n in 0..=config::BRAIN_INTERNEURONS {
+                        ui.horizontal(|ui| {
+                            swatch(ui, coloring::ramp(n as f32 / config::BRAIN_INTERNEURONS as f32));
+                            ui.label(format!("{n} interneurons in use"));
+                        });
+                    }
+                    return;
+                }
+
+                let (low, high) = match mode {
+                    ColorMode::Energy => ("0".to_string(), format!("{:.0}", config::MAX_ENTITY_ENERGY)),
+                    ColorMode::Age => ("Newborn".to_string(), format!("{:.0}s", config::DEATH_AGE)),
+                    ColorMode::Generation => ("0".to_string(), sim.deepest_generation.max(1).to_string()),
+                    ColorMode::Diet => ("Plants".to_string(), "Meat".to_string()),
+                    _ => (String::new(), String::new()),
+                };
+                let (_, rect) = ui.allocate_space(egui::vec2(BAR_WIDTH, 12.0));
+                let step = rect.width() / BAR_STEPS as f32;
+                for i in 0..BAR_STEPS {
+                    let t = (i as f32 + 0.5) / BAR_STEPS as f32;
+                    let c = if mode == ColorMode::Diet { coloring::diet_color(t) } else { coloring::ramp(t) };
+                    let cell = egui::Rect::from_min_size(
+                        rect.min + egui::vec2(i as f32 * step, 0.0),
+                        egui::vec2(step + 0.5, rect.height()),
+                    );
+                    let color = egui::Color32::from_rgb((c.r * 255.0) as u8, (c.g * 255.0) as u8, (c.b * 255.0) as u8);
+                    ui.painter().rect_filled(cell, 0.0, color);
+                }
+                ui.horizontal(|ui| {
+                    ui.set_width(BAR_WIDTH);
+                    ui.label(low);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| ui.label(high));
+                });
+                if mode == ColorMode::Diet {
+                    ui.horizontal(|ui| {
+                        swatch(ui, coloring::NO_DIET_COLOR);
+                        ui.label("No recent meals");
+                    });
+                }
+            });
+        });
+}
@@ -0,0 +1,88 @@
+use egui;
+
+use crate::config;
+use crate::stats::GenerationHistogram;
+
+/// Generation-depth histogram: whether the population is dominated by deep lineages or by
+/// fresh spawns.
+pub fn draw_generations(ctx: &egui::Context, hist: &mut GenerationHistogram, header: &str) {
+    egui::Window::new("Generations")
+        .default_pos(egui::pos2(720.0, 420.0))
+        .default_size(egui::vec2(320.0, 200.0))
+        .resizable(true)
+        .show(ctx, |ui| {
+            let total = hist.total();
+            if total == 0 {
+                ui.label("Waiting for the first sample...");
+                return;
+            }
+            ui.label(format!(
+                "{} entities at tick {} | mean {:.1}, median {}, deepest {}",
+                total,
+                hist.tick,
+                hist.mean(),
+                hist.median(),
+                hist.counts.len() - 1
+            ));
+            ui.label(format!("Original spawns (generation 0): {:.0}%", hist.fresh_fraction() * 100.0));
+
+            draw_histogram(ui, &hist.counts);
+
+            ui.horizontal(|ui| {
+                if ui.button("Export CSV").clicked() {
+                    let path = format!("generations_tick{}.csv", hist.tick);
+                    hist.last_export = Some(match hist.export_csv(&path, header) {
+                        Ok(rows) => format!("{rows} generations → {path}"),
+                        Err(e) => format!("Export failed: {e}"),
+                    });
+                }
+                if let Some(ref msg) = hist.last_export {
+                    ui.label(msg);
+                }
+            });
+        });
+}
+
+/// Bars of entity count per generation depth, merged into wider bins for deep populations.
+fn draw_histogram(ui: &mut egui::Ui, counts: &[u32]) {
+    let bin_width = counts.len().div_ceil(config::GENERATION_HISTOGRAM_MAX_BINS).max(1);
+    let bins: Vec<u32> = counts.chunks(bin_width).map(|c| c.iter().sum()).collect();
+    let max = bins.iter().copied().max().unwrap_or(1).max(1);
+
+    let size = egui::vec2(ui.available_width(), 100.0);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+
+    let bar_width = rect.width() / bins.len() as f32;
+    for (i, &count) in bins.iter().enumerate() {
+        let height = count as f32 / max as f32 * rect.height();
+        let x = rect.left() + i as f32 * bar_width;
+        // Fresh spawns stand out from bred generations
+        let color = if i == 0 && bin_width == 1 {
+            egui::Color32::from_rgb(240, 160, 80)
+        } else {
+            egui::Color32::from_rgb(200, 150, 255)
+        };
+        painter.rect_filled(
+            egui::Rect::from_min_max(
+                egui::pos2(x + 0.5, rect.bottom() - height),
+                egui::pos2(x + (bar_width - 0.5).max(1.0), rect.bottom()),
+            ),
+            0.0,
+            color,
+        );
+    }
+
+    if let Some(pos) = response.hover_pos() {
+        let i = (((pos.x - rect.left()) / bar_width) as usize).min(bins.len() - 1);
+        let first = i * bin_width;
+        let last = (first + bin_width - 1).min(counts.len() - 1);
+        let range = if first == last {
+            format!("Generation {first}")
+        } else {
+            format!("Generations {first}–{last}")
+        };
+        response.on_hover_text(format!("{range}: {} entities", bins[i]));
+    }
+}
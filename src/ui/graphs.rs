@@ -1,9 +1,12 @@
 use egui;
 
-use crate::stats::SimStats;
+use crate::config;
+use crate::genetics::GeneStats;
+use crate::genome::NEURAL_GENOME_SIZE;
+use crate::stats::{QuantileSeries, RingBuffer, SimStats, TraitHistogram};
 
-/// Draw population and energy graphs.
-pub fn draw_graphs(ctx: &egui::Context, stats: &SimStats) {
+/// Draw population, energy and gene-pool graphs.
+pub fn draw_graphs(ctx: &egui::Context, stats: &mut SimStats, tick: u64, header: &str) {
     egui::Window::new("Statistics")
         .default_pos(egui::pos2(300.0, 420.0))
         .default_size(egui::vec2(400.0, 300.0))
@@ -17,6 +20,94 @@ pub fn draw_graphs(ctx: &egui::Context, stats: &SimStats) {
                 draw_line_graph(ui, &stats.avg_energy, "energy_graph", egui::Color32::from_rgb(200, 200, 100));
             });
 
+            ui.collapsing("Tagged vs All", |ui| {
+                let all_color = egui::Color32::from_rgb(200, 200, 100);
+                let tagged_color = egui::Color32::from_rgb(120, 200, 255);
+                let mut filter = stats.tag_filter.clone();
+                egui::ComboBox::from_label("Tag")
+                    .selected_text(filter.as_deref().unwrap_or("Any tag"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut filter, None, "Any tag");
+                        for tag in &stats.known_tags {
+                            ui.selectable_value(&mut filter, Some(tag.clone()), tag);
+                        }
+                    });
+                stats.set_tag_filter(filter);
+
+                draw_comparison_graph(ui, &[(&stats.avg_energy, all_color), (&stats.tagged_energy, tagged_color)]);
+                ui.horizontal(|ui| {
+                    ui.colored_label(all_color, format!("All {:.0}", stats.avg_energy.last().unwrap_or(0.0)));
+                    ui.colored_label(
+                        tagged_color,
+                        format!(
+                            "Tagged {:.0} ({} entities)",
+                            stats.tagged_energy.last().unwrap_or(0.0),
+                            stats.tagged_count.last().unwrap_or(0.0)
+                        ),
+                    );
+                });
+                ui.label("Average energy. Tag entities from the inspector or the selection panel.");
+            });
+
+            ui.collapsing("Energy Distribution", |ui| {
+                draw_quantile_graph(ui, &stats.energy_quantiles, egui::Color32::from_rgb(200, 200, 100), 0);
+            });
+
+            ui.collapsing("Age Distribution (s)", |ui| {
+                draw_quantile_graph(ui, &stats.age_quantiles, egui::Color32::from_rgb(120, 180, 255), 0);
+            });
+
+            ui.collapsing("Size Distribution", |ui| {
+                draw_quantile_graph(ui, &stats.size_quantiles, egui::Color32::from_rgb(255, 150, 120), 2);
+            });
+
+            ui.collapsing("Trait Histograms", |ui| {
+                let traits = &stats.traits;
+                ui.label(format!("Genetic body traits at tick {}", traits.tick));
+                for histogram in &traits.traits {
+                    draw_trait_histogram(ui, histogram, egui::Color32::from_rgb(150, 200, 255));
+                }
+            });
+
+            ui.collapsing("Interneurons", |ui| {
+                let interneurons = &stats.interneurons;
+                let colors = [
+                    egui::Color32::from_rgb(150, 150, 160),
+                    egui::Color32::from_rgb(120, 190, 255),
+                    egui::Color32::from_rgb(255, 190, 90),
+                ];
+                let series: Vec<_> = interneurons
+                    .shares
+                    .iter()
+                    .enumerate()
+                    .map(|(n, share)| (share, colors[n.min(colors.len() - 1)]))
+                    .collect();
+                draw_comparison_graph(ui, &series);
+                ui.horizontal_wrapped(|ui| {
+                    for (n, (share, color)) in series.iter().enumerate() {
+                        ui.colored_label(*color, format!("{n} in use {:.0}%", share.last().unwrap_or(0.0)));
+                    }
+                    ui.label(format!("mean {:.2}", interneurons.mean.last().unwrap_or(0.0)));
+                });
+                ui.label(format!("Largest species at tick {}", interneurons.tick));
+                egui::Grid::new("interneuron_species").striped(true).show(ui, |ui| {
+                    ui.label("Species");
+                    for n in 0..=config::BRAIN_INTERNEURONS {
+                        ui.label(format!("{n}"));
+                    }
+                    ui.label("Mean");
+                    ui.end_row();
+                    for row in interneurons.per_species.iter().take(config::INTERNEURON_SPECIES_ROWS) {
+                        ui.label(format!("#{}", row.species));
+                        for count in &row.counts {
+                            ui.label(format!("{count}"));
+                        }
+                        ui.label(format!("{:.2}", row.mean()));
+                        ui.end_row();
+                    }
+                });
+            });
+
             ui.collapsing("Food Count", |ui| {
                 draw_line_graph(ui, &stats.food_count, "food_graph", egui::Color32::from_rgb(100, 200, 100));
             });
@@ -38,6 +129,132 @@ pub fn draw_graphs(ctx: &egui::Context, stats: &SimStats) {
             ui.collapsing("Average Generation", |ui| {
                 draw_line_graph(ui, &stats.avg_generation, "gen_graph", egui::Color32::from_rgb(200, 150, 255));
             });
+
+            ui.collapsing("Parental Care", |ui| {
+                let survival_color = egui::Color32::from_rgb(120, 220, 140);
+                let care_color = egui::Color32::from_rgb(255, 170, 200);
+                draw_comparison_graph(
+                    ui,
+                    &[(&stats.juvenile_survival, survival_color), (&stats.avg_care, care_color)],
+                );
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        survival_color,
+                        format!("Juvenile survival {:.0}%", stats.juvenile_survival.last().unwrap_or(0.0)),
+                    )
+                    .on_hover_text("Share of bred offspring that reached adulthood, over the last minute");
+                    ui.colored_label(care_color, format!("Mean care gene {:.0}%", stats.avg_care.last().unwrap_or(0.0)));
+                });
+            });
+
+            ui.collapsing("Altruism", |ui| {
+                let kin_color = egui::Color32::from_rgb(120, 200, 255);
+                let nepotism_color = egui::Color32::from_rgb(255, 200, 110);
+                draw_comparison_graph(
+                    ui,
+                    &[(&stats.kin_sharing, kin_color), (&stats.avg_nepotism, nepotism_color)],
+                );
+                ui.horizontal(|ui| {
+                    ui.colored_label(kin_color, format!("Shared with kin {:.0}%", stats.kin_sharing.last().unwrap_or(0.0)))
+                        .on_hover_text("Share of the energy given away that went to relatives, over the last minute");
+                    ui.colored_label(
+                        nepotism_color,
+                        format!("Mean nepotism gene {:.0}%", stats.avg_nepotism.last().unwrap_or(0.0)),
+                    );
+                });
+                ui.label("Energy shared per second");
+                draw_line_graph(ui, &stats.sharing_rate, "sharing_rate_graph", egui::Color32::from_rgb(170, 230, 160));
+            });
+
+            ui.collapsing("Arms Race", |ui| {
+                let attack_color = egui::Color32::from_rgb(255, 110, 90);
+                let armor_color = egui::Color32::from_rgb(150, 170, 210);
+                ui.label("Mean attack power");
+                draw_line_graph(ui, &stats.avg_attack, "attack_graph", attack_color);
+                ui.label("Mean armor");
+                draw_line_graph(ui, &stats.avg_armor, "armor_graph", armor_color);
+                let ranged_color = egui::Color32::from_rgb(190, 240, 90);
+                ui.label("Mean ranged gene");
+                draw_line_graph(ui, &stats.avg_ranged, "ranged_graph", ranged_color);
+                ui.horizontal(|ui| {
+                    ui.colored_label(attack_color, format!("Attack {:.2}x", stats.avg_attack.last().unwrap_or(0.0)));
+                    ui.colored_label(
+                        armor_color,
+                        format!("Armor {:.0}%", stats.avg_armor.last().unwrap_or(0.0) * 100.0),
+                    );
+                    ui.colored_label(
+                        ranged_color,
+                        format!("Ranged {:.0}%", stats.avg_ranged.last().unwrap_or(0.0) * 100.0),
+                    );
+                });
+            });
+
+            ui.collapsing("Hazard Resistance", |ui| {
+                let toxin_color = egui::Color32::from_rgb(170, 230, 90);
+                let storm_color = egui::Color32::from_rgb(140, 170, 255);
+                draw_comparison_graph(
+                    ui,
+                    &[(&stats.avg_toxin_resistance, toxin_color), (&stats.avg_storm_resistance, storm_color)],
+                );
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        toxin_color,
+                        format!("Toxin {:.0}%", stats.avg_toxin_resistance.last().unwrap_or(0.0)),
+                    );
+                    ui.colored_label(
+                        storm_color,
+                        format!("Storm {:.0}%", stats.avg_storm_resistance.last().unwrap_or(0.0)),
+                    );
+                });
+            });
+
+            ui.collapsing("Gene Pool Diversity", |ui| {
+                let genetics = &mut stats.genetics;
+                let variance_color = egui::Color32::from_rgb(120, 220, 200);
+                let het_color = egui::Color32::from_rgb(255, 180, 90);
+                let divergence_color = egui::Color32::from_rgb(220, 120, 220);
+
+                let size = egui::vec2(ui.available_width(), 80.0);
+                let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+                let rect = response.rect;
+                painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+                draw_line_in_rect(&painter, &genetics.variance, rect, variance_color);
+                draw_line_in_rect(&painter, &genetics.heterozygosity, rect, het_color);
+                draw_line_in_rect(&painter, &genetics.divergence, rect, divergence_color);
+
+                ui.horizontal(|ui| {
+                    let last = |b: &crate::stats::RingBuffer| b.last().unwrap_or(0.0);
+                    ui.colored_label(variance_color, format!("Variance {:.0}%", last(&genetics.variance)))
+                        .on_hover_text("Mean gene variance relative to a random population");
+                    ui.colored_label(het_color, format!("Heterozygosity {:.0}%", last(&genetics.heterozygosity)))
+                        .on_hover_text("Genes read as two alleles (above/below 0.5); 2p(1-p) relative to its maximum");
+                    ui.colored_label(divergence_color, format!("Divergence {:.0}%", last(&genetics.divergence)))
+                        .on_hover_text("Mean genetic distance between species representatives");
+                });
+
+                if let Some(ref latest) = genetics.latest {
+                    ui.label(format!(
+                        "Per-gene variance at tick {} ({} genomes, {} species):",
+                        latest.tick,
+                        latest.sample_size,
+                        genetics.species_count.last().unwrap_or(0.0)
+                    ));
+                    draw_gene_profile(ui, latest);
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("Export CSV").clicked() {
+                        let prefix = format!("genetics_tick{tick}");
+                        genetics.last_export = Some(match genetics.export_csv(&prefix, header) {
+                            Ok(msg) => msg,
+                            Err(e) => format!("Export failed: {e}"),
+                        });
+                    }
+                    if let Some(ref msg) = genetics.last_export {
+                        ui.label(msg);
+                    }
+                });
+            });
         });
 }
 
@@ -105,3 +322,178 @@ fn draw_line_in_rect(
         painter.line_segment([pair[0], pair[1]], egui::Stroke::new(1.5, color));
     }
 }
+
+/// Several series on one shared scale, aligned on their most recent sample and cut to the
+/// shortest history.
+fn draw_comparison_graph(ui: &mut egui::Ui, series: &[(&RingBuffer, egui::Color32)]) {
+    let size = egui::vec2(ui.available_width(), 80.0);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+
+    let len = series.iter().map(|(b, _)| b.len()).min().unwrap_or(0);
+    if len < 2 {
+        return;
+    }
+    let tails: Vec<Vec<f32>> = series.iter().map(|(b, _)| b.iter().skip(b.len() - len).collect()).collect();
+    let max_val = tails.iter().flatten().cloned().fold(1.0f32, f32::max);
+    let min_val = tails.iter().flatten().cloned().fold(max_val, f32::min);
+    let range = (max_val - min_val).max(1.0);
+
+    for (samples, (_, color)) in tails.iter().zip(series) {
+        let points: Vec<egui::Pos2> = samples
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                let x = rect.left() + (i as f32 / (len - 1) as f32) * rect.width();
+                let y = rect.bottom() - ((v - min_val) / range) * rect.height();
+                egui::pos2(x, y)
+            })
+            .collect();
+        for pair in points.windows(2) {
+            painter.line_segment([pair[0], pair[1]], egui::Stroke::new(1.5, *color));
+        }
+    }
+}
+
+/// One bar per gene, height = variance relative to a random population. Neural genes are
+/// blue, body genes orange.
+fn draw_gene_profile(ui: &mut egui::Ui, stats: &GeneStats) {
+    let size = egui::vec2(ui.available_width(), 60.0);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+
+    let count = stats.variance.len().max(1);
+    let bar_width = rect.width() / count as f32;
+    for (i, &v) in stats.variance.iter().enumerate() {
+        let height = (v * 12.0).clamp(0.0, 1.0) * rect.height();
+        let x = rect.left() + i as f32 * bar_width;
+        let color = if i < NEURAL_GENOME_SIZE {
+            egui::Color32::from_rgb(90, 140, 220)
+        } else {
+            egui::Color32::from_rgb(240, 160, 80)
+        };
+        painter.rect_filled(
+            egui::Rect::from_min_max(
+                egui::pos2(x, rect.bottom() - height),
+                egui::pos2(x + bar_width.max(1.0), rect.bottom()),
+            ),
+            0.0,
+            color,
+        );
+    }
+
+    if let Some(pos) = response.hover_pos() {
+        let i = (((pos.x - rect.left()) / bar_width) as usize).min(count - 1);
+        response.on_hover_text(format!(
+            "{}: mean {:.2}, variance {:.3}, heterozygosity {:.2}",
+            crate::genome::gene_name(i),
+            stats.mean[i],
+            stats.variance[i],
+            stats.heterozygosity[i]
+        ));
+    }
+}
+
+/// Shaded quantile bands: min–max faint, median–p95 stronger, with the median drawn as a line.
+/// Labels show the latest values with `decimals` places.
+fn draw_quantile_graph(ui: &mut egui::Ui, series: &QuantileSeries, color: egui::Color32, decimals: usize) {
+    let size = egui::vec2(ui.available_width(), 80.0);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+
+    let len = series.min.len();
+    if len >= 2 {
+        let lo: Vec<f32> = series.min.iter().collect();
+        let hi: Vec<f32> = series.max.iter().collect();
+        let min_val = lo.iter().copied().fold(f32::INFINITY, f32::min);
+        let max_val = hi.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let range = (max_val - min_val).max(1e-3);
+        let to_pos = |i: usize, v: f32| {
+            egui::pos2(
+                rect.left() + (i as f32 / (len - 1) as f32) * rect.width(),
+                rect.bottom() - ((v - min_val) / range) * rect.height(),
+            )
+        };
+
+        let fill_band = |low: &RingBuffer, high: &RingBuffer, alpha: u8| {
+            let low: Vec<f32> = low.iter().collect();
+            let high: Vec<f32> = high.iter().collect();
+            let fill = egui::Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), alpha);
+            for i in 0..len - 1 {
+                let quad = vec![to_pos(i, high[i]), to_pos(i + 1, high[i + 1]), to_pos(i + 1, low[i + 1]), to_pos(i, low[i])];
+                painter.add(egui::Shape::convex_polygon(quad, fill, egui::Stroke::NONE));
+            }
+        };
+        fill_band(&series.min, &series.max, 40);
+        fill_band(&series.p50, &series.p95, 90);
+
+        let median: Vec<egui::Pos2> = series.p50.iter().enumerate().map(|(i, v)| to_pos(i, v)).collect();
+        for pair in median.windows(2) {
+            painter.line_segment([pair[0], pair[1]], egui::Stroke::new(1.5, color));
+        }
+    }
+
+    let last = |b: &RingBuffer| b.last().unwrap_or(0.0);
+    ui.label(
+        egui::RichText::new(format!(
+            "min {:.d$}  median {:.d$}  p95 {:.d$}  max {:.d$}",
+            last(&series.min),
+            last(&series.p50),
+            last(&series.p95),
+            last(&series.max),
+            d = decimals
+        ))
+        .size(10.0)
+        .color(color),
+    );
+}
+
+/// Bar chart of one trait over its fixed range, with the population mean marked.
+fn draw_trait_histogram(ui: &mut egui::Ui, histogram: &TraitHistogram, color: egui::Color32) {
+    let discrete = histogram.counts.len() as f32 == histogram.max - histogram.min;
+    let decimals = if discrete { 0 } else { 2 };
+    ui.label(
+        egui::RichText::new(format!("{} (mean {:.2})", histogram.name, histogram.mean))
+            .size(11.0)
+            .color(color),
+    );
+
+    let size = egui::vec2(ui.available_width(), 50.0);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+
+    let peak = histogram.counts.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let bar_w = rect.width() / histogram.counts.len() as f32;
+    for (i, &count) in histogram.counts.iter().enumerate() {
+        let h = count as f32 / peak * rect.height();
+        let x = rect.left() + i as f32 * bar_w;
+        let bar = egui::Rect::from_min_max(egui::pos2(x + 0.5, rect.bottom() - h), egui::pos2(x + bar_w - 0.5, rect.bottom()));
+        painter.rect_filled(bar, 0.0, color.gamma_multiply(0.8));
+    }
+
+    // Discrete traits are binned by value, so the mean sits half a bin to the right of its value
+    let offset = if discrete { 0.5 } else { 0.0 };
+    let t = (histogram.mean + offset - histogram.min) / (histogram.max - histogram.min);
+    let x = rect.left() + t.clamp(0.0, 1.0) * rect.width();
+    painter.line_segment(
+        [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+        egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 220, 120)),
+    );
+
+    if let Some(pos) = response.hover_pos() {
+        let bin = (((pos.x - rect.left()) / bar_w) as usize).min(histogram.counts.len() - 1);
+        let step = (histogram.max - histogram.min) / histogram.counts.len() as f32;
+        let lo = histogram.min + bin as f32 * step;
+        let hi = lo + step;
+        let text = if discrete {
+            format!("{lo:.0}: {} entities", histogram.counts[bin])
+        } else {
+            format!("{lo:.d$}–{hi:.d$}: {} entities", histogram.counts[bin], d = decimals)
+        };
+        response.on_hover_text(text);
+    }
+}
@@ -0,0 +1,153 @@
+use egui;
+use macroquad::prelude::*;
+use ::rand::Rng;
+
+use crate::camera::CameraController;
+use crate::hall_of_fame::{self, ArchivedGenome, FameCategory};
+use crate::journal::InterventionKind;
+use crate::simulation::SimState;
+
+/// Radius around the view center within which injected genomes are scattered.
+const INJECT_SPREAD: f32 = 60.0;
+
+/// State of the hall-of-fame browser.
+pub struct HallOfFameState {
+    pub category: FameCategory,
+    /// Number of entities spawned per injection.
+    pub copies: u32,
+    /// Spawn at the center of the view instead of random world positions.
+    pub at_view_center: bool,
+    pub last_result: Option<String>,
+}
+
+impl Default for HallOfFameState {
+    fn default() -> Self {
+        Self {
+            category: FameCategory::Offspring,
+            copies: 5,
+            at_view_center: true,
+            last_result: None,
+        }
+    }
+}
+
+/// Hall of fame panel: browse archived genomes by category and reinject them into the world.
+pub fn draw_hall_of_fame(
+    ctx: &egui::Context,
+    sim: &mut SimState,
+    camera: &CameraController,
+    state: &mut HallOfFameState,
+) {
+    egui::Window::new("Hall of Fame")
+        .default_pos(egui::pos2(300.0, 320.0))
+        .default_size(egui::vec2(360.0, 300.0))
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for category in FameCategory::ALL {
+                    ui.selectable_value(&mut state.category, category, category.name());
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Inject");
+                ui.add(egui::DragValue::new(&mut state.copies).range(1..=50));
+                ui.label("copies at");
+                ui.radio_value(&mut state.at_view_center, true, "view center");
+                ui.radio_value(&mut state.at_view_center, false, "random");
+            });
+
+            ui.separator();
+
+            let mut inject: Option<ArchivedGenome> = None;
+            let ranked = sim.hall_of_fame.ranked(state.category);
+            if ranked.is_empty() {
+                ui.label("No genomes archived yet.");
+            }
+            egui::ScrollArea::vertical().max_height(180.0).show(ui, |ui| {
+                egui::Grid::new("hall_of_fame_grid").striped(true).show(ui, |ui| {
+                    ui.label("#");
+                    ui.label("Entity");
+                    ui.label("Score");
+                    ui.label("Gen");
+                    ui.label("Tick");
+                    ui.label("");
+                    ui.end_row();
+
+                    for (rank, entry) in ranked.iter().enumerate() {
+                        ui.label(format!("{}", rank + 1));
+                        ui.horizontal(|ui| {
+                            let c = entry.genome().body_color();
+                            let rect = ui.allocate_space(egui::vec2(10.0, 10.0));
+                            ui.painter().rect_filled(
+                                rect.1,
+                                2.0,
+                                egui::Color32::from_rgb(
+                                    (c.r * 255.0) as u8,
+                                    (c.g * 255.0) as u8,
+                                    (c.b * 255.0) as u8,
+                                ),
+                            );
+                            ui.label(format!("#{}", entry.uid));
+                        });
+                        ui.label(score_label(entry));
+                        ui.label(format!("{}", entry.generation_depth));
+                        ui.label(format!("{}", entry.tick));
+                        if ui.button("Inject").clicked() {
+                            inject = Some((*entry).clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+            if let Some(entry) = inject {
+                let genome = entry.genome();
+                let mut spawned = 0;
+                for _ in 0..state.copies {
+                    let pos = if state.at_view_center {
+                        let angle = sim.rng.gen_range(0.0..std::f32::consts::TAU);
+                        let dist = sim.rng.gen_range(0.0..INJECT_SPREAD);
+                        camera.target + Vec2::from_angle(angle) * dist
+                    } else {
+                        vec2(
+                            sim.rng.gen_range(0.0..sim.world.width),
+                            sim.rng.gen_range(0.0..sim.world.height),
+                        )
+                    };
+                    if sim.inject_genome(&genome, pos).is_some() {
+                        spawned += 1;
+                    }
+                }
+                if spawned > 0 {
+                    let detail = format!("{spawned} copies of hall-of-fame #{}", entry.uid);
+                    sim.journal.record(sim.tick_count, InterventionKind::Spawn, detail);
+                }
+                state.last_result = Some(format!("Injected {spawned} copies of #{}", entry.uid));
+            }
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.button("Save library").clicked() {
+                    state.last_result = Some(match sim.hall_of_fame.save_library(hall_of_fame::LIBRARY_PATH) {
+                        Ok(()) => format!("Saved to {}", hall_of_fame::LIBRARY_PATH),
+                        Err(e) => format!("Save failed: {e}"),
+                    });
+                }
+                ui.label(format!("{} genomes archived", sim.hall_of_fame.entries.len()));
+            });
+
+            if let Some(ref msg) = state.last_result {
+                ui.label(msg);
+            }
+        });
+}
+
+fn score_label(entry: &ArchivedGenome) -> String {
+    match entry.category {
+        FameCategory::Offspring => format!("{} offspring", entry.offspring_count),
+        FameCategory::Lifespan => format!("{:.0}s", entry.age),
+        FameCategory::Kills => format!("{} kills", entry.kills),
+    }
+}
@@ -1,15 +1,59 @@
 use egui;
 
-use crate::camera::CameraController;
+use crate::camera::{CameraController, CameraGroup};
 use crate::config;
+use crate::entity;
+use crate::genome::{self, Genome, BODY_GENE_NAMES};
+use crate::journal::InterventionKind;
+use crate::lineage::LineageRecord;
+use crate::plants::FoodKind;
 use crate::simulation::SimState;
 
+/// Ancestors listed in the lineage section before the chain is cut short.
+const LINEAGE_CHAIN_DEPTH: usize = 16;
+
+/// Decoded value of a body gene, formatted for display.
+fn decoded_body_gene(genome: &Genome, offset: usize) -> String {
+    match offset {
+        genome::BODY_SIZE => format!("{:.2}x", genome.body_size()),
+        genome::BODY_MAX_SPEED => format!("{:.2}x", genome.max_speed()),
+        genome::BODY_SENSOR_RANGE => format!("{:.2}x", genome.sensor_range()),
+        genome::BODY_METABOLIC_RATE => format!("{:.2}x", genome.metabolic_rate()),
+        genome::BODY_MUTATION_RATE => format!("{:.3}", genome.mutation_rate()),
+        genome::BODY_PRIME_AGE => format!("{:.0}s", genome.prime_age()),
+        genome::BODY_ATTACK => format!("{:.2}x", genome.attack_power()),
+        genome::BODY_RANGED if genome.ranged() < config::SPIT_MIN_GENE => "no spit".to_string(),
+        genome::BODY_RANGED => format!("{:.0} dmg spit", genome.ranged() * config::SPIT_DAMAGE),
+        genome::BODY_TASTE => FoodKind::ALL
+            .iter()
+            .map(|k| format!("{} {:.0}%", k.name(), k.digestion(genome.taste()) * 100.0))
+            .collect::<Vec<_>>()
+            .join(", "),
+        genome::BODY_CIRCADIAN if genome.circadian() > 0.5 => format!("{:.0}% nocturnal", (genome.circadian() - 0.5) * 200.0),
+        genome::BODY_CIRCADIAN => format!("{:.0}% diurnal", (0.5 - genome.circadian()) * 200.0),
+        genome::BODY_LEARNING_RATE => format!("{:.3}/s", genome.learning_rate()),
+        genome::BODY_ARMOR => format!("-{:.0}% dmg", genome.armor() * config::ARMOR_DAMAGE_REDUCTION * 100.0),
+        _ => format!("{:.2}", genome.body_gene(offset)),
+    }
+}
+
 /// Entity inspector panel: shows stats for the selected (followed) entity.
+/// With `genome_edit` on, the body genes become sliders that rewrite the genome in place.
 pub fn draw_inspector(
     ctx: &egui::Context,
-    sim: &SimState,
-    camera: &CameraController,
+    sim: &mut SimState,
+    camera: &mut CameraController,
+    genome_edit: &mut bool,
 ) {
+    // Slider change to apply once the panel no longer borrows the simulation
+    let mut gene_change: Option<(usize, f32)> = None;
+    // Whether the slider edit is finished and goes into the intervention journal
+    let mut gene_committed = false;
+    // Edited name and tag, applied the same way (empty text clears the label)
+    let mut name_change: Option<String> = None;
+    let mut tag_change: Option<String> = None;
+    // Relative picked in the lineage section, followed once the panel is done
+    let mut jump_to = None;
     egui::SidePanel::left("inspector")
         .default_width(220.0)
         .resizable(true)
@@ -19,7 +63,49 @@ pub fn draw_inspector(
 
             if let Some(id) = camera.following {
                 if let Some(entity) = sim.arena.get(id) {
-                    ui.label(format!("Slot: {} (gen {})", id.index, id.generation));
+                    ui.label(format!("ID: #{} (slot {}, gen {})", entity.uid, id.index, id.generation));
+                    egui::Grid::new("entity_labels").num_columns(2).show(ui, |ui| {
+                        ui.label("Name:");
+                        let mut name = entity.name.clone().unwrap_or_default();
+                        let edit = egui::TextEdit::singleline(&mut name).desired_width(120.0).hint_text("unnamed");
+                        if ui.add(edit).changed() {
+                            name_change = Some(name);
+                        }
+                        ui.end_row();
+
+                        ui.label("Tag:");
+                        ui.horizontal(|ui| {
+                            let mut tag = entity.tag.clone().unwrap_or_default();
+                            let edit = egui::TextEdit::singleline(&mut tag).desired_width(100.0).hint_text("none");
+                            if ui.add(edit).changed() {
+                                tag_change = Some(tag);
+                            }
+                            if let Some(ref tag) = entity.tag {
+                                let c = entity::tag_color(tag);
+                                let (_, rect) = ui.allocate_space(egui::vec2(14.0, 14.0));
+                                ui.painter().rect_filled(
+                                    rect,
+                                    2.0,
+                                    egui::Color32::from_rgb((c.r * 255.0) as u8, (c.g * 255.0) as u8, (c.b * 255.0) as u8),
+                                );
+                            }
+                        });
+                        ui.end_row();
+                    });
+                    if let Some(species_id) = sim.species.species_of(id.index as usize) {
+                        let (members, since) = sim
+                            .species
+                            .get(species_id)
+                            .map_or((0, 0), |s| (s.member_count, s.first_seen_tick));
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Species: S{} ({} members, since tick {})", species_id, members, since));
+                            let group = CameraGroup::Species(species_id);
+                            let mut framing = camera.following_group == Some(group);
+                            if ui.toggle_value(&mut framing, "Frame (G)").clicked() {
+                                camera.following_group = framing.then_some(group);
+                            }
+                        });
+                    }
                     ui.separator();
 
                     // Position & movement
@@ -28,7 +114,16 @@ pub fn draw_inspector(
                         ui.label(format!("Heading: {:.1}°", entity.heading.to_degrees()));
                         let speed = entity.velocity.length();
                         ui.label(format!("Speed: {:.1}", speed));
-                        ui.label(format!("Radius: {:.1}", entity.radius));
+                        if entity.growth < 1.0 {
+                            ui.label(format!(
+                                "Radius: {:.1} (juvenile, {:.0}% grown to {:.1})",
+                                entity.radius,
+                                entity.growth * 100.0,
+                                entity.adult_radius
+                            ));
+                        } else {
+                            ui.label(format!("Radius: {:.1}", entity.radius));
+                        }
                     });
 
                     ui.separator();
@@ -43,15 +138,54 @@ pub fn draw_inspector(
                             ui.add(bar);
                         });
 
-                        let health_frac = entity.health / entity.max_health;
+                        let max_health = entity.effective_max_health();
+                        let health_frac = entity.health / max_health;
                         ui.horizontal(|ui| {
                             ui.label("Health:");
                             let bar = egui::ProgressBar::new(health_frac.clamp(0.0, 1.0))
-                                .text(format!("{:.0}/{:.0}", entity.health, entity.max_health));
+                                .text(format!("{:.0}/{:.0}", entity.health, max_health));
                             ui.add(bar);
                         });
 
-                        ui.label(format!("Age: {:.0}s", entity.age));
+                        let injury_mult = entity.injury_speed_mult();
+                        if injury_mult < 1.0 {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(220, 110, 90),
+                                format!("Injured: speed -{:.0}%", (1.0 - injury_mult) * 100.0),
+                            );
+                        }
+                        if health_frac < 1.0 && entity.energy >= config::HEALTH_REGEN_MIN_ENERGY {
+                            ui.label(format!("Healing {:.1}/s", config::HEALTH_REGEN_RATE));
+                        }
+
+                        ui.label(format!("Age: {:.0}s (prime {:.0}s)", entity.age, entity.prime_age));
+                        if entity.sheltered {
+                            ui.label("Resting in a shelter");
+                        }
+                        if let Some(&fear) = sim.fear.get(id.index as usize).filter(|f| **f > 0.0) {
+                            ui.colored_label(egui::Color32::from_rgb(220, 140, 90), format!("Fear: {:.0}%", fear * 100.0));
+                        }
+                        let activity = entity.circadian_activity(sim.environment.darkness());
+                        if activity.abs() > 0.05 {
+                            ui.label(format!(
+                                "{}: senses {:+.0}%, metabolism {:+.0}%",
+                                if activity > 0.0 { "Active hours" } else { "Resting hours" },
+                                activity * config::CIRCADIAN_SENSOR_SWING * 100.0,
+                                activity * config::CIRCADIAN_METABOLIC_SWING * 100.0
+                            ));
+                        }
+                        if entity.senescence() > 0.0 {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(200, 160, 100),
+                                format!(
+                                    "Senescent: vigor {:.0}%, fertility {:.0}%",
+                                    entity.vigor() * 100.0,
+                                    entity.fertility() * 100.0
+                                ),
+                            );
+                        } else {
+                            ui.label("In prime: full vigor and fertility");
+                        }
                     });
 
                     ui.separator();
@@ -60,11 +194,31 @@ pub fn draw_inspector(
                     ui.collapsing("Genome Traits", |ui| {
                         let slot = id.index as usize;
                         if let Some(Some(genome)) = sim.genomes.get(slot) {
-                            ui.label(format!("Body size: {:.2}", genome.body_size()));
-                            ui.label(format!("Max speed: {:.2}", genome.max_speed()));
-                            ui.label(format!("Metabolic rate: {:.2}", genome.metabolic_rate()));
-                            ui.label(format!("Sensor range: {:.2}", genome.sensor_range()));
-                            ui.label(format!("Mutation rate: {:.3}", genome.mutation_rate()));
+                            ui.checkbox(genome_edit, "Edit genes")
+                                .on_hover_text("Rewrite this entity's body genes; offspring inherit the edits");
+                            egui::Grid::new("body_genes").num_columns(2).show(ui, |ui| {
+                                for (offset, name) in BODY_GENE_NAMES.iter().enumerate() {
+                                    ui.label(*name);
+                                    let text = decoded_body_gene(genome, offset);
+                                    if *genome_edit {
+                                        let mut value = genome.body_gene(offset);
+                                        let slider = egui::Slider::new(&mut value, 0.0..=1.0)
+                                            .show_value(false)
+                                            .text(text);
+                                        let response = ui.add(slider);
+                                        if response.changed() {
+                                            gene_change = Some((offset, value));
+                                        }
+                                        if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                                            gene_change = Some((offset, value));
+                                            gene_committed = true;
+                                        }
+                                    } else {
+                                        ui.label(text);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
 
                             let c = genome.body_color();
                             ui.horizontal(|ui| {
@@ -89,24 +243,99 @@ pub fn draw_inspector(
                     ui.collapsing("Lineage", |ui| {
                         ui.label(format!("Generation: {}", entity.generation_depth));
                         ui.label(format!("Offspring: {}", entity.offspring_count));
-                        if let Some(pid) = entity.parent_id {
-                            ui.label(format!("Parent: slot {}", pid.index));
-                        } else {
-                            ui.label("Parent: (original)");
+                        ui.label(format!("Kills: {}", entity.kills));
+                        ui.label(format!(
+                            "Food shared: {:.0} donated, {:.0} received",
+                            entity.energy_donated, entity.energy_received
+                        ))
+                        .on_hover_text("Energy passed between neighbors by food sharing, which favors relatives");
+
+                        // A row per relative: alive ones get a button that follows them
+                        let mut relative = |ui: &mut egui::Ui, record: &LineageRecord| {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("#{} gen {}", record.uid, record.generation));
+                                match sim.arena.find_by_uid(record.uid) {
+                                    Some(rid) => {
+                                        if ui.small_button("Go").on_hover_text("Follow this entity").clicked() {
+                                            jump_to = Some(rid);
+                                        }
+                                    }
+                                    None => {
+                                        let died = record.died_tick.map_or("dead".to_string(), |t| format!("died tick {t}"));
+                                        ui.weak(died);
+                                    }
+                                }
+                            });
+                        };
+
+                        ui.separator();
+                        match entity.parent_uid {
+                            None => {
+                                ui.label("Parent: (original)");
+                            }
+                            Some(parent_uid) => {
+                                let ancestors = sim.lineage.ancestors(Some(parent_uid), LINEAGE_CHAIN_DEPTH);
+                                ui.label("Ancestors:");
+                                for record in &ancestors {
+                                    relative(ui, record);
+                                }
+                                // Where the recorded chain ends
+                                let end = match ancestors.last() {
+                                    None => format!("#{parent_uid} (no record)"),
+                                    Some(oldest) if ancestors.len() >= LINEAGE_CHAIN_DEPTH => {
+                                        format!("… older ancestors of #{}", oldest.uid)
+                                    }
+                                    Some(oldest) => match oldest.parent_uid {
+                                        Some(uid) => format!("#{uid} (no record)"),
+                                        None => "(original population)".to_string(),
+                                    },
+                                };
+                                ui.weak(end);
+                                let (siblings, alive) = sim.lineage.sibling_counts(entity.uid, parent_uid);
+                                ui.label(format!("Siblings: {siblings} ({alive} alive)"));
+                            }
+                        }
+
+                        let children = sim.lineage.children(entity.uid);
+                        if !children.is_empty() {
+                            ui.separator();
+                            let alive = children.iter().filter(|c| c.died_tick.is_none()).count();
+                            ui.label(format!("Children: {} recorded ({alive} alive)", children.len()));
+                            egui::ScrollArea::vertical().id_salt("lineage_children").max_height(140.0).show(ui, |ui| {
+                                for record in &children {
+                                    relative(ui, record);
+                                }
+                            });
                         }
                     });
 
                     ui.separator();
 
+                    // Recent events, newest first
+                    ui.collapsing("Event Log", |ui| {
+                        egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                            for event in entity.events.iter().rev() {
+                                ui.label(format!("{:>6.1}s  {}", event.age, event.kind.describe()));
+                            }
+                        });
+                    });
+
+                    ui.separator();
+
                     // Brain outputs
                     ui.collapsing("Brain Outputs", |ui| {
                         let slot = id.index as usize;
                         if slot < sim.brains.active.len() && sim.brains.active[slot] {
-                            let (fwd, turn, attack, signal) = sim.brains.motor_outputs(slot);
-                            ui.label(format!("Forward: {:.2}", fwd));
-                            ui.label(format!("Turn: {:.2}", turn));
-                            ui.label(format!("Attack: {:.2}", attack));
-                            ui.label(format!("Signal: {:.2}", signal));
+                            let m = sim.brains.motor_outputs(slot);
+                            ui.label(format!("Forward: {:.2}", m.forward));
+                            ui.label(format!("Turn: {:.2}", m.turn));
+                            ui.label(format!("Attack: {:.2}", m.attack));
+                            ui.label(format!("Signal: {:.2}", m.signal));
+                            ui.label(format!("Build: {:.2}", m.build));
+                            ui.label(format!("Call: {:.2}", m.call));
+                            ui.label(format!("Trail: {:.2}", m.trail));
+                            ui.label(format!("Cohesion: {:.2}", m.cohesion));
+                            ui.label(format!("Alignment: {:.2}", m.alignment));
                         }
                     });
                 } else {
@@ -147,4 +376,26 @@ pub fn draw_inspector(
                 }
             }
         });
+
+    if let Some(id) = jump_to {
+        camera.following = Some(id);
+        camera.following_group = None;
+    }
+    if let (Some(id), Some((offset, value))) = (camera.following, gene_change) {
+        sim.set_body_gene(id, offset, value);
+        if gene_committed {
+            let uid = sim.arena.get(id).map_or(0, |e| e.uid);
+            let detail = format!("#{uid} gene {} = {value:.2}", BODY_GENE_NAMES[offset]);
+            sim.journal.record(sim.tick_count, InterventionKind::Modify, detail);
+        }
+    }
+    if let Some(entity) = camera.following.and_then(|id| sim.arena.get_mut(id)) {
+        let label = |text: String| Some(text).filter(|t| !t.trim().is_empty());
+        if let Some(name) = name_change {
+            entity.name = label(name);
+        }
+        if let Some(tag) = tag_change {
+            entity.tag = label(tag);
+        }
+    }
 }
@@ -0,0 +1,48 @@
+use egui;
+
+use crate::simulation::SimState;
+
+/// Journal panel state: the result of the last export.
+#[derive(Default)]
+pub struct JournalState {
+    pub last_result: Option<String>,
+}
+
+/// Intervention journal: every user action on the world, newest first, with CSV export.
+pub fn draw_journal(ctx: &egui::Context, sim: &SimState, state: &mut JournalState) {
+    egui::Window::new("Intervention Journal")
+        .default_pos(egui::pos2(660.0, 420.0))
+        .default_size(egui::vec2(340.0, 260.0))
+        .resizable(true)
+        .show(ctx, |ui| {
+            let journal = &sim.journal;
+            ui.horizontal(|ui| {
+                ui.label(format!("{} interventions", journal.entries.len()));
+                if ui.button("Export CSV").on_hover_text("Write interventions_tick{T}.csv").clicked() {
+                    let path = format!("interventions_tick{}.csv", sim.tick_count);
+                    state.last_result = Some(match journal.export_csv(&path, &sim.report_header(), sim.dt) {
+                        Ok(n) => format!("Exported {n} interventions to {path}"),
+                        Err(e) => format!("Export failed: {e}"),
+                    });
+                }
+            });
+            if let Some(ref msg) = state.last_result {
+                ui.label(msg);
+            }
+            ui.separator();
+
+            if journal.entries.is_empty() {
+                ui.label("No interventions: everything so far is emergent.");
+                return;
+            }
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                for entry in journal.entries.iter().rev() {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(format!("t{}", entry.tick)).weak().monospace());
+                        ui.label(egui::RichText::new(entry.kind.id()).strong());
+                        ui.label(&entry.detail);
+                    });
+                }
+            });
+        });
+}
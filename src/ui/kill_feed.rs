@@ -0,0 +1,50 @@
+use egui;
+
+use crate::camera::CameraController;
+use crate::config;
+use crate::simulation::SimState;
+
+/// Overlay of recent combat deaths in the top-right corner, fading out over KILL_FEED_TICKS.
+/// Clicking a name follows that entity if it is still alive.
+pub fn draw_kill_feed(ctx: &egui::Context, sim: &SimState, camera: &mut CameraController, below_inset: bool) {
+    let recent: Vec<_> = sim
+        .kill_feed
+        .iter()
+        .rev()
+        .filter(|k| sim.tick_count.saturating_sub(k.tick) < config::KILL_FEED_TICKS)
+        .collect();
+    if recent.is_empty() {
+        return;
+    }
+
+    let mut top = config::FOLLOW_INSET_TOP;
+    if below_inset {
+        top += config::FOLLOW_INSET_SIZE as f32 + config::FOLLOW_INSET_MARGIN;
+    }
+    egui::Area::new(egui::Id::new("kill_feed"))
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-config::FOLLOW_INSET_MARGIN, top))
+        .interactable(true)
+        .show(ctx, |ui| {
+            for kill in recent {
+                let age = sim.tick_count.saturating_sub(kill.tick) as f32 / config::KILL_FEED_TICKS as f32;
+                let alpha = ((1.0 - age) * 255.0) as u8;
+                let frame = egui::Frame::NONE
+                    .fill(egui::Color32::from_black_alpha(alpha / 2))
+                    .inner_margin(egui::Margin::symmetric(6, 2))
+                    .corner_radius(3.0);
+                frame.show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        let killer = egui::RichText::new(&kill.killer)
+                            .color(egui::Color32::from_rgba_unmultiplied(255, 140, 100, alpha));
+                        if ui.link(killer).on_hover_text(format!("Follow #{}", kill.killer_uid)).clicked() {
+                            camera.following = sim.arena.find_by_uid(kill.killer_uid);
+                        }
+                        ui.label(egui::RichText::new("killed").color(egui::Color32::from_white_alpha(alpha)));
+                        let victim = egui::RichText::new(&kill.victim)
+                            .color(egui::Color32::from_rgba_unmultiplied(170, 170, 190, alpha));
+                        ui.label(victim).on_hover_text(format!("#{}", kill.victim_uid));
+                    });
+                });
+            }
+        });
+}
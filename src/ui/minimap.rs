@@ -2,23 +2,79 @@ use egui;
 use macroquad::prelude::*;
 
 use crate::camera::CameraController;
+use crate::config;
 use crate::simulation::SimState;
 
 const MINIMAP_SIZE: f32 = 180.0;
 
-/// Draw a minimap showing entity positions, food, and camera viewport.
-pub fn draw_minimap(ctx: &egui::Context, sim: &SimState, camera: &CameraController) {
+/// Which world features the minimap draws.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinimapLayers {
+    pub entities: bool,
+    pub food: bool,
+    pub storms: bool,
+    pub toxic_zones: bool,
+    /// Walls and portals.
+    pub walls: bool,
+    /// Rings around entities carrying a tag.
+    pub tagged: bool,
+    /// Food spawn rate per cell, shaded green.
+    pub productivity: bool,
+}
+
+impl Default for MinimapLayers {
+    fn default() -> Self {
+        Self {
+            entities: true,
+            food: true,
+            storms: true,
+            toxic_zones: true,
+            walls: true,
+            tagged: true,
+            productivity: false,
+        }
+    }
+}
+
+impl MinimapLayers {
+    /// Layer toggles by preference key, with their button labels.
+    pub fn flags(&mut self) -> [(&'static str, &'static str, &mut bool); 7] {
+        [
+            ("entities", "Ent", &mut self.entities),
+            ("food", "Food", &mut self.food),
+            ("storms", "Storm", &mut self.storms),
+            ("toxic_zones", "Hazard", &mut self.toxic_zones),
+            ("walls", "Walls", &mut self.walls),
+            ("tagged", "Tagged", &mut self.tagged),
+            ("productivity", "Prod", &mut self.productivity),
+        ]
+    }
+}
+
+#[derive(Default)]
+pub struct MinimapState {
+    pub layers: MinimapLayers,
+    /// Offset from the pointer to the camera target while dragging, in world units.
+    drag_offset: Option<Vec2>,
+}
+
+/// Draw a minimap of the world and the camera viewport. Clicking jumps the camera to that
+/// point; dragging pans it, keeping the grab point under the pointer when the viewport box
+/// itself is dragged.
+pub fn draw_minimap(ctx: &egui::Context, sim: &SimState, camera: &mut CameraController, state: &mut MinimapState) {
     egui::Window::new("Minimap")
         .default_pos(egui::pos2(
             macroquad::prelude::screen_width() - MINIMAP_SIZE - 20.0,
-            macroquad::prelude::screen_height() - MINIMAP_SIZE - 60.0,
+            macroquad::prelude::screen_height() - MINIMAP_SIZE - 90.0,
         ))
-        .fixed_size(egui::vec2(MINIMAP_SIZE, MINIMAP_SIZE))
+        .resizable(false)
         .title_bar(false)
         .show(ctx, |ui| {
+            ui.set_width(MINIMAP_SIZE);
             let (response, painter) =
-                ui.allocate_painter(egui::vec2(MINIMAP_SIZE, MINIMAP_SIZE), egui::Sense::click());
+                ui.allocate_painter(egui::vec2(MINIMAP_SIZE, MINIMAP_SIZE), egui::Sense::click_and_drag());
             let rect = response.rect;
+            let layers = state.layers;
 
             // Background
             painter.rect_filled(rect, 2.0, egui::Color32::from_rgba_unmultiplied(10, 15, 25, 220));
@@ -32,35 +88,93 @@ pub fn draw_minimap(ctx: &egui::Context, sim: &SimState, camera: &CameraControll
                     rect.top() + (world_pos.y / world_h) * MINIMAP_SIZE,
                 )
             };
+            let to_world = |p: egui::Pos2| -> Vec2 {
+                vec2(
+                    ((p.x - rect.left()) / MINIMAP_SIZE).clamp(0.0, 1.0) * world_w,
+                    ((p.y - rect.top()) / MINIMAP_SIZE).clamp(0.0, 1.0) * world_h,
+                )
+            };
+            let to_minimap_len = |len: f32| len / world_w * MINIMAP_SIZE;
+
+            // Productivity as green cells under everything else
+            if layers.productivity {
+                let grid = &sim.heatmaps.productivity;
+                let cell = egui::vec2(to_minimap_len(grid.cell_size), grid.cell_size / world_h * MINIMAP_SIZE);
+                for (i, &val) in grid.cells.iter().enumerate().filter(|(_, v)| **v > 0.0) {
+                    let t = (val / config::PRODUCTIVITY_HEATMAP_MAX).min(1.0).sqrt();
+                    let min = to_minimap(grid.cell_center(i) - vec2(grid.cell_size, grid.cell_size) * 0.5);
+                    let color = egui::Color32::from_rgba_unmultiplied(90, 210, 60, (t * 150.0) as u8);
+                    painter.rect_filled(egui::Rect::from_min_size(min, cell).intersect(rect), 0.0, color);
+                }
+            }
+
+            // Toxic zones as purple discs
+            if layers.toxic_zones {
+                for zone in &sim.environment.toxic_zones {
+                    painter.circle_filled(
+                        to_minimap(zone.pos),
+                        to_minimap_len(zone.radius).max(1.5),
+                        egui::Color32::from_rgba_unmultiplied(150, 40, 130, 90),
+                    );
+                }
+            }
 
-            // Draw food as tiny green dots
-            for food in &sim.food {
-                let p = to_minimap(food.pos);
-                painter.circle_filled(p, 1.0, egui::Color32::from_rgb(50, 150, 50));
+            // Walls as grey lines, portals as linked violet dots
+            if layers.walls {
+                let wall_stroke = egui::Stroke::new(1.5, egui::Color32::from_gray(150));
+                for wall in &sim.environment.walls {
+                    painter.line_segment([to_minimap(wall.a), to_minimap(wall.b)], wall_stroke);
+                }
+                let portal_color = egui::Color32::from_rgb(140, 115, 255);
+                for portal in &sim.environment.portals {
+                    let (a, b) = (to_minimap(portal.a), to_minimap(portal.b));
+                    painter.line_segment([a, b], egui::Stroke::new(1.0, portal_color.gamma_multiply(0.3)));
+                    for end in [a, b] {
+                        painter.circle_filled(end, to_minimap_len(config::PORTAL_RADIUS).max(2.0), portal_color);
+                    }
+                }
             }
 
-            // Draw meat as tiny red dots
-            for item in &sim.meat {
-                let p = to_minimap(item.pos);
-                painter.circle_filled(p, 1.0, egui::Color32::from_rgb(150, 60, 50));
+            if layers.food {
+                // Draw food as tiny green dots
+                for food in &sim.food {
+                    let p = to_minimap(food.pos);
+                    painter.circle_filled(p, 1.0, egui::Color32::from_rgb(50, 150, 50));
+                }
+
+                // Draw meat as tiny red dots
+                for item in &sim.meat {
+                    let p = to_minimap(item.pos);
+                    painter.circle_filled(p, 1.0, egui::Color32::from_rgb(150, 60, 50));
+                }
             }
 
             // Draw entities
-            for (_idx, entity) in sim.arena.iter_alive() {
-                let p = to_minimap(entity.pos);
-                let c = entity.color;
-                let color = egui::Color32::from_rgb(
-                    (c.r * 255.0) as u8,
-                    (c.g * 255.0) as u8,
-                    (c.b * 255.0) as u8,
-                );
-                painter.circle_filled(p, 2.0, color);
+            if layers.entities {
+                for (idx, entity) in sim.arena.iter_alive() {
+                    let p = to_minimap(entity.pos);
+                    let c = sim.display_color(idx, entity);
+                    let color = egui::Color32::from_rgb(
+                        (c.r * 255.0) as u8,
+                        (c.g * 255.0) as u8,
+                        (c.b * 255.0) as u8,
+                    );
+                    painter.circle_filled(p, 2.0, color);
+                }
+            }
+
+            // Tagged entities get a ring, so they stand out even with the entity layer off
+            if layers.tagged {
+                let ring = egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 220, 90));
+                for (_, entity) in sim.arena.iter_alive().filter(|(_, e)| e.tag.is_some()) {
+                    painter.circle_stroke(to_minimap(entity.pos), 3.5, ring);
+                }
             }
 
             // Draw storm
-            if let Some(ref storm) = sim.environment.storm {
+            if let Some(storm) = sim.environment.storm.as_ref().filter(|_| layers.storms) {
                 let center = to_minimap(storm.center);
-                let r = (storm.radius / world_w) * MINIMAP_SIZE;
+                let r = to_minimap_len(storm.radius);
                 painter.circle(
                     center,
                     r,
@@ -77,10 +191,13 @@ pub fn draw_minimap(ctx: &egui::Context, sim: &SimState, camera: &CameraControll
             let tl = to_minimap(vec2(cam_center.x - half_w, cam_center.y - half_h));
             let br = to_minimap(vec2(cam_center.x + half_w, cam_center.y + half_h));
             let cam_rect = egui::Rect::from_min_max(tl, br);
+            let grabbed = state.drag_offset.is_some();
+            let hovered = response.hover_pos().is_some_and(|p| cam_rect.contains(p));
+            let alpha = if grabbed || hovered { 220 } else { 120 };
             painter.rect_stroke(
                 cam_rect,
                 0.0,
-                egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(255, 255, 255, 120)),
+                egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(255, 255, 255, alpha)),
                 egui::StrokeKind::Outside,
             );
 
@@ -91,5 +208,47 @@ pub fn draw_minimap(ctx: &egui::Context, sim: &SimState, camera: &CameraControll
                 egui::Stroke::new(1.0, egui::Color32::from_gray(60)),
                 egui::StrokeKind::Inside,
             );
+
+            // Navigation: a click jumps, a drag pans (grabbing the viewport box keeps its offset)
+            if response.drag_started() {
+                if let Some(p) = response.interact_pointer_pos() {
+                    let offset = if cam_rect.contains(p) { cam_center - to_world(p) } else { Vec2::ZERO };
+                    state.drag_offset = Some(offset);
+                }
+            }
+            if let (Some(offset), Some(p)) = (state.drag_offset, response.interact_pointer_pos()) {
+                if response.dragged() {
+                    let target = sim.world.wrap(to_world(p) + offset);
+                    release_camera(camera);
+                    camera.target = target;
+                    camera.smooth_target = target;
+                }
+            }
+            if response.drag_stopped() {
+                state.drag_offset = None;
+            }
+            if response.clicked() {
+                if let Some(p) = response.interact_pointer_pos() {
+                    release_camera(camera);
+                    camera.target = to_world(p);
+                }
+            }
+            response.on_hover_text("Click to jump there, drag to pan");
+
+            // Layer toggles
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing.x = 4.0;
+                for (_, label, flag) in state.layers.flags() {
+                    if ui.selectable_label(*flag, label).clicked() {
+                        *flag = !*flag;
+                    }
+                }
+            });
         });
 }
+
+/// Stop following so the camera stays where the minimap put it.
+fn release_camera(camera: &mut CameraController) {
+    camera.following = None;
+    camera.following_group = None;
+}
@@ -2,10 +2,28 @@ pub mod toolbar;
 pub mod inspector;
 pub mod neural_viz;
 pub mod graphs;
+pub mod generations;
 pub mod minimap;
 pub mod settings;
+pub mod breeding;
+pub mod hall_of_fame;
+pub mod kill_feed;
+pub mod selection;
+pub mod region;
+pub mod species_legend;
+pub mod color_legend;
+pub mod recovery;
+pub mod notifications;
+pub mod journal;
 
+use crate::audio::AudioSettings;
 use crate::camera::CameraController;
+use crate::coloring::ColorMode;
+use crate::editing::{EditHistory, EraseBrush, ToolMode, ToxicBrush, WallEditState};
+use crate::keybindings::KeyBindings;
+use crate::post_processing::GraphicsSettings;
+use crate::recorder::NeuronRecorder;
+use crate::run_target::RunUntil;
 use crate::simulation::SimState;
 use crate::stats::SimStats;
 
@@ -16,6 +34,49 @@ pub struct UiState {
     pub show_minimap: bool,
     pub show_settings: bool,
     pub show_neural_viz: bool,
+    pub show_breeding: bool,
+    pub show_hall_of_fame: bool,
+    pub show_generations: bool,
+    /// Overlay of recent combat deaths.
+    pub show_kill_feed: bool,
+    pub show_event_log: bool,
+    /// Timestamped list of user interventions.
+    pub show_journal: bool,
+    /// Pop up new notices as toasts; they always go to the event log.
+    pub show_toasts: bool,
+    /// Picture-in-picture view of the followed entity; the main camera stays free while it is on.
+    pub show_follow_inset: bool,
+    /// Thought bubble above the followed entity with its strongest motor intents.
+    pub show_intent_bubble: bool,
+    /// Whether the inspector's body-gene sliders are editable.
+    pub genome_edit: bool,
+    pub breeding: breeding::BreedingState,
+    pub hall_of_fame: hall_of_fame::HallOfFameState,
+    pub audio: AudioSettings,
+    pub graphics: GraphicsSettings,
+    pub minimap: minimap::MinimapState,
+    pub keybindings: KeyBindings,
+    pub key_editor: settings::KeyEditorState,
+    pub tool_mode: ToolMode,
+    pub edit_history: EditHistory,
+    /// Start of the wall currently being dragged out with the wall tool.
+    pub wall_drag_start: Option<macroquad::prelude::Vec2>,
+    /// First endpoint of the portal currently being dragged out with the portal tool.
+    pub portal_drag_start: Option<macroquad::prelude::Vec2>,
+    /// Start of the rubber-band rectangle being dragged with the select tool.
+    pub select_drag_start: Option<macroquad::prelude::Vec2>,
+    pub wall_edit: WallEditState,
+    pub toxic_brush: ToxicBrush,
+    pub erase_brush: EraseBrush,
+    pub selection: selection::SelectionState,
+    pub region: region::RegionState,
+    pub notifications: notifications::NotificationState,
+    /// Fast-forward in progress toward a tick, generation or event.
+    pub run_until: Option<RunUntil>,
+    pub run_until_input: toolbar::RunUntilInput,
+    pub journal: journal::JournalState,
+    /// Crash-recovery prompt shown at startup, if an autosave can be restored.
+    pub recovery: Option<recovery::RecoveryPrompt>,
 }
 
 impl Default for UiState {
@@ -26,6 +87,38 @@ impl Default for UiState {
             show_minimap: true,
             show_settings: false,
             show_neural_viz: false,
+            show_breeding: false,
+            show_hall_of_fame: false,
+            show_generations: false,
+            show_kill_feed: false,
+            show_event_log: false,
+            show_journal: false,
+            show_toasts: true,
+            show_follow_inset: false,
+            show_intent_bubble: true,
+            genome_edit: false,
+            breeding: breeding::BreedingState::default(),
+            hall_of_fame: hall_of_fame::HallOfFameState::default(),
+            audio: AudioSettings::default(),
+            graphics: GraphicsSettings::default(),
+            minimap: minimap::MinimapState::default(),
+            keybindings: KeyBindings::default(),
+            key_editor: settings::KeyEditorState::default(),
+            tool_mode: ToolMode::Select,
+            edit_history: EditHistory::default(),
+            wall_drag_start: None,
+            portal_drag_start: None,
+            select_drag_start: None,
+            wall_edit: WallEditState::default(),
+            toxic_brush: ToxicBrush::default(),
+            erase_brush: EraseBrush::default(),
+            selection: selection::SelectionState::default(),
+            region: region::RegionState::default(),
+            notifications: notifications::NotificationState::default(),
+            run_until: None,
+            run_until_input: toolbar::RunUntilInput::default(),
+            journal: journal::JournalState::default(),
+            recovery: None,
         }
     }
 }
@@ -35,31 +128,82 @@ pub fn draw_ui(
     sim: &mut SimState,
     camera: &mut CameraController,
     ui_state: &mut UiState,
-    stats: &SimStats,
+    stats: &mut SimStats,
+    recorder: &mut NeuronRecorder,
 ) {
     egui_macroquad::ui(|ctx| {
         toolbar::draw_toolbar(ctx, sim, ui_state);
 
         if ui_state.show_inspector {
-            inspector::draw_inspector(ctx, sim, camera);
+            inspector::draw_inspector(ctx, sim, camera, &mut ui_state.genome_edit);
         }
 
         if ui_state.show_neural_viz {
             if let Some(id) = camera.following {
-                neural_viz::draw_neural_viz(ctx, &sim.brains, id.index as usize);
+                neural_viz::draw_neural_viz(ctx, sim, id, recorder);
             }
         }
 
         if ui_state.show_graphs {
-            graphs::draw_graphs(ctx, stats);
+            graphs::draw_graphs(ctx, stats, sim.tick_count, &sim.report_header());
+        }
+
+        if ui_state.show_generations {
+            generations::draw_generations(ctx, &mut stats.generations, &sim.report_header());
         }
 
         if ui_state.show_minimap {
-            minimap::draw_minimap(ctx, sim, camera);
+            minimap::draw_minimap(ctx, sim, camera, &mut ui_state.minimap);
         }
 
         if ui_state.show_settings {
-            settings::draw_settings(ctx, sim);
+            settings::draw_settings(
+                ctx,
+                sim,
+                &mut ui_state.audio,
+                &mut ui_state.graphics,
+                &mut ui_state.keybindings,
+                &mut ui_state.key_editor,
+                &mut ui_state.edit_history,
+            );
+        } else {
+            ui_state.key_editor.capture = None;
+        }
+
+        if ui_state.show_breeding {
+            breeding::draw_breeding(ctx, sim, camera, &mut ui_state.breeding);
+        }
+
+        if !ui_state.selection.entities.is_empty() {
+            selection::draw_selection(ctx, sim, &mut ui_state.selection);
+        }
+
+        region::draw_region(ctx, sim, &mut ui_state.region, &mut ui_state.selection.entities);
+
+        if ui_state.show_hall_of_fame {
+            hall_of_fame::draw_hall_of_fame(ctx, sim, camera, &mut ui_state.hall_of_fame);
+        }
+
+        match sim.color_mode {
+            ColorMode::Genome => {}
+            ColorMode::Species => species_legend::draw_species_legend(ctx, sim),
+            _ => color_legend::draw_color_legend(ctx, sim),
+        }
+
+        if ui_state.show_kill_feed {
+            kill_feed::draw_kill_feed(ctx, sim, camera, ui_state.show_follow_inset);
+        }
+
+        if ui_state.show_event_log {
+            notifications::draw_event_log(ctx, &mut ui_state.notifications, camera, &mut ui_state.show_toasts);
+        }
+        if ui_state.show_journal {
+            journal::draw_journal(ctx, sim, &mut ui_state.journal);
+        }
+        notifications::draw_toasts(ctx, &mut ui_state.notifications, camera, ui_state.show_toasts);
+
+        if let Some(ref mut prompt) = ui_state.recovery {
+            recovery::draw_recovery_prompt(ctx, prompt);
         }
     });
 
@@ -1,135 +1,256 @@
 use egui;
 
-use crate::brain::BrainStorage;
+use crate::brain::{neuron_description, neuron_label, WEIGHT_SCALE};
 use crate::config;
+use crate::entity::EntityId;
 use crate::genome::N;
+use crate::recorder::NeuronRecorder;
+use crate::simulation::SimState;
 
-const NEURON_LABELS: &[&str] = &[
-    "L.Prox", "R.Prox", "Food", "Entity", "Energy", "Env", // sensors
-    "Inter.0", "Inter.1",                                     // interneurons
-    "Fwd", "Turn", "Attack", "Signal",                       // motors
-];
+/// Weakest connection drawn in the graph.
+const MIN_DRAWN_WEIGHT: f32 = 0.5;
+/// Vertical room each neuron needs before interneurons wrap into another column.
+const MIN_NEURON_SPACING: f32 = 18.0;
 
 /// Draw a neural network visualization for the selected entity's brain.
-pub fn draw_neural_viz(ctx: &egui::Context, brains: &BrainStorage, slot: usize) {
+pub fn draw_neural_viz(
+    ctx: &egui::Context,
+    sim: &SimState,
+    id: EntityId,
+    recorder: &mut NeuronRecorder,
+) {
+    let brains = &sim.brains;
+    let slot = id.index as usize;
     if slot >= brains.active.len() || !brains.active[slot] {
         return;
     }
+    let Some(uid) = sim.arena.get(id).map(|e| e.uid) else {
+        return;
+    };
 
     egui::Window::new("Neural Network")
         .default_pos(egui::pos2(300.0, 60.0))
         .default_size(egui::vec2(360.0, 340.0))
         .resizable(true)
         .show(ctx, |ui| {
+            // CSV recording of this entity's neuron activity
+            ui.horizontal(|ui| {
+                let recording_this = recorder.is_recording() && recorder.target == Some(id);
+                if recording_this {
+                    if ui.button("⏹ Stop recording").clicked() {
+                        recorder.stop();
+                    }
+                    ui.label(format!("{} ticks → {}", recorder.rows, recorder.path));
+                } else if ui.button("⏺ Record CSV").clicked() {
+                    if let Err(e) = recorder.start(id, uid, sim.tick_count, &sim.report_header()) {
+                        eprintln!("[GENESIS] Neuron recording failed: {e}");
+                    }
+                }
+            });
+
+            // Ring buffer of recent activity: export and per-neuron sparklines
+            let history = &mut recorder.history;
+            if history.target == Some(id) {
+                ui.horizontal(|ui| {
+                    if ui.button("Export history").clicked() {
+                        let path = format!("brain_uid{}_tick{}.csv", history.uid, sim.tick_count);
+                        history.last_export = Some(match history.export_csv(&path, &sim.report_header()) {
+                            Ok(n) => format!("{n} ticks → {path}"),
+                            Err(e) => format!("Export failed: {e}"),
+                        });
+                    }
+                    match history.last_export {
+                        Some(ref msg) => ui.label(msg),
+                        None => ui.label(format!("{} ticks buffered", history.samples.len())),
+                    };
+                });
+                ui.collapsing("Recent activity", |ui| {
+                    egui::Grid::new("brain_sparklines").spacing([6.0, 2.0]).show(ui, |ui| {
+                        for i in 0..N {
+                            // Sensors plot their raw input, other neurons their activation
+                            let values: Vec<f32> = if i < config::BRAIN_SENSOR_NEURONS {
+                                history.samples.iter().map(|s| s.states[i]).collect()
+                            } else {
+                                history.samples.iter().map(|s| s.outputs[i]).collect()
+                            };
+                            ui.label(egui::RichText::new(neuron_label(i)).size(10.0));
+                            sparkline(ui, &values, neuron_color(i));
+                            ui.label(
+                                egui::RichText::new(format!("{:.2}", values.last().copied().unwrap_or(0.0))).size(10.0),
+                            );
+                            ui.end_row();
+                        }
+                    });
+                });
+            }
+
             let outputs = &brains.outputs[slot];
             let weights = &brains.weights[slot];
             let states = &brains.states[slot];
 
             let available = ui.available_size();
-            let (response, painter) =
-                ui.allocate_painter(available, egui::Sense::hover());
+            let (response, painter) = ui.allocate_painter(available, egui::Sense::hover());
             let rect = response.rect;
+            let (positions, radius) = layout_neurons(rect);
+            let hovered = response
+                .hover_pos()
+                .and_then(|p| (0..N).find(|&i| positions[i].distance(p) <= radius + 2.0));
 
-            let sensor_n = config::BRAIN_SENSOR_NEURONS;
-            let inter_n = config::BRAIN_INTERNEURONS;
-            let motor_n = N - sensor_n - inter_n;
-
-            // Layout neurons in 3 columns: sensors | interneurons | motors
-            let col_x = [
-                rect.left() + 60.0,
-                rect.center().x,
-                rect.right() - 60.0,
-            ];
-
-            let neuron_positions: Vec<egui::Pos2> = (0..N)
-                .map(|i| {
-                    if i < sensor_n {
-                        // Sensor column
-                        let spacing = (rect.height() - 20.0) / sensor_n as f32;
-                        egui::pos2(col_x[0], rect.top() + 10.0 + spacing * (i as f32 + 0.5))
-                    } else if i < sensor_n + inter_n {
-                        // Interneuron column
-                        let local = i - sensor_n;
-                        let spacing = (rect.height() - 20.0) / inter_n as f32;
-                        egui::pos2(col_x[1], rect.top() + 10.0 + spacing * (local as f32 + 0.5))
-                    } else {
-                        // Motor column
-                        let local = i - sensor_n - inter_n;
-                        let spacing = (rect.height() - 20.0) / motor_n as f32;
-                        egui::pos2(col_x[2], rect.top() + 10.0 + spacing * (local as f32 + 0.5))
-                    }
-                })
-                .collect();
-
-            // Draw connections (weight lines)
-            for to in 0..N {
+            // Connections: color by sign, width by strength, brightness by the live activation
+            // flowing through them. Hovering a neuron isolates its own connections.
+            for to in config::BRAIN_SENSOR_NEURONS..N {
                 for from in 0..N {
                     let w = weights[to][from];
-                    if w.abs() < 0.5 {
-                        continue; // skip weak connections
+                    if w.abs() < MIN_DRAWN_WEIGHT {
+                        continue;
                     }
-                    let alpha = (w.abs() / 16.0).clamp(0.0, 1.0);
-                    let width = 0.5 + alpha * 2.5;
-                    let color = if w > 0.0 {
-                        egui::Color32::from_rgba_unmultiplied(100, 200, 100, (alpha * 180.0) as u8)
-                    } else {
-                        egui::Color32::from_rgba_unmultiplied(200, 80, 80, (alpha * 180.0) as u8)
-                    };
-                    painter.line_segment(
-                        [neuron_positions[from], neuron_positions[to]],
-                        egui::Stroke::new(width, color),
+                    let touches_hovered = hovered.is_none_or(|h| h == from || h == to);
+                    let strength = (w.abs() / WEIGHT_SCALE).clamp(0.0, 1.0);
+                    let flow = 0.25 + 0.75 * outputs[from].clamp(0.0, 1.0);
+                    let alpha = if touches_hovered { strength.sqrt() * flow } else { 0.04 };
+                    let rgb = if w > 0.0 { (100, 200, 100) } else { (200, 80, 80) };
+                    let stroke = egui::Stroke::new(
+                        0.5 + strength * 2.5,
+                        egui::Color32::from_rgba_unmultiplied(rgb.0, rgb.1, rgb.2, (alpha * 220.0) as u8),
                     );
+                    let (a, b) = (positions[from], positions[to]);
+                    if from == to {
+                        // Self-connection as a ring
+                        painter.circle_stroke(a, radius + 3.0, stroke);
+                    } else if (a.x - b.x).abs() < 1.0 {
+                        // Same column: bow sideways so the edge is not hidden behind the neurons between
+                        let bow = egui::vec2((b.y - a.y).abs() * 0.3, 0.0);
+                        let control = egui::pos2((a.x + b.x) * 0.5, (a.y + b.y) * 0.5) + bow;
+                        painter.add(egui::epaint::QuadraticBezierShape::from_points_stroke(
+                            [a, control, b],
+                            false,
+                            egui::Color32::TRANSPARENT,
+                            stroke,
+                        ));
+                    } else {
+                        painter.line_segment([a, b], stroke);
+                    }
                 }
             }
 
-            // Draw neurons
+            // Neurons, filled by activation
+            let sensor_n = config::BRAIN_SENSOR_NEURONS;
+            let motor_start = sensor_n + config::BRAIN_INTERNEURONS;
             for i in 0..N {
-                let pos = neuron_positions[i];
-                let activation = outputs[i];
-                let brightness = (activation * 255.0).clamp(0.0, 255.0) as u8;
-
+                let pos = positions[i];
+                let brightness = (outputs[i] * 255.0).clamp(0.0, 255.0) as u8;
                 let fill = if i < sensor_n {
                     egui::Color32::from_rgb(brightness / 2, brightness, brightness / 2)
-                } else if i < sensor_n + inter_n {
+                } else if i < motor_start {
                     egui::Color32::from_rgb(brightness, brightness, brightness / 2)
                 } else {
                     egui::Color32::from_rgb(brightness / 2, brightness / 2, brightness)
                 };
+                let outline = if hovered == Some(i) { egui::Color32::WHITE } else { egui::Color32::from_gray(180) };
+                painter.circle(pos, radius, fill, egui::Stroke::new(1.0, outline));
 
-                let radius = 10.0;
-                painter.circle(
-                    pos,
-                    radius,
-                    fill,
-                    egui::Stroke::new(1.0, egui::Color32::from_gray(180)),
-                );
-
-                // Label
-                let label = if i < NEURON_LABELS.len() {
-                    NEURON_LABELS[i]
-                } else {
-                    "?"
-                };
-
-                let label_x = if i < sensor_n {
-                    pos.x - 55.0
-                } else if i >= sensor_n + inter_n {
-                    pos.x + 14.0
-                } else {
-                    pos.x - 16.0
-                };
+                // Sensors and motors are labelled outside the graph; interneurons only on hover
+                let text = format!("{}\n{:.2}", neuron_label(i), states[i]);
+                let font = egui::FontId::proportional(9.0);
+                let color = egui::Color32::from_gray(200);
+                if i < sensor_n {
+                    painter.text(pos - egui::vec2(radius + 4.0, 0.0), egui::Align2::RIGHT_CENTER, text, font, color);
+                } else if i >= motor_start {
+                    painter.text(pos + egui::vec2(radius + 4.0, 0.0), egui::Align2::LEFT_CENTER, text, font, color);
+                }
+            }
 
-                painter.text(
-                    egui::pos2(label_x, pos.y - 5.0),
-                    if i < sensor_n {
-                        egui::Align2::RIGHT_CENTER
-                    } else {
-                        egui::Align2::LEFT_CENTER
-                    },
-                    format!("{label}\n{:.2}", states[i]),
-                    egui::FontId::proportional(9.0),
-                    egui::Color32::from_gray(200),
-                );
+            if let Some(i) = hovered {
+                response.on_hover_ui_at_pointer(|ui| {
+                    ui.label(egui::RichText::new(neuron_description(i)).strong());
+                    ui.label(format!(
+                        "State {:.2}, activation {:.2}, bias {:.2}, tau {:.2}s",
+                        states[i],
+                        outputs[i],
+                        brains.biases[slot][i],
+                        1.0 / brains.tau_inv[slot][i]
+                    ));
+                    // Strongest incoming connections (sensors ignore theirs: their state is clamped)
+                    if i >= sensor_n {
+                        let mut inputs: Vec<(usize, f32)> = (0..N).map(|j| (j, weights[i][j])).collect();
+                        inputs.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+                        for (j, w) in inputs.into_iter().take(3).filter(|(_, w)| w.abs() >= MIN_DRAWN_WEIGHT) {
+                            ui.label(format!("{:+.1} from {}", w, neuron_label(j)));
+                        }
+                    }
+                });
             }
         });
 }
+
+/// Neuron centres for a sensors | interneurons | motors layout inside `rect`, and the neuron
+/// radius. Interneurons wrap into extra columns once one column would crowd them, so any
+/// interneuron count fits; the radius shrinks with the densest column.
+fn layout_neurons(rect: egui::Rect) -> (Vec<egui::Pos2>, f32) {
+    let sensor_n = config::BRAIN_SENSOR_NEURONS;
+    let inter_n = config::BRAIN_INTERNEURONS;
+    let motor_n = N - sensor_n - inter_n;
+    let height = (rect.height() - 20.0).max(1.0);
+    let per_column = ((height / MIN_NEURON_SPACING) as usize).max(1);
+    let inter_columns = inter_n.div_ceil(per_column).max(1);
+
+    let left = rect.left() + 60.0;
+    let right = (rect.right() - 60.0).max(left + 1.0);
+    let column_gap = (right - left) / (inter_columns + 1) as f32;
+
+    // Spread `count` neurons down a column, offset by half a slot on alternate columns so
+    // edges between neighbouring interneuron columns do not run straight through neurons.
+    let column = |x: f32, count: usize, stagger: bool| -> Vec<egui::Pos2> {
+        let spacing = height / count as f32;
+        let offset = if stagger { 0.25 } else { 0.0 };
+        (0..count)
+            .map(|k| egui::pos2(x, rect.top() + 10.0 + spacing * (k as f32 + 0.5 + offset)))
+            .collect()
+    };
+
+    let mut positions = column(left, sensor_n, false);
+    let rows = inter_n.div_ceil(inter_columns);
+    for c in 0..inter_columns {
+        let count = rows.min(inter_n - c * rows);
+        positions.extend(column(left + column_gap * (c + 1) as f32, count, c % 2 == 1));
+    }
+    positions.extend(column(right, motor_n, false));
+
+    let densest = sensor_n.max(motor_n).max(rows) as f32;
+    let radius = (height / densest * 0.4).clamp(3.0, 10.0);
+    (positions, radius)
+}
+
+/// Column color of a neuron: green sensors, yellow interneurons, blue motors.
+fn neuron_color(i: usize) -> egui::Color32 {
+    if i < config::BRAIN_SENSOR_NEURONS {
+        egui::Color32::from_rgb(120, 220, 120)
+    } else if i < config::BRAIN_SENSOR_NEURONS + config::BRAIN_INTERNEURONS {
+        egui::Color32::from_rgb(220, 220, 120)
+    } else {
+        egui::Color32::from_rgb(130, 150, 240)
+    }
+}
+
+/// Small line plot of a series, scaled to its own min/max.
+fn sparkline(ui: &mut egui::Ui, values: &[f32], color: egui::Color32) {
+    let (response, painter) = ui.allocate_painter(egui::vec2(180.0, 16.0), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(30));
+    if values.len() < 2 {
+        return;
+    }
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let range = (max - min).max(1e-3);
+    let points: Vec<egui::Pos2> = values
+        .iter()
+        .enumerate()
+        .map(|(k, v)| {
+            let x = rect.left() + rect.width() * k as f32 / (values.len() - 1) as f32;
+            let y = rect.bottom() - 1.0 - (rect.height() - 2.0) * (v - min) / range;
+            egui::pos2(x, y)
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, color)));
+}
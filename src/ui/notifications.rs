@@ -0,0 +1,134 @@
+use std::collections::VecDeque;
+
+use egui;
+use macroquad::prelude::Vec2;
+
+use crate::camera::CameraController;
+use crate::config;
+use crate::notifications::{Notice, NoticeKind, NoticeLog};
+
+/// Event log contents plus the toasts currently on screen.
+#[derive(Default)]
+pub struct NotificationState {
+    pub log: NoticeLog,
+    /// Notice kinds hidden from the event log panel.
+    pub hidden: Vec<NoticeKind>,
+    /// Value of `log.total` when toasts were last refreshed.
+    seen: u64,
+    /// Toasts on screen, oldest first, with the egui time they appeared.
+    toasts: VecDeque<(Notice, f64)>,
+}
+
+fn kind_color(kind: NoticeKind) -> egui::Color32 {
+    match kind {
+        NoticeKind::Milestone => egui::Color32::from_rgb(120, 190, 255),
+        NoticeKind::Extinction => egui::Color32::from_rgb(255, 120, 110),
+        NoticeKind::Storm => egui::Color32::from_rgb(175, 165, 230),
+        NoticeKind::Save => egui::Color32::from_rgb(130, 210, 140),
+    }
+}
+
+fn jump_to(camera: &mut CameraController, pos: Vec2) {
+    camera.following = None;
+    camera.following_group = None;
+    camera.target = pos;
+}
+
+/// Transient toasts for new notices under the toolbar, fading out over TOAST_DURATION.
+/// Clicking a toast that has a location moves the camera there. With `enabled` off, new
+/// notices only go to the log.
+pub fn draw_toasts(ctx: &egui::Context, state: &mut NotificationState, camera: &mut CameraController, enabled: bool) {
+    let now = ctx.input(|i| i.time);
+    let new = state.log.total.saturating_sub(state.seen).min(state.log.entries.len() as u64) as usize;
+    state.seen = state.log.total;
+    if enabled {
+        let start = state.log.entries.len() - new;
+        state.toasts.extend(state.log.entries.range(start..).map(|n| (n.clone(), now)));
+    }
+    while state.toasts.len() > config::TOAST_MAX {
+        state.toasts.pop_front();
+    }
+    state.toasts.retain(|(_, shown)| now - shown < config::TOAST_DURATION);
+    if state.toasts.is_empty() {
+        return;
+    }
+
+    egui::Area::new(egui::Id::new("toasts"))
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, config::FOLLOW_INSET_TOP))
+        .interactable(true)
+        .show(ctx, |ui| {
+            for (notice, shown) in &state.toasts {
+                // Fully opaque until the last second, then fade out
+                let fade = ((config::TOAST_DURATION - (now - shown)) as f32).clamp(0.0, 1.0);
+                let alpha = (fade * 255.0) as u8;
+                let frame = egui::Frame::NONE
+                    .fill(egui::Color32::from_black_alpha((fade * 200.0) as u8))
+                    .stroke(egui::Stroke::new(1.0, kind_color(notice.kind).gamma_multiply(fade)))
+                    .inner_margin(egui::Margin::symmetric(10, 4))
+                    .corner_radius(4.0);
+                let response = frame
+                    .show(ui, |ui| {
+                        ui.label(egui::RichText::new(&notice.message).color(egui::Color32::from_white_alpha(alpha)));
+                    })
+                    .response;
+                if let Some(pos) = notice.pos {
+                    if response.interact(egui::Sense::click()).on_hover_text("Click to go there").clicked() {
+                        jump_to(camera, pos);
+                    }
+                }
+            }
+        });
+}
+
+/// Reviewable list of every notice, newest first, filterable by kind.
+pub fn draw_event_log(
+    ctx: &egui::Context,
+    state: &mut NotificationState,
+    camera: &mut CameraController,
+    toasts: &mut bool,
+) {
+    egui::Window::new("Event Log")
+        .default_pos(egui::pos2(300.0, 420.0))
+        .default_size(egui::vec2(340.0, 260.0))
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for kind in NoticeKind::ALL {
+                    let mut shown = !state.hidden.contains(&kind);
+                    let label = egui::RichText::new(kind.name()).color(kind_color(kind));
+                    if ui.toggle_value(&mut shown, label).changed() {
+                        if shown {
+                            state.hidden.retain(|k| *k != kind);
+                        } else {
+                            state.hidden.push(kind);
+                        }
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(toasts, "Toasts").on_hover_text("Pop up new events under the toolbar");
+                if ui.button("Clear").clicked() {
+                    state.log.entries.clear();
+                }
+            });
+            ui.separator();
+
+            if state.log.entries.is_empty() {
+                ui.label("No events yet.");
+                return;
+            }
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                for notice in state.log.entries.iter().rev().filter(|n| !state.hidden.contains(&n.kind)) {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(format!("t{}", notice.tick)).weak().monospace());
+                        ui.label(egui::RichText::new(&notice.message).color(kind_color(notice.kind)));
+                        if let Some(pos) = notice.pos {
+                            if ui.small_button("Go").on_hover_text("Move the camera there").clicked() {
+                                jump_to(camera, pos);
+                            }
+                        }
+                    });
+                }
+            });
+        });
+}
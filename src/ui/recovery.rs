@@ -0,0 +1,39 @@
+use egui;
+
+/// Startup prompt offered when a valid autosave exists: urgently after a crash, otherwise as
+/// a dismissable offer while the fresh world runs.
+pub struct RecoveryPrompt {
+    pub message: String,
+    /// Whether the previous session did not exit cleanly.
+    pub crashed: bool,
+    /// Set by the prompt: Some(true) to restore the autosave, Some(false) to start fresh.
+    pub choice: Option<bool>,
+}
+
+/// Draw the recovery prompt: in the middle of the screen after a crash, at the top otherwise.
+pub fn draw_recovery_prompt(ctx: &egui::Context, prompt: &mut RecoveryPrompt) {
+    let (anchor, offset) = if prompt.crashed {
+        (egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+    } else {
+        (egui::Align2::CENTER_TOP, egui::vec2(0.0, 50.0))
+    };
+    egui::Window::new("Recover autosave?")
+        .anchor(anchor, offset)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            if prompt.crashed {
+                ui.label("The previous session did not exit cleanly.");
+            }
+            ui.label(&prompt.message);
+            ui.horizontal(|ui| {
+                if ui.button("Recover latest autosave").clicked() {
+                    prompt.choice = Some(true);
+                }
+                let dismiss = if prompt.crashed { "Start fresh" } else { "Dismiss" };
+                if ui.button(dismiss).clicked() {
+                    prompt.choice = Some(false);
+                }
+            });
+        });
+}
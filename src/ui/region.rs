@@ -0,0 +1,188 @@
+use egui;
+use macroquad::prelude::*;
+
+use crate::entity::EntityId;
+use crate::save_load;
+use crate::simulation::SimState;
+use crate::species;
+use super::species_legend::swatch;
+
+/// An area of the world marked with the region tool, in world coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Region {
+    Rect(Rect),
+    Polygon(Vec<Vec2>),
+}
+
+impl Region {
+    /// Alive entities currently inside the region.
+    pub fn entities(&self, sim: &SimState) -> Vec<EntityId> {
+        let indices = match self {
+            Region::Rect(rect) => sim.spatial_hash.query_rect(*rect, &sim.arena),
+            Region::Polygon(points) => sim.spatial_hash.query_polygon(points, &sim.arena),
+        };
+        indices
+            .into_iter()
+            .map(|index| EntityId {
+                index,
+                generation: sim.arena.generations[index as usize],
+            })
+            .collect()
+    }
+
+    /// Area in square world units (shoelace formula for polygons).
+    pub fn area(&self) -> f32 {
+        match self {
+            Region::Rect(rect) => rect.w * rect.h,
+            Region::Polygon(points) => {
+                let twice: f32 = points
+                    .iter()
+                    .zip(points.iter().cycle().skip(1))
+                    .map(|(a, b)| a.x * b.y - b.x * a.y)
+                    .sum();
+                twice.abs() / 2.0
+            }
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Region::Rect(rect) => format!("Rectangle {:.0} x {:.0}", rect.w, rect.h),
+            Region::Polygon(points) => format!("Polygon with {} vertices", points.len()),
+        }
+    }
+}
+
+/// Region tool state: the finished region (if any) and the rectangle or polygon being drawn.
+#[derive(Default)]
+pub struct RegionState {
+    pub region: Option<Region>,
+    /// Start of the rectangle being dragged out.
+    pub drag_start: Option<Vec2>,
+    /// Vertices of the polygon being placed, one per click.
+    pub polygon: Vec<Vec2>,
+    pub last_result: Option<String>,
+}
+
+impl RegionState {
+    /// Finish a click or drag of the region tool. A drag of at least `min_drag` world units
+    /// makes a rectangle; a click adds a polygon vertex, and clicking within `close_radius` of
+    /// the first vertex closes the polygon.
+    pub fn release(&mut self, start: Vec2, end: Vec2, min_drag: f32, close_radius: f32) {
+        if start.distance(end) >= min_drag {
+            let rect = Rect::new(start.x.min(end.x), start.y.min(end.y), (start.x - end.x).abs(), (start.y - end.y).abs());
+            self.region = Some(Region::Rect(rect));
+            self.polygon.clear();
+        } else if self.polygon.len() >= 3 && self.polygon[0].distance(end) <= close_radius {
+            self.region = Some(Region::Polygon(std::mem::take(&mut self.polygon)));
+        } else {
+            self.polygon.push(end);
+        }
+        self.last_result = None;
+    }
+
+    /// Drop the region and anything being drawn.
+    pub fn clear(&mut self) {
+        self.region = None;
+        self.drag_start = None;
+        self.polygon.clear();
+        self.last_result = None;
+    }
+}
+
+/// Live statistics of the entities inside a region.
+pub struct RegionStats {
+    pub count: usize,
+    pub avg_energy: f32,
+    pub avg_generation: f32,
+    /// Members per species, largest first; `None` counts newborns not yet clustered.
+    pub species: Vec<(Option<u32>, usize)>,
+}
+
+impl RegionStats {
+    pub fn compute(sim: &SimState, ids: &[EntityId]) -> Self {
+        let mut energy = 0.0;
+        let mut generation = 0.0;
+        let mut species: Vec<(Option<u32>, usize)> = Vec::new();
+        for id in ids {
+            let Some(entity) = sim.arena.get(*id) else { continue };
+            energy += entity.energy;
+            generation += entity.generation_depth as f32;
+            let s = sim.species.species_of(id.index as usize);
+            match species.iter_mut().find(|(k, _)| *k == s) {
+                Some((_, n)) => *n += 1,
+                None => species.push((s, 1)),
+            }
+        }
+        species.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let n = ids.len().max(1) as f32;
+        Self {
+            count: ids.len(),
+            avg_energy: energy / n,
+            avg_generation: generation / n,
+            species,
+        }
+    }
+}
+
+/// Region panel: live population statistics of the marked region, with genome export.
+pub fn draw_region(
+    ctx: &egui::Context,
+    sim: &SimState,
+    state: &mut RegionState,
+    selection: &mut Vec<EntityId>,
+) {
+    let Some(region) = state.region.clone() else { return };
+    let ids = region.entities(sim);
+    let stats = RegionStats::compute(sim, &ids);
+
+    egui::Window::new("Region")
+        .default_pos(egui::pos2(300.0, 280.0))
+        .default_size(egui::vec2(260.0, 260.0))
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label(format!("{} ({:.0} units²)", region.describe(), region.area()));
+            ui.label(format!("{} entities inside", stats.count));
+            ui.label(format!("Avg energy: {:.1}", stats.avg_energy));
+            ui.label(format!("Avg generation: {:.1}", stats.avg_generation));
+
+            ui.separator();
+            ui.label(egui::RichText::new(format!("Species ({})", stats.species.len())).strong());
+            egui::ScrollArea::vertical().max_height(140.0).show(ui, |ui| {
+                for (id, n) in &stats.species {
+                    let share = *n as f32 / stats.count.max(1) as f32 * 100.0;
+                    ui.horizontal(|ui| match id {
+                        Some(id) => {
+                            swatch(ui, species::species_color(*id));
+                            ui.label(format!("S{id}: {n} ({share:.0}%)"));
+                        }
+                        None => {
+                            swatch(ui, species::UNASSIGNED_COLOR);
+                            ui.label(format!("Not yet clustered: {n} ({share:.0}%)"));
+                        }
+                    });
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Export genomes").clicked() {
+                    let path = format!("region_genomes_tick{}.csv", sim.tick_count);
+                    state.last_result = Some(match save_load::export_genomes_csv(sim, &ids, &path) {
+                        Ok(n) => format!("Exported {n} genomes to {path}"),
+                        Err(e) => format!("Export failed: {e}"),
+                    });
+                }
+                if ui.button("Select").on_hover_text("Select these entities for batch actions").clicked() {
+                    *selection = ids.clone();
+                }
+                if ui.button("Clear").clicked() {
+                    state.clear();
+                }
+            });
+
+            if let Some(ref msg) = state.last_result {
+                ui.label(msg);
+            }
+        });
+}
@@ -0,0 +1,187 @@
+use egui;
+use macroquad::prelude::*;
+
+use crate::config;
+use crate::entity::EntityId;
+use crate::journal::InterventionKind;
+use crate::save_load;
+use crate::simulation::SimState;
+
+/// Entities picked with the rubber-band select tool, plus batch-action settings.
+pub struct SelectionState {
+    pub entities: Vec<EntityId>,
+    /// Energy added to each selected entity by "Feed".
+    pub feed_amount: f32,
+    pub tag: String,
+    /// Waiting for a world click that chooses where "Relocate" moves the selection.
+    pub picking_destination: bool,
+    pub last_result: Option<String>,
+}
+
+impl Default for SelectionState {
+    fn default() -> Self {
+        Self {
+            entities: Vec::new(),
+            feed_amount: 50.0,
+            tag: String::new(),
+            picking_destination: false,
+            last_result: None,
+        }
+    }
+}
+
+impl SelectionState {
+    /// Select every alive entity inside the world-space rectangle spanned by `a` and `b`.
+    /// With `add`, the entities are added to the current selection instead of replacing it.
+    pub fn select_rect(&mut self, sim: &SimState, a: Vec2, b: Vec2, add: bool) {
+        if !add {
+            self.entities.clear();
+        }
+        let rect = Rect::new(a.x.min(b.x), a.y.min(b.y), (a.x - b.x).abs(), (a.y - b.y).abs());
+        for (idx, entity) in sim.arena.iter_alive() {
+            if !rect.contains(entity.pos) {
+                continue;
+            }
+            let id = EntityId {
+                index: idx as u32,
+                generation: sim.arena.generations[idx],
+            };
+            if !self.entities.contains(&id) {
+                self.entities.push(id);
+            }
+        }
+    }
+
+    /// Forget entities that have died.
+    pub fn prune(&mut self, sim: &SimState) {
+        self.entities.retain(|id| sim.arena.get(*id).is_some());
+    }
+
+    /// Move the whole selection so its centre lands on `dest`, keeping the entities' positions
+    /// relative to each other. Returns the number moved.
+    pub fn relocate(&mut self, sim: &mut SimState, dest: Vec2) -> usize {
+        let positions: Vec<(EntityId, Vec2)> =
+            self.entities.iter().filter_map(|id| sim.arena.get(*id).map(|e| (*id, e.pos))).collect();
+        let Some(&(_, anchor)) = positions.first() else { return 0 };
+        let dest = sim.world.wrap(dest);
+        // Centre measured through wrapped offsets, so a group straddling a world edge stays together
+        let offsets: Vec<Vec2> = positions.iter().map(|&(_, pos)| sim.world.delta(anchor, pos)).collect();
+        let center = offsets.iter().copied().sum::<Vec2>() / offsets.len() as f32;
+        let mut moved = 0;
+        for (&(id, _), offset) in positions.iter().zip(&offsets) {
+            if sim.teleport_entity(id, dest + *offset - center) {
+                moved += 1;
+            }
+        }
+        let detail = format!("{moved} selected entities to ({:.0}, {:.0})", dest.x, dest.y);
+        sim.journal.record(sim.tick_count, InterventionKind::Move, detail);
+        moved
+    }
+
+    /// Kill every selected entity.
+    pub fn delete_all(&mut self, sim: &mut SimState) -> usize {
+        let mut deleted = 0;
+        for id in self.entities.drain(..) {
+            if let Some(entity) = sim.arena.get_mut(id) {
+                entity.alive = false;
+                deleted += 1;
+            }
+        }
+        if deleted > 0 {
+            sim.journal.record(sim.tick_count, InterventionKind::Delete, format!("{deleted} selected entities"));
+        }
+        deleted
+    }
+}
+
+/// Selection panel: batch actions on all entities picked with the rubber-band select tool.
+pub fn draw_selection(ctx: &egui::Context, sim: &mut SimState, state: &mut SelectionState) {
+    egui::Window::new("Selection")
+        .default_pos(egui::pos2(300.0, 60.0))
+        .default_size(egui::vec2(260.0, 200.0))
+        .resizable(true)
+        .show(ctx, |ui| {
+            let count = state.entities.len();
+            let total_energy: f32 = state
+                .entities
+                .iter()
+                .filter_map(|id| sim.arena.get(*id))
+                .map(|e| e.energy)
+                .sum();
+            ui.label(format!("{} entities selected", count));
+            ui.label(format!("Avg energy: {:.1}", total_energy / count.max(1) as f32));
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut state.feed_amount).range(1.0..=config::MAX_ENTITY_ENERGY));
+                if ui.button("Feed").clicked() {
+                    for id in &state.entities {
+                        if let Some(entity) = sim.arena.get_mut(*id) {
+                            entity.energy = (entity.energy + state.feed_amount).min(config::MAX_ENTITY_ENERGY);
+                        }
+                    }
+                    let detail = format!("fed {} selected entities +{:.0}", count, state.feed_amount);
+                    sim.journal.record(sim.tick_count, InterventionKind::Modify, detail);
+                    state.last_result = Some(format!("Fed {} entities +{:.0}", count, state.feed_amount));
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut state.tag).desired_width(100.0).hint_text("tag"));
+                let tag = state.tag.trim().to_string();
+                if ui.add_enabled(!tag.is_empty(), egui::Button::new("Tag")).clicked() {
+                    for id in &state.entities {
+                        if let Some(entity) = sim.arena.get_mut(*id) {
+                            entity.tag = Some(tag.clone());
+                        }
+                    }
+                    state.last_result = Some(format!("Tagged {} entities '{}'", count, tag));
+                }
+                if ui.button("Untag").clicked() {
+                    for id in &state.entities {
+                        if let Some(entity) = sim.arena.get_mut(*id) {
+                            entity.tag = None;
+                        }
+                    }
+                    state.last_result = Some(format!("Cleared tags of {} entities", count));
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Export genomes").clicked() {
+                    let path = format!("genomes_tick{}.csv", sim.tick_count);
+                    state.last_result = Some(match save_load::export_genomes_csv(sim, &state.entities, &path) {
+                        Ok(n) => format!("Exported {n} genomes to {path}"),
+                        Err(e) => format!("Export failed: {e}"),
+                    });
+                }
+                if ui.button("Delete").clicked() {
+                    let n = state.delete_all(sim);
+                    state.last_result = Some(format!("Deleted {n} entities"));
+                }
+                if ui.button("Clear").clicked() {
+                    state.entities.clear();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if state.picking_destination {
+                    ui.colored_label(egui::Color32::from_rgb(120, 200, 255), "Click the world to place them");
+                    if ui.button("Cancel").clicked() {
+                        state.picking_destination = false;
+                    }
+                } else if ui
+                    .add_enabled(count > 0, egui::Button::new("Relocate…"))
+                    .on_hover_text("Move the selection as a group, keeping its shape")
+                    .clicked()
+                {
+                    state.picking_destination = true;
+                }
+            });
+
+            if let Some(ref msg) = state.last_result {
+                ui.label(msg);
+            }
+        });
+}
@@ -1,9 +1,35 @@
 use egui;
 
+use crate::audio::AudioSettings;
+use crate::coloring::ColorMode;
+use crate::editing::{self, EditHistory};
+use crate::environment::WorldPreset;
+use crate::heatmap::HeatmapLayer;
+use crate::journal::InterventionKind;
+use crate::plants::FoodModel;
+use crate::keybindings::{self, Action, KeyBindings, SLOTS};
+use crate::post_processing::{GraphicsSettings, VisualQuality};
+use crate::sensory::RayMode;
 use crate::simulation::SimState;
 
+/// State of the key binding editor.
+#[derive(Default)]
+pub struct KeyEditorState {
+    /// Action and slot waiting for the next key press.
+    pub capture: Option<(Action, usize)>,
+    pub status: Option<String>,
+}
+
 /// Runtime settings panel for tuning simulation parameters.
-pub fn draw_settings(ctx: &egui::Context, sim: &mut SimState) {
+pub fn draw_settings(
+    ctx: &egui::Context,
+    sim: &mut SimState,
+    audio: &mut AudioSettings,
+    graphics: &mut GraphicsSettings,
+    keys: &mut KeyBindings,
+    editor: &mut KeyEditorState,
+    history: &mut EditHistory,
+) {
     egui::Window::new("Settings")
         .default_pos(egui::pos2(300.0, 60.0))
         .default_size(egui::vec2(280.0, 360.0))
@@ -22,36 +48,163 @@ pub fn draw_settings(ctx: &egui::Context, sim: &mut SimState) {
                 "Season progress: {:.0}%",
                 sim.environment.season_progress * 100.0
             ));
+            if crate::config::LATITUDE_GRADIENT_ENABLED {
+                let bias = sim.environment.hemisphere_bias();
+                let favored = if bias >= 0.0 { "North" } else { "South" };
+                ui.label(format!("Food gradient: {favored} favored ({:.0}%)", bias.abs() * 100.0));
+            }
+            if crate::config::CLIMATE_ZONES_ENABLED {
+                let season = sim.environment.season.food_multiplier();
+                ui.label(format!(
+                    "Climate: equator ×{:.2}, poles ×{:.2} food",
+                    season * sim.environment.climate_zone_multiplier(0.0),
+                    season * sim.environment.climate_zone_multiplier(1.0)
+                ));
+            }
+
+            let wind = sim.environment.wind();
+            ui.label(format!(
+                "Wind: {:.0} u/s toward {:.0}°",
+                wind.length(),
+                wind.y.atan2(wind.x).to_degrees().rem_euclid(360.0)
+            ));
+            let weather = &sim.environment.weather;
+            let wet = weather.moisture.iter().filter(|&&m| m > 0.1).count();
+            ui.label(format!(
+                "Rain clouds: {} | Wet ground: {:.0}%",
+                weather.clouds.len(),
+                wet as f32 / weather.moisture.len().max(1) as f32 * 100.0
+            ));
+            ui.label(format!(
+                "Carcass-enriched ground: {:.1}%",
+                sim.environment.nutrients.enriched_fraction() * 100.0
+            ))
+            .on_hover_text("Meat that decays uneaten fertilizes the ground beneath it for a few minutes");
+            if let Some(eta) = sim.environment.storm_eta() {
+                ui.label(format!("Next storm in {:.0}s", eta));
+            }
 
             ui.separator();
 
-            ui.heading("Spawn Tools");
+            ui.heading("World");
+            let mut preset = sim.environment.world_preset;
+            let mut regenerate = false;
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_label("Preset")
+                    .selected_text(preset.name())
+                    .show_ui(ui, |ui| {
+                        for p in WorldPreset::ALL {
+                            regenerate |= ui.selectable_value(&mut preset, p, p.name()).changed();
+                        }
+                    });
+                regenerate |= ui.button("Reroll").on_hover_text("Regenerate terrain with a new seed").clicked();
+            });
+            ui.label(format!(
+                "Food rate x{:.1}, storms x{:.1}",
+                preset.food_rate(),
+                preset.storm_frequency()
+            ));
+            let mut terrain_seed = sim.environment.terrain_seed;
+            ui.horizontal(|ui| {
+                ui.label("Terrain seed:");
+                if ui.add(egui::DragValue::new(&mut terrain_seed)).changed() {
+                    sim.environment.regenerate_terrain(preset, terrain_seed);
+                }
+            });
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_label("Food")
+                    .selected_text(sim.plants.model.name())
+                    .show_ui(ui, |ui| {
+                        for m in FoodModel::ALL {
+                            ui.selectable_value(&mut sim.plants.model, m, m.name());
+                        }
+                    });
+            })
+            .response
+            .on_hover_text("Plant patches grow, spread and are grazed down; uniform food appears anywhere");
+            if sim.plants.model == FoodModel::Patches {
+                ui.label(format!("Plant cover: {:.0}% of the world", sim.plants.covered_fraction() * 100.0));
+            }
+            ui.checkbox(&mut sim.food_spoilage, "Food spoilage").on_hover_text(format!(
+                "Uneaten berries rot after {:.0}s and fruit after {:.0}s; grass keeps",
+                crate::config::BERRY_SPOIL_TIME,
+                crate::config::FRUIT_SPOIL_TIME
+            ));
+            ui.label(format!("Simulation seed: {}", sim.seed))
+                .on_hover_text("Set with --seed; the terrain seed is independent (--terrain-seed)");
+            if regenerate {
+                use ::rand::Rng;
+                let seed = sim.rng.gen();
+                sim.environment.regenerate_terrain(preset, seed);
+            }
+
+            ui.separator();
+
+            ui.heading("Simulation");
+            let mut sim_hz = sim.sim_hz().round();
+            let slider = egui::Slider::new(&mut sim_hz, crate::config::SIM_HZ_MIN..=crate::config::SIM_HZ_MAX)
+                .step_by(10.0)
+                .suffix(" Hz")
+                .text("Sim rate");
+            if ui
+                .add(slider)
+                .on_hover_text("Ticks per simulated second. Higher rates are more precise but cost more CPU")
+                .changed()
+            {
+                sim.set_sim_hz(sim_hz);
+            }
 
+            ui.separator();
+
+            ui.heading("Overlays");
+            let mut population = sim.heatmap_layer == Some(HeatmapLayer::Population);
+            if ui.checkbox(&mut population, "Population heatmap").changed() {
+                sim.heatmap_layer = population.then_some(HeatmapLayer::Population);
+            }
+            ui.add(egui::Slider::new(&mut sim.heatmap_opacity, 0.05..=1.0).text("Heatmap opacity"));
             ui.horizontal(|ui| {
-                if ui.button("Spawn 10 Food").clicked() {
-                    use ::rand::Rng;
-                    for _ in 0..10 {
-                        let pos = macroquad::prelude::vec2(
-                            sim.rng.gen_range(0.0..sim.world.width),
-                            sim.rng.gen_range(0.0..sim.world.height),
-                        );
-                        sim.food.push(crate::simulation::FoodItem {
-                            pos,
-                            energy: crate::config::FOOD_ENERGY,
-                        });
-                    }
+                egui::ComboBox::from_label("Entity color")
+                    .selected_text(sim.color_mode.name())
+                    .show_ui(ui, |ui| {
+                        for mode in ColorMode::ALL {
+                            ui.selectable_value(&mut sim.color_mode, mode, mode.name());
+                        }
+                    });
+            })
+            .response
+            .on_hover_text("Recolor entities by an attribute, with an on-screen legend (C cycles)");
+            ui.horizontal(|ui| {
+                ui.label("Sensor rays");
+                for mode in RayMode::ALL {
+                    ui.radio_value(&mut sim.ray_mode, mode, mode.name());
+                }
+            })
+            .response
+            .on_hover_text("Selected only draws the followed entity and the multi-selection");
+
+            ui.separator();
+
+            ui.heading("Graphics");
+            ui.horizontal(|ui| {
+                ui.label("Quality");
+                for quality in VisualQuality::ALL {
+                    ui.radio_value(&mut graphics.quality, quality, quality.name());
                 }
-                if ui.button("Spawn 50 Food").clicked() {
-                    use ::rand::Rng;
-                    for _ in 0..50 {
-                        let pos = macroquad::prelude::vec2(
-                            sim.rng.gen_range(0.0..sim.world.width),
-                            sim.rng.gen_range(0.0..sim.world.height),
-                        );
-                        sim.food.push(crate::simulation::FoodItem {
-                            pos,
-                            energy: crate::config::FOOD_ENERGY,
-                        });
+            })
+            .response
+            .on_hover_text("Bloom resolution and blur passes: Low renders bloom at quarter resolution, Ultra at full resolution with a wider glow");
+            ui.checkbox(&mut graphics.fxaa, "FXAA anti-aliasing")
+                .on_hover_text("Smooth jagged edges with a final full-screen pass");
+
+            ui.separator();
+
+            ui.heading("Spawn Tools");
+
+            ui.horizontal(|ui| {
+                for count in [10, 50] {
+                    if ui.button(format!("Spawn {count} Food")).on_hover_text("Undo with Ctrl+Z").clicked() {
+                        let op = editing::scattered_food_op(sim, count);
+                        history.perform(sim, op);
                     }
                 }
             });
@@ -63,39 +216,151 @@ pub fn draw_settings(ctx: &egui::Context, sim: &mut SimState) {
                     sim.rng.gen_range(50.0..sim.world.height - 50.0),
                 );
                 let genome = crate::genome::Genome::random(&mut sim.rng);
-                let entity = crate::entity::Entity::new_from_genome_rng(
-                    &genome,
-                    pos,
-                    sim.tick_count,
-                    &mut sim.rng,
-                );
-                if let Some(id) = sim.arena.spawn(entity) {
-                    let slot = id.index as usize;
-                    sim.brains.init_from_genome(slot, &genome);
-                    if slot < sim.genomes.len() {
-                        sim.genomes[slot] = Some(genome);
-                    }
+                if let Some(id) = sim.inject_genome(&genome, pos) {
+                    let uid = sim.arena.get(id).map_or(0, |e| e.uid);
+                    let detail = format!("random #{uid} at ({:.0}, {:.0})", pos.x, pos.y);
+                    sim.journal.record(sim.tick_count, InterventionKind::Spawn, detail);
                 }
             }
 
             if ui.button("Trigger Storm").clicked() {
                 use ::rand::Rng;
-                sim.environment.storm = Some(crate::environment::Storm {
-                    center: macroquad::prelude::vec2(
-                        sim.rng.gen_range(0.0..sim.world.width),
-                        sim.rng.gen_range(0.0..sim.world.height),
-                    ),
-                    radius: crate::config::STORM_RADIUS,
-                    velocity: macroquad::prelude::Vec2::from_angle(
-                        sim.rng.gen_range(0.0..std::f32::consts::TAU),
-                    ) * 30.0,
-                    timer: crate::config::STORM_DURATION,
-                });
+                let center = macroquad::prelude::vec2(
+                    sim.rng.gen_range(0.0..sim.world.width),
+                    sim.rng.gen_range(0.0..sim.world.height),
+                );
+                sim.environment.storm =
+                    Some(crate::environment::Storm::new(center, crate::config::STORM_RADIUS, &mut sim.rng));
+                sim.sound_events.storm_started = true;
+                let detail = format!("at ({:.0}, {:.0})", center.x, center.y);
+                sim.journal.record(sim.tick_count, InterventionKind::Storm, detail);
             }
 
+            ui.separator();
+            ui.heading("Audio");
+
+            ui.checkbox(&mut audio.enabled, "Sound enabled");
+            ui.add_enabled_ui(audio.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut audio.master_volume, 0.0..=1.0).text("Master volume"));
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut audio.births, "Births");
+                    ui.checkbox(&mut audio.deaths, "Deaths");
+                    ui.checkbox(&mut audio.combat, "Combat");
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut audio.storms, "Storms");
+                    ui.checkbox(&mut audio.ui_clicks, "UI clicks");
+                });
+            });
+
+            ui.separator();
+            ui.collapsing("Energy Audit", |ui| {
+                let mut enabled = sim.energy_audit.enabled;
+                if ui.checkbox(&mut enabled, "Check energy ledger every tick").changed() {
+                    sim.energy_audit.toggle(enabled);
+                }
+                let audit = &sim.energy_audit;
+                if !audit.enabled {
+                    return;
+                }
+                ui.label(format!(
+                    "{} ticks checked, {} unbalanced (worst {:.4})",
+                    audit.ticks_checked, audit.violations, audit.worst
+                ));
+                egui::Grid::new("energy_ledger").striped(true).show(ui, |ui| {
+                    ui.label("Opening stock");
+                    ui.label(format!("{:.2}", audit.opening));
+                    ui.end_row();
+                    for (name, amount) in audit.last.rows() {
+                        ui.label(name);
+                        ui.label(format!("{amount:+.3}"));
+                        ui.end_row();
+                    }
+                    ui.label("Closing stock");
+                    ui.label(format!("{:.2}", audit.closing));
+                    ui.end_row();
+                    ui.label("Discrepancy");
+                    ui.label(format!("{:+.5}", audit.discrepancy));
+                    ui.end_row();
+                    ui.label("External edits");
+                    ui.label(format!("{:+.2}", audit.external));
+                    ui.end_row();
+                });
+            });
+
+            ui.separator();
+            ui.collapsing("Key Bindings", |ui| {
+                draw_key_bindings(ui, keys, editor);
+            });
+
             ui.separator();
             ui.heading("Info");
             ui.label(format!("Spatial cells: {}x{}", sim.spatial_hash.cols, sim.spatial_hash.rows));
             ui.label(format!("Pheromone grid: {}x{}", sim.pheromone_grid.width, sim.pheromone_grid.height));
         });
 }
+
+/// Key binding editor: click a slot and press a key to rebind it, right-click to clear it.
+fn draw_key_bindings(ui: &mut egui::Ui, keys: &mut KeyBindings, editor: &mut KeyEditorState) {
+    if let Some((action, slot)) = editor.capture {
+        if let Some(binding) = keybindings::capture_key() {
+            keys.set(action, slot, Some(binding));
+            editor.capture = None;
+        }
+    }
+
+    egui::Grid::new("key_bindings_grid").striped(true).show(ui, |ui| {
+        for action in Action::ALL {
+            ui.label(action.name());
+            let bindings = keys.get(action);
+            for (slot, binding) in bindings.iter().enumerate().take(SLOTS) {
+                let capturing = editor.capture == Some((action, slot));
+                let label = if capturing {
+                    "Press a key...".to_string()
+                } else {
+                    binding.map_or("-".to_string(), |b| b.to_string())
+                };
+                let response = ui.selectable_label(capturing, label);
+                if response.clicked() {
+                    editor.capture = if capturing { None } else { Some((action, slot)) };
+                }
+                if response.secondary_clicked() {
+                    keys.set(action, slot, None);
+                }
+            }
+            ui.end_row();
+        }
+    });
+
+    let conflicts = keys.conflicts();
+    for c in &conflicts {
+        ui.colored_label(
+            egui::Color32::from_rgb(230, 90, 80),
+            format!("{} is bound to both {} and {}", c.binding, c.first, c.second),
+        );
+    }
+
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(conflicts.is_empty(), egui::Button::new("Save"))
+            .on_disabled_hover_text("Resolve conflicts first")
+            .clicked()
+        {
+            editor.status = Some(match keys.save(keybindings::KEYBINDINGS_PATH) {
+                Ok(()) => format!("Saved to {}", keybindings::KEYBINDINGS_PATH),
+                Err(e) => format!("Save failed: {e}"),
+            });
+        }
+        if ui.button("Reset to defaults").clicked() {
+            *keys = KeyBindings::default();
+            editor.capture = None;
+        }
+        if editor.capture.is_some() && ui.button("Cancel").clicked() {
+            editor.capture = None;
+        }
+    });
+
+    if let Some(ref msg) = editor.status {
+        ui.label(msg);
+    }
+}
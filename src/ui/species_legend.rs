@@ -0,0 +1,43 @@
+use egui;
+
+use crate::config;
+use crate::simulation::SimState;
+use crate::species::{self, Species};
+
+/// Legend for the species color mode: the largest species with their palette colors.
+pub fn draw_species_legend(ctx: &egui::Context, sim: &SimState) {
+    let mut ranked: Vec<&Species> = sim.species.species.iter().collect();
+    ranked.sort_by(|a, b| b.member_count.cmp(&a.member_count).then(a.id.cmp(&b.id)));
+
+    let corner = ctx.available_rect().left_bottom() + egui::vec2(12.0, -12.0);
+    egui::Area::new(egui::Id::new("species_legend"))
+        .pivot(egui::Align2::LEFT_BOTTOM)
+        .fixed_pos(corner)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(egui::RichText::new(format!("Species ({})", ranked.len())).strong());
+                for s in ranked.iter().take(config::SPECIES_LEGEND_ROWS) {
+                    ui.horizontal(|ui| {
+                        swatch(ui, species::species_color(s.id));
+                        ui.label(format!("S{}: {} members", s.id, s.member_count));
+                    });
+                }
+                if ranked.len() > config::SPECIES_LEGEND_ROWS {
+                    ui.label(format!("+{} smaller species", ranked.len() - config::SPECIES_LEGEND_ROWS));
+                }
+                ui.horizontal(|ui| {
+                    swatch(ui, species::UNASSIGNED_COLOR);
+                    ui.label("Newborn, not yet clustered");
+                });
+            });
+        });
+}
+
+pub fn swatch(ui: &mut egui::Ui, c: macroquad::prelude::Color) {
+    let (_, rect) = ui.allocate_space(egui::vec2(12.0, 12.0));
+    ui.painter().rect_filled(
+        rect,
+        2.0,
+        egui::Color32::from_rgb((c.r * 255.0) as u8, (c.g * 255.0) as u8, (c.b * 255.0) as u8),
+    );
+}
@@ -1,8 +1,55 @@
 use egui;
 
+use crate::config;
+use crate::editing::ToolMode;
+use crate::heatmap::HeatmapLayer;
+use crate::journal::InterventionKind;
+use crate::run_target::{self, RunTarget, RunUntil};
 use crate::simulation::SimState;
 use super::UiState;
 
+/// Values typed into the run-until menu.
+#[derive(Default)]
+pub struct RunUntilInput {
+    pub tick: u64,
+    pub generation: u32,
+}
+
+/// Run-until menu: a tick, a generation or the next event. Returns the chosen target.
+fn draw_run_until_menu(ui: &mut egui::Ui, sim: &SimState, input: &mut RunUntilInput) -> Option<RunTarget> {
+    if input.tick <= sim.tick_count {
+        input.tick = sim.tick_count + config::RUN_UNTIL_DEFAULT_TICKS;
+    }
+    let deepest = sim.arena.deepest_generation();
+    if input.generation <= deepest {
+        input.generation = deepest + config::RUN_UNTIL_DEFAULT_GENERATIONS;
+    }
+    let mut target = None;
+    egui::Grid::new("run_until").num_columns(3).show(ui, |ui| {
+        ui.label("Tick");
+        ui.add(egui::DragValue::new(&mut input.tick).speed(100.0));
+        if ui.button("Go").clicked() {
+            target = Some(RunTarget::Tick(input.tick));
+        }
+        ui.end_row();
+        ui.label("Generation");
+        ui.add(egui::DragValue::new(&mut input.generation));
+        if ui.button("Go").clicked() {
+            target = Some(RunTarget::Generation(input.generation));
+        }
+        ui.end_row();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Next");
+        for kind in RunTarget::EVENTS {
+            if ui.button(run_target::event_id(kind)).clicked() {
+                target = Some(RunTarget::Event(kind));
+            }
+        }
+    });
+    target
+}
+
 /// Top toolbar with simulation controls and panel toggles.
 pub fn draw_toolbar(ctx: &egui::Context, sim: &mut SimState, ui_state: &mut UiState) {
     egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
@@ -17,15 +64,95 @@ pub fn draw_toolbar(ctx: &egui::Context, sim: &mut SimState, ui_state: &mut UiSt
 
             // Speed control
             ui.label("Speed:");
-            let speeds = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+            let speeds = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 50.0, 100.0];
             for &s in &speeds {
                 let label = format!("{s}x");
                 let selected = (sim.speed_multiplier - s).abs() < 0.01;
-                if ui.selectable_label(selected, &label).clicked() {
+                let mut button = ui.selectable_label(selected, &label);
+                if s >= config::FAST_FORWARD_MIN_SPEED {
+                    button = button.on_hover_text("Fast-forward: the world is not drawn, only a progress overlay");
+                }
+                if button.clicked() && !selected {
+                    let detail = format!("{}x to {s}x", sim.speed_multiplier);
+                    sim.journal.record(sim.tick_count, InterventionKind::Speed, detail);
                     sim.speed_multiplier = s;
                 }
             }
 
+            // Run until a tick, a generation or an event, then pause
+            match ui_state.run_until {
+                Some(run) => {
+                    let status = match run.progress(sim) {
+                        Some(frac) => format!("⏩ {} {:.0}%", run.target.describe(), frac * 100.0),
+                        None => format!("⏩ {}", run.target.describe()),
+                    };
+                    ui.label(status);
+                    if ui.small_button("✕").on_hover_text("Stop running until the target").clicked() {
+                        ui_state.run_until = None;
+                        run.cancel(sim);
+                    }
+                }
+                None => {
+                    ui.menu_button("Until…", |ui| {
+                        let target = draw_run_until_menu(ui, sim, &mut ui_state.run_until_input);
+                        if let Some(target) = target {
+                            ui_state.run_until = Some(RunUntil::start(sim, target));
+                            ui.close_menu();
+                        }
+                    })
+                    .response
+                    .on_hover_text("Fast-forward until a tick, a generation or an event, then pause");
+                }
+            }
+
+            ui.separator();
+
+            // World-editing tools
+            ui.label("Tool:");
+            for mode in ToolMode::ALL {
+                if ui.selectable_label(ui_state.tool_mode == mode, mode.name()).clicked() {
+                    ui_state.tool_mode = mode;
+                    ui_state.wall_drag_start = None;
+                    ui_state.portal_drag_start = None;
+                }
+            }
+            if ui_state.tool_mode == ToolMode::ToxicZone {
+                let brush = ui_state.toxic_brush;
+                ui.label(format!("r {:.0} · x{:.2}", brush.radius, brush.intensity))
+                    .on_hover_text("Scroll to resize the hazard, Shift+scroll to change its intensity");
+            }
+            if ui_state.tool_mode == ToolMode::Erase {
+                ui.label(format!("r {:.0}", ui_state.erase_brush.radius))
+                    .on_hover_text("Click to erase food, meat and hazards; Shift+click also erases walls. Scroll to resize");
+            }
+            let undo_hint = ui_state.edit_history.next_undo().map(|op| op.describe());
+            let undo = ui.add_enabled(undo_hint.is_some(), egui::Button::new("↶ Undo"));
+            if undo.on_hover_text(format!("Undo {} (Ctrl+Z)", undo_hint.unwrap_or("edit"))).clicked() {
+                ui_state.edit_history.undo(sim);
+            }
+            let redo_hint = ui_state.edit_history.next_redo().map(|op| op.describe());
+            let redo = ui.add_enabled(redo_hint.is_some(), egui::Button::new("↷ Redo"));
+            if redo.on_hover_text(format!("Redo {} (Ctrl+Y)", redo_hint.unwrap_or("edit"))).clicked() {
+                ui_state.edit_history.redo(sim);
+            }
+
+            ui.separator();
+
+            // Heatmap overlay selector
+            ui.label("Heatmap:");
+            let current = sim.heatmap_layer.map(|l| l.name()).unwrap_or("Off");
+            egui::ComboBox::from_id_salt("heatmap_layer")
+                .selected_text(current)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut sim.heatmap_layer, None, "Off");
+                    for layer in HeatmapLayer::ALL {
+                        ui.selectable_value(&mut sim.heatmap_layer, Some(layer), layer.name());
+                    }
+                });
+            if sim.heatmap_layer.is_some_and(|l| l.accumulates()) && ui.small_button("Clear").clicked() {
+                sim.heatmaps.clear();
+            }
+
             ui.separator();
 
             // Stats
@@ -34,7 +161,17 @@ pub fn draw_toolbar(ctx: &egui::Context, sim: &mut SimState, ui_state: &mut UiSt
                 sim.arena.count,
                 sim.food.len(),
                 sim.tick_count,
-            ));
+            ))
+            .on_hover_text({
+                let slots = sim.arena.occupancy();
+                format!(
+                    "{} entity slots allocated, growing on demand up to {}\n{:.0}% occupied; {:.0}% of the slots up to the highest living one are empty",
+                    slots.capacity,
+                    config::MAX_ENTITY_COUNT,
+                    slots.occupancy() * 100.0,
+                    slots.fragmentation() * 100.0
+                )
+            });
 
             ui.separator();
 
@@ -42,8 +179,21 @@ pub fn draw_toolbar(ctx: &egui::Context, sim: &mut SimState, ui_state: &mut UiSt
             ui.toggle_value(&mut ui_state.show_inspector, "Inspector");
             ui.toggle_value(&mut ui_state.show_neural_viz, "Brain");
             ui.toggle_value(&mut ui_state.show_graphs, "Graphs");
+            ui.toggle_value(&mut ui_state.show_generations, "Generations");
             ui.toggle_value(&mut ui_state.show_minimap, "Minimap");
+            ui.toggle_value(&mut ui_state.show_follow_inset, "Inset")
+                .on_hover_text("Picture-in-picture of the selected entity; the main camera stays free (P)");
+            ui.toggle_value(&mut ui_state.show_intent_bubble, "Intents")
+                .on_hover_text("Thought bubble above the selected entity showing what its brain is trying to do");
+            ui.toggle_value(&mut ui_state.show_kill_feed, "Kill feed")
+                .on_hover_text("Recent combat deaths; click a killer to follow it");
+            ui.toggle_value(&mut ui_state.show_event_log, "Events")
+                .on_hover_text("Log of milestones, extinctions, storms and saves");
+            ui.toggle_value(&mut ui_state.show_journal, "Journal")
+                .on_hover_text("Every intervention you made, with its tick; stored in saves");
             ui.toggle_value(&mut ui_state.show_settings, "Settings");
+            ui.toggle_value(&mut ui_state.show_breeding, "Breeding");
+            ui.toggle_value(&mut ui_state.show_hall_of_fame, "Hall of Fame");
         });
     });
 }
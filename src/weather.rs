@@ -0,0 +1,150 @@
+use macroquad::prelude::*;
+use ::rand::Rng;
+
+use crate::config;
+use crate::entity::EntityArena;
+use crate::world::World;
+
+/// A drifting rain cloud. Wets the ground beneath it until its timer runs out.
+#[derive(Clone, Debug)]
+pub struct RainCloud {
+    pub center: Vec2,
+    pub radius: f32,
+    pub timer: f32,
+}
+
+/// Wind gusts, rain clouds and the soil moisture they leave behind.
+pub struct Weather {
+    /// Drifting wind component on top of the seasonal breeze.
+    pub gust: Vec2,
+    /// Gust the wind is currently veering toward; re-rolled every WIND_GUST_INTERVAL seconds.
+    pub gust_target: Vec2,
+    pub gust_timer: f32,
+    pub clouds: Vec<RainCloud>,
+    pub rain_cooldown: f32,
+    /// Soil moisture per cell in [0, 1]. Raised by rain, dries out over time, boosts food growth.
+    pub moisture: Vec<f32>,
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: f32,
+}
+
+impl Weather {
+    pub fn new(world_w: f32, world_h: f32, cell_size: f32) -> Self {
+        let width = (world_w / cell_size).ceil() as usize;
+        let height = (world_h / cell_size).ceil() as usize;
+        Self {
+            gust: Vec2::ZERO,
+            gust_target: Vec2::ZERO,
+            gust_timer: 0.0,
+            clouds: Vec::new(),
+            rain_cooldown: config::RAIN_INTERVAL_MIN,
+            moisture: vec![0.0; width * height],
+            width,
+            height,
+            cell_size,
+        }
+    }
+
+    /// Advance gusts and clouds, then rain on the ground under clouds (and the storm, if any).
+    pub fn tick(&mut self, wind: Vec2, storm: Option<(Vec2, f32)>, dt: f32, world: &World, rng: &mut impl Rng) {
+        // Wind gusts veer smoothly toward a new random target
+        self.gust_timer -= dt;
+        if self.gust_timer <= 0.0 {
+            self.gust_timer = config::WIND_GUST_INTERVAL;
+            self.gust_target = Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU))
+                * rng.gen_range(0.0..config::WIND_GUST_STRENGTH);
+        }
+        self.gust = self.gust.lerp(self.gust_target, (dt / config::WIND_GUST_INTERVAL).min(1.0));
+
+        // Clouds drift with the wind
+        for cloud in &mut self.clouds {
+            cloud.center = world.wrap(cloud.center + wind * config::RAIN_CLOUD_DRIFT * dt);
+            cloud.timer -= dt;
+        }
+        self.clouds.retain(|c| c.timer > 0.0);
+
+        self.rain_cooldown -= dt;
+        if self.rain_cooldown <= 0.0 && self.clouds.len() < config::MAX_RAIN_CLOUDS {
+            self.rain_cooldown = rng.gen_range(config::RAIN_INTERVAL_MIN..config::RAIN_INTERVAL_MAX);
+            self.clouds.push(RainCloud {
+                center: vec2(rng.gen_range(0.0..world.width), rng.gen_range(0.0..world.height)),
+                radius: rng.gen_range(config::RAIN_CLOUD_RADIUS * 0.6..config::RAIN_CLOUD_RADIUS * 1.4),
+                timer: config::RAIN_DURATION,
+            });
+        }
+
+        // Ground dries out, then rain soaks it
+        let dry = config::MOISTURE_DRY_RATE * dt;
+        self.moisture.iter_mut().for_each(|m| *m = (*m - dry).max(0.0));
+        let wet = config::RAIN_MOISTURE_RATE * dt;
+        let rain: Vec<(Vec2, f32)> = self
+            .clouds
+            .iter()
+            .map(|c| (c.center, c.radius))
+            .chain(storm)
+            .collect();
+        for (center, radius) in rain {
+            self.soak(center, radius, wet, world);
+        }
+    }
+
+    /// Add `amount` of moisture to every cell whose center lies within `radius` of `center`.
+    fn soak(&mut self, center: Vec2, radius: f32, amount: f32, world: &World) {
+        let r_cells = (radius / self.cell_size).ceil() as i32;
+        let cx = (center.x / self.cell_size) as i32;
+        let cy = (center.y / self.cell_size) as i32;
+        for dy in -r_cells..=r_cells {
+            for dx in -r_cells..=r_cells {
+                let x = (cx + dx).rem_euclid(self.width as i32) as usize;
+                let y = (cy + dy).rem_euclid(self.height as i32) as usize;
+                let cell_center = vec2((x as f32 + 0.5) * self.cell_size, (y as f32 + 0.5) * self.cell_size);
+                if world.distance_sq(cell_center, center) <= radius * radius {
+                    let m = &mut self.moisture[y * self.width + x];
+                    *m = (*m + amount).min(1.0);
+                }
+            }
+        }
+    }
+
+    pub fn moisture_at(&self, pos: Vec2) -> f32 {
+        let x = ((pos.x / self.cell_size) as usize).min(self.width - 1);
+        let y = ((pos.y / self.cell_size) as usize).min(self.height - 1);
+        self.moisture[y * self.width + x]
+    }
+
+    /// Food spawn multiplier from soil moisture at a position.
+    pub fn food_multiplier(&self, pos: Vec2) -> f32 {
+        1.0 + config::RAIN_FOOD_BOOST * self.moisture_at(pos)
+    }
+
+    pub fn is_raining(&self) -> bool {
+        !self.clouds.is_empty()
+    }
+}
+
+/// Nudge unsheltered entities downwind.
+pub fn apply_wind(arena: &mut EntityArena, wind: Vec2, dt: f32) {
+    let push = wind * config::WIND_PUSH * dt;
+    for (_, entity) in arena.iter_alive_mut() {
+        if !entity.sheltered {
+            entity.velocity += push;
+        }
+    }
+}
+
+/// Draw rain clouds as soft grey-blue discs with a few rain streaks.
+pub fn draw_rain_clouds(clouds: &[RainCloud], time: f32) {
+    for cloud in clouds {
+        draw_circle(cloud.center.x, cloud.center.y, cloud.radius, Color::new(0.35, 0.45, 0.6, 0.12));
+        draw_circle_lines(cloud.center.x, cloud.center.y, cloud.radius, 2.0, Color::new(0.5, 0.6, 0.8, 0.25));
+        // Deterministic streaks that scroll downward over time
+        for i in 0..12 {
+            let angle = i as f32 * 2.399; // golden angle spreads streaks evenly
+            let dist = cloud.radius * ((i as f32 * 0.37).fract() * 0.9);
+            let fall = ((time * 1.5 + i as f32 * 0.13).fract() - 0.5) * 30.0;
+            let p = cloud.center + Vec2::from_angle(angle) * dist + vec2(0.0, fall);
+            draw_line(p.x, p.y, p.x - 3.0, p.y + 12.0, 1.5, Color::new(0.6, 0.7, 0.9, 0.35));
+        }
+    }
+}
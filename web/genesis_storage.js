@@ -0,0 +1,56 @@
+// miniquad plugin backing GENESIS file storage with localStorage (see src/storage.rs).
+// Values are stored base64-encoded under "genesis:<path>".
+(function () {
+    function key(ptr, len) {
+        const bytes = new Uint8Array(wasm_memory.buffer, ptr, len);
+        return "genesis:" + new TextDecoder().decode(bytes);
+    }
+
+    function decode(text) {
+        const binary = atob(text);
+        const bytes = new Uint8Array(binary.length);
+        for (let i = 0; i < binary.length; i++) {
+            bytes[i] = binary.charCodeAt(i);
+        }
+        return bytes;
+    }
+
+    function encode(bytes) {
+        let binary = "";
+        for (let i = 0; i < bytes.length; i += 0x8000) {
+            binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+        }
+        return btoa(binary);
+    }
+
+    miniquad_add_plugin({
+        name: "genesis_storage",
+        version: 1,
+        register_plugin: function (importObject) {
+            importObject.env.genesis_storage_len = function (ptr, len) {
+                const value = localStorage.getItem(key(ptr, len));
+                return value === null ? -1 : decode(value).length;
+            };
+            importObject.env.genesis_storage_get = function (ptr, len, out) {
+                const value = localStorage.getItem(key(ptr, len));
+                if (value !== null) {
+                    const bytes = decode(value);
+                    new Uint8Array(wasm_memory.buffer, out, bytes.length).set(bytes);
+                }
+            };
+            importObject.env.genesis_storage_set = function (ptr, len, value, value_len) {
+                const bytes = new Uint8Array(wasm_memory.buffer, value, value_len);
+                try {
+                    localStorage.setItem(key(ptr, len), encode(bytes));
+                    return 1;
+                } catch (e) {
+                    console.error("GENESIS storage:", e);
+                    return 0;
+                }
+            };
+            importObject.env.genesis_storage_remove = function (ptr, len) {
+                localStorage.removeItem(key(ptr, len));
+            };
+        },
+    });
+})();